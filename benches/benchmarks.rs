@@ -11,7 +11,7 @@ use cursednoise::intrinsics::*;
 use cursednoise::*;
 
 fn d3(c: &mut Criterion) {
-    let setting = NoiseBuilder::fbm_3d(64, 64, 64).wrap();
+    let setting = NoiseBuilder::fbm_3d(64, 64, 64).wrap().unwrap();
     let mut group = c.benchmark_group("fbm3d");
     group.bench_function("avx2 3d", move |b| {
         b.iter(|| unsafe { avx2::get_3d_noise::<simdeez::Avx2>(&setting) })
@@ -23,7 +23,7 @@ fn d3(c: &mut Criterion) {
 }
 
 fn d2(c: &mut Criterion) {
-    let setting = NoiseBuilder::fbm_2d(3840, 2160).wrap();
+    let setting = NoiseBuilder::fbm_2d(3840, 2160).wrap().unwrap();
     let mut group = c.benchmark_group("fbm2d");
     group.bench_function("avx2 2d", move |b| {
         b.iter(|| unsafe { avx2::get_2d_noise::<simdeez::Avx2>(&setting) })
@@ -35,7 +35,7 @@ fn d2(c: &mut Criterion) {
 }
 
 fn d1(c: &mut Criterion) {
-    let setting = NoiseBuilder::fbm_1d(1024).wrap();
+    let setting = NoiseBuilder::fbm_1d(1024).wrap().unwrap();
     let mut group = c.benchmark_group("fbm1d");
     group.bench_function("avx2 1d", move |b| {
         b.iter(|| unsafe { avx2::get_1d_noise::<simdeez::Avx2>(&setting) })
@@ -6,16 +6,26 @@ use core::panic::PanicInfo;
 use libc_print::std_name::{print, println};
 use cursednoise::{NoiseBuilder, Settings, SimplexSettings};
 
+/// Samples `(x0 + i) * xStep` along x, and likewise along y and z, with the frequency left at 1,
+/// the same positions this always sampled. Returns 0 on success, or -1 and leaves `noise`
+/// untouched when the settings are invalid
 #[unsafe(no_mangle)]
 pub extern "C" fn generateNoise(
     noise: *mut f32, scale: f32, seed: i32,
     x0: f32, y0: f32, z0: f32,
     xLen: usize, yLen: usize, zLen: usize,
-    xStep: f32, yStep: f32, zStep: f32) {
-    NoiseBuilder::gradient_3d_offset(x0, xLen, y0, yLen, z0, zLen)
-        .with_freq_3d(xStep, yStep, zStep)
-        .with_seed(seed)
-        .generate_scaled(0.0, scale, noise);
+    xStep: f32, yStep: f32, zStep: f32) -> i32 {
+    let result = unsafe {
+        NoiseBuilder::gradient_3d_offset(x0 * xStep, xLen, y0 * yStep, yLen, z0 * zStep, zLen)
+            .with_step_3d(xStep, yStep, zStep)
+            .with_freq(1.0)
+            .with_legacy_seed(seed)
+            .generate_scaled(0.0, scale, noise)
+    };
+    match result {
+        Ok(()) => 0,
+        Err(_) => -1,
+    }
 }
 
 fn test() {
@@ -24,7 +34,8 @@ fn test() {
         .with_freq(0.01)
         .with_seed(4002)
         .with_octaves(3)
-        .generate_scaled(0.0, 5.0, AAAA.as_mut_ptr());
+        .generate_scaled_into(0.0, 5.0, &mut AAAA)
+        .unwrap();
 
     for i in 0..3 {
         for ii in 0..9 {
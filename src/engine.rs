@@ -0,0 +1,115 @@
+//! Picks the SIMD engine the top level `get_*` functions and `Settings::generate` run on.
+//!
+//! The best engine the CPU supports is detected on first use and cached, there is no std
+//! so detection talks to `cpuid` directly instead of `is_x86_feature_detected!`.
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+/// Instruction sets noise can be generated with, from slowest to fastest
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Engine {
+    /// One value at a time, works everywhere
+    Scalar,
+    Sse2,
+    Sse41,
+    /// AVX2 together with FMA
+    Avx2,
+}
+
+// 0 means nothing has been stored yet
+const UNSET: u8 = 0;
+
+static DETECTED: AtomicU8 = AtomicU8::new(UNSET);
+static FORCED: AtomicU8 = AtomicU8::new(UNSET);
+
+impl Engine {
+    fn to_u8(self) -> u8 {
+        match self {
+            Engine::Scalar => 1,
+            Engine::Sse2 => 2,
+            Engine::Sse41 => 3,
+            Engine::Avx2 => 4,
+        }
+    }
+
+    fn from_u8(value: u8) -> Option<Engine> {
+        match value {
+            1 => Some(Engine::Scalar),
+            2 => Some(Engine::Sse2),
+            3 => Some(Engine::Sse41),
+            4 => Some(Engine::Avx2),
+            _ => None,
+        }
+    }
+
+    /// Whether this CPU can run the engine
+    pub fn is_supported(self) -> bool {
+        self <= detected_engine()
+    }
+}
+
+/// The engine noise is currently generated with, a forced engine wins over the detected one
+pub fn active_engine() -> Engine {
+    Engine::from_u8(FORCED.load(Ordering::Relaxed)).unwrap_or_else(detected_engine)
+}
+
+/// Runs every following call on `engine`, mostly useful to test the slower paths on a fast
+/// machine. Returns false and changes nothing when the CPU doesn't support `engine`.
+pub fn force_engine(engine: Engine) -> bool {
+    if !engine.is_supported() {
+        return false;
+    }
+    FORCED.store(engine.to_u8(), Ordering::Relaxed);
+    true
+}
+
+/// Drops an engine set with `force_engine` and goes back to the detected one
+pub fn reset_engine() {
+    FORCED.store(UNSET, Ordering::Relaxed);
+}
+
+/// The fastest engine this CPU supports, detected once and cached
+pub fn detected_engine() -> Engine {
+    match Engine::from_u8(DETECTED.load(Ordering::Relaxed)) {
+        Some(engine) => engine,
+        None => {
+            // Racing threads all come to the same answer, so storing it twice is harmless
+            let engine = detect();
+            DETECTED.store(engine.to_u8(), Ordering::Relaxed);
+            engine
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[allow(unused_unsafe)]
+fn detect() -> Engine {
+    use core::arch::x86_64::{__cpuid, __cpuid_count, _xgetbv};
+
+    let max_leaf = unsafe { __cpuid(0) }.eax;
+    let leaf1 = unsafe { __cpuid(1) };
+    let sse2 = leaf1.edx & (1 << 26) != 0;
+    let sse41 = leaf1.ecx & (1 << 19) != 0;
+    let fma = leaf1.ecx & (1 << 12) != 0;
+    let osxsave = leaf1.ecx & (1 << 27) != 0;
+    let avx = leaf1.ecx & (1 << 28) != 0;
+    let avx2 = max_leaf >= 7 && unsafe { __cpuid_count(7, 0) }.ebx & (1 << 5) != 0;
+
+    // The OS also has to save the upper halves of the ymm registers on context switches
+    let ymm_enabled = osxsave && unsafe { _xgetbv(0) } & 0b110 == 0b110;
+
+    if avx && avx2 && fma && ymm_enabled {
+        Engine::Avx2
+    } else if sse41 {
+        Engine::Sse41
+    } else if sse2 {
+        Engine::Sse2
+    } else {
+        Engine::Scalar
+    }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn detect() -> Engine {
+    Engine::Scalar
+}
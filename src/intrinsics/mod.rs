@@ -1,24 +1,29 @@
+// The single value functions mirror the argument lists of the kernels they call
+#![allow(clippy::too_many_arguments)]
+
+use crate::noise::lattice::{Lattice, Lattice4d};
+use crate::noise::octaves::OctaveOptions;
+use crate::SeedMode;
+
 macro_rules! simplex {
     ("1d", $fn_name: ident, $f_type: ty, $transmute_from: path, $seed_type: ty, $mod: ident, $transmute_to: ident) => {
-        #[cfg(target_feature = "avx2")]
         /// Get a single value of 1d simplex noise, results are not scaled.
         pub unsafe fn $fn_name<S: simdeez::Simd>(x: $f_type, seed: $seed_type) -> $f_type {
             $mod::simplex_1d::<S>($transmute_from(x), seed).$transmute_to()
         }
     };
     ("2d", $fn_name: ident, $f_type: ty, $transmute_from: path, $seed_type: ty, $mod: ident, $transmute_to: ident) => {
-        #[cfg(target_feature = "avx2")]
         /// Get a single value of 2d simplex noise, results are not scaled.
         pub unsafe fn $fn_name<S: simdeez::Simd>(
             x: $f_type,
             y: $f_type,
             seed: $seed_type,
         ) -> $f_type {
-            $mod::simplex_2d::<S>($transmute_from(x), $transmute_from(y), seed).$transmute_to()
+            $mod::simplex_2d::<S>($transmute_from(x), $transmute_from(y), seed)
+                .$transmute_to()
         }
     };
     ("3d", $fn_name: ident, $f_type: ty, $transmute_from: path, $seed_type: ty, $mod: ident, $transmute_to: ident) => {
-        #[cfg(target_feature = "avx2")]
         /// Get a single value of 3d simplex noise, results are not scaled.
         pub unsafe fn $fn_name<S: simdeez::Simd>(
             x: $f_type,
@@ -35,11 +40,29 @@ macro_rules! simplex {
             .$transmute_to()
         }
     };
+    ("4d", $fn_name: ident, $f_type: ty, $transmute_from: path, $seed_type: ty, $mod: ident, $transmute_to: ident) => {
+        /// Get a single value of 4d simplex noise, results are not scaled.
+        pub unsafe fn $fn_name<S: simdeez::Simd>(
+            x: $f_type,
+            y: $f_type,
+            z: $f_type,
+            w: $f_type,
+            seed: $seed_type,
+        ) -> $f_type {
+            $mod::simplex_4d::<S>(
+                $transmute_from(x),
+                $transmute_from(y),
+                $transmute_from(z),
+                $transmute_from(w),
+                seed,
+            )
+            .$transmute_to()
+        }
+    };
 }
 
 macro_rules! fbm {
     ("1d", $fn_name: ident, $f_type: ty, $transmute_from: path, $seed_type: ty, $mod: ident, $transmute_to: ident) => {
-        #[cfg(target_feature = "avx2")]
         /// Get a single value of 1d fractal brownian motion.
         pub unsafe fn $fn_name<S: simdeez::Simd>(
             x: $f_type,
@@ -54,12 +77,12 @@ macro_rules! fbm {
                 $transmute_from(gain),
                 octaves,
                 seed,
+                crate::intrinsics::legacy_options(),
             )
             .$transmute_to()
         }
     };
     ("2d", $fn_name: ident, $f_type: ty, $transmute_from: path, $seed_type: ty, $mod: ident, $transmute_to: ident) => {
-        #[cfg(target_feature = "avx2")]
         /// Get a single value of 2d fractal brownian motion.
         pub unsafe fn $fn_name<S: simdeez::Simd>(
             x: $f_type,
@@ -76,12 +99,12 @@ macro_rules! fbm {
                 $transmute_from(gain),
                 octaves,
                 seed,
+                crate::intrinsics::legacy_options(),
             )
             .$transmute_to()
         }
     };
     ("3d", $fn_name: ident, $f_type: ty, $transmute_from: path, $seed_type: ty, $mod: ident, $transmute_to: ident) => {
-        #[cfg(target_feature = "avx2")]
         /// Get a single value of 3d fractal brownian motion.
         pub unsafe fn $fn_name<S: simdeez::Simd>(
             x: $f_type,
@@ -100,6 +123,355 @@ macro_rules! fbm {
                 $transmute_from(gain),
                 octaves,
                 seed,
+                crate::intrinsics::legacy_options(),
+            )
+            .$transmute_to()
+        }
+    };
+    ("4d", $fn_name: ident, $f_type: ty, $transmute_from: path, $seed_type: ty, $mod: ident, $transmute_to: ident) => {
+        /// Get a single value of 4d fractal brownian motion.
+        pub unsafe fn $fn_name<S: simdeez::Simd>(
+            x: $f_type,
+            y: $f_type,
+            z: $f_type,
+            w: $f_type,
+            lacunarity: $f_type,
+            gain: $f_type,
+            octaves: u8,
+            seed: $seed_type,
+        ) -> $f_type {
+            $mod::fbm_4d::<S>(
+                $transmute_from(x),
+                $transmute_from(y),
+                $transmute_from(z),
+                $transmute_from(w),
+                $transmute_from(lacunarity),
+                $transmute_from(gain),
+                octaves,
+                seed,
+                crate::intrinsics::legacy_options_4d(),
+            )
+            .$transmute_to()
+        }
+    };
+}
+
+macro_rules! ridge {
+    ("1d", $fn_name: ident, $f_type: ty, $transmute_from: path, $seed_type: ty, $mod: ident, $transmute_to: ident) => {
+        /// Get a single value of 1d ridged multifractal noise.
+        pub unsafe fn $fn_name<S: simdeez::Simd>(
+            x: $f_type,
+            lacunarity: $f_type,
+            gain: $f_type,
+            persistence: $f_type,
+            offset: $f_type,
+            octaves: u8,
+            seed: $seed_type,
+        ) -> $f_type {
+            $mod::ridge_1d::<S>(
+                $transmute_from(x),
+                $transmute_from(lacunarity),
+                $transmute_from(gain),
+                $transmute_from(persistence),
+                $transmute_from(offset),
+                octaves,
+                seed,
+                crate::intrinsics::legacy_options(),
+            )
+            .$transmute_to()
+        }
+    };
+    ("2d", $fn_name: ident, $f_type: ty, $transmute_from: path, $seed_type: ty, $mod: ident, $transmute_to: ident) => {
+        /// Get a single value of 2d ridged multifractal noise.
+        pub unsafe fn $fn_name<S: simdeez::Simd>(
+            x: $f_type,
+            y: $f_type,
+            lacunarity: $f_type,
+            gain: $f_type,
+            persistence: $f_type,
+            offset: $f_type,
+            octaves: u8,
+            seed: $seed_type,
+        ) -> $f_type {
+            $mod::ridge_2d::<S>(
+                $transmute_from(x),
+                $transmute_from(y),
+                $transmute_from(lacunarity),
+                $transmute_from(gain),
+                $transmute_from(persistence),
+                $transmute_from(offset),
+                octaves,
+                seed,
+                crate::intrinsics::legacy_options(),
+            )
+            .$transmute_to()
+        }
+    };
+    ("3d", $fn_name: ident, $f_type: ty, $transmute_from: path, $seed_type: ty, $mod: ident, $transmute_to: ident) => {
+        /// Get a single value of 3d ridged multifractal noise.
+        pub unsafe fn $fn_name<S: simdeez::Simd>(
+            x: $f_type,
+            y: $f_type,
+            z: $f_type,
+            lacunarity: $f_type,
+            gain: $f_type,
+            persistence: $f_type,
+            offset: $f_type,
+            octaves: u8,
+            seed: $seed_type,
+        ) -> $f_type {
+            $mod::ridge_3d::<S>(
+                $transmute_from(x),
+                $transmute_from(y),
+                $transmute_from(z),
+                $transmute_from(lacunarity),
+                $transmute_from(gain),
+                $transmute_from(persistence),
+                $transmute_from(offset),
+                octaves,
+                seed,
+                crate::intrinsics::legacy_options(),
+            )
+            .$transmute_to()
+        }
+    };
+    ("4d", $fn_name: ident, $f_type: ty, $transmute_from: path, $seed_type: ty, $mod: ident, $transmute_to: ident) => {
+        /// Get a single value of 4d ridged multifractal noise.
+        pub unsafe fn $fn_name<S: simdeez::Simd>(
+            x: $f_type,
+            y: $f_type,
+            z: $f_type,
+            w: $f_type,
+            lacunarity: $f_type,
+            gain: $f_type,
+            persistence: $f_type,
+            offset: $f_type,
+            octaves: u8,
+            seed: $seed_type,
+        ) -> $f_type {
+            $mod::ridge_4d::<S>(
+                $transmute_from(x),
+                $transmute_from(y),
+                $transmute_from(z),
+                $transmute_from(w),
+                $transmute_from(lacunarity),
+                $transmute_from(gain),
+                $transmute_from(persistence),
+                $transmute_from(offset),
+                octaves,
+                seed,
+                crate::intrinsics::legacy_options_4d(),
+            )
+            .$transmute_to()
+        }
+    };
+}
+
+macro_rules! turbulence {
+    ("1d", $fn_name: ident, $f_type: ty, $transmute_from: path, $seed_type: ty, $mod: ident, $transmute_to: ident) => {
+        /// Get a single value of 1d turbulence.
+        pub unsafe fn $fn_name<S: simdeez::Simd>(
+            x: $f_type,
+            lacunarity: $f_type,
+            gain: $f_type,
+            octaves: u8,
+            seed: $seed_type,
+        ) -> $f_type {
+            $mod::turbulence_1d::<S>(
+                $transmute_from(x),
+                $transmute_from(lacunarity),
+                $transmute_from(gain),
+                octaves,
+                seed,
+                crate::intrinsics::legacy_options(),
+            )
+            .$transmute_to()
+        }
+    };
+    ("2d", $fn_name: ident, $f_type: ty, $transmute_from: path, $seed_type: ty, $mod: ident, $transmute_to: ident) => {
+        /// Get a single value of 2d turbulence.
+        pub unsafe fn $fn_name<S: simdeez::Simd>(
+            x: $f_type,
+            y: $f_type,
+            lacunarity: $f_type,
+            gain: $f_type,
+            octaves: u8,
+            seed: $seed_type,
+        ) -> $f_type {
+            $mod::turbulence_2d::<S>(
+                $transmute_from(x),
+                $transmute_from(y),
+                $transmute_from(lacunarity),
+                $transmute_from(gain),
+                octaves,
+                seed,
+                crate::intrinsics::legacy_options(),
+            )
+            .$transmute_to()
+        }
+    };
+    ("3d", $fn_name: ident, $f_type: ty, $transmute_from: path, $seed_type: ty, $mod: ident, $transmute_to: ident) => {
+        /// Get a single value of 3d turbulence.
+        pub unsafe fn $fn_name<S: simdeez::Simd>(
+            x: $f_type,
+            y: $f_type,
+            z: $f_type,
+            lacunarity: $f_type,
+            gain: $f_type,
+            octaves: u8,
+            seed: $seed_type,
+        ) -> $f_type {
+            $mod::turbulence_3d::<S>(
+                $transmute_from(x),
+                $transmute_from(y),
+                $transmute_from(z),
+                $transmute_from(lacunarity),
+                $transmute_from(gain),
+                octaves,
+                seed,
+                crate::intrinsics::legacy_options(),
+            )
+            .$transmute_to()
+        }
+    };
+    ("4d", $fn_name: ident, $f_type: ty, $transmute_from: path, $seed_type: ty, $mod: ident, $transmute_to: ident) => {
+        /// Get a single value of 4d turbulence.
+        pub unsafe fn $fn_name<S: simdeez::Simd>(
+            x: $f_type,
+            y: $f_type,
+            z: $f_type,
+            w: $f_type,
+            lacunarity: $f_type,
+            gain: $f_type,
+            octaves: u8,
+            seed: $seed_type,
+        ) -> $f_type {
+            $mod::turbulence_4d::<S>(
+                $transmute_from(x),
+                $transmute_from(y),
+                $transmute_from(z),
+                $transmute_from(w),
+                $transmute_from(lacunarity),
+                $transmute_from(gain),
+                octaves,
+                seed,
+                crate::intrinsics::legacy_options_4d(),
+            )
+            .$transmute_to()
+        }
+    };
+}
+
+macro_rules! billow {
+    ("1d", $fn_name: ident, $f_type: ty, $transmute_from: path, $seed_type: ty, $mod: ident, $transmute_to: ident) => {
+        /// Get a single value of 1d billow noise.
+        pub unsafe fn $fn_name<S: simdeez::Simd>(
+            x: $f_type,
+            lacunarity: $f_type,
+            gain: $f_type,
+            octaves: u8,
+            seed: $seed_type,
+        ) -> $f_type {
+            $mod::billow_1d::<S>(
+                $transmute_from(x),
+                $transmute_from(lacunarity),
+                $transmute_from(gain),
+                octaves,
+                seed,
+                crate::intrinsics::legacy_options(),
+            )
+            .$transmute_to()
+        }
+    };
+    ("2d", $fn_name: ident, $f_type: ty, $transmute_from: path, $seed_type: ty, $mod: ident, $transmute_to: ident) => {
+        /// Get a single value of 2d billow noise.
+        pub unsafe fn $fn_name<S: simdeez::Simd>(
+            x: $f_type,
+            y: $f_type,
+            lacunarity: $f_type,
+            gain: $f_type,
+            octaves: u8,
+            seed: $seed_type,
+        ) -> $f_type {
+            $mod::billow_2d::<S>(
+                $transmute_from(x),
+                $transmute_from(y),
+                $transmute_from(lacunarity),
+                $transmute_from(gain),
+                octaves,
+                seed,
+                crate::intrinsics::legacy_options(),
+            )
+            .$transmute_to()
+        }
+    };
+    ("3d", $fn_name: ident, $f_type: ty, $transmute_from: path, $seed_type: ty, $mod: ident, $transmute_to: ident) => {
+        /// Get a single value of 3d billow noise.
+        pub unsafe fn $fn_name<S: simdeez::Simd>(
+            x: $f_type,
+            y: $f_type,
+            z: $f_type,
+            lacunarity: $f_type,
+            gain: $f_type,
+            octaves: u8,
+            seed: $seed_type,
+        ) -> $f_type {
+            $mod::billow_3d::<S>(
+                $transmute_from(x),
+                $transmute_from(y),
+                $transmute_from(z),
+                $transmute_from(lacunarity),
+                $transmute_from(gain),
+                octaves,
+                seed,
+                crate::intrinsics::legacy_options(),
+            )
+            .$transmute_to()
+        }
+    };
+}
+
+macro_rules! cellular {
+    ("2d", $fn_name: ident, $f_type: ty, $transmute_from: path, $seed_type: ty, $mod: ident, $transmute_to: ident) => {
+        /// Get a single value of 2d cellular noise.
+        pub unsafe fn $fn_name<S: simdeez::Simd>(
+            x: $f_type,
+            y: $f_type,
+            distance_function: crate::CellDistanceFunction,
+            return_type: crate::CellReturnType,
+            jitter: $f_type,
+            seed: $seed_type,
+        ) -> $f_type {
+            $mod::cellular_2d::<S>(
+                $transmute_from(x),
+                $transmute_from(y),
+                distance_function,
+                return_type,
+                $transmute_from(jitter),
+                seed,
+            )
+            .$transmute_to()
+        }
+    };
+    ("3d", $fn_name: ident, $f_type: ty, $transmute_from: path, $seed_type: ty, $mod: ident, $transmute_to: ident) => {
+        /// Get a single value of 3d cellular noise.
+        pub unsafe fn $fn_name<S: simdeez::Simd>(
+            x: $f_type,
+            y: $f_type,
+            z: $f_type,
+            distance_function: crate::CellDistanceFunction,
+            return_type: crate::CellReturnType,
+            jitter: $f_type,
+            seed: $seed_type,
+        ) -> $f_type {
+            $mod::cellular_3d::<S>(
+                $transmute_from(x),
+                $transmute_from(y),
+                $transmute_from(z),
+                distance_function,
+                return_type,
+                $transmute_from(jitter),
+                seed,
             )
             .$transmute_to()
         }
@@ -120,6 +492,19 @@ macro_rules! get_noise {
         }
     };
 }
+/// Like `get_noise!`, for noise that not every noise type has
+macro_rules! try_get_noise {
+    ($call: ident, $fn_name: ident, $f_type: ty, $mod: ident) => {
+        /// Gets a width X height X depth X time sized block of noise, unscaled. Fails if
+        /// `noise_type` has no 4d noise, otherwise returns the 'min' and 'max' noise values so
+        /// you can scale and transform the noise as you see fit in a single pass.
+        pub unsafe fn $fn_name<S: simdeez::Simd>(
+            noise_type: &NoiseType, noise: *mut $f_type
+        ) -> Result<($f_type, $f_type), NoiseError> {
+            $mod::$call::<S>(noise_type, noise)
+        }
+    };
+}
 macro_rules! get_noise_scaled {
     ($call: ident, $fn_name: ident, $f_type: ty) => {
         /// Gets a width sized block of scaled noise
@@ -127,12 +512,465 @@ macro_rules! get_noise_scaled {
         /// `scaled_min` and `scaled_max` specify the range you want the noise scaled to.
 
         pub unsafe fn $fn_name<S: simdeez::Simd>(noise_type: &NoiseType, noise: *mut $f_type) {
-            let (min, max) = $call::<S>(noise_type, noise);
+            let (min, max) = scale_source(noise_type, $call::<S>(noise_type, noise));
+            let dim = noise_type.get_dimensions();
+            scale_noise::<S>(dim.min, dim.max, min, max, noise, dim.len());
+        }
+    };
+}
+/// Like `get_noise_scaled!`, for noise that not every noise type has
+macro_rules! try_get_noise_scaled {
+    ($call: ident, $fn_name: ident, $f_type: ty) => {
+        /// Gets a width X height X depth X time sized block of scaled noise, fails if
+        /// `noise_type` has no 4d noise.
+        pub unsafe fn $fn_name<S: simdeez::Simd>(
+            noise_type: &NoiseType, noise: *mut $f_type
+        ) -> Result<(), NoiseError> {
+            let (min, max) = scale_source(noise_type, $call::<S>(noise_type, noise)?);
             let dim = noise_type.get_dimensions();
             scale_noise::<S>(dim.min, dim.max, min, max, noise, dim.len());
+            Ok(())
         }
     };
 }
 
+/// Every function of an engine module, `$f32_type` and `$f64_type` are the vectors the engine
+/// works on and the transmutes move single values in and out of them
+macro_rules! engine_functions {
+    ($f32_type: ty, $f64_type: ty, $transmute_from_f32: path, $transmute_from_f64: path, $transmute_to: ident) => {
+        use crate::noise::billow_32;
+        use crate::noise::billow_64;
+        use crate::noise::cellular_32;
+        use crate::noise::cellular_64;
+        use crate::noise::fbm_32;
+        use crate::noise::fbm_64;
+        use crate::noise::ridge_32;
+        use crate::noise::ridge_64;
+        use crate::noise::simplex_32;
+        use crate::noise::simplex_64;
+        use crate::noise::turbulence_32;
+        use crate::noise::turbulence_64;
+        use crate::noise_helpers_32;
+        use crate::noise_helpers_64;
+        use crate::shared::{scale_noise, scale_source};
+        use crate::{DimensionalBeing, NoiseError, NoiseType};
+
+        simplex!(
+            "1d",
+            simplex_1d,
+            $f32_type,
+            $transmute_from_f32,
+            i32,
+            simplex_32,
+            $transmute_to
+        );
+        simplex!(
+            "2d",
+            simplex_2d,
+            $f32_type,
+            $transmute_from_f32,
+            i32,
+            simplex_32,
+            $transmute_to
+        );
+        simplex!(
+            "3d",
+            simplex_3d,
+            $f32_type,
+            $transmute_from_f32,
+            i32,
+            simplex_32,
+            $transmute_to
+        );
+        simplex!(
+            "4d",
+            simplex_4d,
+            $f32_type,
+            $transmute_from_f32,
+            i32,
+            simplex_32,
+            $transmute_to
+        );
+        simplex!(
+            "1d",
+            simplex_1d_f64,
+            $f64_type,
+            $transmute_from_f64,
+            i64,
+            simplex_64,
+            $transmute_to
+        );
+        simplex!(
+            "2d",
+            simplex_2d_f64,
+            $f64_type,
+            $transmute_from_f64,
+            i64,
+            simplex_64,
+            $transmute_to
+        );
+        simplex!(
+            "3d",
+            simplex_3d_f64,
+            $f64_type,
+            $transmute_from_f64,
+            i64,
+            simplex_64,
+            $transmute_to
+        );
+        simplex!(
+            "4d",
+            simplex_4d_f64,
+            $f64_type,
+            $transmute_from_f64,
+            i64,
+            simplex_64,
+            $transmute_to
+        );
+        fbm!(
+            "1d",
+            fbm_1d,
+            $f32_type,
+            $transmute_from_f32,
+            i32,
+            fbm_32,
+            $transmute_to
+        );
+        fbm!(
+            "2d",
+            fbm_2d,
+            $f32_type,
+            $transmute_from_f32,
+            i32,
+            fbm_32,
+            $transmute_to
+        );
+        fbm!(
+            "3d",
+            fbm_3d,
+            $f32_type,
+            $transmute_from_f32,
+            i32,
+            fbm_32,
+            $transmute_to
+        );
+        fbm!(
+            "4d",
+            fbm_4d,
+            $f32_type,
+            $transmute_from_f32,
+            i32,
+            fbm_32,
+            $transmute_to
+        );
+        fbm!(
+            "1d",
+            fbm_1d_f64,
+            $f64_type,
+            $transmute_from_f64,
+            i64,
+            fbm_64,
+            $transmute_to
+        );
+        fbm!(
+            "2d",
+            fbm_2d_f64,
+            $f64_type,
+            $transmute_from_f64,
+            i64,
+            fbm_64,
+            $transmute_to
+        );
+        fbm!(
+            "3d",
+            fbm_3d_f64,
+            $f64_type,
+            $transmute_from_f64,
+            i64,
+            fbm_64,
+            $transmute_to
+        );
+        fbm!(
+            "4d",
+            fbm_4d_f64,
+            $f64_type,
+            $transmute_from_f64,
+            i64,
+            fbm_64,
+            $transmute_to
+        );
+
+        ridge!(
+            "1d",
+            ridge_1d,
+            $f32_type,
+            $transmute_from_f32,
+            i32,
+            ridge_32,
+            $transmute_to
+        );
+        ridge!(
+            "2d",
+            ridge_2d,
+            $f32_type,
+            $transmute_from_f32,
+            i32,
+            ridge_32,
+            $transmute_to
+        );
+        ridge!(
+            "3d",
+            ridge_3d,
+            $f32_type,
+            $transmute_from_f32,
+            i32,
+            ridge_32,
+            $transmute_to
+        );
+        ridge!(
+            "4d",
+            ridge_4d,
+            $f32_type,
+            $transmute_from_f32,
+            i32,
+            ridge_32,
+            $transmute_to
+        );
+        ridge!(
+            "1d",
+            ridge_1d_f64,
+            $f64_type,
+            $transmute_from_f64,
+            i64,
+            ridge_64,
+            $transmute_to
+        );
+        ridge!(
+            "2d",
+            ridge_2d_f64,
+            $f64_type,
+            $transmute_from_f64,
+            i64,
+            ridge_64,
+            $transmute_to
+        );
+        ridge!(
+            "3d",
+            ridge_3d_f64,
+            $f64_type,
+            $transmute_from_f64,
+            i64,
+            ridge_64,
+            $transmute_to
+        );
+        ridge!(
+            "4d",
+            ridge_4d_f64,
+            $f64_type,
+            $transmute_from_f64,
+            i64,
+            ridge_64,
+            $transmute_to
+        );
+
+        turbulence!(
+            "1d",
+            turbulence_1d,
+            $f32_type,
+            $transmute_from_f32,
+            i32,
+            turbulence_32,
+            $transmute_to
+        );
+        turbulence!(
+            "2d",
+            turbulence_2d,
+            $f32_type,
+            $transmute_from_f32,
+            i32,
+            turbulence_32,
+            $transmute_to
+        );
+        turbulence!(
+            "3d",
+            turbulence_3d,
+            $f32_type,
+            $transmute_from_f32,
+            i32,
+            turbulence_32,
+            $transmute_to
+        );
+        turbulence!(
+            "4d",
+            turbulence_4d,
+            $f32_type,
+            $transmute_from_f32,
+            i32,
+            turbulence_32,
+            $transmute_to
+        );
+        turbulence!(
+            "1d",
+            turbulence_1d_f64,
+            $f64_type,
+            $transmute_from_f64,
+            i64,
+            turbulence_64,
+            $transmute_to
+        );
+        turbulence!(
+            "2d",
+            turbulence_2d_f64,
+            $f64_type,
+            $transmute_from_f64,
+            i64,
+            turbulence_64,
+            $transmute_to
+        );
+        turbulence!(
+            "3d",
+            turbulence_3d_f64,
+            $f64_type,
+            $transmute_from_f64,
+            i64,
+            turbulence_64,
+            $transmute_to
+        );
+        turbulence!(
+            "4d",
+            turbulence_4d_f64,
+            $f64_type,
+            $transmute_from_f64,
+            i64,
+            turbulence_64,
+            $transmute_to
+        );
+
+        billow!(
+            "1d",
+            billow_1d,
+            $f32_type,
+            $transmute_from_f32,
+            i32,
+            billow_32,
+            $transmute_to
+        );
+        billow!(
+            "2d",
+            billow_2d,
+            $f32_type,
+            $transmute_from_f32,
+            i32,
+            billow_32,
+            $transmute_to
+        );
+        billow!(
+            "3d",
+            billow_3d,
+            $f32_type,
+            $transmute_from_f32,
+            i32,
+            billow_32,
+            $transmute_to
+        );
+        billow!(
+            "1d",
+            billow_1d_f64,
+            $f64_type,
+            $transmute_from_f64,
+            i64,
+            billow_64,
+            $transmute_to
+        );
+        billow!(
+            "2d",
+            billow_2d_f64,
+            $f64_type,
+            $transmute_from_f64,
+            i64,
+            billow_64,
+            $transmute_to
+        );
+        billow!(
+            "3d",
+            billow_3d_f64,
+            $f64_type,
+            $transmute_from_f64,
+            i64,
+            billow_64,
+            $transmute_to
+        );
+
+        cellular!(
+            "2d",
+            cellular_2d,
+            $f32_type,
+            $transmute_from_f32,
+            i32,
+            cellular_32,
+            $transmute_to
+        );
+        cellular!(
+            "3d",
+            cellular_3d,
+            $f32_type,
+            $transmute_from_f32,
+            i32,
+            cellular_32,
+            $transmute_to
+        );
+        cellular!(
+            "2d",
+            cellular_2d_f64,
+            $f64_type,
+            $transmute_from_f64,
+            i64,
+            cellular_64,
+            $transmute_to
+        );
+        cellular!(
+            "3d",
+            cellular_3d_f64,
+            $f64_type,
+            $transmute_from_f64,
+            i64,
+            cellular_64,
+            $transmute_to
+        );
+
+        get_noise!(get_1d_noise, get_1d_noise, f32, noise_helpers_32);
+        get_noise!(get_2d_noise, get_2d_noise, f32, noise_helpers_32);
+        get_noise!(get_3d_noise, get_3d_noise, f32, noise_helpers_32);
+        try_get_noise!(get_4d_noise, get_4d_noise, f32, noise_helpers_32);
+        get_noise!(get_1d_noise, get_1d_noise_64, f64, noise_helpers_64);
+        get_noise!(get_2d_noise, get_2d_noise_64, f64, noise_helpers_64);
+        get_noise!(get_3d_noise, get_3d_noise_64, f64, noise_helpers_64);
+        try_get_noise!(get_4d_noise, get_4d_noise_64, f64, noise_helpers_64);
+        get_noise_scaled!(get_1d_noise, get_1d_scaled_noise, f32);
+        get_noise_scaled!(get_2d_noise, get_2d_scaled_noise, f32);
+        get_noise_scaled!(get_3d_noise, get_3d_scaled_noise, f32);
+        try_get_noise_scaled!(get_4d_noise, get_4d_scaled_noise, f32);
+    };
+}
+
+/// The octave options of the fractal functions above. Their seeds go to the kernels as they are,
+/// so they hash simplex noise the way they always did.
+pub(crate) fn legacy_options() -> OctaveOptions {
+    OctaveOptions::<Lattice>::default().for_seed_mode(SeedMode::Legacy)
+}
+
+/// `legacy_options` for the 4d fractal functions above
+pub(crate) fn legacy_options_4d() -> OctaveOptions<Lattice4d> {
+    OctaveOptions {
+        lattice: Lattice4d::LegacySimplex,
+        ..OctaveOptions::default()
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
 pub mod avx2;
 pub mod scalar;
+#[cfg(target_arch = "x86_64")]
+pub mod sse2;
+#[cfg(target_arch = "x86_64")]
+pub mod sse41;
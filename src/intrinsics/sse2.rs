@@ -0,0 +1,22 @@
+//! SSE2 Accelerated noise functions.
+//! All 64bit x86 CPUs support this.
+//!
+//! Only call these where `Engine::Sse2.is_supported()`, without std there is no
+//! `is_x86_feature_detected!` so support is detected with `cpuid`, see `crate::engine`. The
+//! top level `get_*` functions pick the engine for you, `force_engine` pins them to one.
+//!
+//! When using the `get_` functions, you will get a performance boost when width
+//! is evenly divisble by 4, and when it is not small relative height and depth.
+
+use simdeez::{SimdTransmuteF32, SimdTransmuteF64};
+
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+engine_functions!(
+    __m128,
+    __m128d,
+    SimdTransmuteF32::try_transmute_from_sse2,
+    SimdTransmuteF64::try_transmute_from_sse2,
+    try_transmute_sse2
+);
@@ -0,0 +1,23 @@
+//! SSE41 Accelerated noise functions.
+//! CPUs since Intel Penryn (~2008) and AMD Bulldozer (~2011) support this.
+//! It is a little faster than the SSE2 version, mostly thanks to a native floor.
+//!
+//! Only call these where `Engine::Sse41.is_supported()`, without std there is no
+//! `is_x86_feature_detected!` so support is detected with `cpuid`, see `crate::engine`. The
+//! top level `get_*` functions pick the engine for you, `force_engine` pins them to one.
+//!
+//! When using the `get_` functions, you will get a performance boost when width
+//! is evenly divisble by 4, and when it is not small relative height and depth.
+
+use simdeez::{SimdTransmuteF32, SimdTransmuteF64};
+
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+engine_functions!(
+    __m128,
+    __m128d,
+    SimdTransmuteF32::try_transmute_from_sse41,
+    SimdTransmuteF64::try_transmute_from_sse41,
+    try_transmute_sse41
+);
@@ -5,9 +5,21 @@
 //!
 //!## Features
 //!
-//!* AVX2 instruction and a non SIMD fallback
-//!* Simplex noise, fractal brownian motion
-//!* 1D, 2D, 3D
+//!* AVX2, SSE4.1 and SSE2 instructions, picked at runtime, and a non SIMD fallback
+//!* Simplex noise, fractal brownian motion, ridged multifractal, turbulence, billow
+//!* Cellular noise (aka Voronoi / Worley) 2D, 3D
+//!* White noise 1D, 2D, 3D, a hashed random value per lattice cell
+//!* Domain warping of any of the above by any other, single pass or fractal
+//!* Curl noise, divergence free 2D and 3D vector fields
+//!* Analytic derivatives alongside gradient and fbm noise, for normals and slopes
+//!* OpenSimplex2 and OpenSimplex2S lattices for gradient and fractal noise, without the axis
+//!  aligned artefacts of simplex noise
+//!* Improved Perlin noise 1D, 2D, 3D, as the base of gradient and fractal noise
+//!* Value noise with linear, Hermite or quintic interpolation, and cubic value noise, 1D, 2D, 3D,
+//!  as the base of gradient and fractal noise
+//!* Seamless 2D and 3D gradient and fbm noise that repeats with a given period, for tiling
+//!* 1D, 2D, 3D, 4D
+//!* Blocks on a regular grid, or any set of points with `sample_points_*`
 //!
 //!# Examples
 //!
@@ -16,7 +28,7 @@
 //!
 //! // Get a block of 2d fbm noise with default settings, 100 x 100, with values scaled to the range [0,1]
 //! let mut noise = [0.0; 100 * 100];
-//! NoiseBuilder::fbm_2d(100, 100).generate_scaled(0.0,1.0, noise.as_mut_ptr());
+//! NoiseBuilder::fbm_2d(100, 100).generate_scaled_into(0.0, 1.0, &mut noise).unwrap();
 //!
 //! ```
 //!
@@ -26,10 +38,12 @@
 extern crate simdeez;
 
 mod dimensional_being;
+mod engine;
 pub mod intrinsics;
 pub mod noise;
 mod noise_builder;
 mod noise_dimensions;
+mod noise_error;
 mod noise_helpers_32;
 mod noise_helpers_64;
 mod noise_type;
@@ -39,45 +53,388 @@ use simdeez::prelude::*;
 
 use dimensional_being::DimensionalBeing;
 pub use noise_builder::NoiseBuilder;
-pub use noise_dimensions::NoiseDimensions;
+pub use noise_dimensions::{NoiseDimensions, ScaleMode, SeedMode};
+pub use noise_error::NoiseError;
+pub use noise::lattice::{Interpolation, Lattice, Lattice4d};
+pub use noise::octaves::OctaveOptions;
 pub use noise_type::NoiseType;
-use simdeez::engines::avx2::Avx2;
+pub use engine::{active_engine, detected_engine, force_engine, reset_engine, Engine};
+#[cfg(target_arch = "x86_64")]
+use simdeez::engines::{avx2::Avx2, sse2::Sse2, sse41::Sse41};
+use simdeez::scalar::Scalar;
 
 pub const VECSIZE: usize = 64 * 32 * 16;
 
 
-macro_rules! avxecute {
-    ($call:stmt) => {
+/// Runs `$call` on the engine picked by `active_engine`, `$s` names that engine inside `$call`
+macro_rules! dispatch {
+    ($s:ident => $call:expr) => {
         unsafe {
-            Avx2::invoke(#[inline(always)] || { $call })
+            match active_engine() {
+                #[cfg(target_arch = "x86_64")]
+                Engine::Avx2 => {
+                    type $s = Avx2;
+                    $s::invoke(#[inline(always)] || $call)
+                }
+                #[cfg(target_arch = "x86_64")]
+                Engine::Sse41 => {
+                    type $s = Sse41;
+                    $s::invoke(#[inline(always)] || $call)
+                }
+                #[cfg(target_arch = "x86_64")]
+                Engine::Sse2 => {
+                    type $s = Sse2;
+                    $s::invoke(#[inline(always)] || $call)
+                }
+                _ => {
+                    type $s = Scalar;
+                    $s::invoke(#[inline(always)] || $call)
+                }
+            }
         }
     };
 }
 
-pub fn get_1d_noise(noise_type: &NoiseType, noise: *mut f32) -> (f32, f32) {
-    avxecute!(noise_helpers_32::get_1d_noise::<Avx2>(noise_type, noise))
+/// # Safety
+/// `noise` must be valid for writes of `NoiseDimensions::len` values
+pub unsafe fn get_1d_noise(noise_type: &NoiseType, noise: *mut f32) -> (f32, f32) {
+    dispatch!(S => noise_helpers_32::get_1d_noise::<S>(noise_type, noise))
 }
 
-pub fn get_2d_noise(noise_type: &NoiseType, noise: *mut f32) -> (f32, f32) {
-    avxecute!(noise_helpers_32::get_2d_noise::<Avx2>(noise_type, noise))
+/// # Safety
+/// `noise` must be valid for writes of `NoiseDimensions::len` values
+pub unsafe fn get_2d_noise(noise_type: &NoiseType, noise: *mut f32) -> (f32, f32) {
+    dispatch!(S => noise_helpers_32::get_2d_noise::<S>(noise_type, noise))
 }
 
-pub fn get_3d_noise(noise_type: &NoiseType, noise: *mut f32) -> (f32, f32) {
-    avxecute!(noise_helpers_32::get_3d_noise::<Avx2>(noise_type, noise))
+/// # Safety
+/// `noise` must be valid for writes of `NoiseDimensions::len` values
+pub unsafe fn get_3d_noise(noise_type: &NoiseType, noise: *mut f32) -> (f32, f32) {
+    dispatch!(S => noise_helpers_32::get_3d_noise::<S>(noise_type, noise))
 }
 
-pub fn get_1d_scaled_noise(noise_type: &NoiseType, noise: *mut f32) {
-    avxecute!(get_scaled_noise::<Avx2, _>(noise_type, noise, get_1d_noise))
+/// Fails if `noise_type` has no 4d noise
+///
+/// # Safety
+/// `noise` must be valid for writes of `NoiseDimensions::len` values
+pub unsafe fn get_4d_noise(
+    noise_type: &NoiseType,
+    noise: *mut f32,
+) -> Result<(f32, f32), NoiseError> {
+    dispatch!(S => noise_helpers_32::get_4d_noise::<S>(noise_type, noise))
 }
 
-pub fn get_2d_scaled_noise(noise_type: &NoiseType, noise: *mut f32) {
-    avxecute!(get_scaled_noise::<Avx2, _>(noise_type, noise, get_2d_noise))
+/// # Safety
+/// `noise` must be valid for writes of `NoiseDimensions::len` values
+pub unsafe fn get_1d_scaled_noise(noise_type: &NoiseType, noise: *mut f32) {
+    dispatch!(S => get_scaled_noise::<S>(noise_type, noise, get_1d_noise))
 }
 
-pub fn get_3d_scaled_noise(noise_type: &NoiseType, noise: *mut f32) {
-    avxecute!(get_scaled_noise::<Avx2, _>(noise_type, noise, get_3d_noise))
+/// # Safety
+/// `noise` must be valid for writes of `NoiseDimensions::len` values
+pub unsafe fn get_2d_scaled_noise(noise_type: &NoiseType, noise: *mut f32) {
+    dispatch!(S => get_scaled_noise::<S>(noise_type, noise, get_2d_noise))
+}
+
+/// # Safety
+/// `noise` must be valid for writes of `NoiseDimensions::len` values
+pub unsafe fn get_3d_scaled_noise(noise_type: &NoiseType, noise: *mut f32) {
+    dispatch!(S => get_scaled_noise::<S>(noise_type, noise, get_3d_noise))
+}
+
+/// Fails if `noise_type` has no 4d noise
+///
+/// # Safety
+/// `noise` must be valid for writes of `NoiseDimensions::len` values
+pub unsafe fn get_4d_scaled_noise(
+    noise_type: &NoiseType,
+    noise: *mut f32,
+) -> Result<(), NoiseError> {
+    let observed = get_4d_noise(noise_type, noise)?;
+    dispatch!(S => scale_block::<S>(noise_type, noise, observed));
+    Ok(())
+}
+
+/// Like `get_1d_noise`, in double precision
+///
+/// # Safety
+/// `noise` must be valid for writes of `NoiseDimensions::len` values
+pub unsafe fn get_1d_noise_f64(noise_type: &NoiseType, noise: *mut f64) -> (f64, f64) {
+    dispatch!(S => noise_helpers_64::get_1d_noise::<S>(noise_type, noise))
+}
+
+/// Like `get_2d_noise`, in double precision
+///
+/// # Safety
+/// `noise` must be valid for writes of `NoiseDimensions::len` values
+pub unsafe fn get_2d_noise_f64(noise_type: &NoiseType, noise: *mut f64) -> (f64, f64) {
+    dispatch!(S => noise_helpers_64::get_2d_noise::<S>(noise_type, noise))
+}
+
+/// Like `get_3d_noise`, in double precision
+///
+/// # Safety
+/// `noise` must be valid for writes of `NoiseDimensions::len` values
+pub unsafe fn get_3d_noise_f64(noise_type: &NoiseType, noise: *mut f64) -> (f64, f64) {
+    dispatch!(S => noise_helpers_64::get_3d_noise::<S>(noise_type, noise))
+}
+
+/// Like `get_4d_noise`, in double precision
+///
+/// # Safety
+/// `noise` must be valid for writes of `NoiseDimensions::len` values
+pub unsafe fn get_4d_noise_f64(
+    noise_type: &NoiseType,
+    noise: *mut f64,
+) -> Result<(f64, f64), NoiseError> {
+    dispatch!(S => noise_helpers_64::get_4d_noise::<S>(noise_type, noise))
+}
+
+/// Like `get_1d_scaled_noise`, in double precision. The noise is scaled onto `min..max`, the
+/// single precision `min` and `max` of the dimensions are ignored.
+///
+/// # Safety
+/// `noise` must be valid for writes of `NoiseDimensions::len` values
+pub unsafe fn get_1d_scaled_noise_f64(
+    noise_type: &NoiseType,
+    min: f64,
+    max: f64,
+    noise: *mut f64,
+) {
+    dispatch!(S => get_scaled_noise_f64::<S>(noise_type, min, max, noise, get_1d_noise_f64))
+}
+
+/// Like `get_2d_scaled_noise`, in double precision. The noise is scaled onto `min..max`, the
+/// single precision `min` and `max` of the dimensions are ignored.
+///
+/// # Safety
+/// `noise` must be valid for writes of `NoiseDimensions::len` values
+pub unsafe fn get_2d_scaled_noise_f64(
+    noise_type: &NoiseType,
+    min: f64,
+    max: f64,
+    noise: *mut f64,
+) {
+    dispatch!(S => get_scaled_noise_f64::<S>(noise_type, min, max, noise, get_2d_noise_f64))
+}
+
+/// Like `get_3d_scaled_noise`, in double precision. The noise is scaled onto `min..max`, the
+/// single precision `min` and `max` of the dimensions are ignored.
+///
+/// # Safety
+/// `noise` must be valid for writes of `NoiseDimensions::len` values
+pub unsafe fn get_3d_scaled_noise_f64(
+    noise_type: &NoiseType,
+    min: f64,
+    max: f64,
+    noise: *mut f64,
+) {
+    dispatch!(S => get_scaled_noise_f64::<S>(noise_type, min, max, noise, get_3d_noise_f64))
+}
+
+/// Like `get_4d_scaled_noise`, in double precision. The noise is scaled onto `min..max`, the
+/// single precision `min` and `max` of the dimensions are ignored.
+///
+/// # Safety
+/// `noise` must be valid for writes of `NoiseDimensions::len` values
+pub unsafe fn get_4d_scaled_noise_f64(
+    noise_type: &NoiseType,
+    min: f64,
+    max: f64,
+    noise: *mut f64,
+) -> Result<(), NoiseError> {
+    let observed = get_4d_noise_f64(noise_type, noise)?;
+    dispatch!(S => scale_block_f64::<S>(noise_type, min, max, noise, observed));
+    Ok(())
+}
+
+/// Like `get_1d_noise` to `get_4d_noise`, also writes the partial derivatives of every value
+/// along every axis, see `Settings::generate_with_derivatives_into`
+///
+/// # Safety
+/// `noise` must be valid for writes of `NoiseDimensions::len` values and `derivatives` for
+/// that many times the dimension count. Fails with `NoiseError::DerivativesUnsupported` unless
+/// `noise_type` wraps gradient or fbm settings, and like the other generators if its lattice has
+/// no noise in its dimension count.
+pub unsafe fn get_noise_deriv(
+    noise_type: &NoiseType,
+    noise: *mut f32,
+    derivatives: *mut f32,
+    layout: ChannelLayout,
+) -> Result<(f32, f32), NoiseError> {
+    dispatch!(S => noise_helpers_32::get_noise_deriv::<S>(noise_type, noise, derivatives, layout))
+}
+
+/// Generates the 2d vector field `settings` describe, see `CurlSettings`
+///
+/// # Safety
+/// `noise` must be valid for writes of twice `NoiseDimensions::len` values
+pub unsafe fn get_2d_curl(settings: &CurlSettings, noise: *mut f32) {
+    dispatch!(S => noise_helpers_32::get_2d_curl::<S>(settings, noise))
+}
+
+/// Generates the 3d vector field `settings` describe, see `CurlSettings`
+///
+/// # Safety
+/// `noise` must be valid for writes of three times `NoiseDimensions::len` values
+pub unsafe fn get_3d_curl(settings: &CurlSettings, noise: *mut f32) {
+    dispatch!(S => noise_helpers_32::get_3d_curl::<S>(settings, noise))
+}
+
+/// Every coordinate slice has to hold exactly as many points as the output
+fn check_points(coords: &[usize], out: usize) -> Result<(), NoiseError> {
+    match coords.iter().find(|&&len| len != out) {
+        Some(&actual) => Err(NoiseError::PointCountMismatch {
+            expected: out,
+            actual,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Samples 1d noise at every point `xs[i]` into `out[i]`
+///
+/// The coordinates are multiplied by the frequencies of `settings` just like the block
+/// generators do, the offset and size of its dimensions are ignored.
+pub fn sample_points_1d<T: Settings + Copy>(
+    settings: &T,
+    xs: &[f32],
+    out: &mut [f32],
+) -> Result<(), NoiseError> {
+    let noise_type = settings.wrap()?;
+    noise_type.validate_dim(1)?;
+    check_points(&[xs.len()], out.len())?;
+    let (ptr, len) = (out.as_mut_ptr(), out.len());
+    dispatch!(S => noise_helpers_32::sample_points_1d::<S>(&noise_type, xs, ptr, len));
+    Ok(())
+}
+
+/// Samples 2d noise at every point `(xs[i], ys[i])` into `out[i]`
+///
+/// The coordinates are multiplied by the frequencies of `settings` just like the block
+/// generators do, the offset and size of its dimensions are ignored.
+pub fn sample_points_2d<T: Settings + Copy>(
+    settings: &T,
+    xs: &[f32],
+    ys: &[f32],
+    out: &mut [f32],
+) -> Result<(), NoiseError> {
+    let noise_type = settings.wrap()?;
+    noise_type.validate_dim(2)?;
+    check_points(&[xs.len(), ys.len()], out.len())?;
+    let (ptr, len) = (out.as_mut_ptr(), out.len());
+    dispatch!(S => noise_helpers_32::sample_points_2d::<S>(&noise_type, xs, ys, ptr, len));
+    Ok(())
+}
+
+/// Samples 3d noise at every point `(xs[i], ys[i], zs[i])` into `out[i]`
+///
+/// The coordinates are multiplied by the frequencies of `settings` just like the block
+/// generators do, the offset and size of its dimensions are ignored.
+pub fn sample_points_3d<T: Settings + Copy>(
+    settings: &T,
+    xs: &[f32],
+    ys: &[f32],
+    zs: &[f32],
+    out: &mut [f32],
+) -> Result<(), NoiseError> {
+    let noise_type = settings.wrap()?;
+    noise_type.validate_dim(3)?;
+    check_points(&[xs.len(), ys.len(), zs.len()], out.len())?;
+    let (ptr, len) = (out.as_mut_ptr(), out.len());
+    dispatch!(S => noise_helpers_32::sample_points_3d::<S>(&noise_type, xs, ys, zs, ptr, len));
+    Ok(())
+}
+
+/// Samples 4d noise at every point `(xs[i], ys[i], zs[i], ws[i])` into `out[i]`
+///
+/// The coordinates are multiplied by the frequencies of `settings` just like the block
+/// generators do, the offset and size of its dimensions are ignored.
+pub fn sample_points_4d<T: Settings + Copy>(
+    settings: &T,
+    xs: &[f32],
+    ys: &[f32],
+    zs: &[f32],
+    ws: &[f32],
+    out: &mut [f32],
+) -> Result<(), NoiseError> {
+    let noise_type = settings.wrap()?;
+    noise_type.validate_dim(4)?;
+    check_points(&[xs.len(), ys.len(), zs.len(), ws.len()], out.len())?;
+    let (ptr, len) = (out.as_mut_ptr(), out.len());
+    dispatch!(S => noise_helpers_32::sample_points_4d::<S>(&noise_type, xs, ys, zs, ws, ptr, len))
+}
+
+/// Like `sample_points_1d`, with `f64` coordinates and output
+pub fn sample_points_1d_f64<T: Settings + Copy>(
+    settings: &T,
+    xs: &[f64],
+    out: &mut [f64],
+) -> Result<(), NoiseError> {
+    let noise_type = settings.wrap()?;
+    noise_type.validate_f64()?;
+    noise_type.validate_dim(1)?;
+    check_points(&[xs.len()], out.len())?;
+    let (ptr, len) = (out.as_mut_ptr(), out.len());
+    dispatch!(S => noise_helpers_64::sample_points_1d::<S>(&noise_type, xs, ptr, len));
+    Ok(())
+}
+
+/// Like `sample_points_2d`, with `f64` coordinates and output
+pub fn sample_points_2d_f64<T: Settings + Copy>(
+    settings: &T,
+    xs: &[f64],
+    ys: &[f64],
+    out: &mut [f64],
+) -> Result<(), NoiseError> {
+    let noise_type = settings.wrap()?;
+    noise_type.validate_f64()?;
+    noise_type.validate_dim(2)?;
+    check_points(&[xs.len(), ys.len()], out.len())?;
+    let (ptr, len) = (out.as_mut_ptr(), out.len());
+    dispatch!(S => noise_helpers_64::sample_points_2d::<S>(&noise_type, xs, ys, ptr, len));
+    Ok(())
+}
+
+/// Like `sample_points_3d`, with `f64` coordinates and output
+pub fn sample_points_3d_f64<T: Settings + Copy>(
+    settings: &T,
+    xs: &[f64],
+    ys: &[f64],
+    zs: &[f64],
+    out: &mut [f64],
+) -> Result<(), NoiseError> {
+    let noise_type = settings.wrap()?;
+    noise_type.validate_f64()?;
+    noise_type.validate_dim(3)?;
+    check_points(&[xs.len(), ys.len(), zs.len()], out.len())?;
+    let (ptr, len) = (out.as_mut_ptr(), out.len());
+    dispatch!(S => noise_helpers_64::sample_points_3d::<S>(&noise_type, xs, ys, zs, ptr, len));
+    Ok(())
+}
+
+/// Like `sample_points_4d`, with `f64` coordinates and output
+pub fn sample_points_4d_f64<T: Settings + Copy>(
+    settings: &T,
+    xs: &[f64],
+    ys: &[f64],
+    zs: &[f64],
+    ws: &[f64],
+    out: &mut [f64],
+) -> Result<(), NoiseError> {
+    let noise_type = settings.wrap()?;
+    noise_type.validate_f64()?;
+    noise_type.validate_dim(4)?;
+    check_points(&[xs.len(), ys.len(), zs.len(), ws.len()], out.len())?;
+    let (ptr, len) = (out.as_mut_ptr(), out.len());
+    dispatch!(S => noise_helpers_64::sample_points_4d::<S>(&noise_type, xs, ys, zs, ws, ptr, len))
 }
 
 mod settings;
-use crate::shared::get_scaled_noise;
-pub use settings::{FbmSettings, GradientSettings, Settings, SimplexSettings};
+use crate::shared::{get_scaled_noise, get_scaled_noise_f64, scale_block, scale_block_f64};
+pub use settings::{
+    BillowSettings, CellDistanceFunction, CellReturnType, CellularSettings, ChannelLayout,
+    CurlSettings, DomainWarpSettings, FbmSettings, GradientSettings, LatticeSettings,
+    PeriodicSettings, RidgeSettings, Settings, Settings4d, SimplexSettings, TurbulenceSettings,
+    WarpMode, WarpSource, WhiteSettings,
+};
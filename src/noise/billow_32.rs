@@ -0,0 +1,184 @@
+use crate::noise::lattice::Lattice4d;
+use crate::noise::octaves::OctaveOptions;
+
+use simdeez::prelude::*;
+
+/// Folds `n` at zero and stretches it back over [-1, 1], every octave billows up from -1
+#[inline(always)]
+fn fold<S: Simd>(n: S::Vf32) -> S::Vf32 {
+    n.abs() * S::Vf32::set1(2.0) - S::Vf32::set1(1.0)
+}
+
+#[inline(always)]
+pub fn billow_1d<S: Simd>(
+    mut x: S::Vf32,
+    lacunarity: S::Vf32,
+    gain: S::Vf32,
+    octaves: u8,
+    seed: i32,
+    options: OctaveOptions,
+) -> S::Vf32 {
+    let mut amp = S::Vf32::set1(1.0);
+    let mut result = fold::<S>(options.lattice.noise_1d_32::<S>(x, seed));
+    let next = |v: S::Vf32, axis| options.offset_32::<S>(v * lacunarity, axis);
+
+    for octave in 1..octaves {
+        x = next(x, 0);
+        amp = amp * gain;
+        let n = options.lattice.noise_1d_32::<S>(x, options.seed_32(seed, octave));
+        result = (fold::<S>(n) * amp) + result;
+    }
+
+    if options.fraction > 0.0 {
+        let fraction = S::Vf32::set1(options.fraction);
+        x = next(x, 0);
+        amp = amp * gain * fraction;
+        let n = options.lattice.noise_1d_32::<S>(x, options.seed_32(seed, octaves));
+        result = (fold::<S>(n) * amp) + result;
+    }
+
+    result
+}
+
+#[inline(always)]
+pub fn billow_2d<S: Simd>(
+    mut x: S::Vf32,
+    mut y: S::Vf32,
+    lac: S::Vf32,
+    gain: S::Vf32,
+    octaves: u8,
+    seed: i32,
+    options: OctaveOptions,
+) -> S::Vf32 {
+    let mut result = fold::<S>(options.lattice.noise_2d_32::<S>(x, y, seed));
+    let mut amp = S::Vf32::set1(1.0);
+    let next = |v: S::Vf32, axis| options.offset_32::<S>(v * lac, axis);
+
+    for octave in 1..octaves {
+        x = next(x, 0);
+        y = next(y, 1);
+        amp = amp * gain;
+        let n = options.lattice.noise_2d_32::<S>(x, y, options.seed_32(seed, octave));
+        result = (fold::<S>(n) * amp) + result;
+    }
+
+    if options.fraction > 0.0 {
+        let fraction = S::Vf32::set1(options.fraction);
+        x = next(x, 0);
+        y = next(y, 1);
+        amp = amp * gain * fraction;
+        let n = options.lattice.noise_2d_32::<S>(x, y, options.seed_32(seed, octaves));
+        result = (fold::<S>(n) * amp) + result;
+    }
+
+    result
+}
+
+#[inline(always)]
+pub fn billow_3d<S: Simd>(
+    mut x: S::Vf32,
+    mut y: S::Vf32,
+    mut z: S::Vf32,
+    lac: S::Vf32,
+    gain: S::Vf32,
+    octaves: u8,
+    seed: i32,
+    options: OctaveOptions,
+) -> S::Vf32 {
+    let mut result = fold::<S>(options.lattice.noise_3d_32::<S>(x, y, z, seed));
+    let mut amp = S::Vf32::set1(1.0);
+    let next = |v: S::Vf32, axis| options.offset_32::<S>(v * lac, axis);
+
+    for octave in 1..octaves {
+        x = next(x, 0);
+        y = next(y, 1);
+        z = next(z, 2);
+        amp = amp * gain;
+        let n = options.lattice.noise_3d_32::<S>(x, y, z, options.seed_32(seed, octave));
+        result = (fold::<S>(n) * amp) + result;
+    }
+
+    if options.fraction > 0.0 {
+        let fraction = S::Vf32::set1(options.fraction);
+        x = next(x, 0);
+        y = next(y, 1);
+        z = next(z, 2);
+        amp = amp * gain * fraction;
+        let n = options.lattice.noise_3d_32::<S>(x, y, z, options.seed_32(seed, octaves));
+        result = (fold::<S>(n) * amp) + result;
+    }
+
+    result
+}
+
+#[inline(always)]
+pub fn billow_4d<S: Simd>(
+    mut x: S::Vf32,
+    mut y: S::Vf32,
+    mut z: S::Vf32,
+    mut w: S::Vf32,
+    lac: S::Vf32,
+    gain: S::Vf32,
+    octaves: u8,
+    seed: i32,
+    options: OctaveOptions<Lattice4d>,
+) -> S::Vf32 {
+    let mut result = fold::<S>(options.lattice.noise_4d_32::<S>(x, y, z, w, seed));
+    let mut amp = S::Vf32::set1(1.0);
+    let next = |v: S::Vf32, axis| options.offset_32::<S>(v * lac, axis);
+
+    for octave in 1..octaves {
+        x = next(x, 0);
+        y = next(y, 1);
+        z = next(z, 2);
+        w = next(w, 3);
+        amp = amp * gain;
+        let n = options.lattice.noise_4d_32::<S>(x, y, z, w, options.seed_32(seed, octave));
+        result = (fold::<S>(n) * amp) + result;
+    }
+
+    if options.fraction > 0.0 {
+        let fraction = S::Vf32::set1(options.fraction);
+        x = next(x, 0);
+        y = next(y, 1);
+        z = next(z, 2);
+        w = next(w, 3);
+        amp = amp * gain * fraction;
+        let n = options.lattice.noise_4d_32::<S>(x, y, z, w, options.seed_32(seed, octaves));
+        result = (fold::<S>(n) * amp) + result;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simdeez::scalar::{F32x1, Scalar};
+
+    #[test]
+    fn test_noise_billow32_2d_range() {
+        const OCTAVES: u8 = 3;
+        let mut min = f32::INFINITY;
+        let mut max = -f32::INFINITY;
+        for y in 0..100 {
+            for x in 0..100 {
+                let n = billow_2d::<Scalar>(
+                    F32x1(x as f32 / 10.0),
+                    F32x1(y as f32 / 10.0),
+                    F32x1(2.0),
+                    F32x1(0.5),
+                    OCTAVES,
+                    1337,
+                    OctaveOptions::default(),
+                )
+                .0;
+                min = min.min(n);
+                max = max.max(n);
+            }
+        }
+        // Every octave folds into [-amp, amp], 1 + 0.5 + 0.25
+        assert!(min >= -1.75 && min < 0.0, "min out of range {}", min);
+        assert!(max <= 1.75 && max > 0.0, "max out of range: {}", max);
+    }
+}
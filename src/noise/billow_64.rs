@@ -0,0 +1,152 @@
+use crate::noise::lattice::Lattice4d;
+use crate::noise::octaves::OctaveOptions;
+
+use simdeez::prelude::*;
+
+/// Folds `n` at zero and stretches it back over [-1, 1], every octave billows up from -1
+#[inline(always)]
+fn fold<S: Simd>(n: S::Vf64) -> S::Vf64 {
+    n.abs() * S::Vf64::set1(2.0) - S::Vf64::set1(1.0)
+}
+
+#[inline(always)]
+pub fn billow_1d<S: Simd>(
+    mut x: S::Vf64,
+    lacunarity: S::Vf64,
+    gain: S::Vf64,
+    octaves: u8,
+    seed: i64,
+    options: OctaveOptions,
+) -> S::Vf64 {
+    let mut amp = S::Vf64::set1(1.0);
+    let mut result = fold::<S>(options.lattice.noise_1d_64::<S>(x, seed));
+    let next = |v: S::Vf64, axis| options.offset_64::<S>(v * lacunarity, axis);
+
+    for octave in 1..octaves {
+        x = next(x, 0);
+        amp = amp * gain;
+        let n = options.lattice.noise_1d_64::<S>(x, options.seed_64(seed, octave));
+        result = (fold::<S>(n) * amp) + result;
+    }
+
+    if options.fraction > 0.0 {
+        let fraction = S::Vf64::set1(options.fraction.into());
+        x = next(x, 0);
+        amp = amp * gain * fraction;
+        let n = options.lattice.noise_1d_64::<S>(x, options.seed_64(seed, octaves));
+        result = (fold::<S>(n) * amp) + result;
+    }
+
+    result
+}
+
+#[inline(always)]
+pub fn billow_2d<S: Simd>(
+    mut x: S::Vf64,
+    mut y: S::Vf64,
+    lac: S::Vf64,
+    gain: S::Vf64,
+    octaves: u8,
+    seed: i64,
+    options: OctaveOptions,
+) -> S::Vf64 {
+    let mut result = fold::<S>(options.lattice.noise_2d_64::<S>(x, y, seed));
+    let mut amp = S::Vf64::set1(1.0);
+    let next = |v: S::Vf64, axis| options.offset_64::<S>(v * lac, axis);
+
+    for octave in 1..octaves {
+        x = next(x, 0);
+        y = next(y, 1);
+        amp = amp * gain;
+        let n = options.lattice.noise_2d_64::<S>(x, y, options.seed_64(seed, octave));
+        result = (fold::<S>(n) * amp) + result;
+    }
+
+    if options.fraction > 0.0 {
+        let fraction = S::Vf64::set1(options.fraction.into());
+        x = next(x, 0);
+        y = next(y, 1);
+        amp = amp * gain * fraction;
+        let n = options.lattice.noise_2d_64::<S>(x, y, options.seed_64(seed, octaves));
+        result = (fold::<S>(n) * amp) + result;
+    }
+
+    result
+}
+
+#[inline(always)]
+pub fn billow_3d<S: Simd>(
+    mut x: S::Vf64,
+    mut y: S::Vf64,
+    mut z: S::Vf64,
+    lac: S::Vf64,
+    gain: S::Vf64,
+    octaves: u8,
+    seed: i64,
+    options: OctaveOptions,
+) -> S::Vf64 {
+    let mut result = fold::<S>(options.lattice.noise_3d_64::<S>(x, y, z, seed));
+    let mut amp = S::Vf64::set1(1.0);
+    let next = |v: S::Vf64, axis| options.offset_64::<S>(v * lac, axis);
+
+    for octave in 1..octaves {
+        x = next(x, 0);
+        y = next(y, 1);
+        z = next(z, 2);
+        amp = amp * gain;
+        let n = options.lattice.noise_3d_64::<S>(x, y, z, options.seed_64(seed, octave));
+        result = (fold::<S>(n) * amp) + result;
+    }
+
+    if options.fraction > 0.0 {
+        let fraction = S::Vf64::set1(options.fraction.into());
+        x = next(x, 0);
+        y = next(y, 1);
+        z = next(z, 2);
+        amp = amp * gain * fraction;
+        let n = options.lattice.noise_3d_64::<S>(x, y, z, options.seed_64(seed, octaves));
+        result = (fold::<S>(n) * amp) + result;
+    }
+
+    result
+}
+
+#[inline(always)]
+pub fn billow_4d<S: Simd>(
+    mut x: S::Vf64,
+    mut y: S::Vf64,
+    mut z: S::Vf64,
+    mut w: S::Vf64,
+    lac: S::Vf64,
+    gain: S::Vf64,
+    octaves: u8,
+    seed: i64,
+    options: OctaveOptions<Lattice4d>,
+) -> S::Vf64 {
+    let mut result = fold::<S>(options.lattice.noise_4d_64::<S>(x, y, z, w, seed));
+    let mut amp = S::Vf64::set1(1.0);
+    let next = |v: S::Vf64, axis| options.offset_64::<S>(v * lac, axis);
+
+    for octave in 1..octaves {
+        x = next(x, 0);
+        y = next(y, 1);
+        z = next(z, 2);
+        w = next(w, 3);
+        amp = amp * gain;
+        let n = options.lattice.noise_4d_64::<S>(x, y, z, w, options.seed_64(seed, octave));
+        result = (fold::<S>(n) * amp) + result;
+    }
+
+    if options.fraction > 0.0 {
+        let fraction = S::Vf64::set1(options.fraction.into());
+        x = next(x, 0);
+        y = next(y, 1);
+        z = next(z, 2);
+        w = next(w, 3);
+        amp = amp * gain * fraction;
+        let n = options.lattice.noise_4d_64::<S>(x, y, z, w, options.seed_64(seed, octaves));
+        result = (fold::<S>(n) * amp) + result;
+    }
+
+    result
+}
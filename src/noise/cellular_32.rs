@@ -0,0 +1,405 @@
+//! Cellular (Worley) noise
+//!
+//! Every lattice cell holds one feature point, offset from the cell centre by a hashed amount
+//! scaled by `jitter`. Samples look at the neighbouring cells and report distances to the closest
+//! feature points, or a random value belonging to the closest one.
+
+use crate::noise::simplex_32::{X_PRIME_32, Y_PRIME_32, Z_PRIME_32};
+use crate::{CellDistanceFunction, CellReturnType};
+
+use simdeez::prelude::*;
+
+/// Hashes pre-multiplied lattice coordinates, see `hash3d_32::hash3d` for the origin of the
+/// constants.
+#[inline(always)]
+pub(crate) fn hash<S: Simd>(seed: i32, i: S::Vi32, j: S::Vi32, k: S::Vi32) -> S::Vi32 {
+    let mut hash = i ^ S::Vi32::set1(seed);
+    hash = j ^ hash;
+    hash = k ^ hash;
+    hash = ((hash * hash) * S::Vi32::set1(60493)) * hash;
+    (hash >> 13) ^ hash
+}
+
+/// Picks 10 bits out of `hash` starting at `shift` and maps them onto [-0.5, 0.5]
+#[inline(always)]
+fn hash_offset<S: Simd>(hash: S::Vi32, shift: i32) -> S::Vf32 {
+    ((hash >> shift) & S::Vi32::set1(0x3ff)).cast_f32() * S::Vf32::set1(1.0 / 1023.0)
+        - S::Vf32::set1(0.5)
+}
+
+/// Random value in [-1, 1] identifying the cell the hash belongs to
+#[inline(always)]
+pub(crate) fn hash_value<S: Simd>(hash: S::Vi32) -> S::Vf32 {
+    let hash = hash * S::Vi32::set1(0x27d4eb2d);
+    ((hash >> 8) & S::Vi32::set1(0xffff)).cast_f32() * S::Vf32::set1(2.0 / 65535.0)
+        - S::Vf32::set1(1.0)
+}
+
+/// Distance measure used while searching, Euclidean distances are kept squared until the end
+#[inline(always)]
+fn distance_2d<S: Simd>(distance: CellDistanceFunction, dx: S::Vf32, dy: S::Vf32) -> S::Vf32 {
+    match distance {
+        CellDistanceFunction::Euclidean => dx * dx + dy * dy,
+        CellDistanceFunction::Manhattan => dx.abs() + dy.abs(),
+        CellDistanceFunction::Chebyshev => dx.abs().max(dy.abs()),
+        CellDistanceFunction::Natural => (dx.abs() + dy.abs()) + (dx * dx + dy * dy),
+    }
+}
+
+#[inline(always)]
+fn distance_3d<S: Simd>(
+    distance: CellDistanceFunction,
+    dx: S::Vf32,
+    dy: S::Vf32,
+    dz: S::Vf32,
+) -> S::Vf32 {
+    match distance {
+        CellDistanceFunction::Euclidean => dx * dx + dy * dy + dz * dz,
+        CellDistanceFunction::Manhattan => dx.abs() + dy.abs() + dz.abs(),
+        CellDistanceFunction::Chebyshev => dx.abs().max(dy.abs()).max(dz.abs()),
+        CellDistanceFunction::Natural => {
+            (dx.abs() + dy.abs() + dz.abs()) + (dx * dx + dy * dy + dz * dz)
+        }
+    }
+}
+
+/// Converts a search distance back into a real one
+#[inline(always)]
+fn finish_distance<S: Simd>(distance: CellDistanceFunction, d: S::Vf32) -> S::Vf32 {
+    match distance {
+        CellDistanceFunction::Euclidean => d.sqrt(),
+        _ => d,
+    }
+}
+
+/// Distance from the sample to the bisector between the closest feature point `r1` and the
+/// feature point `r`, both relative to the sample. Lanes where `r` is `r1` itself are pushed to
+/// infinity so they never win the minimum.
+#[inline(always)]
+fn edge_distance<S: Simd>(r1: &[S::Vf32], r: &[S::Vf32]) -> S::Vf32 {
+    let mut mid_dot_dir = S::Vf32::zeroes();
+    let mut len2 = S::Vf32::zeroes();
+    for (&a, &b) in r.iter().zip(r1) {
+        let dir = a - b;
+        mid_dot_dir += (a + b) * S::Vf32::set1(0.5) * dir;
+        len2 += dir * dir;
+    }
+    let same_point = len2.cmp_lt(S::Vf32::set1(1e-12));
+    let safe_len2 = same_point.blendv(len2, S::Vf32::set1(1.0));
+    same_point.blendv(mid_dot_dir / safe_len2.sqrt(), S::Vf32::set1(f32::MAX))
+}
+
+/// Samples 2-dimensional cellular noise
+///
+/// `jitter` scales how far feature points may stray from their cell centre, values above 1.0
+/// can push points beyond the searched neighbourhood and produce discontinuities.
+/// `DistanceToEdge` always measures Euclidean distance to the nearest cell border.
+#[inline(always)]
+pub fn cellular_2d<S: Simd>(
+    x: S::Vf32,
+    y: S::Vf32,
+    distance: CellDistanceFunction,
+    return_type: CellReturnType,
+    jitter: S::Vf32,
+    seed: i32,
+) -> S::Vf32 {
+    let xf = x.floor();
+    let yf = y.floor();
+    let xi = xf.cast_i32();
+    let yi = yf.cast_i32();
+
+    let mut f1 = S::Vf32::set1(f32::MAX);
+    let mut f2 = S::Vf32::set1(f32::MAX);
+    let mut value = S::Vf32::zeroes();
+    let mut r1 = [S::Vf32::zeroes(); 2];
+
+    for xo in -1..=1 {
+        let i = (xi + S::Vi32::set1(xo)) * S::Vi32::set1(X_PRIME_32);
+        let cx = xf + S::Vf32::set1(xo as f32 + 0.5) - x;
+        for yo in -1..=1 {
+            let j = (yi + S::Vi32::set1(yo)) * S::Vi32::set1(Y_PRIME_32);
+            let cy = yf + S::Vf32::set1(yo as f32 + 0.5) - y;
+
+            let h = hash::<S>(seed, i, j, S::Vi32::zeroes());
+            let dx = cx + hash_offset::<S>(h, 0) * jitter;
+            let dy = cy + hash_offset::<S>(h, 10) * jitter;
+            let d = distance_2d::<S>(distance, dx, dy);
+
+            let closer = d.cmp_lt(f1);
+            f2 = f2.min(f1.max(d));
+            f1 = f1.min(d);
+            value = closer.blendv(value, hash_value::<S>(h));
+            r1[0] = closer.blendv(r1[0], dx);
+            r1[1] = closer.blendv(r1[1], dy);
+        }
+    }
+
+    match return_type {
+        CellReturnType::F1 => finish_distance::<S>(distance, f1),
+        CellReturnType::F2 => finish_distance::<S>(distance, f2),
+        CellReturnType::F2MinusF1 => {
+            finish_distance::<S>(distance, f2) - finish_distance::<S>(distance, f1)
+        }
+        CellReturnType::CellValue => value,
+        CellReturnType::DistanceToEdge => {
+            let mut edge = S::Vf32::set1(f32::MAX);
+            for xo in -1..=1 {
+                let i = (xi + S::Vi32::set1(xo)) * S::Vi32::set1(X_PRIME_32);
+                let cx = xf + S::Vf32::set1(xo as f32 + 0.5) - x;
+                for yo in -1..=1 {
+                    let j = (yi + S::Vi32::set1(yo)) * S::Vi32::set1(Y_PRIME_32);
+                    let cy = yf + S::Vf32::set1(yo as f32 + 0.5) - y;
+
+                    let h = hash::<S>(seed, i, j, S::Vi32::zeroes());
+                    let dx = cx + hash_offset::<S>(h, 0) * jitter;
+                    let dy = cy + hash_offset::<S>(h, 10) * jitter;
+                    edge = edge.min(edge_distance::<S>(&r1, &[dx, dy]));
+                }
+            }
+            edge
+        }
+    }
+}
+
+/// Samples 3-dimensional cellular noise
+///
+/// See `cellular_2d` for the meaning of the parameters.
+#[inline(always)]
+pub fn cellular_3d<S: Simd>(
+    x: S::Vf32,
+    y: S::Vf32,
+    z: S::Vf32,
+    distance: CellDistanceFunction,
+    return_type: CellReturnType,
+    jitter: S::Vf32,
+    seed: i32,
+) -> S::Vf32 {
+    let xf = x.floor();
+    let yf = y.floor();
+    let zf = z.floor();
+    let xi = xf.cast_i32();
+    let yi = yf.cast_i32();
+    let zi = zf.cast_i32();
+
+    let mut f1 = S::Vf32::set1(f32::MAX);
+    let mut f2 = S::Vf32::set1(f32::MAX);
+    let mut value = S::Vf32::zeroes();
+    let mut r1 = [S::Vf32::zeroes(); 3];
+
+    for xo in -1..=1 {
+        let i = (xi + S::Vi32::set1(xo)) * S::Vi32::set1(X_PRIME_32);
+        let cx = xf + S::Vf32::set1(xo as f32 + 0.5) - x;
+        for yo in -1..=1 {
+            let j = (yi + S::Vi32::set1(yo)) * S::Vi32::set1(Y_PRIME_32);
+            let cy = yf + S::Vf32::set1(yo as f32 + 0.5) - y;
+            for zo in -1..=1 {
+                let k = (zi + S::Vi32::set1(zo)) * S::Vi32::set1(Z_PRIME_32);
+                let cz = zf + S::Vf32::set1(zo as f32 + 0.5) - z;
+
+                let h = hash::<S>(seed, i, j, k);
+                let dx = cx + hash_offset::<S>(h, 0) * jitter;
+                let dy = cy + hash_offset::<S>(h, 10) * jitter;
+                let dz = cz + hash_offset::<S>(h, 20) * jitter;
+                let d = distance_3d::<S>(distance, dx, dy, dz);
+
+                let closer = d.cmp_lt(f1);
+                f2 = f2.min(f1.max(d));
+                f1 = f1.min(d);
+                value = closer.blendv(value, hash_value::<S>(h));
+                r1[0] = closer.blendv(r1[0], dx);
+                r1[1] = closer.blendv(r1[1], dy);
+                r1[2] = closer.blendv(r1[2], dz);
+            }
+        }
+    }
+
+    match return_type {
+        CellReturnType::F1 => finish_distance::<S>(distance, f1),
+        CellReturnType::F2 => finish_distance::<S>(distance, f2),
+        CellReturnType::F2MinusF1 => {
+            finish_distance::<S>(distance, f2) - finish_distance::<S>(distance, f1)
+        }
+        CellReturnType::CellValue => value,
+        CellReturnType::DistanceToEdge => {
+            let mut edge = S::Vf32::set1(f32::MAX);
+            for xo in -1..=1 {
+                let i = (xi + S::Vi32::set1(xo)) * S::Vi32::set1(X_PRIME_32);
+                let cx = xf + S::Vf32::set1(xo as f32 + 0.5) - x;
+                for yo in -1..=1 {
+                    let j = (yi + S::Vi32::set1(yo)) * S::Vi32::set1(Y_PRIME_32);
+                    let cy = yf + S::Vf32::set1(yo as f32 + 0.5) - y;
+                    for zo in -1..=1 {
+                        let k = (zi + S::Vi32::set1(zo)) * S::Vi32::set1(Z_PRIME_32);
+                        let cz = zf + S::Vf32::set1(zo as f32 + 0.5) - z;
+
+                        let h = hash::<S>(seed, i, j, k);
+                        let dx = cx + hash_offset::<S>(h, 0) * jitter;
+                        let dy = cy + hash_offset::<S>(h, 10) * jitter;
+                        let dz = cz + hash_offset::<S>(h, 20) * jitter;
+                        edge = edge.min(edge_distance::<S>(&r1, &[dx, dy, dz]));
+                    }
+                }
+            }
+            edge
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simdeez::scalar::{F32x1, Scalar};
+
+    fn sample_2d(x: f32, y: f32, return_type: CellReturnType) -> f32 {
+        cellular_2d::<Scalar>(
+            F32x1(x),
+            F32x1(y),
+            CellDistanceFunction::Euclidean,
+            return_type,
+            F32x1(1.0),
+            1337,
+        )
+        .0
+    }
+
+    #[test]
+    fn test_noise_cellular32_2d_ordering() {
+        for y in 0..50 {
+            for x in 0..50 {
+                let (x, y) = (x as f32 / 7.0, y as f32 / 7.0);
+                let f1 = sample_2d(x, y, CellReturnType::F1);
+                let f2 = sample_2d(x, y, CellReturnType::F2);
+                let edge = sample_2d(x, y, CellReturnType::DistanceToEdge);
+                let value = sample_2d(x, y, CellReturnType::CellValue);
+                assert!(f1 >= 0.0 && f1 <= f2, "f1 {} f2 {}", f1, f2);
+                // Bisectors are at least half the gap between F1 and F2 away, and the one shared
+                // with the second closest point is no further than halfway between both points
+                assert!(edge >= (f2 - f1) * 0.5 - 1e-4, "edge {} f1 {} f2 {}", edge, f1, f2);
+                assert!(edge <= (f2 + f1) * 0.5 + 1e-4, "edge {} f1 {} f2 {}", edge, f1, f2);
+                assert!((-1.0..=1.0).contains(&value), "value {}", value);
+            }
+        }
+    }
+
+    /// F1, F2, F2 - F1, the cell value and the edge distance at `p`, found by looking at every
+    /// feature point within `reach` cells of the cell `p` lies in
+    fn brute_force_3d(
+        p: [f32; 3],
+        distance: CellDistanceFunction,
+        jitter: f32,
+        reach: i32,
+    ) -> [f32; 5] {
+        let primes = [X_PRIME_32, Y_PRIME_32, Z_PRIME_32];
+        let cell = p.map(|v| F32x1(v).floor().0);
+        // The feature point of the cell `offset` away, relative to `p`, and its cell value
+        let point = |offset: [i32; 3]| {
+            let lattice: [_; 3] = core::array::from_fn(|axis| {
+                let i = cell[axis] as i32 + offset[axis];
+                <Scalar as Simd>::Vi32::set1(i.wrapping_mul(primes[axis]))
+            });
+            let h = hash::<Scalar>(1337, lattice[0], lattice[1], lattice[2]);
+            let r: [f32; 3] = core::array::from_fn(|axis| {
+                let centre = cell[axis] + (offset[axis] as f32 + 0.5) - p[axis];
+                centre + hash_offset::<Scalar>(h, 10 * axis as i32).0 * jitter
+            });
+            (r, hash_value::<Scalar>(h).0)
+        };
+        let mut offsets = [[0; 3]; 125];
+        let mut count = 0;
+        for xo in -reach..=reach {
+            for yo in -reach..=reach {
+                for zo in -reach..=reach {
+                    offsets[count] = [xo, yo, zo];
+                    count += 1;
+                }
+            }
+        }
+        let offsets = &offsets[..count];
+
+        let (mut f1, mut f2) = (f32::MAX, f32::MAX);
+        let (mut r1, mut value) = ([0.0; 3], 0.0);
+        for &offset in offsets {
+            let (r, v) = point(offset);
+            let [dx, dy, dz] = r.map(F32x1);
+            let d = distance_3d::<Scalar>(distance, dx, dy, dz).0;
+            if d < f1 {
+                (f2, f1, r1, value) = (f1, d, r, v);
+            } else if d < f2 {
+                f2 = d;
+            }
+        }
+
+        let mut edge = f32::MAX;
+        for &offset in offsets {
+            let (r, _) = point(offset);
+            let dir: [f32; 3] = core::array::from_fn(|axis| r[axis] - r1[axis]);
+            let len2 = dir[0] * dir[0] + dir[1] * dir[1] + dir[2] * dir[2];
+            if len2 >= 1e-12 {
+                let mid_dot_dir: f32 = (0..3)
+                    .map(|axis| (r[axis] + r1[axis]) * 0.5 * dir[axis])
+                    .sum();
+                edge = edge.min(mid_dot_dir / F32x1(len2).sqrt().0);
+            }
+        }
+
+        let finish = |d: f32| finish_distance::<Scalar>(distance, F32x1(d)).0;
+        [finish(f1), finish(f2), finish(f2) - finish(f1), value, edge]
+    }
+
+    fn sample_3d(p: [f32; 3], distance: CellDistanceFunction, jitter: f32) -> [f32; 5] {
+        [
+            CellReturnType::F1,
+            CellReturnType::F2,
+            CellReturnType::F2MinusF1,
+            CellReturnType::CellValue,
+            CellReturnType::DistanceToEdge,
+        ]
+        .map(|return_type| {
+            cellular_3d::<Scalar>(
+                F32x1(p[0]),
+                F32x1(p[1]),
+                F32x1(p[2]),
+                distance,
+                return_type,
+                F32x1(jitter),
+                1337,
+            )
+            .0
+        })
+    }
+
+    #[test]
+    fn test_noise_cellular32_3d_matches_brute_force() {
+        let names = ["F1", "F2", "F2 - F1", "CellValue", "DistanceToEdge"];
+        for i in 0..400 {
+            let p = [
+                i as f32 * 0.173 - 20.0,
+                i as f32 * -0.091 + 3.3,
+                (i % 37) as f32 * 0.29 - 5.0,
+            ];
+
+            // The 3x3x3 search looks at the same points as a search over the same cells
+            for distance in [
+                CellDistanceFunction::Euclidean,
+                CellDistanceFunction::Manhattan,
+                CellDistanceFunction::Chebyshev,
+                CellDistanceFunction::Natural,
+            ] {
+                let actual = sample_3d(p, distance, 1.0);
+                let expected = brute_force_3d(p, distance, 1.0, 1);
+                for ((name, a), e) in names.iter().zip(actual).zip(expected) {
+                    assert!((a - e).abs() < 1e-4, "{} at {:?}: {} != {}", name, p, a, e);
+                }
+            }
+
+            // With a jitter of 0.25 the two closest points are at most 1.22 away, every point
+            // two cells out at least 1.375, so searching further finds nothing closer. Edges
+            // are left out, a farther point can still end up with a closer bisector.
+            let actual = sample_3d(p, CellDistanceFunction::Euclidean, 0.25);
+            let expected = brute_force_3d(p, CellDistanceFunction::Euclidean, 0.25, 2);
+            for ((name, a), e) in names.iter().zip(actual).zip(expected).take(4) {
+                assert!((a - e).abs() < 1e-4, "{} at {:?}: {} != {}", name, p, a, e);
+            }
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! Cellular (Worley) noise
+//!
+//! Every lattice cell holds one feature point, offset from the cell centre by a hashed amount
+//! scaled by `jitter`. Samples look at the neighbouring cells and report distances to the closest
+//! feature points, or a random value belonging to the closest one.
+
+use crate::noise::simplex_32::{X_PRIME_64, Y_PRIME_64, Z_PRIME_64};
+use crate::{CellDistanceFunction, CellReturnType};
+
+use simdeez::prelude::*;
+
+/// Hashes pre-multiplied lattice coordinates, see `hash3d_32::hash3d` for the origin of the
+/// constants.
+#[inline(always)]
+pub(crate) fn hash<S: Simd>(seed: i64, i: S::Vi64, j: S::Vi64, k: S::Vi64) -> S::Vi64 {
+    let mut hash = i ^ S::Vi64::set1(seed);
+    hash = j ^ hash;
+    hash = k ^ hash;
+    hash = ((hash * hash) * S::Vi64::set1(60493)) * hash;
+    (hash >> 13) ^ hash
+}
+
+/// Picks 10 bits out of `hash` starting at `shift` and maps them onto [-0.5, 0.5]
+#[inline(always)]
+fn hash_offset<S: Simd>(hash: S::Vi64, shift: i32) -> S::Vf64 {
+    ((hash >> shift) & S::Vi64::set1(0x3ff)).cast_f64() * S::Vf64::set1(1.0 / 1023.0)
+        - S::Vf64::set1(0.5)
+}
+
+/// Random value in [-1, 1] identifying the cell the hash belongs to
+#[inline(always)]
+pub(crate) fn hash_value<S: Simd>(hash: S::Vi64) -> S::Vf64 {
+    let hash = hash * S::Vi64::set1(0x27d4eb2d);
+    ((hash >> 8) & S::Vi64::set1(0xffff)).cast_f64() * S::Vf64::set1(2.0 / 65535.0)
+        - S::Vf64::set1(1.0)
+}
+
+/// Distance measure used while searching, Euclidean distances are kept squared until the end
+#[inline(always)]
+fn distance_2d<S: Simd>(distance: CellDistanceFunction, dx: S::Vf64, dy: S::Vf64) -> S::Vf64 {
+    match distance {
+        CellDistanceFunction::Euclidean => dx * dx + dy * dy,
+        CellDistanceFunction::Manhattan => dx.abs() + dy.abs(),
+        CellDistanceFunction::Chebyshev => dx.abs().max(dy.abs()),
+        CellDistanceFunction::Natural => (dx.abs() + dy.abs()) + (dx * dx + dy * dy),
+    }
+}
+
+#[inline(always)]
+fn distance_3d<S: Simd>(
+    distance: CellDistanceFunction,
+    dx: S::Vf64,
+    dy: S::Vf64,
+    dz: S::Vf64,
+) -> S::Vf64 {
+    match distance {
+        CellDistanceFunction::Euclidean => dx * dx + dy * dy + dz * dz,
+        CellDistanceFunction::Manhattan => dx.abs() + dy.abs() + dz.abs(),
+        CellDistanceFunction::Chebyshev => dx.abs().max(dy.abs()).max(dz.abs()),
+        CellDistanceFunction::Natural => {
+            (dx.abs() + dy.abs() + dz.abs()) + (dx * dx + dy * dy + dz * dz)
+        }
+    }
+}
+
+/// Converts a search distance back into a real one
+#[inline(always)]
+fn finish_distance<S: Simd>(distance: CellDistanceFunction, d: S::Vf64) -> S::Vf64 {
+    match distance {
+        CellDistanceFunction::Euclidean => d.sqrt(),
+        _ => d,
+    }
+}
+
+/// Distance from the sample to the bisector between the closest feature point `r1` and the
+/// feature point `r`, both relative to the sample. Lanes where `r` is `r1` itself are pushed to
+/// infinity so they never win the minimum.
+#[inline(always)]
+fn edge_distance<S: Simd>(r1: &[S::Vf64], r: &[S::Vf64]) -> S::Vf64 {
+    let mut mid_dot_dir = S::Vf64::zeroes();
+    let mut len2 = S::Vf64::zeroes();
+    for (&a, &b) in r.iter().zip(r1) {
+        let dir = a - b;
+        mid_dot_dir += (a + b) * S::Vf64::set1(0.5) * dir;
+        len2 += dir * dir;
+    }
+    let same_point = len2.cmp_lt(S::Vf64::set1(1e-12));
+    let safe_len2 = same_point.blendv(len2, S::Vf64::set1(1.0));
+    same_point.blendv(mid_dot_dir / safe_len2.sqrt(), S::Vf64::set1(f64::MAX))
+}
+
+/// Samples 2-dimensional cellular noise
+///
+/// `jitter` scales how far feature points may stray from their cell centre, values above 1.0
+/// can push points beyond the searched neighbourhood and produce discontinuities.
+/// `DistanceToEdge` always measures Euclidean distance to the nearest cell border.
+#[inline(always)]
+pub fn cellular_2d<S: Simd>(
+    x: S::Vf64,
+    y: S::Vf64,
+    distance: CellDistanceFunction,
+    return_type: CellReturnType,
+    jitter: S::Vf64,
+    seed: i64,
+) -> S::Vf64 {
+    let xf = x.floor();
+    let yf = y.floor();
+    let xi = xf.cast_i64();
+    let yi = yf.cast_i64();
+
+    let mut f1 = S::Vf64::set1(f64::MAX);
+    let mut f2 = S::Vf64::set1(f64::MAX);
+    let mut value = S::Vf64::zeroes();
+    let mut r1 = [S::Vf64::zeroes(); 2];
+
+    for xo in -1..=1 {
+        let i = (xi + S::Vi64::set1(xo)) * S::Vi64::set1(X_PRIME_64);
+        let cx = xf + S::Vf64::set1(xo as f64 + 0.5) - x;
+        for yo in -1..=1 {
+            let j = (yi + S::Vi64::set1(yo)) * S::Vi64::set1(Y_PRIME_64);
+            let cy = yf + S::Vf64::set1(yo as f64 + 0.5) - y;
+
+            let h = hash::<S>(seed, i, j, S::Vi64::zeroes());
+            let dx = cx + hash_offset::<S>(h, 0) * jitter;
+            let dy = cy + hash_offset::<S>(h, 10) * jitter;
+            let d = distance_2d::<S>(distance, dx, dy);
+
+            let closer = d.cmp_lt(f1);
+            f2 = f2.min(f1.max(d));
+            f1 = f1.min(d);
+            value = closer.blendv(value, hash_value::<S>(h));
+            r1[0] = closer.blendv(r1[0], dx);
+            r1[1] = closer.blendv(r1[1], dy);
+        }
+    }
+
+    match return_type {
+        CellReturnType::F1 => finish_distance::<S>(distance, f1),
+        CellReturnType::F2 => finish_distance::<S>(distance, f2),
+        CellReturnType::F2MinusF1 => {
+            finish_distance::<S>(distance, f2) - finish_distance::<S>(distance, f1)
+        }
+        CellReturnType::CellValue => value,
+        CellReturnType::DistanceToEdge => {
+            let mut edge = S::Vf64::set1(f64::MAX);
+            for xo in -1..=1 {
+                let i = (xi + S::Vi64::set1(xo)) * S::Vi64::set1(X_PRIME_64);
+                let cx = xf + S::Vf64::set1(xo as f64 + 0.5) - x;
+                for yo in -1..=1 {
+                    let j = (yi + S::Vi64::set1(yo)) * S::Vi64::set1(Y_PRIME_64);
+                    let cy = yf + S::Vf64::set1(yo as f64 + 0.5) - y;
+
+                    let h = hash::<S>(seed, i, j, S::Vi64::zeroes());
+                    let dx = cx + hash_offset::<S>(h, 0) * jitter;
+                    let dy = cy + hash_offset::<S>(h, 10) * jitter;
+                    edge = edge.min(edge_distance::<S>(&r1, &[dx, dy]));
+                }
+            }
+            edge
+        }
+    }
+}
+
+/// Samples 3-dimensional cellular noise
+///
+/// See `cellular_2d` for the meaning of the parameters.
+#[inline(always)]
+pub fn cellular_3d<S: Simd>(
+    x: S::Vf64,
+    y: S::Vf64,
+    z: S::Vf64,
+    distance: CellDistanceFunction,
+    return_type: CellReturnType,
+    jitter: S::Vf64,
+    seed: i64,
+) -> S::Vf64 {
+    let xf = x.floor();
+    let yf = y.floor();
+    let zf = z.floor();
+    let xi = xf.cast_i64();
+    let yi = yf.cast_i64();
+    let zi = zf.cast_i64();
+
+    let mut f1 = S::Vf64::set1(f64::MAX);
+    let mut f2 = S::Vf64::set1(f64::MAX);
+    let mut value = S::Vf64::zeroes();
+    let mut r1 = [S::Vf64::zeroes(); 3];
+
+    for xo in -1..=1 {
+        let i = (xi + S::Vi64::set1(xo)) * S::Vi64::set1(X_PRIME_64);
+        let cx = xf + S::Vf64::set1(xo as f64 + 0.5) - x;
+        for yo in -1..=1 {
+            let j = (yi + S::Vi64::set1(yo)) * S::Vi64::set1(Y_PRIME_64);
+            let cy = yf + S::Vf64::set1(yo as f64 + 0.5) - y;
+            for zo in -1..=1 {
+                let k = (zi + S::Vi64::set1(zo)) * S::Vi64::set1(Z_PRIME_64);
+                let cz = zf + S::Vf64::set1(zo as f64 + 0.5) - z;
+
+                let h = hash::<S>(seed, i, j, k);
+                let dx = cx + hash_offset::<S>(h, 0) * jitter;
+                let dy = cy + hash_offset::<S>(h, 10) * jitter;
+                let dz = cz + hash_offset::<S>(h, 20) * jitter;
+                let d = distance_3d::<S>(distance, dx, dy, dz);
+
+                let closer = d.cmp_lt(f1);
+                f2 = f2.min(f1.max(d));
+                f1 = f1.min(d);
+                value = closer.blendv(value, hash_value::<S>(h));
+                r1[0] = closer.blendv(r1[0], dx);
+                r1[1] = closer.blendv(r1[1], dy);
+                r1[2] = closer.blendv(r1[2], dz);
+            }
+        }
+    }
+
+    match return_type {
+        CellReturnType::F1 => finish_distance::<S>(distance, f1),
+        CellReturnType::F2 => finish_distance::<S>(distance, f2),
+        CellReturnType::F2MinusF1 => {
+            finish_distance::<S>(distance, f2) - finish_distance::<S>(distance, f1)
+        }
+        CellReturnType::CellValue => value,
+        CellReturnType::DistanceToEdge => {
+            let mut edge = S::Vf64::set1(f64::MAX);
+            for xo in -1..=1 {
+                let i = (xi + S::Vi64::set1(xo)) * S::Vi64::set1(X_PRIME_64);
+                let cx = xf + S::Vf64::set1(xo as f64 + 0.5) - x;
+                for yo in -1..=1 {
+                    let j = (yi + S::Vi64::set1(yo)) * S::Vi64::set1(Y_PRIME_64);
+                    let cy = yf + S::Vf64::set1(yo as f64 + 0.5) - y;
+                    for zo in -1..=1 {
+                        let k = (zi + S::Vi64::set1(zo)) * S::Vi64::set1(Z_PRIME_64);
+                        let cz = zf + S::Vf64::set1(zo as f64 + 0.5) - z;
+
+                        let h = hash::<S>(seed, i, j, k);
+                        let dx = cx + hash_offset::<S>(h, 0) * jitter;
+                        let dy = cy + hash_offset::<S>(h, 10) * jitter;
+                        let dz = cz + hash_offset::<S>(h, 20) * jitter;
+                        edge = edge.min(edge_distance::<S>(&r1, &[dx, dy, dz]));
+                    }
+                }
+            }
+            edge
+        }
+    }
+}
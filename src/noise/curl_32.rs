@@ -0,0 +1,57 @@
+//! Curl noise
+//!
+//! The curl of a potential field has no divergence, which makes it a good incompressible flow
+//! field for particles and smoke. The potentials are fbm layered simplex noise and their
+//! gradients come from the analytic fbm derivatives, so no finite differences are needed.
+//! 2d rotates the gradient of one potential by 90 degrees, 3d takes the curl of three
+//! potentials with unrelated seeds.
+
+use crate::noise::fbm_32::{fbm_2d_deriv, fbm_3d_deriv};
+use crate::noise::octaves::OctaveOptions;
+
+use simdeez::prelude::*;
+
+/// Added to the seed for the second and third potential of the 3d curl
+const POTENTIAL_SEED_STEP: i32 = 0x2c1b_3c6d;
+
+/// Curl of 2d fbm, `(d/dy, -d/dx)`. `x` and `y` are already multiplied by `freq`, which the
+/// chain rule brings back out of the derivatives.
+#[inline(always)]
+pub fn curl_2d<S: Simd>(
+    x: S::Vf32,
+    y: S::Vf32,
+    freq: [S::Vf32; 2],
+    lac: S::Vf32,
+    gain: S::Vf32,
+    octaves: u8,
+    seed: i32,
+    options: OctaveOptions,
+) -> [S::Vf32; 2] {
+    let (_, [dx, dy]) = fbm_2d_deriv::<S>(x, y, lac, gain, octaves, seed, options);
+    [dy * freq[1], S::Vf32::zeroes() - dx * freq[0]]
+}
+
+/// Curl of three 3d fbm potentials. The coordinates are already multiplied by `freq`, which
+/// the chain rule brings back out of the derivatives.
+#[inline(always)]
+pub fn curl_3d<S: Simd>(
+    x: S::Vf32,
+    y: S::Vf32,
+    z: S::Vf32,
+    freq: [S::Vf32; 3],
+    lac: S::Vf32,
+    gain: S::Vf32,
+    octaves: u8,
+    seed: i32,
+    options: OctaveOptions,
+) -> [S::Vf32; 3] {
+    let potential = |i: i32| {
+        let seed = seed.wrapping_add(POTENTIAL_SEED_STEP.wrapping_mul(i));
+        let (_, [dx, dy, dz]) = fbm_3d_deriv::<S>(x, y, z, lac, gain, octaves, seed, options);
+        [dx * freq[0], dy * freq[1], dz * freq[2]]
+    };
+    let [_, a_y, a_z] = potential(0);
+    let [b_x, _, b_z] = potential(1);
+    let [c_x, c_y, _] = potential(2);
+    [c_y - b_z, a_z - c_x, b_x - a_y]
+}
@@ -0,0 +1,50 @@
+//! Domain warping
+//!
+//! Moves the sample coordinates by the output of a warp noise before the inner noise is sampled.
+//! Every displaced axis reads the warp noise at its own fixed offset so the axes move
+//! independently. With more than one octave the warp noise is layered like fbm, either every
+//! octave reading the original coordinates or each one reading the coordinates the previous
+//! octaves already warped.
+
+use crate::WarpMode;
+
+use simdeez::prelude::*;
+
+/// Where the warp noise is read for each displaced axis, far enough apart to be unrelated
+pub(crate) const AXIS_OFFSET: [f32; 4] = [0.0, 131.7, 263.3, 395.1];
+
+/// Warps the `D` coordinates in `p`. `freq` is the frequency of the warp noise, `warp` samples
+/// it without applying any frequency and its first error is passed on.
+#[inline(always)]
+pub fn warp<S: Simd, const D: usize, E>(
+    mut p: [S::Vf32; D],
+    freq: [S::Vf32; D],
+    amplitude: f32,
+    lacunarity: f32,
+    gain: f32,
+    octaves: u8,
+    mode: WarpMode,
+    warp: impl Fn([S::Vf32; D]) -> Result<S::Vf32, E>,
+) -> Result<[S::Vf32; D], E> {
+    let origin = p;
+    let mut freq = freq;
+    let mut amp = S::Vf32::set1(amplitude);
+    for _ in 0..octaves {
+        let base = match mode {
+            WarpMode::Independent => origin,
+            WarpMode::Progressive => p,
+        };
+        let mut shift = [S::Vf32::zeroes(); D];
+        for (axis, shift) in shift.iter_mut().enumerate() {
+            let offset = S::Vf32::set1(AXIS_OFFSET[axis]);
+            let q: [S::Vf32; D] = core::array::from_fn(|i| base[i] * freq[i] + offset);
+            *shift = warp(q)? * amp;
+        }
+        for (v, shift) in p.iter_mut().zip(shift) {
+            *v = *v + shift;
+        }
+        freq = freq.map(|f| f * S::Vf32::set1(lacunarity));
+        amp = amp * S::Vf32::set1(gain);
+    }
+    Ok(p)
+}
@@ -0,0 +1,42 @@
+//! Domain warping in double precision, see `domain_warp_32`
+
+use crate::noise::domain_warp_32::AXIS_OFFSET;
+use crate::WarpMode;
+
+use simdeez::prelude::*;
+
+/// Warps the `D` coordinates in `p`. `freq` is the frequency of the warp noise, `warp` samples
+/// it without applying any frequency and its first error is passed on.
+#[inline(always)]
+pub fn warp<S: Simd, const D: usize, E>(
+    mut p: [S::Vf64; D],
+    freq: [S::Vf64; D],
+    amplitude: f64,
+    lacunarity: f64,
+    gain: f64,
+    octaves: u8,
+    mode: WarpMode,
+    warp: impl Fn([S::Vf64; D]) -> Result<S::Vf64, E>,
+) -> Result<[S::Vf64; D], E> {
+    let origin = p;
+    let mut freq = freq;
+    let mut amp = S::Vf64::set1(amplitude);
+    for _ in 0..octaves {
+        let base = match mode {
+            WarpMode::Independent => origin,
+            WarpMode::Progressive => p,
+        };
+        let mut shift = [S::Vf64::zeroes(); D];
+        for (axis, shift) in shift.iter_mut().enumerate() {
+            let offset = S::Vf64::set1(AXIS_OFFSET[axis].into());
+            let q: [S::Vf64; D] = core::array::from_fn(|i| base[i] * freq[i] + offset);
+            *shift = warp(q)? * amp;
+        }
+        for (v, shift) in p.iter_mut().zip(shift) {
+            *v = *v + shift;
+        }
+        freq = freq.map(|f| f * S::Vf64::set1(lacunarity));
+        amp = amp * S::Vf64::set1(gain);
+    }
+    Ok(p)
+}
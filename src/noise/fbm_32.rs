@@ -1,7 +1,19 @@
-use crate::noise::simplex_32::{simplex_1d, simplex_2d, simplex_3d};
+use crate::noise::lattice::Lattice4d;
+use crate::noise::octaves::OctaveOptions;
+use crate::noise::simplex_32::{simplex_periodic, simplex_periodic_deriv};
 
 use simdeez::prelude::*;
 
+/// Adds the derivative `d` of one octave, weighted by `weight`, to the running sum
+#[inline(always)]
+fn add_deriv<S: Simd, const D: usize>(sum: &mut [S::Vf32; D], d: [S::Vf32; D], weight: S::Vf32) {
+    for (sum, d) in sum.iter_mut().zip(d) {
+        *sum = (d * weight) + *sum;
+    }
+}
+
+/// Unless `options.weighted_1d` is set, 1d fbm adds every octave at full strength and ignores
+/// the gain, as it always has.
 #[inline(always)]
 pub fn fbm_1d<S: Simd>(
     mut x: S::Vf32,
@@ -9,17 +21,113 @@ pub fn fbm_1d<S: Simd>(
     gain: S::Vf32,
     octaves: u8,
     seed: i32,
+    options: OctaveOptions,
 ) -> S::Vf32 {
-    let mut amp = S::Vf32::set1(1.0);
-    let mut result = simplex_1d::<S>(x, seed);
+    let one = S::Vf32::set1(1.0);
+    let mut amp = one;
+    let mut total = one;
+    let mut result = options.lattice.noise_1d_32::<S>(x, seed);
+    let next = |v: S::Vf32, axis| options.offset_32::<S>(v * lacunarity, axis);
+
+    for octave in 1..octaves {
+        x = next(x, 0);
+        amp = amp * gain;
+        let weight = if options.weighted_1d { amp } else { one };
+        let n = options
+            .lattice
+            .noise_1d_32::<S>(x, options.seed_32(seed, octave));
+        result = (n * weight) + result;
+        if options.normalize {
+            total = total + weight.abs();
+        }
+    }
+
+    if options.fraction > 0.0 {
+        let fraction = S::Vf32::set1(options.fraction);
+        x = next(x, 0);
+        amp = amp * gain;
+        let weight = if options.weighted_1d {
+            amp * fraction
+        } else {
+            fraction
+        };
+        result = (options
+            .lattice
+            .noise_1d_32::<S>(x, options.seed_32(seed, octaves))
+            * weight)
+            + result;
+        if options.normalize {
+            total = total + weight.abs();
+        }
+    }
+
+    if options.normalize {
+        result / total
+    } else {
+        result
+    }
+}
+
+/// Like `fbm_1d`, but also computes the derivative. The octaves are weighed the same way.
+#[inline(always)]
+pub fn fbm_1d_deriv<S: Simd>(
+    mut x: S::Vf32,
+    lacunarity: S::Vf32,
+    gain: S::Vf32,
+    octaves: u8,
+    seed: i32,
+    options: OctaveOptions,
+) -> (S::Vf32, S::Vf32) {
+    let one = S::Vf32::set1(1.0);
+    let mut amp = one;
+    let mut total = one;
+    let (mut result, first) = options.lattice.noise_1d_deriv_32::<S>(x, seed);
+    let mut deriv = [first];
+    // Frequency of the current octave relative to the first, the chain rule pulls it out of
+    // the octave's derivative
+    let mut chain = one;
+    let next = |v: S::Vf32, axis| options.offset_32::<S>(v * lacunarity, axis);
 
-    for _ in 1..octaves {
-        x = x * lacunarity;
+    for octave in 1..octaves {
+        x = next(x, 0);
         amp = amp * gain;
-        result = result + simplex_1d::<S>(x, seed);
+        chain = chain * lacunarity;
+        let weight = if options.weighted_1d { amp } else { one };
+        let (n, d) = options
+            .lattice
+            .noise_1d_deriv_32::<S>(x, options.seed_32(seed, octave));
+        result = (n * weight) + result;
+        if options.normalize {
+            total = total + weight.abs();
+        }
+        add_deriv::<S, 1>(&mut deriv, [d], weight * chain);
     }
 
-    result
+    if options.fraction > 0.0 {
+        let fraction = S::Vf32::set1(options.fraction);
+        x = next(x, 0);
+        amp = amp * gain;
+        chain = chain * lacunarity;
+        let weight = if options.weighted_1d {
+            amp * fraction
+        } else {
+            fraction
+        };
+        let (n, d) = options
+            .lattice
+            .noise_1d_deriv_32::<S>(x, options.seed_32(seed, octaves));
+        result = (n * weight) + result;
+        if options.normalize {
+            total = total + weight.abs();
+        }
+        add_deriv::<S, 1>(&mut deriv, [d], weight * chain);
+    }
+
+    if options.normalize {
+        (result / total, deriv[0] / total)
+    } else {
+        (result, deriv[0])
+    }
 }
 
 #[inline(always)]
@@ -30,18 +138,104 @@ pub fn fbm_2d<S: Simd>(
     gain: S::Vf32,
     octaves: u8,
     seed: i32,
+    options: OctaveOptions,
 ) -> S::Vf32 {
-    let mut result = simplex_2d::<S>(x, y, seed);
+    let mut result = options.lattice.noise_2d_32::<S>(x, y, seed);
     let mut amp = S::Vf32::set1(1.0);
+    let mut total = amp;
+    let next = |v: S::Vf32, axis| options.offset_32::<S>(v * lac, axis);
 
-    for _ in 1..octaves {
-        x = x * lac;
-        y = y * lac;
+    for octave in 1..octaves {
+        x = next(x, 0);
+        y = next(y, 1);
         amp = amp * gain;
-        result = (simplex_2d::<S>(x, y, seed) * amp) + result;
+        result = (options
+            .lattice
+            .noise_2d_32::<S>(x, y, options.seed_32(seed, octave))
+            * amp)
+            + result;
+        if options.normalize {
+            total = total + amp.abs();
+        }
     }
 
-    result
+    if options.fraction > 0.0 {
+        let fraction = S::Vf32::set1(options.fraction);
+        x = next(x, 0);
+        y = next(y, 1);
+        amp = amp * gain * fraction;
+        result = (options
+            .lattice
+            .noise_2d_32::<S>(x, y, options.seed_32(seed, octaves))
+            * amp)
+            + result;
+        if options.normalize {
+            total = total + amp.abs();
+        }
+    }
+
+    if options.normalize {
+        result / total
+    } else {
+        result
+    }
+}
+
+/// Like `fbm_2d`, but also computes the derivative
+#[inline(always)]
+pub fn fbm_2d_deriv<S: Simd>(
+    mut x: S::Vf32,
+    mut y: S::Vf32,
+    lac: S::Vf32,
+    gain: S::Vf32,
+    octaves: u8,
+    seed: i32,
+    options: OctaveOptions,
+) -> (S::Vf32, [S::Vf32; 2]) {
+    let (mut result, mut deriv) = options.lattice.noise_2d_deriv_32::<S>(x, y, seed);
+    let mut amp = S::Vf32::set1(1.0);
+    let mut total = amp;
+    // Frequency of the current octave relative to the first, the chain rule pulls it out of
+    // the octave's derivative
+    let mut chain = amp;
+    let next = |v: S::Vf32, axis| options.offset_32::<S>(v * lac, axis);
+
+    for octave in 1..octaves {
+        x = next(x, 0);
+        y = next(y, 1);
+        amp = amp * gain;
+        chain = chain * lac;
+        let (n, d) = options
+            .lattice
+            .noise_2d_deriv_32::<S>(x, y, options.seed_32(seed, octave));
+        result = (n * amp) + result;
+        if options.normalize {
+            total = total + amp.abs();
+        }
+        add_deriv::<S, 2>(&mut deriv, d, amp * chain);
+    }
+
+    if options.fraction > 0.0 {
+        let fraction = S::Vf32::set1(options.fraction);
+        x = next(x, 0);
+        y = next(y, 1);
+        amp = amp * gain * fraction;
+        chain = chain * lac;
+        let (n, d) = options
+            .lattice
+            .noise_2d_deriv_32::<S>(x, y, options.seed_32(seed, octaves));
+        result = (n * amp) + result;
+        if options.normalize {
+            total = total + amp.abs();
+        }
+        add_deriv::<S, 2>(&mut deriv, d, amp * chain);
+    }
+
+    if options.normalize {
+        (result / total, deriv.map(|d| d / total))
+    } else {
+        (result, deriv)
+    }
 }
 
 #[inline(always)]
@@ -53,17 +247,337 @@ pub fn fbm_3d<S: Simd>(
     gain: S::Vf32,
     octaves: u8,
     seed: i32,
+    options: OctaveOptions,
+) -> S::Vf32 {
+    let mut result = options.lattice.noise_3d_32::<S>(x, y, z, seed);
+    let mut amp = S::Vf32::set1(1.0);
+    let mut total = amp;
+    let next = |v: S::Vf32, axis| options.offset_32::<S>(v * lac, axis);
+
+    for octave in 1..octaves {
+        x = next(x, 0);
+        y = next(y, 1);
+        z = next(z, 2);
+        amp = amp * gain;
+        result = (options
+            .lattice
+            .noise_3d_32::<S>(x, y, z, options.seed_32(seed, octave))
+            * amp)
+            + result;
+        if options.normalize {
+            total = total + amp.abs();
+        }
+    }
+
+    if options.fraction > 0.0 {
+        let fraction = S::Vf32::set1(options.fraction);
+        x = next(x, 0);
+        y = next(y, 1);
+        z = next(z, 2);
+        amp = amp * gain * fraction;
+        result = (options
+            .lattice
+            .noise_3d_32::<S>(x, y, z, options.seed_32(seed, octaves))
+            * amp)
+            + result;
+        if options.normalize {
+            total = total + amp.abs();
+        }
+    }
+
+    if options.normalize {
+        result / total
+    } else {
+        result
+    }
+}
+
+/// Like `fbm_3d`, but also computes the derivative
+#[inline(always)]
+pub fn fbm_3d_deriv<S: Simd>(
+    mut x: S::Vf32,
+    mut y: S::Vf32,
+    mut z: S::Vf32,
+    lac: S::Vf32,
+    gain: S::Vf32,
+    octaves: u8,
+    seed: i32,
+    options: OctaveOptions,
+) -> (S::Vf32, [S::Vf32; 3]) {
+    let (mut result, mut deriv) = options.lattice.noise_3d_deriv_32::<S>(x, y, z, seed);
+    let mut amp = S::Vf32::set1(1.0);
+    let mut total = amp;
+    // Frequency of the current octave relative to the first, the chain rule pulls it out of
+    // the octave's derivative
+    let mut chain = amp;
+    let next = |v: S::Vf32, axis| options.offset_32::<S>(v * lac, axis);
+
+    for octave in 1..octaves {
+        x = next(x, 0);
+        y = next(y, 1);
+        z = next(z, 2);
+        amp = amp * gain;
+        chain = chain * lac;
+        let (n, d) = options
+            .lattice
+            .noise_3d_deriv_32::<S>(x, y, z, options.seed_32(seed, octave));
+        result = (n * amp) + result;
+        if options.normalize {
+            total = total + amp.abs();
+        }
+        add_deriv::<S, 3>(&mut deriv, d, amp * chain);
+    }
+
+    if options.fraction > 0.0 {
+        let fraction = S::Vf32::set1(options.fraction);
+        x = next(x, 0);
+        y = next(y, 1);
+        z = next(z, 2);
+        amp = amp * gain * fraction;
+        chain = chain * lac;
+        let (n, d) =
+            options
+                .lattice
+                .noise_3d_deriv_32::<S>(x, y, z, options.seed_32(seed, octaves));
+        result = (n * amp) + result;
+        if options.normalize {
+            total = total + amp.abs();
+        }
+        add_deriv::<S, 3>(&mut deriv, d, amp * chain);
+    }
+
+    if options.normalize {
+        (result / total, deriv.map(|d| d / total))
+    } else {
+        (result, deriv)
+    }
+}
+
+#[inline(always)]
+pub fn fbm_4d<S: Simd>(
+    mut x: S::Vf32,
+    mut y: S::Vf32,
+    mut z: S::Vf32,
+    mut w: S::Vf32,
+    lac: S::Vf32,
+    gain: S::Vf32,
+    octaves: u8,
+    seed: i32,
+    options: OctaveOptions<Lattice4d>,
 ) -> S::Vf32 {
-    let mut result = simplex_3d::<S>(x, y, z, seed);
+    let mut result = options.lattice.noise_4d_32::<S>(x, y, z, w, seed);
+    let mut amp = S::Vf32::set1(1.0);
+    let mut total = amp;
+    let next = |v: S::Vf32, axis| options.offset_32::<S>(v * lac, axis);
+
+    for octave in 1..octaves {
+        x = next(x, 0);
+        y = next(y, 1);
+        z = next(z, 2);
+        w = next(w, 3);
+        amp = amp * gain;
+        result = (options
+            .lattice
+            .noise_4d_32::<S>(x, y, z, w, options.seed_32(seed, octave))
+            * amp)
+            + result;
+        if options.normalize {
+            total = total + amp.abs();
+        }
+    }
+
+    if options.fraction > 0.0 {
+        let fraction = S::Vf32::set1(options.fraction);
+        x = next(x, 0);
+        y = next(y, 1);
+        z = next(z, 2);
+        w = next(w, 3);
+        amp = amp * gain * fraction;
+        result = (options
+            .lattice
+            .noise_4d_32::<S>(x, y, z, w, options.seed_32(seed, octaves))
+            * amp)
+            + result;
+        if options.normalize {
+            total = total + amp.abs();
+        }
+    }
+
+    if options.normalize {
+        result / total
+    } else {
+        result
+    }
+}
+
+/// Like `fbm_4d`, but also computes the derivative
+#[inline(always)]
+pub fn fbm_4d_deriv<S: Simd>(
+    mut x: S::Vf32,
+    mut y: S::Vf32,
+    mut z: S::Vf32,
+    mut w: S::Vf32,
+    lac: S::Vf32,
+    gain: S::Vf32,
+    octaves: u8,
+    seed: i32,
+    options: OctaveOptions<Lattice4d>,
+) -> (S::Vf32, [S::Vf32; 4]) {
+    let (mut result, mut deriv) = options.lattice.noise_4d_deriv_32::<S>(x, y, z, w, seed);
     let mut amp = S::Vf32::set1(1.0);
+    let mut total = amp;
+    // Frequency of the current octave relative to the first, the chain rule pulls it out of
+    // the octave's derivative
+    let mut chain = amp;
+    let next = |v: S::Vf32, axis| options.offset_32::<S>(v * lac, axis);
 
-    for _ in 1..octaves {
-        x = x * lac;
-        y = y * lac;
-        z = z * lac;
+    for octave in 1..octaves {
+        x = next(x, 0);
+        y = next(y, 1);
+        z = next(z, 2);
+        w = next(w, 3);
         amp = amp * gain;
-        result = (simplex_3d::<S>(x, y, z, seed) * amp) + result;
+        chain = chain * lac;
+        let (n, d) =
+            options
+                .lattice
+                .noise_4d_deriv_32::<S>(x, y, z, w, options.seed_32(seed, octave));
+        result = (n * amp) + result;
+        if options.normalize {
+            total = total + amp.abs();
+        }
+        add_deriv::<S, 4>(&mut deriv, d, amp * chain);
     }
 
-    result
+    if options.fraction > 0.0 {
+        let fraction = S::Vf32::set1(options.fraction);
+        x = next(x, 0);
+        y = next(y, 1);
+        z = next(z, 2);
+        w = next(w, 3);
+        amp = amp * gain * fraction;
+        chain = chain * lac;
+        let (n, d) =
+            options
+                .lattice
+                .noise_4d_deriv_32::<S>(x, y, z, w, options.seed_32(seed, octaves));
+        result = (n * amp) + result;
+        if options.normalize {
+            total = total + amp.abs();
+        }
+        add_deriv::<S, 4>(&mut deriv, d, amp * chain);
+    }
+
+    if options.normalize {
+        (result / total, deriv.map(|d| d / total))
+    } else {
+        (result, deriv)
+    }
+}
+
+/// Fbm of `simplex_periodic`, repeating every `period` lattice units along each axis. Every
+/// octave scales the period by the lacunarity along with the coordinates, so the period of every
+/// octave has to be a whole multiple of 3.
+#[inline(always)]
+pub fn fbm_periodic<S: Simd, const D: usize>(
+    mut p: [S::Vf32; D],
+    mut period: [S::Vf32; D],
+    lac: S::Vf32,
+    gain: S::Vf32,
+    octaves: u8,
+    seed: i32,
+    options: OctaveOptions,
+) -> S::Vf32 {
+    let mut result = simplex_periodic::<S, D>(p, period, seed);
+    let mut amp = S::Vf32::set1(1.0);
+    let mut total = amp;
+    let next = |p: &mut [S::Vf32; D], period: &mut [S::Vf32; D]| {
+        for (axis, (v, period)) in p.iter_mut().zip(period.iter_mut()).enumerate() {
+            *v = options.offset_32::<S>(*v * lac, axis);
+            *period = *period * lac;
+        }
+    };
+
+    for octave in 1..octaves {
+        next(&mut p, &mut period);
+        amp = amp * gain;
+        result =
+            (simplex_periodic::<S, D>(p, period, options.seed_32(seed, octave)) * amp) + result;
+        if options.normalize {
+            total = total + amp.abs();
+        }
+    }
+
+    if options.fraction > 0.0 {
+        let fraction = S::Vf32::set1(options.fraction);
+        next(&mut p, &mut period);
+        amp = amp * gain * fraction;
+        result =
+            (simplex_periodic::<S, D>(p, period, options.seed_32(seed, octaves)) * amp) + result;
+        if options.normalize {
+            total = total + amp.abs();
+        }
+    }
+
+    if options.normalize {
+        result / total
+    } else {
+        result
+    }
+}
+
+/// Like `fbm_periodic`, but also computes the derivative
+#[inline(always)]
+pub fn fbm_periodic_deriv<S: Simd, const D: usize>(
+    mut p: [S::Vf32; D],
+    mut period: [S::Vf32; D],
+    lac: S::Vf32,
+    gain: S::Vf32,
+    octaves: u8,
+    seed: i32,
+    options: OctaveOptions,
+) -> (S::Vf32, [S::Vf32; D]) {
+    let (mut result, mut deriv) = simplex_periodic_deriv::<S, D>(p, period, seed);
+    let mut amp = S::Vf32::set1(1.0);
+    let mut total = amp;
+    // Frequency of the current octave relative to the first, the chain rule pulls it out of
+    // the octave's derivative
+    let mut chain = amp;
+    let next = |p: &mut [S::Vf32; D], period: &mut [S::Vf32; D]| {
+        for (axis, (v, period)) in p.iter_mut().zip(period.iter_mut()).enumerate() {
+            *v = options.offset_32::<S>(*v * lac, axis);
+            *period = *period * lac;
+        }
+    };
+
+    for octave in 1..octaves {
+        next(&mut p, &mut period);
+        amp = amp * gain;
+        chain = chain * lac;
+        let (n, d) = simplex_periodic_deriv::<S, D>(p, period, options.seed_32(seed, octave));
+        result = (n * amp) + result;
+        if options.normalize {
+            total = total + amp.abs();
+        }
+        add_deriv::<S, D>(&mut deriv, d, amp * chain);
+    }
+
+    if options.fraction > 0.0 {
+        let fraction = S::Vf32::set1(options.fraction);
+        next(&mut p, &mut period);
+        amp = amp * gain * fraction;
+        chain = chain * lac;
+        let (n, d) = simplex_periodic_deriv::<S, D>(p, period, options.seed_32(seed, octaves));
+        result = (n * amp) + result;
+        if options.normalize {
+            total = total + amp.abs();
+        }
+        add_deriv::<S, D>(&mut deriv, d, amp * chain);
+    }
+
+    if options.normalize {
+        (result / total, deriv.map(|d| d / total))
+    } else {
+        (result, deriv)
+    }
 }
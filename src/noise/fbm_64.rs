@@ -1,7 +1,10 @@
-use crate::noise::simplex_64::{simplex_1d, simplex_2d, simplex_3d};
+use crate::noise::lattice::Lattice4d;
+use crate::noise::octaves::OctaveOptions;
 
 use simdeez::prelude::*;
 
+/// Unless `options.weighted_1d` is set, 1d fbm adds every octave at full strength and ignores
+/// the gain, as it always has.
 #[inline(always)]
 pub fn fbm_1d<S: Simd>(
     mut x: S::Vf64,
@@ -9,17 +12,41 @@ pub fn fbm_1d<S: Simd>(
     gain: S::Vf64,
     octaves: u8,
     seed: i64,
+    options: OctaveOptions,
 ) -> S::Vf64 {
-    let mut amp = S::Vf64::set1(1.0);
-    let mut result = simplex_1d::<S>(x, seed);
+    let one = S::Vf64::set1(1.0);
+    let mut amp = one;
+    let mut total = one;
+    let mut result = options.lattice.noise_1d_64::<S>(x, seed);
+    let next = |v: S::Vf64, axis| options.offset_64::<S>(v * lacunarity, axis);
+
+    for octave in 1..octaves {
+        x = next(x, 0);
+        amp = amp * gain;
+        let weight = if options.weighted_1d { amp } else { one };
+        let n = options.lattice.noise_1d_64::<S>(x, options.seed_64(seed, octave));
+        result = (n * weight) + result;
+        if options.normalize {
+            total = total + weight.abs();
+        }
+    }
 
-    for _ in 1..octaves {
-        x = x * lacunarity;
+    if options.fraction > 0.0 {
+        let fraction = S::Vf64::set1(options.fraction.into());
+        x = next(x, 0);
         amp = amp * gain;
-        result = result + simplex_1d::<S>(x, seed);
+        let weight = if options.weighted_1d { amp * fraction } else { fraction };
+        result = (options.lattice.noise_1d_64::<S>(x, options.seed_64(seed, octaves)) * weight) + result;
+        if options.normalize {
+            total = total + weight.abs();
+        }
     }
 
-    result
+    if options.normalize {
+        result / total
+    } else {
+        result
+    }
 }
 
 #[inline(always)]
@@ -30,18 +57,39 @@ pub fn fbm_2d<S: Simd>(
     gain: S::Vf64,
     octaves: u8,
     seed: i64,
+    options: OctaveOptions,
 ) -> S::Vf64 {
-    let mut result = simplex_2d::<S>(x, y, seed);
+    let mut result = options.lattice.noise_2d_64::<S>(x, y, seed);
     let mut amp = S::Vf64::set1(1.0);
+    let mut total = amp;
+    let next = |v: S::Vf64, axis| options.offset_64::<S>(v * lac, axis);
 
-    for _ in 1..octaves {
-        x = x * lac;
-        y = y * lac;
+    for octave in 1..octaves {
+        x = next(x, 0);
+        y = next(y, 1);
         amp = amp * gain;
-        result = (simplex_2d::<S>(x, y, seed) * amp) + result;
+        result = (options.lattice.noise_2d_64::<S>(x, y, options.seed_64(seed, octave)) * amp) + result;
+        if options.normalize {
+            total = total + amp.abs();
+        }
+    }
+
+    if options.fraction > 0.0 {
+        let fraction = S::Vf64::set1(options.fraction.into());
+        x = next(x, 0);
+        y = next(y, 1);
+        amp = amp * gain * fraction;
+        result = (options.lattice.noise_2d_64::<S>(x, y, options.seed_64(seed, octaves)) * amp) + result;
+        if options.normalize {
+            total = total + amp.abs();
+        }
     }
 
-    result
+    if options.normalize {
+        result / total
+    } else {
+        result
+    }
 }
 
 #[inline(always)]
@@ -53,15 +101,88 @@ pub fn fbm_3d<S: Simd>(
     gain: S::Vf64,
     octaves: u8,
     seed: i64,
+    options: OctaveOptions,
+) -> S::Vf64 {
+    let mut result = options.lattice.noise_3d_64::<S>(x, y, z, seed);
+    let mut amp = S::Vf64::set1(1.0);
+    let mut total = amp;
+    let next = |v: S::Vf64, axis| options.offset_64::<S>(v * lac, axis);
+
+    for octave in 1..octaves {
+        x = next(x, 0);
+        y = next(y, 1);
+        z = next(z, 2);
+        amp = amp * gain;
+        result = (options.lattice.noise_3d_64::<S>(x, y, z, options.seed_64(seed, octave)) * amp) + result;
+        if options.normalize {
+            total = total + amp.abs();
+        }
+    }
+
+    if options.fraction > 0.0 {
+        let fraction = S::Vf64::set1(options.fraction.into());
+        x = next(x, 0);
+        y = next(y, 1);
+        z = next(z, 2);
+        amp = amp * gain * fraction;
+        result = (options.lattice.noise_3d_64::<S>(x, y, z, options.seed_64(seed, octaves)) * amp) + result;
+        if options.normalize {
+            total = total + amp.abs();
+        }
+    }
+
+    if options.normalize {
+        result / total
+    } else {
+        result
+    }
+}
+
+#[inline(always)]
+pub fn fbm_4d<S: Simd>(
+    mut x: S::Vf64,
+    mut y: S::Vf64,
+    mut z: S::Vf64,
+    mut w: S::Vf64,
+    lac: S::Vf64,
+    gain: S::Vf64,
+    octaves: u8,
+    seed: i64,
+    options: OctaveOptions<Lattice4d>,
 ) -> S::Vf64 {
-    let mut result = simplex_3d::<S>(x, y, z, seed);
+    let mut result = options.lattice.noise_4d_64::<S>(x, y, z, w, seed);
     let mut amp = S::Vf64::set1(1.0);
-    for _ in 1..octaves {
-        x = x * lac;
-        y = y * lac;
-        z = z * lac;
+    let mut total = amp;
+    let next = |v: S::Vf64, axis| options.offset_64::<S>(v * lac, axis);
+
+    for octave in 1..octaves {
+        x = next(x, 0);
+        y = next(y, 1);
+        z = next(z, 2);
+        w = next(w, 3);
         amp = amp * gain;
-        result = (simplex_3d::<S>(x, y, z, seed) * amp) + result;
+        result = (options.lattice.noise_4d_64::<S>(x, y, z, w, options.seed_64(seed, octave)) * amp) + result;
+        if options.normalize {
+            total = total + amp.abs();
+        }
+    }
+
+    if options.fraction > 0.0 {
+        let fraction = S::Vf64::set1(options.fraction.into());
+        x = next(x, 0);
+        y = next(y, 1);
+        z = next(z, 2);
+        w = next(w, 3);
+        amp = amp * gain * fraction;
+        result = (options.lattice.noise_4d_64::<S>(x, y, z, w, options.seed_64(seed, octaves)) * amp) + result;
+        if options.normalize {
+            total = total + amp.abs();
+        }
+    }
+
+    if options.normalize {
+        result / total
+    } else {
+        result
     }
-    result
 }
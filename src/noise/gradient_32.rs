@@ -87,3 +87,36 @@ pub fn grad3d<S: Simd>(seed: i32, i: S::Vi32, j: S::Vi32, k: S::Vi32) -> [S::Vf3
     );
     [gx, gy, gz]
 }
+
+/// Generates a random gradient vector from the origin towards the midpoint of an edge of a
+/// double-unit hypercube, three components are ±1 and the fourth is zero.
+///
+/// This is the 32 gradient set used by Gustavson's 4d simplex noise.
+#[inline(always)]
+pub fn grad4<S: Simd>(seed: i32, hash: S::Vi32) -> [S::Vf32; 4] {
+    let h = (S::Vi32::set1(seed) ^ hash) & S::Vi32::set1(31);
+    let lt24 = S::Vi32::set1(24).cmp_gt(h).bitcast_f32();
+    let lt16 = S::Vi32::set1(16).cmp_gt(h).bitcast_f32();
+    let lt8 = S::Vi32::set1(8).cmp_gt(h).bitcast_f32();
+
+    // Signs for the three selected axes
+    let one = S::Vf32::set1(1.0);
+    let minus_one = S::Vf32::set1(-1.0);
+    let su = ((h & S::Vi32::set1(1)).cmp_eq(S::Vi32::zeroes()))
+        .bitcast_f32()
+        .blendv(minus_one, one);
+    let sv = ((h & S::Vi32::set1(2)).cmp_eq(S::Vi32::zeroes()))
+        .bitcast_f32()
+        .blendv(minus_one, one);
+    let sw = ((h & S::Vi32::set1(4)).cmp_eq(S::Vi32::zeroes()))
+        .bitcast_f32()
+        .blendv(minus_one, one);
+
+    // h < 8 drops w, h < 16 drops z, h < 24 drops y and the rest drops x
+    let zero = S::Vf32::zeroes();
+    let gx = lt24.blendv(zero, su);
+    let gy = lt16.blendv(lt24.blendv(su, zero), sv);
+    let gz = lt8.blendv(lt16.blendv(sv, zero), sw);
+    let gw = lt8.blendv(sw, zero);
+    [gx, gy, gz, gw]
+}
@@ -11,7 +11,7 @@ pub fn grad1<S: Simd>(seed: i64, hash: S::Vi64) -> S::Vf64 {
     let h = (S::Vi64::set1(seed) ^ hash) & S::Vi64::set1(15);
     let v = (h & S::Vi64::set1(7)).cast_f64();
 
-    let h_and_8 = ((h & S::Vi64::set1(8)).cmp_eq(S::Vi64::zeroes())).cast_f64();
+    let h_and_8 = ((h & S::Vi64::set1(8)).cmp_eq(S::Vi64::zeroes())).bitcast_f64();
     h_and_8.blendv(S::Vf64::zeroes() - v, v)
 }
 
@@ -22,12 +22,12 @@ pub fn grad1<S: Simd>(seed: i64, hash: S::Vi64) -> S::Vf64 {
 #[inline(always)]
 pub fn grad2<S: Simd>(seed: i64, hash: S::Vi64) -> [S::Vf64; 2] {
     let h = (hash ^ S::Vi64::set1(seed)) & S::Vi64::set1(7);
-    let mask = (S::Vi64::set1(4).cmp_gt(h)).cast_f64();
+    let mask = (S::Vi64::set1(4).cmp_gt(h)).bitcast_f64();
     let x_magnitude = mask.blendv(S::Vf64::set1(2.0), S::Vf64::set1(1.0));
     let y_magnitude = mask.blendv(S::Vf64::set1(1.0), S::Vf64::set1(2.0));
 
-    let h_and_1 = ((h & S::Vi64::set1(1)).cmp_eq(S::Vi64::zeroes())).cast_f64();
-    let h_and_2 = ((h & S::Vi64::set1(2)).cmp_eq(S::Vi64::zeroes())).cast_f64();
+    let h_and_1 = ((h & S::Vi64::set1(1)).cmp_eq(S::Vi64::zeroes())).bitcast_f64();
+    let h_and_2 = ((h & S::Vi64::set1(2)).cmp_eq(S::Vi64::zeroes())).bitcast_f64();
 
     let gx = mask
         .blendv(h_and_2, h_and_1)
@@ -86,4 +86,37 @@ pub fn grad3d<S: Simd>(seed: i64, i: S::Vi64, j: S::Vi64, k: S::Vi64) -> [S::Vf6
         "exactly two axes are chosen"
     );
     [gx, gy, gz]
-}
\ No newline at end of file
+}
+
+/// Generates a random gradient vector from the origin towards the midpoint of an edge of a
+/// double-unit hypercube, three components are ±1 and the fourth is zero.
+///
+/// This is the 32 gradient set used by Gustavson's 4d simplex noise.
+#[inline(always)]
+pub fn grad4<S: Simd>(seed: i64, hash: S::Vi64) -> [S::Vf64; 4] {
+    let h = (S::Vi64::set1(seed) ^ hash) & S::Vi64::set1(31);
+    let lt24 = S::Vi64::set1(24).cmp_gt(h).bitcast_f64();
+    let lt16 = S::Vi64::set1(16).cmp_gt(h).bitcast_f64();
+    let lt8 = S::Vi64::set1(8).cmp_gt(h).bitcast_f64();
+
+    // Signs for the three selected axes
+    let one = S::Vf64::set1(1.0);
+    let minus_one = S::Vf64::set1(-1.0);
+    let su = ((h & S::Vi64::set1(1)).cmp_eq(S::Vi64::zeroes()))
+        .bitcast_f64()
+        .blendv(minus_one, one);
+    let sv = ((h & S::Vi64::set1(2)).cmp_eq(S::Vi64::zeroes()))
+        .bitcast_f64()
+        .blendv(minus_one, one);
+    let sw = ((h & S::Vi64::set1(4)).cmp_eq(S::Vi64::zeroes()))
+        .bitcast_f64()
+        .blendv(minus_one, one);
+
+    // h < 8 drops w, h < 16 drops z, h < 24 drops y and the rest drops x
+    let zero = S::Vf64::zeroes();
+    let gx = lt24.blendv(zero, su);
+    let gy = lt16.blendv(lt24.blendv(su, zero), sv);
+    let gz = lt8.blendv(lt16.blendv(sv, zero), sw);
+    let gw = lt8.blendv(sw, zero);
+    [gx, gy, gz, gw]
+}
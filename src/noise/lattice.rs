@@ -0,0 +1,303 @@
+//! Picks the lattice noise is sampled on and what its points hold, for gradient noise and the
+//! fractal kernels
+
+use crate::noise::opensimplex2_32::{
+    opensimplex2_2d_deriv, opensimplex2_3d_deriv, opensimplex2_4d_deriv, opensimplex2s_2d_deriv,
+    opensimplex2s_3d_deriv, opensimplex2s_4d_deriv,
+};
+use crate::noise::opensimplex2_64::{
+    opensimplex2_2d_deriv as opensimplex2_2d_deriv_f64,
+    opensimplex2_3d_deriv as opensimplex2_3d_deriv_f64,
+    opensimplex2_4d_deriv as opensimplex2_4d_deriv_f64,
+    opensimplex2s_2d_deriv as opensimplex2s_2d_deriv_f64,
+    opensimplex2s_3d_deriv as opensimplex2s_3d_deriv_f64,
+    opensimplex2s_4d_deriv as opensimplex2s_4d_deriv_f64,
+};
+use crate::noise::perlin_32::{
+    perlin_1d, perlin_1d_deriv, perlin_2d, perlin_2d_deriv, perlin_3d, perlin_3d_deriv,
+};
+use crate::noise::perlin_64::{
+    perlin_1d as perlin_1d_f64, perlin_2d as perlin_2d_f64, perlin_3d as perlin_3d_f64,
+};
+use crate::noise::simplex_32::{
+    hashed_simplex_1d, hashed_simplex_1d_deriv, hashed_simplex_2d, hashed_simplex_2d_deriv,
+    simplex_1d, simplex_1d_deriv, simplex_2d, simplex_2d_deriv, simplex_3d, simplex_3d_deriv,
+    simplex_4d, simplex_4d_deriv,
+};
+use crate::noise::simplex_64::{
+    hashed_simplex_1d as hashed_simplex_1d_f64, hashed_simplex_2d as hashed_simplex_2d_f64,
+    simplex_1d as simplex_1d_f64, simplex_2d as simplex_2d_f64, simplex_3d as simplex_3d_f64,
+    simplex_4d as simplex_4d_f64,
+};
+use crate::noise::value_32::{
+    value_1d_deriv, value_2d_deriv, value_3d_deriv, value_cubic_1d_deriv, value_cubic_2d_deriv,
+    value_cubic_3d_deriv,
+};
+use crate::noise::value_64::{
+    value_1d as value_1d_f64, value_2d as value_2d_f64, value_3d as value_3d_f64,
+    value_cubic_1d as value_cubic_1d_f64, value_cubic_2d as value_cubic_2d_f64,
+    value_cubic_3d as value_cubic_3d_f64,
+};
+use crate::{NoiseError, SeedMode};
+
+use simdeez::prelude::*;
+
+/// How value noise blends the values of the lattice points around a sample
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Straight lines between the points, creased at the cell edges
+    Linear,
+    /// Smoothstep, the slope is continuous at the cell edges
+    Hermite,
+    /// The fade of Perlin noise, the curvature is continuous at the cell edges too
+    #[default]
+    Quintic,
+}
+
+/// Picks the lattice noise is sampled on and what its points hold, gradients or values. The
+/// cubic lattices have no 4d noise, see `Lattice4d`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Lattice {
+    /// The classic skewed simplex lattice, shows some axis aligned artefacts in 3d. 1d and 2d
+    /// hash the whole seed, see `hashed_simplex_1d` and `hashed_simplex_2d`.
+    #[default]
+    Simplex,
+    /// `Simplex`, with the 1d and 2d gradients hashed the way versions before 64 bit seeds did,
+    /// see `simplex_1d` and `simplex_2d`. Only a few bits of the seed pick them, so many seeds
+    /// give the same noise. Settings seeded with `with_legacy_seed` sample this instead of
+    /// `Simplex`.
+    LegacySimplex,
+    /// OpenSimplex2, looks the same in every direction and costs about as much as `Simplex`
+    OpenSimplex2,
+    /// OpenSimplex2S, smoother than `OpenSimplex2` and slower
+    OpenSimplex2S,
+    /// Ken Perlin's improved noise on the cubic lattice, for matching Perlin based tools
+    Perlin,
+    /// Value noise, random values on the cubic lattice blended with the given interpolation.
+    /// Cheaper than gradient noise, and blockier.
+    Value(Interpolation),
+    /// Cubic value noise, smoother than `Value` and 2^d times as many points to hash
+    ValueCubic,
+}
+
+impl Lattice {
+    /// The lattice noise seeded with `mode` is sampled on, legacy seeds hash simplex noise the way
+    /// they always did
+    #[inline(always)]
+    pub fn for_seed_mode(self, mode: SeedMode) -> Lattice {
+        match (self, mode) {
+            (Lattice::Simplex, SeedMode::Legacy) => Lattice::LegacySimplex,
+            _ => self,
+        }
+    }
+
+    /// Samples 1-dimensional noise, 1d OpenSimplex2 noise is a slice through 2d noise
+    #[inline(always)]
+    pub fn noise_1d_32<S: Simd>(self, x: S::Vf32, seed: i32) -> S::Vf32 {
+        match self {
+            Lattice::Simplex => hashed_simplex_1d::<S>(x, seed),
+            Lattice::LegacySimplex => simplex_1d::<S>(x, seed),
+            Lattice::Perlin => perlin_1d::<S>(x, seed),
+            _ => self.noise_1d_deriv_32::<S>(x, seed).0,
+        }
+    }
+
+    /// Like `noise_1d_32`, but also computes the derivative
+    #[inline(always)]
+    pub fn noise_1d_deriv_32<S: Simd>(self, x: S::Vf32, seed: i32) -> (S::Vf32, S::Vf32) {
+        match self {
+            Lattice::Simplex => hashed_simplex_1d_deriv::<S>(x, seed),
+            Lattice::LegacySimplex => simplex_1d_deriv::<S>(x, seed),
+            Lattice::Perlin => perlin_1d_deriv::<S>(x, seed),
+            Lattice::Value(interpolation) => value_1d_deriv::<S>(x, interpolation, seed),
+            Lattice::ValueCubic => value_cubic_1d_deriv::<S>(x, seed),
+            _ => {
+                let (value, [dx, _]) = self.noise_2d_deriv_32::<S>(x, S::Vf32::zeroes(), seed);
+                (value, dx)
+            }
+        }
+    }
+
+    /// Samples 2-dimensional noise
+    #[inline(always)]
+    pub fn noise_2d_32<S: Simd>(self, x: S::Vf32, y: S::Vf32, seed: i32) -> S::Vf32 {
+        match self {
+            Lattice::Simplex => hashed_simplex_2d::<S>(x, y, seed),
+            Lattice::LegacySimplex => simplex_2d::<S>(x, y, seed),
+            Lattice::Perlin => perlin_2d::<S>(x, y, seed),
+            _ => self.noise_2d_deriv_32::<S>(x, y, seed).0,
+        }
+    }
+
+    /// Like `noise_2d_32`, but also computes the derivative
+    #[inline(always)]
+    pub fn noise_2d_deriv_32<S: Simd>(
+        self,
+        x: S::Vf32,
+        y: S::Vf32,
+        seed: i32,
+    ) -> (S::Vf32, [S::Vf32; 2]) {
+        match self {
+            Lattice::Simplex => hashed_simplex_2d_deriv::<S>(x, y, seed),
+            Lattice::LegacySimplex => simplex_2d_deriv::<S>(x, y, seed),
+            Lattice::OpenSimplex2 => opensimplex2_2d_deriv::<S>(x, y, seed),
+            Lattice::OpenSimplex2S => opensimplex2s_2d_deriv::<S>(x, y, seed),
+            Lattice::Perlin => perlin_2d_deriv::<S>(x, y, seed),
+            Lattice::Value(interpolation) => value_2d_deriv::<S>(x, y, interpolation, seed),
+            Lattice::ValueCubic => value_cubic_2d_deriv::<S>(x, y, seed),
+        }
+    }
+
+    /// Samples 3-dimensional noise
+    #[inline(always)]
+    pub fn noise_3d_32<S: Simd>(self, x: S::Vf32, y: S::Vf32, z: S::Vf32, seed: i32) -> S::Vf32 {
+        match self {
+            Lattice::Simplex | Lattice::LegacySimplex => simplex_3d::<S>(x, y, z, seed),
+            Lattice::Perlin => perlin_3d::<S>(x, y, z, seed),
+            _ => self.noise_3d_deriv_32::<S>(x, y, z, seed).0,
+        }
+    }
+
+    /// Like `noise_3d_32`, but also computes the derivative
+    #[inline(always)]
+    pub fn noise_3d_deriv_32<S: Simd>(
+        self,
+        x: S::Vf32,
+        y: S::Vf32,
+        z: S::Vf32,
+        seed: i32,
+    ) -> (S::Vf32, [S::Vf32; 3]) {
+        match self {
+            Lattice::Simplex | Lattice::LegacySimplex => simplex_3d_deriv::<S>(x, y, z, seed),
+            Lattice::OpenSimplex2 => opensimplex2_3d_deriv::<S>(x, y, z, seed),
+            Lattice::OpenSimplex2S => opensimplex2s_3d_deriv::<S>(x, y, z, seed),
+            Lattice::Perlin => perlin_3d_deriv::<S>(x, y, z, seed),
+            Lattice::Value(interpolation) => value_3d_deriv::<S>(x, y, z, interpolation, seed),
+            Lattice::ValueCubic => value_cubic_3d_deriv::<S>(x, y, z, seed),
+        }
+    }
+
+    /// Like `noise_1d_32`, in double precision
+    #[inline(always)]
+    pub fn noise_1d_64<S: Simd>(self, x: S::Vf64, seed: i64) -> S::Vf64 {
+        match self {
+            Lattice::Simplex => hashed_simplex_1d_f64::<S>(x, seed),
+            Lattice::LegacySimplex => simplex_1d_f64::<S>(x, seed),
+            Lattice::Perlin => perlin_1d_f64::<S>(x, seed),
+            Lattice::Value(interpolation) => value_1d_f64::<S>(x, interpolation, seed),
+            Lattice::ValueCubic => value_cubic_1d_f64::<S>(x, seed),
+            Lattice::OpenSimplex2 | Lattice::OpenSimplex2S => {
+                self.noise_2d_64::<S>(x, S::Vf64::zeroes(), seed)
+            }
+        }
+    }
+
+    /// Like `noise_2d_32`, in double precision
+    #[inline(always)]
+    pub fn noise_2d_64<S: Simd>(self, x: S::Vf64, y: S::Vf64, seed: i64) -> S::Vf64 {
+        match self {
+            Lattice::Simplex => hashed_simplex_2d_f64::<S>(x, y, seed),
+            Lattice::LegacySimplex => simplex_2d_f64::<S>(x, y, seed),
+            Lattice::Perlin => perlin_2d_f64::<S>(x, y, seed),
+            Lattice::Value(interpolation) => value_2d_f64::<S>(x, y, interpolation, seed),
+            Lattice::ValueCubic => value_cubic_2d_f64::<S>(x, y, seed),
+            Lattice::OpenSimplex2 => opensimplex2_2d_deriv_f64::<S>(x, y, seed).0,
+            Lattice::OpenSimplex2S => opensimplex2s_2d_deriv_f64::<S>(x, y, seed).0,
+        }
+    }
+
+    /// Like `noise_3d_32`, in double precision
+    #[inline(always)]
+    pub fn noise_3d_64<S: Simd>(self, x: S::Vf64, y: S::Vf64, z: S::Vf64, seed: i64) -> S::Vf64 {
+        match self {
+            Lattice::Simplex | Lattice::LegacySimplex => simplex_3d_f64::<S>(x, y, z, seed),
+            Lattice::Perlin => perlin_3d_f64::<S>(x, y, z, seed),
+            Lattice::Value(interpolation) => value_3d_f64::<S>(x, y, z, interpolation, seed),
+            Lattice::ValueCubic => value_cubic_3d_f64::<S>(x, y, z, seed),
+            Lattice::OpenSimplex2 => opensimplex2_3d_deriv_f64::<S>(x, y, z, seed).0,
+            Lattice::OpenSimplex2S => opensimplex2s_3d_deriv_f64::<S>(x, y, z, seed).0,
+        }
+    }
+}
+
+/// The lattices that have 4d noise, each variant samples the `Lattice` of the same name. The 4d
+/// kernels take this instead of a `Lattice`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Lattice4d {
+    #[default]
+    Simplex,
+    LegacySimplex,
+    OpenSimplex2,
+    OpenSimplex2S,
+}
+
+impl TryFrom<Lattice> for Lattice4d {
+    type Error = NoiseError;
+
+    /// Fails with `NoiseError::UnsupportedDimension` on the cubic lattices
+    fn try_from(lattice: Lattice) -> Result<Lattice4d, NoiseError> {
+        match lattice {
+            Lattice::Simplex => Ok(Lattice4d::Simplex),
+            Lattice::LegacySimplex => Ok(Lattice4d::LegacySimplex),
+            Lattice::OpenSimplex2 => Ok(Lattice4d::OpenSimplex2),
+            Lattice::OpenSimplex2S => Ok(Lattice4d::OpenSimplex2S),
+            Lattice::Perlin | Lattice::Value(_) | Lattice::ValueCubic => {
+                Err(NoiseError::UnsupportedDimension { dim: 4 })
+            }
+        }
+    }
+}
+
+impl Lattice4d {
+    /// Samples 4-dimensional noise
+    #[inline(always)]
+    pub fn noise_4d_32<S: Simd>(
+        self,
+        x: S::Vf32,
+        y: S::Vf32,
+        z: S::Vf32,
+        w: S::Vf32,
+        seed: i32,
+    ) -> S::Vf32 {
+        match self {
+            Lattice4d::Simplex | Lattice4d::LegacySimplex => simplex_4d::<S>(x, y, z, w, seed),
+            _ => self.noise_4d_deriv_32::<S>(x, y, z, w, seed).0,
+        }
+    }
+
+    /// Like `noise_4d_32`, but also computes the derivative
+    #[inline(always)]
+    pub fn noise_4d_deriv_32<S: Simd>(
+        self,
+        x: S::Vf32,
+        y: S::Vf32,
+        z: S::Vf32,
+        w: S::Vf32,
+        seed: i32,
+    ) -> (S::Vf32, [S::Vf32; 4]) {
+        match self {
+            Lattice4d::Simplex | Lattice4d::LegacySimplex => {
+                simplex_4d_deriv::<S>(x, y, z, w, seed)
+            }
+            Lattice4d::OpenSimplex2 => opensimplex2_4d_deriv::<S>(x, y, z, w, seed),
+            Lattice4d::OpenSimplex2S => opensimplex2s_4d_deriv::<S>(x, y, z, w, seed),
+        }
+    }
+
+    /// Like `noise_4d_32`, in double precision
+    #[inline(always)]
+    pub fn noise_4d_64<S: Simd>(
+        self,
+        x: S::Vf64,
+        y: S::Vf64,
+        z: S::Vf64,
+        w: S::Vf64,
+        seed: i64,
+    ) -> S::Vf64 {
+        match self {
+            Lattice4d::Simplex | Lattice4d::LegacySimplex => simplex_4d_f64::<S>(x, y, z, w, seed),
+            Lattice4d::OpenSimplex2 => opensimplex2_4d_deriv_f64::<S>(x, y, z, w, seed).0,
+            Lattice4d::OpenSimplex2S => opensimplex2s_4d_deriv_f64::<S>(x, y, z, w, seed).0,
+        }
+    }
+}
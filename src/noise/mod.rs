@@ -1,9 +1,34 @@
+// The kernels take one vector per coordinate and per octave setting, so the fractal and warp
+// functions end up with more arguments than clippy likes
+#![allow(clippy::too_many_arguments)]
+
+pub mod billow_32;
+pub mod billow_64;
+pub mod cellular_32;
+pub mod cellular_64;
+pub mod curl_32;
+pub mod domain_warp_32;
+pub mod domain_warp_64;
 pub mod fbm_32;
 pub mod fbm_64;
 mod gradient_32;
 mod gradient_64;
 mod hash3d_32;
 mod hash3d_64;
+pub mod lattice;
+pub mod octaves;
 pub mod ops;
+pub mod opensimplex2_32;
+pub mod opensimplex2_64;
+pub mod perlin_32;
+pub mod perlin_64;
+pub mod ridge_32;
+pub mod ridge_64;
 pub mod simplex_32;
 pub mod simplex_64;
+pub mod turbulence_32;
+pub mod turbulence_64;
+pub mod value_32;
+pub mod value_64;
+pub mod white_32;
+pub mod white_64;
@@ -0,0 +1,106 @@
+//! Options for how the octaves of the fractal kernels relate to each other
+
+use crate::noise::lattice::{Lattice, Lattice4d};
+use crate::{NoiseError, SeedMode};
+
+use simdeez::prelude::*;
+
+/// Added to the seed once per octave, an odd constant so every octave of a u8 count differs
+const OCTAVE_SEED_STEP: i32 = 0x9e37_79b9_u32 as i32;
+
+/// Domain shift applied after every octave, the fractional parts of the golden ratio, sqrt(2),
+/// sqrt(3) and sqrt(5) so the shifted lattices never line up again
+const OCTAVE_OFFSET: [f32; 4] = [0.618_034, 0.414_213_57, 0.732_050_8, 0.236_068];
+
+/// Per octave behaviour of the fractal kernels. `Default` keeps every octave on the same seed
+/// and origin, which is what older versions did. The 4d kernels take `OctaveOptions<Lattice4d>`,
+/// see `for_4d`.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct OctaveOptions<L = Lattice> {
+    /// Derives a distinct seed for every octave from the base seed
+    pub seed_per_octave: bool,
+    /// Shifts every octave by a fixed offset, so they don't all share a feature at the origin
+    pub offset_per_octave: bool,
+    /// Divides the sum by the summed octave weights, so the output stays in [-1, 1] for any
+    /// octave count. Only fbm looks at this.
+    pub normalize: bool,
+    /// Weighs the octaves of 1d fbm by their amplitude like the other dimensions do. 1d fbm
+    /// always added every octave at full strength, so this is off by default.
+    pub weighted_1d: bool,
+    /// Weight in [0, 1) of one more octave past the octave count, so the level of detail can
+    /// change smoothly instead of one whole octave at a time
+    pub fraction: f32,
+    /// The lattice every octave is sampled on
+    pub lattice: L,
+}
+
+impl OctaveOptions {
+    /// The options noise seeded with `mode` is sampled with, see `Lattice::for_seed_mode`. Older
+    /// versions had no seed per octave, so those octaves hash the whole seed whatever the mode.
+    #[inline(always)]
+    pub fn for_seed_mode(self, mode: SeedMode) -> OctaveOptions {
+        if self.seed_per_octave {
+            return self;
+        }
+        OctaveOptions {
+            lattice: self.lattice.for_seed_mode(mode),
+            ..self
+        }
+    }
+
+    /// The options for the 4d kernels, fails with `NoiseError::UnsupportedDimension` if the
+    /// lattice has no 4d noise
+    #[inline(always)]
+    pub fn for_4d(self) -> Result<OctaveOptions<Lattice4d>, NoiseError> {
+        Ok(OctaveOptions {
+            seed_per_octave: self.seed_per_octave,
+            offset_per_octave: self.offset_per_octave,
+            normalize: self.normalize,
+            weighted_1d: self.weighted_1d,
+            fraction: self.fraction,
+            lattice: Lattice4d::try_from(self.lattice)?,
+        })
+    }
+}
+
+impl<L> OctaveOptions<L> {
+    /// The seed octave `octave` of a 32 bit kernel hashes with
+    #[inline(always)]
+    pub fn seed_32(&self, seed: i32, octave: u8) -> i32 {
+        if self.seed_per_octave {
+            seed.wrapping_add(OCTAVE_SEED_STEP.wrapping_mul(octave.into()))
+        } else {
+            seed
+        }
+    }
+
+    /// The seed octave `octave` of a 64 bit kernel hashes with
+    #[inline(always)]
+    pub fn seed_64(&self, seed: i64, octave: u8) -> i64 {
+        if self.seed_per_octave {
+            seed.wrapping_add(i64::from(OCTAVE_SEED_STEP).wrapping_mul(octave.into()))
+        } else {
+            seed
+        }
+    }
+
+    /// Shifts coordinate `axis` of the next octave
+    #[inline(always)]
+    pub fn offset_32<S: Simd>(&self, v: S::Vf32, axis: usize) -> S::Vf32 {
+        if self.offset_per_octave {
+            v + S::Vf32::set1(OCTAVE_OFFSET[axis])
+        } else {
+            v
+        }
+    }
+
+    /// Shifts coordinate `axis` of the next octave
+    #[inline(always)]
+    pub fn offset_64<S: Simd>(&self, v: S::Vf64, axis: usize) -> S::Vf64 {
+        if self.offset_per_octave {
+            v + S::Vf64::set1(OCTAVE_OFFSET[axis].into())
+        } else {
+            v
+        }
+    }
+}
@@ -0,0 +1,544 @@
+//! OpenSimplex2 and OpenSimplex2S noise, after KdotJPG's reference implementation.
+//!
+//! Both sum gradient kernels over lattices picked to look the same in every direction, which
+//! the classic simplex lattice doesn't manage in 3d. OpenSimplex2 visits as few vertices as
+//! it can, OpenSimplex2S uses wider kernels that visit more of them and comes out smoother.
+//! 3d noise is sampled on a rotated body centered cubic lattice, so axis aligned slices don't
+//! line up with it.
+//!
+//! Every kernel returns the value and its analytic derivative. Values are in [-1, 1].
+use crate::noise::gradient_32::{grad3d, grad4};
+use crate::noise::ops::gather_f32;
+
+use simdeez::prelude::*;
+
+/// The high halves of the primes the reference implementation hashes with
+const PRIME_X: i32 = 0x5205402B;
+const PRIME_Y: i32 = 0x598CD327;
+const PRIME_Z: i32 = 0x5BCC226F;
+const PRIME_W: i32 = 0x56CC5227;
+pub(crate) const PRIMES: [i32; 4] = [PRIME_X, PRIME_Y, PRIME_Z, PRIME_W];
+pub(crate) const HASH_MULTIPLIER: i32 = 0x53A3F72D;
+
+/// Seed difference between neighbouring copies of the 4d lattice
+pub(crate) const SEED_OFFSET_4D: i32 = 0x0DA7164D;
+
+const SKEW_2D: f32 = 0.366_025_42;
+const UNSKEW_2D: f32 = 0.211_324_87;
+const SKEW_4D: f32 = 0.309_017;
+const UNSKEW_4D: f32 = 0.138_196_6;
+
+/// Skew and unskew of the 4d lattice OpenSimplex2 samples five shifted copies of, the dual of
+/// the lattice `SKEW_4D` skews into
+const DUAL_SKEW_4D: f32 = -0.138_196_6;
+const DUAL_UNSKEW_4D: f32 = 0.309_017;
+const DUAL_STEP_4D: f32 = 0.2;
+
+/// Squared kernel radii. The OpenSimplex2 radius is the largest one where every lattice copy
+/// has at most one vertex in reach, so visiting only the closest vertex keeps the noise
+/// continuous.
+const R_SQUARED_FAST: f32 = 0.5;
+const R_SQUARED_2D: f32 = 2.0 / 3.0;
+const R_SQUARED_3D: f32 = 0.75;
+const R_SQUARED_4D: f32 = 0.8;
+
+// Scaling factors found by numerical approximation
+const SCALE_2D: f32 = 97.84;
+const SCALE_3D: f32 = 75.36;
+const SCALE_4D: f32 = 61.6;
+const SCALE_2D_SMOOTH: f32 = 17.88;
+const SCALE_3D_SMOOTH: f32 = 8.873;
+const SCALE_4D_SMOOTH: f32 = 5.444;
+
+/// 24 unit gradients 15 degrees apart, turned by 7.5 degrees so none lines up with an axis
+const GRAD_2D_X: [f32; 24] = [
+    0.991_444_9, 0.923_879_5, 0.793_353_3, 0.608_761_4, 0.382_683_43, 0.130_526_19,
+    -0.130_526_19, -0.382_683_43, -0.608_761_4, -0.793_353_3, -0.923_879_5, -0.991_444_9,
+    -0.991_444_9, -0.923_879_5, -0.793_353_3, -0.608_761_4, -0.382_683_43, -0.130_526_19,
+    0.130_526_19, 0.382_683_43, 0.608_761_4, 0.793_353_3, 0.923_879_5, 0.991_444_9,
+];
+const GRAD_2D_Y: [f32; 24] = [
+    0.130_526_19, 0.382_683_43, 0.608_761_4, 0.793_353_3, 0.923_879_5, 0.991_444_9,
+    0.991_444_9, 0.923_879_5, 0.793_353_3, 0.608_761_4, 0.382_683_43, 0.130_526_19,
+    -0.130_526_19, -0.382_683_43, -0.608_761_4, -0.793_353_3, -0.923_879_5, -0.991_444_9,
+    -0.991_444_9, -0.923_879_5, -0.793_353_3, -0.608_761_4, -0.382_683_43, -0.130_526_19,
+];
+
+/// Every vertex of the simplex lattice an OpenSimplex2S kernel can reach, relative to the
+/// skewed cell and in the order of the axes from the largest offset into the cell to the
+/// smallest
+pub(crate) const REACH_2D_FAST: [[f32; 2]; 3] = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0]];
+pub(crate) const REACH_2D: [[f32; 2]; 6] = [
+    [0.0, 0.0],
+    [1.0, 0.0],
+    [1.0, 1.0],
+    [0.0, -1.0],
+    [0.0, 1.0],
+    [2.0, 1.0],
+];
+pub(crate) const REACH_4D: [[f32; 4]; 30] = [
+    // The corners of the simplex the point is in
+    [0.0, 0.0, 0.0, 0.0],
+    [1.0, 0.0, 0.0, 0.0],
+    [1.0, 1.0, 0.0, 0.0],
+    [1.0, 1.0, 1.0, 0.0],
+    [1.0, 1.0, 1.0, 1.0],
+    // One step further out
+    [0.0, 0.0, 0.0, -1.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [1.0, 0.0, 1.0, 0.0],
+    [1.0, 1.0, 0.0, 1.0],
+    [2.0, 1.0, 1.0, 1.0],
+    [0.0, 0.0, -1.0, -1.0],
+    [0.0, 0.0, -1.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 1.0, 1.0, 0.0],
+    [1.0, 0.0, 0.0, -1.0],
+    [1.0, 0.0, 0.0, 1.0],
+    [1.0, 0.0, 1.0, 1.0],
+    [1.0, 2.0, 1.0, 1.0],
+    [2.0, 1.0, 1.0, 0.0],
+    [2.0, 2.0, 1.0, 1.0],
+    [0.0, -1.0, -1.0, -1.0],
+    [0.0, -1.0, 0.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+    [0.0, 1.0, 1.0, 1.0],
+    [1.0, 0.0, -1.0, 0.0],
+    [1.0, 1.0, 0.0, -1.0],
+    [1.0, 1.0, 2.0, 1.0],
+    [1.0, 2.0, 1.0, 0.0],
+    [2.0, 1.0, 0.0, 0.0],
+    [2.0, 2.0, 2.0, 1.0],
+];
+
+/// The vertices of a cube of the 3d lattice an OpenSimplex2S kernel can reach, relative to the
+/// closest vertex and mirrored towards the point
+pub(crate) const REACH_3D: [[f32; 3]; 7] = [
+    [0.0, 0.0, 0.0],
+    [1.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0],
+    [0.0, 0.0, 1.0],
+    [1.0, 1.0, 0.0],
+    [1.0, 0.0, 1.0],
+    [0.0, 1.0, 1.0],
+];
+
+/// Hashes the primed coordinates of a vertex
+#[inline(always)]
+fn hash<S: Simd, const D: usize>(seed: S::Vi32, primed: [S::Vi32; D]) -> S::Vi32 {
+    let h = primed.into_iter().fold(seed, |h, p| h ^ p) * S::Vi32::set1(HASH_MULTIPLIER);
+    h ^ (h >> 15)
+}
+
+/// One of the 24 gradients of `GRAD_2D_X` and `GRAD_2D_Y`
+#[inline(always)]
+fn grad2<S: Simd>(hash: S::Vi32) -> [S::Vf32; 2] {
+    // The low 16 bits scaled onto the 24 gradients
+    let index = ((hash & S::Vi32::set1(0xffff)) * S::Vi32::set1(24)) >> 16;
+    unsafe { [gather_f32::<S>(&GRAD_2D_X, index), gather_f32::<S>(&GRAD_2D_Y, index)] }
+}
+
+/// Adds the contribution of a vertex `d` away with gradient `g` and its derivative to `acc`
+#[inline(always)]
+fn contribute<S: Simd, const D: usize>(
+    acc: &mut (S::Vf32, [S::Vf32; D]),
+    r_squared: f32,
+    d: [S::Vf32; D],
+    g: [S::Vf32; D],
+) {
+    let a = d
+        .iter()
+        .fold(S::Vf32::set1(r_squared), |a, &d| a - d * d)
+        .max(S::Vf32::zeroes());
+    let a2 = a * a;
+    let a4 = a2 * a2;
+    let dot = d.iter().zip(g).fold(S::Vf32::zeroes(), |sum, (&d, g)| (d * g) + sum);
+    acc.0 = (a4 * dot) + acc.0;
+    // d/dd of a^4 (g . d) is a^4 g - 8 a^3 (g . d) d
+    let falloff = a2 * a * dot * S::Vf32::set1(-8.0);
+    for ((deriv, d), g) in acc.1.iter_mut().zip(d).zip(g) {
+        *deriv = (g * a4) + (d * falloff) + *deriv;
+    }
+}
+
+/// Scales a sum of contributions into range
+#[inline(always)]
+fn scaled<S: Simd, const D: usize>(
+    (value, deriv): (S::Vf32, [S::Vf32; D]),
+    scale: f32,
+) -> (S::Vf32, [S::Vf32; D]) {
+    let scale = S::Vf32::set1(scale);
+    (value * scale, deriv.map(|d| d * scale))
+}
+
+/// Sums the kernels of every vertex in `reach` around the cell of the skewed simplex lattice
+/// `p` is in. `reach` is in the order of the axes from the largest offset into the cell to the
+/// smallest, which picks the simplex the point is in.
+#[inline(always)]
+fn simplex_lattice<S: Simd, const D: usize>(
+    p: [S::Vf32; D],
+    seed: i32,
+    skew: f32,
+    unskew: f32,
+    r_squared: f32,
+    reach: &[[f32; D]],
+    gradient: impl Fn(S::Vi32) -> [S::Vf32; D],
+) -> (S::Vf32, [S::Vf32; D]) {
+    let s = p.iter().fold(S::Vf32::zeroes(), |s, &v| s + v) * S::Vf32::set1(skew);
+    let cell = p.map(|v| (v + s).floor());
+    let t = cell.iter().fold(S::Vf32::zeroes(), |t, &v| t + v) * S::Vf32::set1(unskew);
+    let d0: [S::Vf32; D] = core::array::from_fn(|i| p[i] - (cell[i] - t));
+    let primed: [S::Vi32; D] =
+        core::array::from_fn(|i| cell[i].cast_i32() * S::Vi32::set1(PRIMES[i]));
+
+    // How many axes have a larger offset, ties go to the lower axis. Masks are -1 where set,
+    // so subtracting a mask counts it.
+    let order: [S::Vi32; D] = core::array::from_fn(|i| {
+        (0..D).filter(|&j| j != i).fold(S::Vi32::zeroes(), |n, j| {
+            let larger = if j < i { d0[j].cmp_gte(d0[i]) } else { d0[j].cmp_gt(d0[i]) };
+            n - larger.bitcast_i32()
+        })
+    });
+    let is_rank: [[S::Vf32; D]; D] = core::array::from_fn(|i| {
+        core::array::from_fn(|rank| order[i].cmp_eq(S::Vi32::set1(rank as i32)).bitcast_f32())
+    });
+
+    let seed = S::Vi32::set1(seed);
+    let mut acc = (S::Vf32::zeroes(), [S::Vf32::zeroes(); D]);
+    for vertex in reach {
+        let shift = S::Vf32::set1(vertex.iter().sum::<f32>() * unskew);
+        let offset: [S::Vf32; D] = core::array::from_fn(|i| {
+            (1..D).fold(S::Vf32::set1(vertex[0]), |offset, rank| {
+                is_rank[i][rank].blendv(offset, S::Vf32::set1(vertex[rank]))
+            })
+        });
+        let d = core::array::from_fn(|i| (d0[i] - offset[i]) + shift);
+        let h = hash::<S, D>(
+            seed,
+            core::array::from_fn(|i| primed[i] + offset[i].cast_i32() * S::Vi32::set1(PRIMES[i])),
+        );
+        contribute::<S, D>(&mut acc, r_squared, d, gradient(h));
+    }
+    acc
+}
+
+/// Reflects 3d coordinates through the plane orthogonal to the main diagonal, so the diagonal
+/// of the lattice points along an axis. Applied twice it gives back the original coordinates,
+/// so it also turns derivatives back.
+#[inline(always)]
+fn rotate_3d<S: Simd>([x, y, z]: [S::Vf32; 3]) -> [S::Vf32; 3] {
+    let r = ((x + y) + z) * S::Vf32::set1(2.0 / 3.0);
+    [r - x, r - y, r - z]
+}
+
+/// Sums the kernels of the body centered cubic lattice around the rotated point `p`, made of
+/// the integer lattice and a copy shifted by a half. `reach` picks the vertices of each copy
+/// relative to its closest vertex, mirrored towards `p`; `None` visits the closest vertex and
+/// its neighbour along the axis `p` is furthest out on.
+#[inline(always)]
+fn bcc_lattice<S: Simd>(
+    p: [S::Vf32; 3],
+    seed: i32,
+    r_squared: f32,
+    reach: Option<&[[f32; 3]]>,
+) -> (S::Vf32, [S::Vf32; 3]) {
+    let mut acc = (S::Vf32::zeroes(), [S::Vf32::zeroes(); 3]);
+    for (shift, seed) in [(0.0, seed), (0.5, !seed)] {
+        let q = p.map(|v| v - S::Vf32::set1(shift));
+        let closest = q.map(|v| (v + S::Vf32::set1(0.5)).floor());
+        let d0: [S::Vf32; 3] = core::array::from_fn(|i| q[i] - closest[i]);
+        let sign = d0.map(|d| {
+            d.cmp_lt(S::Vf32::zeroes()).blendv(S::Vf32::set1(1.0), S::Vf32::set1(-1.0))
+        });
+        let mut visit = |offset: [S::Vf32; 3]| {
+            let vertex = core::array::from_fn::<_, 3, _>(|i| (closest[i] + offset[i]).cast_i32());
+            let d = core::array::from_fn(|i| d0[i] - offset[i]);
+            let [i, j, k] = core::array::from_fn(|i| vertex[i] * S::Vi32::set1(PRIMES[i]));
+            contribute::<S, 3>(&mut acc, r_squared, d, grad3d::<S>(seed, i, j, k));
+        };
+        match reach {
+            Some(reach) => {
+                for vertex in reach {
+                    visit(core::array::from_fn(|i| sign[i] * S::Vf32::set1(vertex[i])));
+                }
+            }
+            None => {
+                visit([S::Vf32::zeroes(); 3]);
+                let [ax, ay, az] = d0.map(|d| d.abs());
+                let x_out = ax.cmp_gte(ay) & ax.cmp_gte(az);
+                let y_out = ay.cmp_gte(az).and_not(x_out);
+                let z_out = !(x_out | y_out);
+                visit([x_out & sign[0], y_out & sign[1], z_out & sign[2]]);
+            }
+        }
+    }
+    acc
+}
+
+/// Samples 2-dimensional OpenSimplex2 noise and its derivative
+#[inline(always)]
+pub fn opensimplex2_2d_deriv<S: Simd>(
+    x: S::Vf32,
+    y: S::Vf32,
+    seed: i32,
+) -> (S::Vf32, [S::Vf32; 2]) {
+    let acc = simplex_lattice::<S, 2>(
+        [x, y],
+        seed,
+        SKEW_2D,
+        UNSKEW_2D,
+        R_SQUARED_FAST,
+        &REACH_2D_FAST,
+        grad2::<S>,
+    );
+    scaled::<S, 2>(acc, SCALE_2D)
+}
+
+/// Samples 3-dimensional OpenSimplex2 noise and its derivative
+#[inline(always)]
+pub fn opensimplex2_3d_deriv<S: Simd>(
+    x: S::Vf32,
+    y: S::Vf32,
+    z: S::Vf32,
+    seed: i32,
+) -> (S::Vf32, [S::Vf32; 3]) {
+    let (value, deriv) = bcc_lattice::<S>(rotate_3d::<S>([x, y, z]), seed, R_SQUARED_FAST, None);
+    scaled::<S, 3>((value, rotate_3d::<S>(deriv)), SCALE_3D)
+}
+
+/// Samples 4-dimensional OpenSimplex2 noise and its derivative
+///
+/// Five copies of a lattice, each shifted a fifth of the way along the main diagonal, make up
+/// a lattice whose kernels cover space evenly. Only the closest vertex of every copy is in
+/// reach, found by walking the simplex from the cell corner.
+#[inline(always)]
+pub fn opensimplex2_4d_deriv<S: Simd>(
+    x: S::Vf32,
+    y: S::Vf32,
+    z: S::Vf32,
+    w: S::Vf32,
+    seed: i32,
+) -> (S::Vf32, [S::Vf32; 4]) {
+    let p = [x, y, z, w];
+    let s = ((x + y) + (z + w)) * S::Vf32::set1(DUAL_SKEW_4D);
+    let cell = p.map(|v| (v + s).floor());
+    let mut si: [S::Vf32; 4] = core::array::from_fn(|i| (p[i] + s) - cell[i]);
+    let si_sum = (si[0] + si[1]) + (si[2] + si[3]);
+
+    // The copy that is sure to have a vertex in the corner simplex of the cell
+    let start = (si_sum * S::Vf32::set1(1.25)).floor();
+    let start_offset = start * S::Vf32::set1(-DUAL_STEP_4D);
+    si = si.map(|v| v + start_offset);
+    let mut ssi = (si_sum + start_offset * S::Vf32::set1(4.0)) * S::Vf32::set1(DUAL_UNSKEW_4D);
+    let mut primed: [S::Vi32; 4] =
+        core::array::from_fn(|i| cell[i].cast_i32() * S::Vi32::set1(PRIMES[i]));
+    let mut seed = S::Vi32::set1(seed) + start.cast_i32() * S::Vi32::set1(SEED_OFFSET_4D);
+
+    let mut acc = (S::Vf32::zeroes(), [S::Vf32::zeroes(); 4]);
+    for copy in 0..5 {
+        // Steps to the next corner of the simplex along the axis furthest out, when that is
+        // closer than the corner the walk is at
+        let score = S::Vf32::set1(1.0) + ssi * S::Vf32::set1(-1.0 / DUAL_UNSKEW_4D);
+        let mut taken = S::Vf32::zeroes();
+        for axis in 0..4 {
+            let mut step = !taken & si[axis].cmp_gte(score);
+            for other in 0..4 {
+                if other < axis {
+                    step = step & si[axis].cmp_gt(si[other]);
+                } else if other > axis {
+                    step = step & si[axis].cmp_gte(si[other]);
+                }
+            }
+            si[axis] = si[axis] - (step & S::Vf32::set1(1.0));
+            primed[axis] = primed[axis] + (step.bitcast_i32() & S::Vi32::set1(PRIMES[axis]));
+            taken = taken | step;
+        }
+        ssi = ssi - (taken & S::Vf32::set1(DUAL_UNSKEW_4D));
+
+        let d = si.map(|v| v + ssi);
+        contribute::<S, 4>(&mut acc, R_SQUARED_FAST, d, grad4::<S>(0, hash::<S, 4>(seed, primed)));
+
+        if copy == 4 {
+            break;
+        }
+        // On to the next copy, shifted back by a fifth
+        si = si.map(|v| v + S::Vf32::set1(DUAL_STEP_4D));
+        ssi = ssi + S::Vf32::set1(DUAL_STEP_4D * 4.0 * DUAL_UNSKEW_4D);
+        seed = seed - S::Vi32::set1(SEED_OFFSET_4D);
+        // Copies past the last one wrap around to the first
+        let wrap = start.cmp_eq(S::Vf32::set1(copy as f32)).bitcast_i32();
+        for (primed, prime) in primed.iter_mut().zip(PRIMES) {
+            *primed = *primed - (wrap & S::Vi32::set1(prime));
+        }
+        seed = seed + (wrap & S::Vi32::set1(SEED_OFFSET_4D.wrapping_mul(5)));
+    }
+    scaled::<S, 4>(acc, SCALE_4D)
+}
+
+/// Samples 2-dimensional OpenSimplex2S noise and its derivative
+#[inline(always)]
+pub fn opensimplex2s_2d_deriv<S: Simd>(
+    x: S::Vf32,
+    y: S::Vf32,
+    seed: i32,
+) -> (S::Vf32, [S::Vf32; 2]) {
+    let acc = simplex_lattice::<S, 2>(
+        [x, y],
+        seed,
+        SKEW_2D,
+        UNSKEW_2D,
+        R_SQUARED_2D,
+        &REACH_2D,
+        grad2::<S>,
+    );
+    scaled::<S, 2>(acc, SCALE_2D_SMOOTH)
+}
+
+/// Samples 3-dimensional OpenSimplex2S noise and its derivative
+#[inline(always)]
+pub fn opensimplex2s_3d_deriv<S: Simd>(
+    x: S::Vf32,
+    y: S::Vf32,
+    z: S::Vf32,
+    seed: i32,
+) -> (S::Vf32, [S::Vf32; 3]) {
+    let (value, deriv) =
+        bcc_lattice::<S>(rotate_3d::<S>([x, y, z]), seed, R_SQUARED_3D, Some(&REACH_3D));
+    scaled::<S, 3>((value, rotate_3d::<S>(deriv)), SCALE_3D_SMOOTH)
+}
+
+/// Samples 4-dimensional OpenSimplex2S noise and its derivative
+#[inline(always)]
+pub fn opensimplex2s_4d_deriv<S: Simd>(
+    x: S::Vf32,
+    y: S::Vf32,
+    z: S::Vf32,
+    w: S::Vf32,
+    seed: i32,
+) -> (S::Vf32, [S::Vf32; 4]) {
+    let acc = simplex_lattice::<S, 4>(
+        [x, y, z, w],
+        seed,
+        SKEW_4D,
+        UNSKEW_4D,
+        R_SQUARED_4D,
+        &REACH_4D,
+        |h| grad4::<S>(0, h),
+    );
+    scaled::<S, 4>(acc, SCALE_4D_SMOOTH)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simdeez::scalar::{F32x1, Scalar};
+
+    type Kernel<const D: usize> = fn([F32x1; D], i32) -> (F32x1, [F32x1; D]);
+
+    const KERNELS_2D: [Kernel<2>; 2] = [
+        |[x, y], seed| opensimplex2_2d_deriv::<Scalar>(x, y, seed),
+        |[x, y], seed| opensimplex2s_2d_deriv::<Scalar>(x, y, seed),
+    ];
+    const KERNELS_3D: [Kernel<3>; 2] = [
+        |[x, y, z], seed| opensimplex2_3d_deriv::<Scalar>(x, y, z, seed),
+        |[x, y, z], seed| opensimplex2s_3d_deriv::<Scalar>(x, y, z, seed),
+    ];
+    const KERNELS_4D: [Kernel<4>; 2] = [
+        |[x, y, z, w], seed| opensimplex2_4d_deriv::<Scalar>(x, y, z, w, seed),
+        |[x, y, z, w], seed| opensimplex2s_4d_deriv::<Scalar>(x, y, z, w, seed),
+    ];
+
+    fn check_bounds(min: f32, max: f32) {
+        assert!(min < -0.75 && min >= -1.0, "min out of range {}", min);
+        assert!(max > 0.75 && max <= 1.0, "max out of range: {}", max);
+    }
+
+    /// The point `i` of a grid `size` points wide along every axis, spaced to land on many
+    /// different spots of the lattice cells and a bit off the lattice
+    fn grid_point<const D: usize>(i: usize, size: usize) -> [f32; D] {
+        let mut i = i;
+        core::array::from_fn(|_| {
+            let v = (i % size) as f32 * 0.37 + 0.1234;
+            i /= size;
+            v
+        })
+    }
+
+    fn check_range<const D: usize>(kernel: Kernel<D>, size: usize) {
+        for seed in 0..10 {
+            let mut min = f32::INFINITY;
+            let mut max = -f32::INFINITY;
+            for i in 0..size.pow(D as u32) {
+                let n = kernel(grid_point::<D>(i, size).map(F32x1), seed).0 .0;
+                min = min.min(n);
+                max = max.max(n);
+            }
+            check_bounds(min, max);
+        }
+    }
+
+    fn check_deriv<const D: usize>(kernel: Kernel<D>, size: usize) {
+        const H: f32 = 0.01;
+        const SEEDS: i32 = 10;
+        let points = size.pow(D as u32);
+        let mut avg_err = 0.0;
+        for seed in 0..SEEDS {
+            for i in 0..points {
+                let p = grid_point::<D>(i, size);
+                let (value, d) = kernel(p.map(F32x1), seed);
+                for axis in 0..D {
+                    for h in [-H, H] {
+                        let mut moved = p;
+                        moved[axis] += h;
+                        let n = kernel(moved.map(F32x1), seed).0 .0;
+                        avg_err += (n - (value.0 + d[axis].0 * h)).abs()
+                            / (SEEDS as usize * points * D * 2) as f32;
+                    }
+                }
+            }
+        }
+        assert!(avg_err < 1e-3, "average error {}", avg_err);
+    }
+
+    #[test]
+    fn test_noise_opensimplex2_32_2d_range() {
+        for kernel in KERNELS_2D {
+            check_range::<2>(kernel, 100);
+        }
+    }
+
+    #[test]
+    fn test_noise_opensimplex2_32_2d_deriv_sanity() {
+        for kernel in KERNELS_2D {
+            check_deriv::<2>(kernel, 10);
+        }
+    }
+
+    #[test]
+    fn test_noise_opensimplex2_32_3d_range() {
+        for kernel in KERNELS_3D {
+            check_range::<3>(kernel, 25);
+        }
+    }
+
+    #[test]
+    fn test_noise_opensimplex2_32_3d_deriv_sanity() {
+        for kernel in KERNELS_3D {
+            check_deriv::<3>(kernel, 6);
+        }
+    }
+
+    #[test]
+    fn test_noise_opensimplex2_32_4d_range() {
+        for kernel in KERNELS_4D {
+            check_range::<4>(kernel, 12);
+        }
+    }
+
+    #[test]
+    fn test_noise_opensimplex2_32_4d_deriv_sanity() {
+        for kernel in KERNELS_4D {
+            check_deriv::<4>(kernel, 4);
+        }
+    }
+}
@@ -0,0 +1,498 @@
+//! OpenSimplex2 and OpenSimplex2S noise in double precision
+//!
+//! Like `opensimplex2_32`, the low 32 bits of a seed give the same noise in both precisions.
+//! Hashes are worked out in the low 32 bits of the lanes, wrapping the way they do in
+//! `opensimplex2_32`.
+use crate::noise::gradient_64::grad4;
+use crate::noise::opensimplex2_32::{
+    HASH_MULTIPLIER, PRIMES, REACH_2D, REACH_2D_FAST, REACH_3D, REACH_4D, SEED_OFFSET_4D,
+};
+use crate::noise::ops::gather_f64;
+
+use simdeez::prelude::*;
+
+/// Masks a lane down to the 32 bits the hashes wrap in
+const LOW_32: i64 = 0xffff_ffff;
+
+const SKEW_2D: f64 = 0.366_025_403_784_438_6;
+const UNSKEW_2D: f64 = 0.211_324_865_405_187_13;
+const SKEW_4D: f64 = 0.309_016_994_374_947_45;
+const UNSKEW_4D: f64 = 0.138_196_601_125_010_5;
+
+/// Skew and unskew of the 4d lattice OpenSimplex2 samples five shifted copies of
+const DUAL_SKEW_4D: f64 = -0.138_196_601_125_010_5;
+const DUAL_UNSKEW_4D: f64 = 0.309_016_994_374_947_45;
+const DUAL_STEP_4D: f64 = 0.2;
+
+/// Squared kernel radii, see `opensimplex2_32`
+const R_SQUARED_FAST: f64 = 0.5;
+const R_SQUARED_2D: f64 = 2.0 / 3.0;
+const R_SQUARED_3D: f64 = 0.75;
+const R_SQUARED_4D: f64 = 0.8;
+
+// Scaling factors found by numerical approximation
+const SCALE_2D: f64 = 97.84;
+const SCALE_3D: f64 = 75.36;
+const SCALE_4D: f64 = 61.6;
+const SCALE_2D_SMOOTH: f64 = 17.88;
+const SCALE_3D_SMOOTH: f64 = 8.873;
+const SCALE_4D_SMOOTH: f64 = 5.444;
+
+/// 24 unit gradients 15 degrees apart, turned by 7.5 degrees so none lines up with an axis
+const GRAD_2D_X: [f64; 24] = [
+    0.991_444_861_373_810_4,
+    0.923_879_532_511_286_7,
+    0.793_353_340_291_235_2,
+    0.608_761_429_008_720_7,
+    0.382_683_432_365_089_84,
+    0.130_526_192_220_051_7,
+    -0.130_526_192_220_051_7,
+    -0.382_683_432_365_089_84,
+    -0.608_761_429_008_720_7,
+    -0.793_353_340_291_235_2,
+    -0.923_879_532_511_286_7,
+    -0.991_444_861_373_810_4,
+    -0.991_444_861_373_810_4,
+    -0.923_879_532_511_286_7,
+    -0.793_353_340_291_235_2,
+    -0.608_761_429_008_720_7,
+    -0.382_683_432_365_089_84,
+    -0.130_526_192_220_051_7,
+    0.130_526_192_220_051_7,
+    0.382_683_432_365_089_84,
+    0.608_761_429_008_720_7,
+    0.793_353_340_291_235_2,
+    0.923_879_532_511_286_7,
+    0.991_444_861_373_810_4,
+];
+const GRAD_2D_Y: [f64; 24] = [
+    0.130_526_192_220_051_7,
+    0.382_683_432_365_089_84,
+    0.608_761_429_008_720_7,
+    0.793_353_340_291_235_2,
+    0.923_879_532_511_286_7,
+    0.991_444_861_373_810_4,
+    0.991_444_861_373_810_4,
+    0.923_879_532_511_286_7,
+    0.793_353_340_291_235_2,
+    0.608_761_429_008_720_7,
+    0.382_683_432_365_089_84,
+    0.130_526_192_220_051_7,
+    -0.130_526_192_220_051_7,
+    -0.382_683_432_365_089_84,
+    -0.608_761_429_008_720_7,
+    -0.793_353_340_291_235_2,
+    -0.923_879_532_511_286_7,
+    -0.991_444_861_373_810_4,
+    -0.991_444_861_373_810_4,
+    -0.923_879_532_511_286_7,
+    -0.793_353_340_291_235_2,
+    -0.608_761_429_008_720_7,
+    -0.382_683_432_365_089_84,
+    -0.130_526_192_220_051_7,
+];
+
+/// Hashes the primed coordinates of a vertex
+#[inline(always)]
+fn hash<S: Simd, const D: usize>(seed: S::Vi64, primed: [S::Vi64; D]) -> S::Vi64 {
+    let h = primed.into_iter().fold(seed, |h, p| h ^ p) * S::Vi64::set1(HASH_MULTIPLIER.into());
+    let h = h & S::Vi64::set1(LOW_32);
+    h ^ (h >> 15)
+}
+
+/// One of the 24 gradients of `GRAD_2D_X` and `GRAD_2D_Y`
+#[inline(always)]
+fn grad2<S: Simd>(hash: S::Vi64) -> [S::Vf64; 2] {
+    // The low 16 bits scaled onto the 24 gradients
+    let index = ((hash & S::Vi64::set1(0xffff)) * S::Vi64::set1(24)) >> 16;
+    unsafe {
+        [
+            gather_f64::<S>(&GRAD_2D_X, index),
+            gather_f64::<S>(&GRAD_2D_Y, index),
+        ]
+    }
+}
+
+/// The gradient towards the midpoint of an edge of a double-unit cube `gradient_32::grad3d`
+/// picks for the primed vertex `[i, j, k]`. `gradient_64` has no hash to pick it with.
+#[inline(always)]
+fn grad3<S: Simd>(seed: i64, i: S::Vi64, j: S::Vi64, k: S::Vi64) -> [S::Vf64; 3] {
+    let hash = ((i ^ S::Vi64::set1(seed)) ^ j) ^ k;
+    let hash = (((hash * hash) * S::Vi64::set1(60493)) * hash) & S::Vi64::set1(LOW_32);
+    let hash = (hash >> 13) ^ hash;
+    let h = hash & S::Vi64::set1(13);
+    let sign = |bit: i64| {
+        let positive = (hash & S::Vi64::set1(bit))
+            .cmp_eq(S::Vi64::zeroes())
+            .bitcast_f64();
+        positive.blendv(S::Vf64::set1(-1.0), S::Vf64::set1(1.0))
+    };
+    let (first, second) = (sign(1), sign(2));
+
+    // The first sign goes on x when h < 8 and on y otherwise. The second goes on y when h < 2,
+    // on x when h is 12 and on z otherwise.
+    let lt8 = S::Vi64::set1(8).cmp_gt(h).bitcast_f64();
+    let lt2 = S::Vi64::set1(2).cmp_gt(h).bitcast_f64();
+    let is12 = h.cmp_eq(S::Vi64::set1(12)).bitcast_f64();
+    let zero = S::Vf64::zeroes();
+    [
+        is12.blendv(lt8.blendv(zero, first), second),
+        lt2.blendv(lt8.blendv(first, zero), second),
+        (is12 | lt2).blendv(second, zero),
+    ]
+}
+
+/// Adds the contribution of a vertex `d` away with gradient `g` and its derivative to `acc`
+#[inline(always)]
+fn contribute<S: Simd, const D: usize>(
+    acc: &mut (S::Vf64, [S::Vf64; D]),
+    r_squared: f64,
+    d: [S::Vf64; D],
+    g: [S::Vf64; D],
+) {
+    let a = d
+        .iter()
+        .fold(S::Vf64::set1(r_squared), |a, &d| a - d * d)
+        .max(S::Vf64::zeroes());
+    let a2 = a * a;
+    let a4 = a2 * a2;
+    let dot = d
+        .iter()
+        .zip(g)
+        .fold(S::Vf64::zeroes(), |sum, (&d, g)| (d * g) + sum);
+    acc.0 = (a4 * dot) + acc.0;
+    // d/dd of a^4 (g . d) is a^4 g - 8 a^3 (g . d) d
+    let falloff = a2 * a * dot * S::Vf64::set1(-8.0);
+    for ((deriv, d), g) in acc.1.iter_mut().zip(d).zip(g) {
+        *deriv = (g * a4) + (d * falloff) + *deriv;
+    }
+}
+
+/// Scales a sum of contributions into range
+#[inline(always)]
+fn scaled<S: Simd, const D: usize>(
+    (value, deriv): (S::Vf64, [S::Vf64; D]),
+    scale: f64,
+) -> (S::Vf64, [S::Vf64; D]) {
+    let scale = S::Vf64::set1(scale);
+    (value * scale, deriv.map(|d| d * scale))
+}
+
+/// Sums the kernels of every vertex in `reach` around the cell of the skewed simplex lattice
+/// `p` is in. `reach` is in the order of the axes from the largest offset into the cell to the
+/// smallest, which picks the simplex the point is in.
+#[inline(always)]
+fn simplex_lattice<S: Simd, const D: usize>(
+    p: [S::Vf64; D],
+    seed: i64,
+    skew: f64,
+    unskew: f64,
+    r_squared: f64,
+    reach: &[[f32; D]],
+    gradient: impl Fn(S::Vi64) -> [S::Vf64; D],
+) -> (S::Vf64, [S::Vf64; D]) {
+    let s = p.iter().fold(S::Vf64::zeroes(), |s, &v| s + v) * S::Vf64::set1(skew);
+    let cell = p.map(|v| (v + s).floor());
+    let t = cell.iter().fold(S::Vf64::zeroes(), |t, &v| t + v) * S::Vf64::set1(unskew);
+    let d0: [S::Vf64; D] = core::array::from_fn(|i| p[i] - (cell[i] - t));
+    let primed: [S::Vi64; D] =
+        core::array::from_fn(|i| cell[i].cast_i64() * S::Vi64::set1(PRIMES[i].into()));
+
+    // How many axes have a larger offset, ties go to the lower axis. Masks are -1 where set,
+    // so subtracting a mask counts it.
+    let order: [S::Vi64; D] = core::array::from_fn(|i| {
+        (0..D).filter(|&j| j != i).fold(S::Vi64::zeroes(), |n, j| {
+            let larger = if j < i {
+                d0[j].cmp_gte(d0[i])
+            } else {
+                d0[j].cmp_gt(d0[i])
+            };
+            n - larger.bitcast_i64()
+        })
+    });
+    let is_rank: [[S::Vf64; D]; D] = core::array::from_fn(|i| {
+        core::array::from_fn(|rank| order[i].cmp_eq(S::Vi64::set1(rank as i64)).bitcast_f64())
+    });
+
+    let seed = S::Vi64::set1(seed);
+    let mut acc = (S::Vf64::zeroes(), [S::Vf64::zeroes(); D]);
+    for vertex in reach {
+        let vertex = vertex.map(f64::from);
+        let shift = S::Vf64::set1(vertex.iter().sum::<f64>() * unskew);
+        let offset: [S::Vf64; D] = core::array::from_fn(|i| {
+            (1..D).fold(S::Vf64::set1(vertex[0]), |offset, rank| {
+                is_rank[i][rank].blendv(offset, S::Vf64::set1(vertex[rank]))
+            })
+        });
+        let d = core::array::from_fn(|i| (d0[i] - offset[i]) + shift);
+        let h = hash::<S, D>(
+            seed,
+            core::array::from_fn(|i| {
+                primed[i] + offset[i].cast_i64() * S::Vi64::set1(PRIMES[i].into())
+            }),
+        );
+        contribute::<S, D>(&mut acc, r_squared, d, gradient(h));
+    }
+    acc
+}
+
+/// Reflects 3d coordinates through the plane orthogonal to the main diagonal, so the diagonal
+/// of the lattice points along an axis. Applied twice it gives back the original coordinates,
+/// so it also turns derivatives back.
+#[inline(always)]
+fn rotate_3d<S: Simd>([x, y, z]: [S::Vf64; 3]) -> [S::Vf64; 3] {
+    let r = ((x + y) + z) * S::Vf64::set1(2.0 / 3.0);
+    [r - x, r - y, r - z]
+}
+
+/// Sums the kernels of the body centered cubic lattice around the rotated point `p`, made of
+/// the integer lattice and a copy shifted by a half. `reach` picks the vertices of each copy
+/// relative to its closest vertex, mirrored towards `p`; `None` visits the closest vertex and
+/// its neighbour along the axis `p` is furthest out on.
+#[inline(always)]
+fn bcc_lattice<S: Simd>(
+    p: [S::Vf64; 3],
+    seed: i64,
+    r_squared: f64,
+    reach: Option<&[[f32; 3]]>,
+) -> (S::Vf64, [S::Vf64; 3]) {
+    let mut acc = (S::Vf64::zeroes(), [S::Vf64::zeroes(); 3]);
+    for (shift, seed) in [(0.0, seed), (0.5, !seed)] {
+        let q = p.map(|v| v - S::Vf64::set1(shift));
+        let closest = q.map(|v| (v + S::Vf64::set1(0.5)).floor());
+        let d0: [S::Vf64; 3] = core::array::from_fn(|i| q[i] - closest[i]);
+        let sign = d0.map(|d| {
+            d.cmp_lt(S::Vf64::zeroes())
+                .blendv(S::Vf64::set1(1.0), S::Vf64::set1(-1.0))
+        });
+        let mut visit = |offset: [S::Vf64; 3]| {
+            let vertex = core::array::from_fn::<_, 3, _>(|i| (closest[i] + offset[i]).cast_i64());
+            let d = core::array::from_fn(|i| d0[i] - offset[i]);
+            let [i, j, k] = core::array::from_fn(|i| vertex[i] * S::Vi64::set1(PRIMES[i].into()));
+            contribute::<S, 3>(&mut acc, r_squared, d, grad3::<S>(seed, i, j, k));
+        };
+        match reach {
+            Some(reach) => {
+                for vertex in reach {
+                    visit(core::array::from_fn(|i| {
+                        sign[i] * S::Vf64::set1(vertex[i].into())
+                    }));
+                }
+            }
+            None => {
+                visit([S::Vf64::zeroes(); 3]);
+                let [ax, ay, az] = d0.map(|d| d.abs());
+                let x_out = ax.cmp_gte(ay) & ax.cmp_gte(az);
+                let y_out = ay.cmp_gte(az).and_not(x_out);
+                let z_out = !(x_out | y_out);
+                visit([x_out & sign[0], y_out & sign[1], z_out & sign[2]]);
+            }
+        }
+    }
+    acc
+}
+
+/// Samples 2-dimensional OpenSimplex2 noise and its derivative
+#[inline(always)]
+pub fn opensimplex2_2d_deriv<S: Simd>(
+    x: S::Vf64,
+    y: S::Vf64,
+    seed: i64,
+) -> (S::Vf64, [S::Vf64; 2]) {
+    let acc = simplex_lattice::<S, 2>(
+        [x, y],
+        seed,
+        SKEW_2D,
+        UNSKEW_2D,
+        R_SQUARED_FAST,
+        &REACH_2D_FAST,
+        grad2::<S>,
+    );
+    scaled::<S, 2>(acc, SCALE_2D)
+}
+
+/// Samples 3-dimensional OpenSimplex2 noise and its derivative
+#[inline(always)]
+pub fn opensimplex2_3d_deriv<S: Simd>(
+    x: S::Vf64,
+    y: S::Vf64,
+    z: S::Vf64,
+    seed: i64,
+) -> (S::Vf64, [S::Vf64; 3]) {
+    let (value, deriv) = bcc_lattice::<S>(rotate_3d::<S>([x, y, z]), seed, R_SQUARED_FAST, None);
+    scaled::<S, 3>((value, rotate_3d::<S>(deriv)), SCALE_3D)
+}
+
+/// Samples 4-dimensional OpenSimplex2 noise and its derivative, see
+/// `opensimplex2_32::opensimplex2_4d_deriv`
+#[inline(always)]
+pub fn opensimplex2_4d_deriv<S: Simd>(
+    x: S::Vf64,
+    y: S::Vf64,
+    z: S::Vf64,
+    w: S::Vf64,
+    seed: i64,
+) -> (S::Vf64, [S::Vf64; 4]) {
+    let p = [x, y, z, w];
+    let s = ((x + y) + (z + w)) * S::Vf64::set1(DUAL_SKEW_4D);
+    let cell = p.map(|v| (v + s).floor());
+    let mut si: [S::Vf64; 4] = core::array::from_fn(|i| (p[i] + s) - cell[i]);
+    let si_sum = (si[0] + si[1]) + (si[2] + si[3]);
+
+    // The copy that is sure to have a vertex in the corner simplex of the cell
+    let start = (si_sum * S::Vf64::set1(1.25)).floor();
+    let start_offset = start * S::Vf64::set1(-DUAL_STEP_4D);
+    si = si.map(|v| v + start_offset);
+    let mut ssi = (si_sum + start_offset * S::Vf64::set1(4.0)) * S::Vf64::set1(DUAL_UNSKEW_4D);
+    let mut primed: [S::Vi64; 4] =
+        core::array::from_fn(|i| cell[i].cast_i64() * S::Vi64::set1(PRIMES[i].into()));
+    let mut seed = S::Vi64::set1(seed) + start.cast_i64() * S::Vi64::set1(SEED_OFFSET_4D.into());
+
+    let mut acc = (S::Vf64::zeroes(), [S::Vf64::zeroes(); 4]);
+    for copy in 0..5 {
+        // Steps to the next corner of the simplex along the axis furthest out, when that is
+        // closer than the corner the walk is at
+        let score = S::Vf64::set1(1.0) + ssi * S::Vf64::set1(-1.0 / DUAL_UNSKEW_4D);
+        let mut taken = S::Vf64::zeroes();
+        for axis in 0..4 {
+            let mut step = !taken & si[axis].cmp_gte(score);
+            for other in 0..4 {
+                if other < axis {
+                    step = step & si[axis].cmp_gt(si[other]);
+                } else if other > axis {
+                    step = step & si[axis].cmp_gte(si[other]);
+                }
+            }
+            si[axis] = si[axis] - (step & S::Vf64::set1(1.0));
+            primed[axis] = primed[axis] + (step.bitcast_i64() & S::Vi64::set1(PRIMES[axis].into()));
+            taken = taken | step;
+        }
+        ssi = ssi - (taken & S::Vf64::set1(DUAL_UNSKEW_4D));
+
+        let d = si.map(|v| v + ssi);
+        contribute::<S, 4>(
+            &mut acc,
+            R_SQUARED_FAST,
+            d,
+            grad4::<S>(0, hash::<S, 4>(seed, primed)),
+        );
+
+        if copy == 4 {
+            break;
+        }
+        // On to the next copy, shifted back by a fifth
+        si = si.map(|v| v + S::Vf64::set1(DUAL_STEP_4D));
+        ssi = ssi + S::Vf64::set1(DUAL_STEP_4D * 4.0 * DUAL_UNSKEW_4D);
+        seed = seed - S::Vi64::set1(SEED_OFFSET_4D.into());
+        // Copies past the last one wrap around to the first
+        let wrap = start.cmp_eq(S::Vf64::set1(copy as f64)).bitcast_i64();
+        for (primed, prime) in primed.iter_mut().zip(PRIMES) {
+            *primed = *primed - (wrap & S::Vi64::set1(prime.into()));
+        }
+        seed = seed + (wrap & S::Vi64::set1(SEED_OFFSET_4D.wrapping_mul(5).into()));
+    }
+    scaled::<S, 4>(acc, SCALE_4D)
+}
+
+/// Samples 2-dimensional OpenSimplex2S noise and its derivative
+#[inline(always)]
+pub fn opensimplex2s_2d_deriv<S: Simd>(
+    x: S::Vf64,
+    y: S::Vf64,
+    seed: i64,
+) -> (S::Vf64, [S::Vf64; 2]) {
+    let acc = simplex_lattice::<S, 2>(
+        [x, y],
+        seed,
+        SKEW_2D,
+        UNSKEW_2D,
+        R_SQUARED_2D,
+        &REACH_2D,
+        grad2::<S>,
+    );
+    scaled::<S, 2>(acc, SCALE_2D_SMOOTH)
+}
+
+/// Samples 3-dimensional OpenSimplex2S noise and its derivative
+#[inline(always)]
+pub fn opensimplex2s_3d_deriv<S: Simd>(
+    x: S::Vf64,
+    y: S::Vf64,
+    z: S::Vf64,
+    seed: i64,
+) -> (S::Vf64, [S::Vf64; 3]) {
+    let (value, deriv) = bcc_lattice::<S>(
+        rotate_3d::<S>([x, y, z]),
+        seed,
+        R_SQUARED_3D,
+        Some(&REACH_3D),
+    );
+    scaled::<S, 3>((value, rotate_3d::<S>(deriv)), SCALE_3D_SMOOTH)
+}
+
+/// Samples 4-dimensional OpenSimplex2S noise and its derivative
+#[inline(always)]
+pub fn opensimplex2s_4d_deriv<S: Simd>(
+    x: S::Vf64,
+    y: S::Vf64,
+    z: S::Vf64,
+    w: S::Vf64,
+    seed: i64,
+) -> (S::Vf64, [S::Vf64; 4]) {
+    let acc = simplex_lattice::<S, 4>(
+        [x, y, z, w],
+        seed,
+        SKEW_4D,
+        UNSKEW_4D,
+        R_SQUARED_4D,
+        &REACH_4D,
+        |h| grad4::<S>(0, h),
+    );
+    scaled::<S, 4>(acc, SCALE_4D_SMOOTH)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::noise::opensimplex2_32;
+    use simdeez::scalar::{F32x1, F64x1, Scalar};
+
+    #[test]
+    fn test_noise_opensimplex2_64_matches_opensimplex2_32() {
+        for seed in [0, 1, -7, 0x1234_5678] {
+            for i in 0..1000 {
+                let p32 = [0.37, -0.21, 0.13, 0.29].map(|step| F32x1(i as f32 * step + 0.1234));
+                let [x, y, z, w] = p32;
+                let expected = [
+                    opensimplex2_32::opensimplex2_2d_deriv::<Scalar>(x, y, seed).0,
+                    opensimplex2_32::opensimplex2_3d_deriv::<Scalar>(x, y, z, seed).0,
+                    opensimplex2_32::opensimplex2_4d_deriv::<Scalar>(x, y, z, w, seed).0,
+                    opensimplex2_32::opensimplex2s_2d_deriv::<Scalar>(x, y, seed).0,
+                    opensimplex2_32::opensimplex2s_3d_deriv::<Scalar>(x, y, z, seed).0,
+                    opensimplex2_32::opensimplex2s_4d_deriv::<Scalar>(x, y, z, w, seed).0,
+                ];
+                let [x, y, z, w] = p32.map(|v| F64x1(v.0.into()));
+                let seed = seed.into();
+                let actual = [
+                    opensimplex2_2d_deriv::<Scalar>(x, y, seed).0,
+                    opensimplex2_3d_deriv::<Scalar>(x, y, z, seed).0,
+                    opensimplex2_4d_deriv::<Scalar>(x, y, z, w, seed).0,
+                    opensimplex2s_2d_deriv::<Scalar>(x, y, seed).0,
+                    opensimplex2s_3d_deriv::<Scalar>(x, y, z, seed).0,
+                    opensimplex2s_4d_deriv::<Scalar>(x, y, z, w, seed).0,
+                ];
+                for (expected, actual) in expected.into_iter().zip(actual) {
+                    let (expected, actual) = (f64::from(expected.0), actual.0);
+                    assert!(
+                        (expected - actual).abs() < 1e-3,
+                        "{} != {} at {} seed {}",
+                        expected,
+                        actual,
+                        i,
+                        seed
+                    );
+                }
+            }
+        }
+    }
+}
@@ -23,3 +23,27 @@ pub unsafe fn gather_64<S: Simd>(arr: &[i64], indices: S::Vi64) -> S::Vi64 {
     }
     dst
 }
+
+/// # Safety:
+/// All array indices must be in-bounds.
+#[inline(always)]
+pub unsafe fn gather_f32<S: Simd>(arr: &[f32], indices: S::Vi32) -> S::Vf32 {
+    let width = S::Vf32::WIDTH;
+    let mut dst = S::Vf32::zeroes();
+    for i in 0..width {
+        *dst.get_unchecked_mut(i) = *arr.get_unchecked(indices[i] as usize);
+    }
+    dst
+}
+
+/// # Safety:
+/// All array indices must be in-bounds.
+#[inline(always)]
+pub unsafe fn gather_f64<S: Simd>(arr: &[f64], indices: S::Vi64) -> S::Vf64 {
+    let width = S::Vf64::WIDTH;
+    let mut dst = S::Vf64::zeroes();
+    for i in 0..width {
+        *dst.get_unchecked_mut(i) = *arr.get_unchecked(indices[i] as usize);
+    }
+    dst
+}
@@ -0,0 +1,265 @@
+//! Low-level Perlin noise primitives
+//!
+//! Ken Perlin's improved noise: the gradients of the corners of the cube a point is in, picked
+//! by hashing the corners through the permutation table, blended with a quintic fade. A seed
+//! of 0 gives the noise of the reference implementation, 2d and 1d noise are its slices at
+//! z = 0 and y = z = 0, scaled into [-1, 1].
+
+use crate::noise::ops::gather_32;
+use crate::noise::simplex_32::PERM;
+
+use simdeez::prelude::*;
+
+// One over the largest sum the gradients of a cell can add up to, 1.036 in 3d found by searching
+// the cell. 2d noise reaches 1 and 1d noise 0.5.
+const SCALE_1D: f32 = 2.0;
+const SCALE_2D: f32 = 1.0;
+const SCALE_3D: f32 = 0.964_9;
+
+/// One of the 12 gradients towards the edges of a cube of the reference implementation, picked
+/// by the low 4 bits of `hash`
+#[inline(always)]
+fn grad<S: Simd>(hash: S::Vi32) -> [S::Vf32; 3] {
+    let h = hash & S::Vi32::set1(15);
+    let is = |value: i32| h.cmp_eq(S::Vi32::set1(value)).bitcast_f32();
+    let sign = |bit: i32| {
+        let positive = (h & S::Vi32::set1(bit)).cmp_eq(S::Vi32::zeroes()).bitcast_f32();
+        positive.blendv(S::Vf32::set1(-1.0), S::Vf32::set1(1.0))
+    };
+    let (u, v) = (sign(1), sign(2));
+    let u_is_x = S::Vi32::set1(8).cmp_gt(h).bitcast_f32();
+    let v_is_y = S::Vi32::set1(4).cmp_gt(h).bitcast_f32();
+    let v_is_x = (is(12) | is(14)).and_not(v_is_y);
+    let v_is_z = !(v_is_x | v_is_y);
+    let zero = S::Vf32::zeroes();
+    [
+        u_is_x.blendv(zero, u) + v_is_x.blendv(zero, v),
+        u_is_x.blendv(u, zero) + v_is_y.blendv(zero, v),
+        v_is_z.blendv(zero, v),
+    ]
+}
+
+/// Hashes the lattice point `coords` through the permutation table, the way the reference
+/// implementation does with the axes past `D` at 0. Every axis mixes 8 bits of the seed into its
+/// coordinate, and the bits left over go into the hash.
+#[inline(always)]
+pub(crate) fn hash<S: Simd, const D: usize>(coords: [S::Vi32; D], seed: i32) -> S::Vi32 {
+    let axes = D.max(3);
+    let hash = (0..axes).fold(S::Vi32::zeroes(), |hash, axis| {
+        let coord = coords.get(axis).map_or(S::Vi32::zeroes(), |&c| c & S::Vi32::set1(0xff));
+        let seed = S::Vi32::set1((seed >> (8 * axis)) & 0xff);
+        // Safety: both summands are in 0..=255, the table is 512 long
+        unsafe { gather_32::<S>(&PERM, hash + (coord ^ seed)) }
+    });
+    hash ^ S::Vi32::set1(seed.checked_shr(8 * axes as u32).unwrap_or(0))
+}
+
+/// Samples `D`-dimensional Perlin noise and its derivative, unscaled
+#[inline(always)]
+fn perlin_deriv<S: Simd, const D: usize>(p: [S::Vf32; D], seed: i32) -> (S::Vf32, [S::Vf32; D]) {
+    let cell = p.map(|v| v.floor());
+    let f: [S::Vf32; D] = core::array::from_fn(|i| p[i] - cell[i]);
+    let i0 = cell.map(|v| v.cast_i32());
+    // 6t^5 - 15t^4 + 10t^3 and its derivative 30t^2 (t - 1)^2
+    let fade = f.map(|t| {
+        t * t * t * (t * (t * S::Vf32::set1(6.0) - S::Vf32::set1(15.0)) + S::Vf32::set1(10.0))
+    });
+    let dfade = f.map(|t| {
+        let t1 = t - S::Vf32::set1(1.0);
+        t * t * t1 * t1 * S::Vf32::set1(30.0)
+    });
+
+    let mut value = S::Vf32::zeroes();
+    let mut deriv = [S::Vf32::zeroes(); D];
+    for corner in 0..1 << D {
+        let far = |axis: usize| corner >> axis & 1 == 1;
+        let coords = core::array::from_fn(|i| i0[i] + S::Vi32::set1(far(i) as i32));
+        let g = grad::<S>(hash::<S, D>(coords, seed));
+        let d: [S::Vf32; D] =
+            core::array::from_fn(|i| if far(i) { f[i] - S::Vf32::set1(1.0) } else { f[i] });
+        let dot = (0..D).fold(S::Vf32::zeroes(), |dot, i| (g[i] * d[i]) + dot);
+        let w: [S::Vf32; D] =
+            core::array::from_fn(|i| if far(i) { fade[i] } else { S::Vf32::set1(1.0) - fade[i] });
+        let weight = w.iter().fold(S::Vf32::set1(1.0), |weight, &w| weight * w);
+        value = (weight * dot) + value;
+        for axis in 0..D {
+            let others = (0..D)
+                .filter(|&i| i != axis)
+                .fold(S::Vf32::set1(1.0), |others, i| others * w[i]);
+            let dw = if far(axis) { dfade[axis] } else { S::Vf32::zeroes() - dfade[axis] };
+            deriv[axis] = (weight * g[axis]) + (dw * others * dot) + deriv[axis];
+        }
+    }
+    (value, deriv)
+}
+
+/// Samples 1-dimensional Perlin noise
+///
+/// Produces a value -1 ≤ n ≤ 1.
+#[inline(always)]
+pub fn perlin_1d<S: Simd>(x: S::Vf32, seed: i32) -> S::Vf32 {
+    perlin_1d_deriv::<S>(x, seed).0
+}
+
+/// Like `perlin_1d`, but also computes the derivative
+#[inline(always)]
+pub fn perlin_1d_deriv<S: Simd>(x: S::Vf32, seed: i32) -> (S::Vf32, S::Vf32) {
+    let (value, [dx]) = perlin_deriv::<S, 1>([x], seed);
+    let scale = S::Vf32::set1(SCALE_1D);
+    (value * scale, dx * scale)
+}
+
+/// Samples 2-dimensional Perlin noise
+///
+/// Produces a value -1 ≤ n ≤ 1.
+#[inline(always)]
+pub fn perlin_2d<S: Simd>(x: S::Vf32, y: S::Vf32, seed: i32) -> S::Vf32 {
+    perlin_2d_deriv::<S>(x, y, seed).0
+}
+
+/// Like `perlin_2d`, but also computes the derivative
+#[inline(always)]
+pub fn perlin_2d_deriv<S: Simd>(x: S::Vf32, y: S::Vf32, seed: i32) -> (S::Vf32, [S::Vf32; 2]) {
+    let (value, deriv) = perlin_deriv::<S, 2>([x, y], seed);
+    let scale = S::Vf32::set1(SCALE_2D);
+    (value * scale, deriv.map(|d| d * scale))
+}
+
+/// Samples 3-dimensional Perlin noise
+///
+/// Produces a value -1 ≤ n ≤ 1.
+#[inline(always)]
+pub fn perlin_3d<S: Simd>(x: S::Vf32, y: S::Vf32, z: S::Vf32, seed: i32) -> S::Vf32 {
+    perlin_3d_deriv::<S>(x, y, z, seed).0
+}
+
+/// Like `perlin_3d`, but also computes the derivative
+#[inline(always)]
+pub fn perlin_3d_deriv<S: Simd>(
+    x: S::Vf32,
+    y: S::Vf32,
+    z: S::Vf32,
+    seed: i32,
+) -> (S::Vf32, [S::Vf32; 3]) {
+    let (value, deriv) = perlin_deriv::<S, 3>([x, y, z], seed);
+    let scale = S::Vf32::set1(SCALE_3D);
+    (value * scale, deriv.map(|d| d * scale))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simdeez::scalar::{F32x1, Scalar};
+
+    fn check_bounds(min: f32, max: f32) {
+        assert!(min < -0.75 && min >= -1.0, "min out of range {}", min);
+        assert!(max > 0.75 && max <= 1.0, "max out of range: {}", max);
+    }
+
+    /// Calls `sample` with the points of a grid `size` points wide along every axis, 0.37 apart so the points
+    /// land on many different spots of the cells, and a bit off the lattice
+    fn sample_grid<const D: usize>(size: usize, mut sample: impl FnMut([f32; D])) {
+        for i in 0..size.pow(D as u32) {
+            let mut i = i;
+            sample(core::array::from_fn(|_| {
+                let v = (i % size) as f32 * 0.37 + 0.1234;
+                i /= size;
+                v
+            }));
+        }
+    }
+
+    fn check_range<const D: usize>(size: usize, noise: impl Fn([f32; D], i32) -> f32) {
+        for seed in 0..10 {
+            let mut min = f32::INFINITY;
+            let mut max = -f32::INFINITY;
+            sample_grid::<D>(size, |p| {
+                let n = noise(p, seed);
+                min = min.min(n);
+                max = max.max(n);
+            });
+            check_bounds(min, max);
+        }
+    }
+
+    fn check_deriv<const D: usize>(
+        size: usize,
+        noise: impl Fn([f32; D], i32) -> (f32, [f32; D]),
+    ) {
+        const H: f32 = 0.01;
+        const SEEDS: i32 = 10;
+        let mut avg_err = 0.0;
+        let count = (SEEDS as usize * size.pow(D as u32) * D * 2) as f32;
+        for seed in 0..SEEDS {
+            sample_grid::<D>(size, |p| {
+                let (value, d) = noise(p, seed);
+                for axis in 0..D {
+                    for h in [-H, H] {
+                        let mut moved = p;
+                        moved[axis] += h;
+                        avg_err += (noise(moved, seed).0 - (value + d[axis] * h)).abs() / count;
+                    }
+                }
+            });
+        }
+        assert!(avg_err < 1e-3, "average error {}", avg_err);
+    }
+
+    #[test]
+    fn test_noise_perlin32_reference() {
+        // Ken Perlin's reference implementation at a few points, scaled like the 3d noise
+        let expected = [
+            ([0.3, 1.7, 2.2], -0.249_968_71),
+            ([10.5, -3.25, 7.75], 0.095_024_11),
+            ([-0.1, 0.9, -5.5], 0.206_745_42),
+        ];
+        for ([x, y, z], reference) in expected {
+            let n = perlin_3d::<Scalar>(F32x1(x), F32x1(y), F32x1(z), 0).0;
+            assert!((n - reference * SCALE_3D).abs() < 1e-6, "{} at {} {} {}", n, x, y, z);
+        }
+        // 2d noise is the slice at z = 0
+        let n = perlin_2d::<Scalar>(F32x1(0.3), F32x1(1.7), 0).0;
+        assert!((n - 0.251_076 * SCALE_2D).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_noise_perlin32_1d_range() {
+        check_range::<1>(1000, |[x], seed| perlin_1d::<Scalar>(F32x1(x), seed).0);
+    }
+
+    #[test]
+    fn test_noise_perlin32_1d_deriv_sanity() {
+        check_deriv::<1>(1000, |[x], seed| {
+            let (value, dx) = perlin_1d_deriv::<Scalar>(F32x1(x), seed);
+            (value.0, [dx.0])
+        });
+    }
+
+    #[test]
+    fn test_noise_perlin32_2d_range() {
+        check_range::<2>(100, |[x, y], seed| perlin_2d::<Scalar>(F32x1(x), F32x1(y), seed).0);
+    }
+
+    #[test]
+    fn test_noise_perlin32_2d_deriv_sanity() {
+        check_deriv::<2>(30, |[x, y], seed| {
+            let (value, d) = perlin_2d_deriv::<Scalar>(F32x1(x), F32x1(y), seed);
+            (value.0, d.map(|d| d.0))
+        });
+    }
+
+    #[test]
+    fn test_noise_perlin32_3d_range() {
+        check_range::<3>(50, |[x, y, z], seed| {
+            perlin_3d::<Scalar>(F32x1(x), F32x1(y), F32x1(z), seed).0
+        });
+    }
+
+    #[test]
+    fn test_noise_perlin32_3d_deriv_sanity() {
+        check_deriv::<3>(10, |[x, y, z], seed| {
+            let (value, d) = perlin_3d_deriv::<Scalar>(F32x1(x), F32x1(y), F32x1(z), seed);
+            (value.0, d.map(|d| d.0))
+        });
+    }
+}
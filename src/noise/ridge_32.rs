@@ -0,0 +1,242 @@
+use crate::noise::lattice::Lattice4d;
+use crate::noise::octaves::OctaveOptions;
+
+use simdeez::prelude::*;
+
+/// Turns a raw simplex sample into a ridge: `(offset - |n|)^2`
+#[inline(always)]
+fn ridge_signal<S: Simd>(n: S::Vf32, offset: S::Vf32) -> S::Vf32 {
+    let signal = offset - n.abs();
+    signal * signal
+}
+
+/// Weight for the next octave, the previous signal scaled by `gain` and clamped to [0, 1].
+///
+/// This is what makes the ridges sharp: detail is only added where the previous octaves
+/// already produced a ridge.
+#[inline(always)]
+fn octave_weight<S: Simd>(signal: S::Vf32, gain: S::Vf32) -> S::Vf32 {
+    (signal * gain)
+        .max(S::Vf32::zeroes())
+        .min(S::Vf32::set1(1.0))
+}
+
+/// Sums the octaves of a ridged multifractal at `p`, `noise` samples the lattice at the
+/// coordinates and seed of an octave
+#[inline(always)]
+fn ridge<S: Simd, L, const D: usize>(
+    mut p: [S::Vf32; D],
+    lacunarity: S::Vf32,
+    gain: S::Vf32,
+    persistence: S::Vf32,
+    offset: S::Vf32,
+    octaves: u8,
+    seed: i32,
+    options: OctaveOptions<L>,
+    noise: impl Fn([S::Vf32; D], i32) -> S::Vf32,
+) -> S::Vf32 {
+    let mut signal = ridge_signal::<S>(noise(p, seed), offset);
+    let mut result = signal;
+    let mut amp = S::Vf32::set1(1.0);
+
+    // The fraction octave is a whole octave with its amplitude scaled by the fraction
+    let mut add_octave = |octave: u8, fraction: Option<S::Vf32>| {
+        for (axis, v) in p.iter_mut().enumerate() {
+            *v = options.offset_32::<S>(*v * lacunarity, axis);
+        }
+        amp = amp * persistence;
+        if let Some(fraction) = fraction {
+            amp = amp * fraction;
+        }
+        let weight = octave_weight::<S>(signal, gain);
+        let n = noise(p, options.seed_32(seed, octave));
+        signal = ridge_signal::<S>(n, offset) * weight;
+        result = (signal * amp) + result;
+    };
+
+    for octave in 1..octaves {
+        add_octave(octave, None);
+    }
+    if options.fraction > 0.0 {
+        add_octave(octaves, Some(S::Vf32::set1(options.fraction)));
+    }
+
+    result
+}
+
+/// Musgrave's ridged multifractal. Octave `i` adds its ridge signal, weighted by the signal of
+/// the octave before, times `persistence^i`. That is the spectral weight `lacunarity^-H` of the
+/// original formulation, 0.5 with a lacunarity of 2 is H = 1.
+#[inline(always)]
+pub fn ridge_1d<S: Simd>(
+    x: S::Vf32,
+    lacunarity: S::Vf32,
+    gain: S::Vf32,
+    persistence: S::Vf32,
+    offset: S::Vf32,
+    octaves: u8,
+    seed: i32,
+    options: OctaveOptions,
+) -> S::Vf32 {
+    ridge::<S, _, 1>(
+        [x],
+        lacunarity,
+        gain,
+        persistence,
+        offset,
+        octaves,
+        seed,
+        options,
+        |[x], seed| options.lattice.noise_1d_32::<S>(x, seed),
+    )
+}
+
+#[inline(always)]
+pub fn ridge_2d<S: Simd>(
+    x: S::Vf32,
+    y: S::Vf32,
+    lac: S::Vf32,
+    gain: S::Vf32,
+    persistence: S::Vf32,
+    offset: S::Vf32,
+    octaves: u8,
+    seed: i32,
+    options: OctaveOptions,
+) -> S::Vf32 {
+    ridge::<S, _, 2>(
+        [x, y],
+        lac,
+        gain,
+        persistence,
+        offset,
+        octaves,
+        seed,
+        options,
+        |[x, y], seed| options.lattice.noise_2d_32::<S>(x, y, seed),
+    )
+}
+
+#[inline(always)]
+pub fn ridge_3d<S: Simd>(
+    x: S::Vf32,
+    y: S::Vf32,
+    z: S::Vf32,
+    lac: S::Vf32,
+    gain: S::Vf32,
+    persistence: S::Vf32,
+    offset: S::Vf32,
+    octaves: u8,
+    seed: i32,
+    options: OctaveOptions,
+) -> S::Vf32 {
+    ridge::<S, _, 3>(
+        [x, y, z],
+        lac,
+        gain,
+        persistence,
+        offset,
+        octaves,
+        seed,
+        options,
+        |[x, y, z], seed| options.lattice.noise_3d_32::<S>(x, y, z, seed),
+    )
+}
+
+#[inline(always)]
+pub fn ridge_4d<S: Simd>(
+    x: S::Vf32,
+    y: S::Vf32,
+    z: S::Vf32,
+    w: S::Vf32,
+    lac: S::Vf32,
+    gain: S::Vf32,
+    persistence: S::Vf32,
+    offset: S::Vf32,
+    octaves: u8,
+    seed: i32,
+    options: OctaveOptions<Lattice4d>,
+) -> S::Vf32 {
+    ridge::<S, _, 4>(
+        [x, y, z, w],
+        lac,
+        gain,
+        persistence,
+        offset,
+        octaves,
+        seed,
+        options,
+        |[x, y, z, w], seed| options.lattice.noise_4d_32::<S>(x, y, z, w, seed),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::noise::simplex_32::hashed_simplex_2d;
+    use simdeez::scalar::{F32x1, Scalar};
+
+    #[test]
+    fn test_noise_ridge32_2d_range() {
+        const OCTAVES: u8 = 4;
+        let mut min = f32::INFINITY;
+        let mut max = -f32::INFINITY;
+        for y in 0..100 {
+            for x in 0..100 {
+                let n = ridge_2d::<Scalar>(
+                    F32x1(x as f32 / 10.0),
+                    F32x1(y as f32 / 10.0),
+                    F32x1(2.0),
+                    F32x1(2.0),
+                    F32x1(0.5),
+                    F32x1(1.0),
+                    OCTAVES,
+                    1337,
+                    OctaveOptions::default(),
+                )
+                .0;
+                min = min.min(n);
+                max = max.max(n);
+            }
+        }
+        // Octave i contributes at most offset^2 * 0.5^i and never goes negative
+        assert!(min >= 0.0, "min out of range {}", min);
+        assert!(max <= 1.875, "max out of range: {}", max);
+        assert!(max > 1.0, "octaves beyond the first never contributed: {}", max);
+    }
+
+    #[test]
+    fn test_noise_ridge32_2d_matches_formula() {
+        const OCTAVES: u8 = 5;
+        let (lacunarity, gain, persistence, offset) = (2.0, 2.0, 0.5, 1.0);
+        for i in 0..100 {
+            let (x, y) = (i as f32 * 0.37 + 0.1234, i as f32 * -0.21 + 0.5678);
+
+            // Musgrave's ridged multifractal, one octave at a time
+            let (mut fx, mut fy) = (x, y);
+            let (mut expected, mut weight, mut spectral) = (0.0, 1.0, 1.0);
+            for _ in 0..OCTAVES {
+                let n = hashed_simplex_2d::<Scalar>(F32x1(fx), F32x1(fy), 1337).0;
+                let signal = (offset - n.abs()) * (offset - n.abs()) * weight;
+                expected += signal * spectral;
+                weight = (signal * gain).clamp(0.0, 1.0);
+                spectral *= persistence;
+                fx *= lacunarity;
+                fy *= lacunarity;
+            }
+
+            let actual = ridge_2d::<Scalar>(
+                F32x1(x),
+                F32x1(y),
+                F32x1(lacunarity),
+                F32x1(gain),
+                F32x1(persistence),
+                F32x1(offset),
+                OCTAVES,
+                1337,
+                OctaveOptions::default(),
+            )
+            .0;
+            assert!((expected - actual).abs() < 1e-5, "{} != {} at {}", expected, actual, i);
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use crate::noise::lattice::Lattice4d;
+use crate::noise::octaves::OctaveOptions;
+
+use simdeez::prelude::*;
+
+/// Turns a raw simplex sample into a ridge: `(offset - |n|)^2`
+#[inline(always)]
+fn ridge_signal<S: Simd>(n: S::Vf64, offset: S::Vf64) -> S::Vf64 {
+    let signal = offset - n.abs();
+    signal * signal
+}
+
+/// Weight for the next octave, the previous signal scaled by `gain` and clamped to [0, 1].
+///
+/// This is what makes the ridges sharp: detail is only added where the previous octaves
+/// already produced a ridge.
+#[inline(always)]
+fn octave_weight<S: Simd>(signal: S::Vf64, gain: S::Vf64) -> S::Vf64 {
+    (signal * gain)
+        .max(S::Vf64::zeroes())
+        .min(S::Vf64::set1(1.0))
+}
+
+/// Sums the octaves of a ridged multifractal at `p`, `noise` samples the lattice at the
+/// coordinates and seed of an octave
+#[inline(always)]
+fn ridge<S: Simd, L, const D: usize>(
+    mut p: [S::Vf64; D],
+    lacunarity: S::Vf64,
+    gain: S::Vf64,
+    persistence: S::Vf64,
+    offset: S::Vf64,
+    octaves: u8,
+    seed: i64,
+    options: OctaveOptions<L>,
+    noise: impl Fn([S::Vf64; D], i64) -> S::Vf64,
+) -> S::Vf64 {
+    let mut signal = ridge_signal::<S>(noise(p, seed), offset);
+    let mut result = signal;
+    let mut amp = S::Vf64::set1(1.0);
+
+    // The fraction octave is a whole octave with its amplitude scaled by the fraction
+    let mut add_octave = |octave: u8, fraction: Option<S::Vf64>| {
+        for (axis, v) in p.iter_mut().enumerate() {
+            *v = options.offset_64::<S>(*v * lacunarity, axis);
+        }
+        amp = amp * persistence;
+        if let Some(fraction) = fraction {
+            amp = amp * fraction;
+        }
+        let weight = octave_weight::<S>(signal, gain);
+        let n = noise(p, options.seed_64(seed, octave));
+        signal = ridge_signal::<S>(n, offset) * weight;
+        result = (signal * amp) + result;
+    };
+
+    for octave in 1..octaves {
+        add_octave(octave, None);
+    }
+    if options.fraction > 0.0 {
+        add_octave(octaves, Some(S::Vf64::set1(options.fraction)));
+    }
+
+    result
+}
+
+/// Musgrave's ridged multifractal. Octave `i` adds its ridge signal, weighted by the signal of
+/// the octave before, times `persistence^i`. That is the spectral weight `lacunarity^-H` of the
+/// original formulation, 0.5 with a lacunarity of 2 is H = 1.
+#[inline(always)]
+pub fn ridge_1d<S: Simd>(
+    x: S::Vf64,
+    lacunarity: S::Vf64,
+    gain: S::Vf64,
+    persistence: S::Vf64,
+    offset: S::Vf64,
+    octaves: u8,
+    seed: i64,
+    options: OctaveOptions,
+) -> S::Vf64 {
+    ridge::<S, _, 1>(
+        [x],
+        lacunarity,
+        gain,
+        persistence,
+        offset,
+        octaves,
+        seed,
+        options,
+        |[x], seed| options.lattice.noise_1d_64::<S>(x, seed),
+    )
+}
+
+#[inline(always)]
+pub fn ridge_2d<S: Simd>(
+    x: S::Vf64,
+    y: S::Vf64,
+    lac: S::Vf64,
+    gain: S::Vf64,
+    persistence: S::Vf64,
+    offset: S::Vf64,
+    octaves: u8,
+    seed: i64,
+    options: OctaveOptions,
+) -> S::Vf64 {
+    ridge::<S, _, 2>(
+        [x, y],
+        lac,
+        gain,
+        persistence,
+        offset,
+        octaves,
+        seed,
+        options,
+        |[x, y], seed| options.lattice.noise_2d_64::<S>(x, y, seed),
+    )
+}
+
+#[inline(always)]
+pub fn ridge_3d<S: Simd>(
+    x: S::Vf64,
+    y: S::Vf64,
+    z: S::Vf64,
+    lac: S::Vf64,
+    gain: S::Vf64,
+    persistence: S::Vf64,
+    offset: S::Vf64,
+    octaves: u8,
+    seed: i64,
+    options: OctaveOptions,
+) -> S::Vf64 {
+    ridge::<S, _, 3>(
+        [x, y, z],
+        lac,
+        gain,
+        persistence,
+        offset,
+        octaves,
+        seed,
+        options,
+        |[x, y, z], seed| options.lattice.noise_3d_64::<S>(x, y, z, seed),
+    )
+}
+
+#[inline(always)]
+pub fn ridge_4d<S: Simd>(
+    x: S::Vf64,
+    y: S::Vf64,
+    z: S::Vf64,
+    w: S::Vf64,
+    lac: S::Vf64,
+    gain: S::Vf64,
+    persistence: S::Vf64,
+    offset: S::Vf64,
+    octaves: u8,
+    seed: i64,
+    options: OctaveOptions<Lattice4d>,
+) -> S::Vf64 {
+    ridge::<S, _, 4>(
+        [x, y, z, w],
+        lac,
+        gain,
+        persistence,
+        offset,
+        octaves,
+        seed,
+        options,
+        |[x, y, z, w], seed| options.lattice.noise_4d_64::<S>(x, y, z, w, seed),
+    )
+}
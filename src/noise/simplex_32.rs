@@ -3,8 +3,9 @@
 //! Useful for writing your own SIMD-generic code for use cases not covered by the higher level
 //! interfaces.
 
-use crate::noise::gradient_32::{grad1, grad2, grad3d, grad3d_dot};
+use crate::noise::gradient_32::{grad1, grad2, grad3d, grad3d_dot, grad4};
 use crate::noise::ops::gather_32;
+use crate::noise::perlin_32::hash;
 
 use simdeez::prelude::*;
 
@@ -29,6 +30,7 @@ pub const F2_64: f64 = 0.36602540378;
 /// Skew factor for 3D simplex noise
 const F3_32: f32 = 1.0 / 3.0;
 pub const F3_64: f64 = 1.0 / 3.0;
+const F4_32: f32 = 0.309016994;
 pub const F4_64: f64 = 0.309016994;
 /// Unskew factor for 2D simplex noise
 const G2_32: f32 = 0.2113248654;
@@ -40,10 +42,16 @@ const G3_32: f32 = 1.0 / 6.0;
 pub const G3_64: f64 = 1.0 / 6.0;
 const G33_32: f32 = 3.0 / 6.0 - 1.0;
 pub const G33_64: f64 = 3.0 / 6.0 - 1.0;
+const G4_32: f32 = 0.138196601;
 pub const G4_64: f64 = 0.138196601;
+const G24_32: f32 = 2.0 * G4_32;
 pub const G24_64: f64 = 2.0 * G4_64;
+const G34_32: f32 = 3.0 * G4_32;
+pub const G34_64: f64 = 3.0 * G4_64;
+const G44_32: f32 = 4.0 * G4_32 - 1.0;
+pub const G44_64: f64 = 4.0 * G4_64 - 1.0;
 
-static PERM: [i32; 512] = [
+pub(crate) static PERM: [i32; 512] = [
     151, 160, 137, 91, 90, 15, 131, 13, 201, 95, 96, 53, 194, 233, 7, 225, 140, 36, 103, 30, 69,
     142, 8, 99, 37, 240, 21, 10, 23, 190, 6, 148, 247, 120, 234, 75, 0, 26, 197, 62, 94, 252, 219,
     203, 117, 35, 11, 32, 57, 177, 33, 88, 237, 149, 56, 87, 174, 20, 125, 136, 171, 168, 68, 175,
@@ -71,41 +79,43 @@ static PERM: [i32; 512] = [
     222, 114, 67, 29, 24, 72, 243, 141, 128, 195, 78, 66, 215, 61, 156, 180,
 ];
 
+/// Like `simplex_1d`, but also computes the derivative
 #[inline(always)]
-fn assert_in_perm_range<S: Simd>(values: S::Vi32) {
-    debug_assert!(values
-        .cmp_lt(S::Vi32::set1(PERM.len() as i32))
-        .iter()
-        .all(|is_less_than| is_less_than != 0));
+pub fn simplex_1d_deriv<S: Simd>(x: S::Vf32, seed: i32) -> (S::Vf32, S::Vf32) {
+    simplex_1d_deriv_with::<S>(x, |i| {
+        // Safety: masked with 0xff, so in 0..=255
+        grad1::<S>(seed, unsafe { gather_32::<S>(&PERM, i & S::Vi32::set1(0xff)) })
+    })
 }
 
-/// Like `simplex_1d`, but also computes the derivative
+/// Like `hashed_simplex_1d`, but also computes the derivative
 #[inline(always)]
-pub fn simplex_1d_deriv<S: Simd>(x: S::Vf32, seed: i32) -> (S::Vf32, S::Vf32) {
+pub fn hashed_simplex_1d_deriv<S: Simd>(x: S::Vf32, seed: i32) -> (S::Vf32, S::Vf32) {
+    simplex_1d_deriv_with::<S>(x, |i| grad1::<S>(0, hash::<S, 1>([i], seed)))
+}
+
+/// Samples 1d simplex noise and its derivative, with `gradient` picking the gradient of a lattice
+/// point
+#[inline(always)]
+fn simplex_1d_deriv_with<S: Simd>(
+    x: S::Vf32,
+    gradient: impl Fn(S::Vi32) -> S::Vf32,
+) -> (S::Vf32, S::Vf32) {
     // Gradients are selected deterministically based on the whole part of `x`
     let ips = x.fast_floor();
-    let mut i0 = ips.cast_i32();
-    let i1 = (i0 + S::Vi32::set1(1)) & S::Vi32::set1(0xff);
+    let i0 = ips.cast_i32();
 
     // the fractional part of x, i.e. the distance to the left gradient node. 0 ≤ x0 < 1.
     let x0 = x - ips;
     // signed distance to the right gradient node
     let x1 = x0 - S::Vf32::set1(1.0);
 
-    i0 = i0 & S::Vi32::set1(0xff);
-    let (gi0, gi1) = unsafe {
-        // Safety: We just masked i0 and i1 with 0xff, so they're in 0..255.
-        let gi0 = gather_32::<S>(&PERM, i0);
-        let gi1 = gather_32::<S>(&PERM, i1);
-        (gi0, gi1)
-    };
-
     // Compute the contribution from the first gradient
     let x20 = x0 * x0; // x^2_0
     let t0 = S::Vf32::set1(1.0) - x20; // t_0
     let t20 = t0 * t0; // t^2_0
     let t40 = t20 * t20; // t^4_0
-    let gx0 = grad1::<S>(seed, gi0);
+    let gx0 = gradient(i0);
     let n0 = t40 * gx0 * x0;
     // n0 = (1 - x0^2)^4 * x0 * grad
 
@@ -114,7 +124,7 @@ pub fn simplex_1d_deriv<S: Simd>(x: S::Vf32, seed: i32) -> (S::Vf32, S::Vf32) {
     let t1 = S::Vf32::set1(1.0) - x21; // t_1
     let t21 = t1 * t1; // t^2_1
     let t41 = t21 * t21; // t^4_1
-    let gx1 = grad1::<S>(seed, gi1);
+    let gx1 = gradient(i0 + S::Vi32::set1(1));
     let n1 = t41 * gx1 * x1;
 
     // n0 + n1 =
@@ -140,23 +150,66 @@ pub fn simplex_1d_deriv<S: Simd>(x: S::Vf32, seed: i32) -> (S::Vf32, S::Vf32) {
 
 /// Samples 1-dimensional simplex noise
 ///
-/// Produces a value -1 ≤ n ≤ 1.
+/// Produces a value -1 ≤ n ≤ 1. Only the low 4 bits of the seed pick the gradients.
 #[inline(always)]
 pub fn simplex_1d<S: Simd>(x: S::Vf32, seed: i32) -> S::Vf32 {
     simplex_1d_deriv::<S>(x, seed).0
 }
 
+/// Like `simplex_1d`, but hashes the whole seed into the gradients, so every seed gives different
+/// noise. This is what `Lattice::Simplex` samples, `simplex_1d` is kept for `SeedMode::Legacy`.
+#[inline(always)]
+pub fn hashed_simplex_1d<S: Simd>(x: S::Vf32, seed: i32) -> S::Vf32 {
+    hashed_simplex_1d_deriv::<S>(x, seed).0
+}
+
 /// Samples 2-dimensional simplex noise
 ///
-/// Produces a value -1 ≤ n ≤ 1.
+/// Produces a value -1 ≤ n ≤ 1. Only the low 3 bits of the seed pick the gradients.
 #[inline(always)]
 pub fn simplex_2d<S: Simd>(x: S::Vf32, y: S::Vf32, seed: i32) -> S::Vf32 {
     simplex_2d_deriv::<S>(x, y, seed).0
 }
 
+/// Like `simplex_2d`, but hashes the whole seed into the gradients, so every seed gives different
+/// noise. This is what `Lattice::Simplex` samples, `simplex_2d` is kept for `SeedMode::Legacy`.
+#[inline(always)]
+pub fn hashed_simplex_2d<S: Simd>(x: S::Vf32, y: S::Vf32, seed: i32) -> S::Vf32 {
+    hashed_simplex_2d_deriv::<S>(x, y, seed).0
+}
+
 /// Like `simplex_2d`, but also computes the derivative
 #[inline(always)]
 pub fn simplex_2d_deriv<S: Simd>(x: S::Vf32, y: S::Vf32, seed: i32) -> (S::Vf32, [S::Vf32; 2]) {
+    // Scaling factor found by numerical approximation
+    simplex_2d_deriv_with::<S>(x, y, 45.26450774985561631259, |i, j| {
+        let ii = i & S::Vi32::set1(0xff);
+        let jj = j & S::Vi32::set1(0xff);
+        // Safety: both summands are in 0..=255, the table is 512 long
+        grad2::<S>(seed, unsafe { gather_32::<S>(&PERM, ii + gather_32::<S>(&PERM, jj)) })
+    })
+}
+
+/// Like `hashed_simplex_2d`, but also computes the derivative
+#[inline(always)]
+pub fn hashed_simplex_2d_deriv<S: Simd>(
+    x: S::Vf32,
+    y: S::Vf32,
+    seed: i32,
+) -> (S::Vf32, [S::Vf32; 2]) {
+    // Every corner can pick any gradient, their contributions add up to at most 1 / 45.23
+    simplex_2d_deriv_with::<S>(x, y, 45.23, |i, j| grad2::<S>(0, hash::<S, 2>([i, j], seed)))
+}
+
+/// Samples 2d simplex noise and its derivative, with `gradient` picking the gradient of a lattice
+/// point and `scale` mapping the result into [-1, 1]
+#[inline(always)]
+fn simplex_2d_deriv_with<S: Simd>(
+    x: S::Vf32,
+    y: S::Vf32,
+    scale: f32,
+    gradient: impl Fn(S::Vi32, S::Vi32) -> [S::Vf32; 2],
+) -> (S::Vf32, [S::Vf32; 2]) {
     // Skew to distort simplexes with side length sqrt(2)/sqrt(3) until they make up
     // squares
     let s = S::Vf32::set1(F2_32) * (x + y);
@@ -183,27 +236,6 @@ pub fn simplex_2d_deriv<S: Simd>(x: S::Vf32, y: S::Vf32, seed: i32) -> (S::Vf32,
     let x2 = (x0 + S::Vf32::set1(-1.0)) + S::Vf32::set1(G22_32);
     let y2 = (y0 + S::Vf32::set1(-1.0)) + S::Vf32::set1(G22_32);
 
-    let ii = i & S::Vi32::set1(0xff);
-    let jj = j & S::Vi32::set1(0xff);
-
-    let (gi0, gi1, gi2) = unsafe {
-        assert_in_perm_range::<S>(ii);
-        assert_in_perm_range::<S>(jj);
-        assert_in_perm_range::<S>(ii - i1);
-        assert_in_perm_range::<S>(jj - j1);
-        assert_in_perm_range::<S>(ii + 1);
-        assert_in_perm_range::<S>(jj + 1);
-
-        let gi0 = gather_32::<S>(&PERM, ii + gather_32::<S>(&PERM, jj));
-        let gi1 = gather_32::<S>(&PERM, (ii - i1) + gather_32::<S>(&PERM, jj - j1));
-        let gi2 = gather_32::<S>(
-            &PERM,
-            (ii - S::Vi32::set1(-1)) + gather_32::<S>(&PERM, jj - S::Vi32::set1(-1)),
-        );
-
-        (gi0, gi1, gi2)
-    };
-
     // Weights associated with the gradients at each corner
     // These FMA operations are equivalent to: let t = 0.5 - x*x - y*y
     let mut t0 = S::Vf32::neg_mul_add(y0, y0, S::Vf32::neg_mul_add(x0, x0, S::Vf32::set1(0.5)));
@@ -222,18 +254,17 @@ pub fn simplex_2d_deriv<S: Simd>(x: S::Vf32, y: S::Vf32, seed: i32) -> (S::Vf32,
     let t22 = t2 * t2;
     let t42 = t22 * t22;
 
-    let [gx0, gy0] = grad2::<S>(seed, gi0);
+    let [gx0, gy0] = gradient(i, j);
     let g0 = gx0 * x0 + gy0 * y0;
     let n0 = t40 * g0;
-    let [gx1, gy1] = grad2::<S>(seed, gi1);
+    let [gx1, gy1] = gradient(i - i1, j - j1);
     let g1 = gx1 * x1 + gy1 * y1;
     let n1 = t41 * g1;
-    let [gx2, gy2] = grad2::<S>(seed, gi2);
+    let [gx2, gy2] = gradient(i + S::Vi32::set1(1), j + S::Vi32::set1(1));
     let g2 = gx2 * x2 + gy2 * y2;
     let n2 = t42 * g2;
 
-    // Scaling factor found by numerical approximation
-    let scale = S::Vf32::set1(45.26450774985561631259);
+    let scale = S::Vf32::set1(scale);
     let value = (n0 + (n1 + n2)) * scale;
     let derivative = {
         let temp0 = t20 * t0 * g0;
@@ -271,17 +302,99 @@ pub fn simplex_3d_deriv<S: Simd>(
     y: S::Vf32,
     z: S::Vf32,
     seed: i32,
+) -> (S::Vf32, [S::Vf32; 3]) {
+    simplex_3d_deriv_with::<S>(x, y, z, seed, |[x0, y0, z0], [[i1, j1, k1], [i2, j2, k2]]| {
+        // Integer grid coordinates
+        let i = x0.cast_i32() * S::Vi32::set1(X_PRIME_32);
+        let j = y0.cast_i32() * S::Vi32::set1(Y_PRIME_32);
+        let k = z0.cast_i32() * S::Vi32::set1(Z_PRIME_32);
+
+        let v1x = i + (i1.bitcast_i32() & S::Vi32::set1(X_PRIME_32));
+        let v1y = j + (j1.bitcast_i32() & S::Vi32::set1(Y_PRIME_32));
+        let v1z = k + (k1.bitcast_i32() & S::Vi32::set1(Z_PRIME_32));
+
+        let v2x = i + (i2.bitcast_i32() & S::Vi32::set1(X_PRIME_32));
+        let v2y = j + (j2.bitcast_i32() & S::Vi32::set1(Y_PRIME_32));
+        let v2z = k + (k2.bitcast_i32() & S::Vi32::set1(Z_PRIME_32));
+
+        let v3x = i + S::Vi32::set1(X_PRIME_32);
+        let v3y = j + S::Vi32::set1(Y_PRIME_32);
+        let v3z = k + S::Vi32::set1(Z_PRIME_32);
+
+        [[i, j, k], [v1x, v1y, v1z], [v2x, v2y, v2z], [v3x, v3y, v3z]]
+    })
+}
+
+/// Samples simplex noise that repeats every `period` lattice units along each axis, 0 for the
+/// axes that don't repeat. The simplex lattice only lines up with itself every 3 units along an
+/// axis, so every period has to be a whole multiple of 3. 2d noise is the `z = 0` slice of 3d
+/// noise, the 2d simplex lattice never lines up with itself along an axis.
+///
+/// Produces a value -1 ≤ n ≤ 1.
+#[inline(always)]
+pub fn simplex_periodic<S: Simd, const D: usize>(
+    p: [S::Vf32; D],
+    period: [S::Vf32; D],
+    seed: i32,
+) -> S::Vf32 {
+    simplex_periodic_deriv::<S, D>(p, period, seed).0
+}
+
+/// Like `simplex_periodic`, but also computes the derivative
+#[inline(always)]
+pub fn simplex_periodic_deriv<S: Simd, const D: usize>(
+    p: [S::Vf32; D],
+    period: [S::Vf32; D],
+    seed: i32,
+) -> (S::Vf32, [S::Vf32; D]) {
+    debug_assert!(D == 2 || D == 3, "periodic noise is 2d or 3d");
+    let axis = |v: [S::Vf32; D], axis: usize| v.get(axis).copied().unwrap_or(S::Vf32::zeroes());
+    let period = [0, 1, 2].map(|i| axis(period, i));
+    // Moved into the first period, so a point and its copies a period away pick the same
+    // simplex even where rounding would break a tie between two of them differently
+    let [x, y, z] = [0, 1, 2].map(|i| {
+        let v = axis(p, i);
+        let wrapped = v - ((v / period[i]).floor() * period[i]);
+        period[i].cmp_gt(S::Vf32::zeroes()).blendv(v, wrapped)
+    });
+    let (value, d) = simplex_3d_deriv_with::<S>(x, y, z, seed, |base, [c1, c2]| {
+        let one = S::Vf32::set1(1.0);
+        let offsets = [[S::Vf32::zeroes(); 3], c1.map(|c| c & one), c2.map(|c| c & one), [one; 3]];
+        offsets.map(|offset| {
+            let [a, b, c] = [0, 1, 2].map(|i| base[i] + offset[i]);
+            let s = (a + b) + c;
+            let primes = [X_PRIME_32, Y_PRIME_32, Z_PRIME_32];
+            [0, 1, 2].map(|i| {
+                // Six times the unskewed position of the corner, whole numbers that repeat every
+                // six periods and tell every corner apart
+                let u = ([a, b, c][i] * S::Vf32::set1(6.0)) - s;
+                let repeat = period[i] * S::Vf32::set1(6.0);
+                let wrapped = u - ((u / repeat).floor() * repeat);
+                let u = period[i].cmp_gt(S::Vf32::zeroes()).blendv(u, wrapped);
+                u.cast_i32() * S::Vi32::set1(primes[i])
+            })
+        })
+    });
+    (value, core::array::from_fn(|i| d[i]))
+}
+
+/// The body of `simplex_3d_deriv`. `lattice` turns the skewed base corner of the simplex and
+/// the masks picking the axes of the second and third corner into the hashed coordinates of
+/// all four corners.
+#[inline(always)]
+fn simplex_3d_deriv_with<S: Simd>(
+    x: S::Vf32,
+    y: S::Vf32,
+    z: S::Vf32,
+    seed: i32,
+    lattice: impl FnOnce([S::Vf32; 3], [[S::Vf32; 3]; 2]) -> [[S::Vi32; 3]; 4],
 ) -> (S::Vf32, [S::Vf32; 3]) {
     // Find skewed simplex grid coordinates associated with the input coordinates
     let f = S::Vf32::set1(F3_32) * ((x + y) + z);
     let mut x0 = (x + f).fast_floor();
     let mut y0 = (y + f).fast_floor();
     let mut z0 = (z + f).fast_floor();
-
-    // Integer grid coordinates
-    let i = x0.cast_i32() * S::Vi32::set1(X_PRIME_32);
-    let j = y0.cast_i32() * S::Vi32::set1(Y_PRIME_32);
-    let k = z0.cast_i32() * S::Vi32::set1(Z_PRIME_32);
+    let base = [x0, y0, z0];
 
     // Compute distance from first simplex vertex to input coordinates
     let g = S::Vf32::set1(G3_32) * ((x0 + y0) + z0);
@@ -301,6 +414,9 @@ pub fn simplex_3d_deriv<S: Simd>(
     let j2 = (!x0_ge_y0) | y0_ge_z0;
     let k2 = !(x0_ge_z0 & y0_ge_z0);
 
+    let [[i, j, k], [v1x, v1y, v1z], [v2x, v2y, v2z], [v3x, v3y, v3z]] =
+        lattice(base, [[i1, j1, k1], [i2, j2, k2]]);
+
     // Compute distances from remaining simplex vertices to input coordinates
     let x1 = x0 - (i1 & S::Vf32::set1(1.0)) + S::Vf32::set1(G3_32);
     let y1 = y0 - (j1 & S::Vf32::set1(1.0)) + S::Vf32::set1(G3_32);
@@ -347,22 +463,13 @@ pub fn simplex_3d_deriv<S: Simd>(
     let g0 = grad3d_dot::<S>(seed, i, j, k, x0, y0, z0);
     let v0 = t40 * g0;
 
-    let v1x = i + (i1.bitcast_i32() & S::Vi32::set1(X_PRIME_32));
-    let v1y = j + (j1.bitcast_i32() & S::Vi32::set1(Y_PRIME_32));
-    let v1z = k + (k1.bitcast_i32() & S::Vi32::set1(Z_PRIME_32));
     let g1 = grad3d_dot::<S>(seed, v1x, v1y, v1z, x1, y1, z1);
     let v1 = t41 * g1;
 
-    let v2x = i + (i2.bitcast_i32() & S::Vi32::set1(X_PRIME_32));
-    let v2y = j + (j2.bitcast_i32() & S::Vi32::set1(Y_PRIME_32));
-    let v2z = k + (k2.bitcast_i32() & S::Vi32::set1(Z_PRIME_32));
     let g2 = grad3d_dot::<S>(seed, v2x, v2y, v2z, x2, y2, z2);
     let v2 = t42 * g2;
 
     //SIMDf v3 = SIMDf_MASK(n3, SIMDf_MUL(SIMDf_MUL(t3, t3), FUNC(GradCoord)(seed, SIMDi_ADD(i, SIMDi_NUM(xPrime)), SIMDi_ADD(j, SIMDi_NUM(yPrime)), SIMDi_ADD(k, SIMDi_NUM(zPrime)), x3, y3, z3)));
-    let v3x = i + S::Vi32::set1(X_PRIME_32);
-    let v3y = j + S::Vi32::set1(Y_PRIME_32);
-    let v3z = k + S::Vi32::set1(Z_PRIME_32);
     //define SIMDf_MASK(m,a) SIMDf_AND(SIMDf_CAST_TO_FLOAT(m),a)
     let g3 = grad3d_dot::<S>(seed, v3x, v3y, v3z, x3, y3, z3);
     let v3 = t43 * g3;
@@ -409,6 +516,141 @@ pub fn simplex_3d_deriv<S: Simd>(
     (result, derivative)
 }
 
+/// Samples 4-dimensional simplex noise
+///
+/// Produces a value -1 ≤ n ≤ 1.
+#[inline(always)]
+pub fn simplex_4d<S: Simd>(
+    x: S::Vf32,
+    y: S::Vf32,
+    z: S::Vf32,
+    w: S::Vf32,
+    seed: i32,
+) -> S::Vf32 {
+    simplex_4d_deriv::<S>(x, y, z, w, seed).0
+}
+
+/// Like `simplex_4d`, but also computes the derivative
+#[inline(always)]
+pub fn simplex_4d_deriv<S: Simd>(
+    x: S::Vf32,
+    y: S::Vf32,
+    z: S::Vf32,
+    w: S::Vf32,
+    seed: i32,
+) -> (S::Vf32, [S::Vf32; 4]) {
+    // Skew the input space to find the hypercube the point is in
+    let s = S::Vf32::set1(F4_32) * (((x + y) + z) + w);
+    let ips = (x + s).floor();
+    let jps = (y + s).floor();
+    let kps = (z + s).floor();
+    let lps = (w + s).floor();
+
+    // Unskewed distances to the first corner of the enclosing simplex
+    let t = S::Vf32::set1(G4_32) * (((ips + jps) + kps) + lps);
+    let x0 = x - (ips - t);
+    let y0 = y - (jps - t);
+    let z0 = z - (kps - t);
+    let w0 = w - (lps - t);
+
+    // Rank every axis by magnitude, the simplex walks from the largest to the smallest.
+    // Masks are -1 where set, so subtracting a mask counts it.
+    let x_gt_y = x0.cmp_gt(y0).bitcast_i32();
+    let x_gt_z = x0.cmp_gt(z0).bitcast_i32();
+    let x_gt_w = x0.cmp_gt(w0).bitcast_i32();
+    let y_gt_z = y0.cmp_gt(z0).bitcast_i32();
+    let y_gt_w = y0.cmp_gt(w0).bitcast_i32();
+    let z_gt_w = z0.cmp_gt(w0).bitcast_i32();
+    let one = S::Vi32::set1(1);
+    let rank_x = S::Vi32::zeroes() - x_gt_y - x_gt_z - x_gt_w;
+    let rank_y = (one + x_gt_y) - y_gt_z - y_gt_w;
+    let rank_z = (one + x_gt_z) + (one + y_gt_z) - z_gt_w;
+    let rank_w = (one + x_gt_w) + (one + y_gt_w) + (one + z_gt_w);
+
+    // Corner offsets, -1 where the axis steps and 0 otherwise
+    let i1 = rank_x.cmp_gt(S::Vi32::set1(2));
+    let j1 = rank_y.cmp_gt(S::Vi32::set1(2));
+    let k1 = rank_z.cmp_gt(S::Vi32::set1(2));
+    let l1 = rank_w.cmp_gt(S::Vi32::set1(2));
+    let i2 = rank_x.cmp_gt(one);
+    let j2 = rank_y.cmp_gt(one);
+    let k2 = rank_z.cmp_gt(one);
+    let l2 = rank_w.cmp_gt(one);
+    let i3 = rank_x.cmp_gt(S::Vi32::zeroes());
+    let j3 = rank_y.cmp_gt(S::Vi32::zeroes());
+    let k3 = rank_z.cmp_gt(S::Vi32::zeroes());
+    let l3 = rank_w.cmp_gt(S::Vi32::zeroes());
+
+    // Distances to the remaining corners of the enclosing simplex
+    let step = |d: S::Vf32, mask: S::Vi32, g: f32| d + mask.cast_f32() + S::Vf32::set1(g);
+    let corners = [
+        [x0, y0, z0, w0],
+        [
+            step(x0, i1, G4_32),
+            step(y0, j1, G4_32),
+            step(z0, k1, G4_32),
+            step(w0, l1, G4_32),
+        ],
+        [
+            step(x0, i2, G24_32),
+            step(y0, j2, G24_32),
+            step(z0, k2, G24_32),
+            step(w0, l2, G24_32),
+        ],
+        [
+            step(x0, i3, G34_32),
+            step(y0, j3, G34_32),
+            step(z0, k3, G34_32),
+            step(w0, l3, G34_32),
+        ],
+        [
+            x0 + S::Vf32::set1(G44_32),
+            y0 + S::Vf32::set1(G44_32),
+            z0 + S::Vf32::set1(G44_32),
+            w0 + S::Vf32::set1(G44_32),
+        ],
+    ];
+
+    let ii = ips.cast_i32();
+    let jj = jps.cast_i32();
+    let kk = kps.cast_i32();
+    let ll = lps.cast_i32();
+
+    // One byte of the seed per axis
+    let hashes = [
+        hash::<S, 4>([ii, jj, kk, ll], seed),
+        hash::<S, 4>([ii - i1, jj - j1, kk - k1, ll - l1], seed),
+        hash::<S, 4>([ii - i2, jj - j2, kk - k2, ll - l2], seed),
+        hash::<S, 4>([ii - i3, jj - j3, kk - k3, ll - l3], seed),
+        hash::<S, 4>([ii + one, jj + one, kk + one, ll + one], seed),
+    ];
+
+    // Same `0.6 - v . v` falloff as the 3d noise, summed over all 5 corners
+    let mut value = S::Vf32::zeroes();
+    let mut dnoise = [S::Vf32::zeroes(); 4];
+    for (d, hash) in corners.iter().zip(hashes.iter()) {
+        let mut t =
+            S::Vf32::set1(0.6) - (d[0] * d[0]) - (d[1] * d[1]) - (d[2] * d[2]) - (d[3] * d[3]);
+        t &= t.cmp_gte(S::Vf32::zeroes());
+        let t2 = t * t;
+        let t4 = t2 * t2;
+
+        let g = grad4::<S>(0, *hash);
+        let dot = g[0] * d[0] + g[1] * d[1] + g[2] * d[2] + g[3] * d[3];
+        value += t4 * dot;
+
+        let temp = S::Vf32::set1(-8.0) * t2 * t * dot;
+        for ((dn, di), gi) in dnoise.iter_mut().zip(d.iter()).zip(g.iter()) {
+            *dn += temp * *di + t4 * *gi;
+        }
+    }
+
+    // Scaling factor found by numerical approximation
+    let scale = S::Vf32::set1(27.0);
+    let [dx, dy, dz, dw] = dnoise;
+    (value * scale, [dx * scale, dy * scale, dz * scale, dw * scale])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -472,6 +714,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_noise_simplex32_hashed_1d_range() {
+        for seed in [0, 1, -1, 1337, i32::MAX] {
+            let mut min = f32::INFINITY;
+            let mut max = -f32::INFINITY;
+            for x in 0..1000 {
+                let n = hashed_simplex_1d::<Scalar>(F32x1(x as f32 / 10.0), seed).0;
+                min = min.min(n);
+                max = max.max(n);
+            }
+            check_bounds(min, max);
+        }
+    }
+
+    #[test]
+    fn test_noise_simplex32_hashed_2d_range() {
+        for seed in [0, 1, -1, 1337, i32::MAX] {
+            let mut min = f32::INFINITY;
+            let mut max = -f32::INFINITY;
+            for y in 0..100 {
+                for x in 0..100 {
+                    let n = hashed_simplex_2d::<Scalar>(
+                        F32x1(x as f32 / 10.0),
+                        F32x1(y as f32 / 10.0),
+                        seed,
+                    )
+                    .0;
+                    min = min.min(n);
+                    max = max.max(n);
+                }
+            }
+            check_bounds(min, max);
+        }
+    }
+
     #[test]
     fn test_noise_simplex32_2d_deriv_sanity() {
         let mut avg_err = 0.0;
@@ -575,4 +852,77 @@ mod tests {
         }
         assert!(avg_err < 1e-3);
     }
+
+    #[test]
+    fn test_noise_simplex32_4d_range() {
+        let mut min = f32::INFINITY;
+        let mut max = -f32::INFINITY;
+        const SEED: i32 = 0;
+        for w in 0..10 {
+            for z in 0..10 {
+                for y in 0..10 {
+                    for x in 0..1000 {
+                        let n = simplex_4d::<Scalar>(
+                            F32x1(x as f32 / 10.0),
+                            F32x1(y as f32 / 10.0),
+                            F32x1(z as f32 / 10.0),
+                            F32x1(w as f32 / 10.0),
+                            SEED,
+                        )
+                        .0;
+                        min = min.min(n);
+                        max = max.max(n);
+                    }
+                }
+            }
+        }
+        check_bounds(min, max);
+    }
+
+    #[test]
+    fn test_noise_simplex32_4d_deriv_sanity() {
+        let mut avg_err = 0.0;
+        const POINTS: i32 = 10;
+        const SEED: i32 = 0;
+        for w in 0..POINTS {
+            for z in 0..POINTS {
+                for y in 0..POINTS {
+                    for x in 0..POINTS {
+                        // Offset a bit so we don't check derivative at lattice points
+                        let center = [
+                            x as f32 / 10.0 + 0.1234,
+                            y as f32 / 10.0 + 0.1234,
+                            z as f32 / 10.0 + 0.1234,
+                            w as f32 / 10.0 + 0.1234,
+                        ];
+                        const H: f32 = 0.01;
+                        let sample = |p: [f32; 4]| {
+                            simplex_4d::<Scalar>(
+                                F32x1(p[0]),
+                                F32x1(p[1]),
+                                F32x1(p[2]),
+                                F32x1(p[3]),
+                                SEED,
+                            )
+                            .0
+                        };
+                        let (value, d) = simplex_4d_deriv::<Scalar>(
+                            F32x1(center[0]),
+                            F32x1(center[1]),
+                            F32x1(center[2]),
+                            F32x1(center[3]),
+                            SEED,
+                        );
+                        for (axis, da) in d.iter().enumerate() {
+                            let mut moved = center;
+                            moved[axis] += H;
+                            avg_err += (sample(moved) - (value.0 + da.0 * H)).abs()
+                                / (POINTS * POINTS * POINTS * POINTS * 4) as f32;
+                        }
+                    }
+                }
+            }
+        }
+        assert!(avg_err < 1e-3);
+    }
 }
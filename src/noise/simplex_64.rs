@@ -1,15 +1,16 @@
-use crate::noise::gradient_64::{grad1, grad2, grad3d};
+use crate::noise::gradient_64::{grad1, grad2, grad3d, grad4};
 
 use simdeez::prelude::*;
 
 use crate::noise::simplex_32::{X_PRIME_64, Y_PRIME_64, Z_PRIME_64};
 use crate::noise::gradient_64::grad3d_dot;
 use crate::noise::ops::gather_64;
+use crate::noise::perlin_64::hash;
 use crate::noise::simplex_32::{
-    F2_64, F3_64, G22_64, G2_64, G33_64, G3_64,
+    F2_64, F3_64, F4_64, G22_64, G24_64, G2_64, G33_64, G34_64, G3_64, G44_64, G4_64,
 };
 
-static PERM64: [i64; 512] = [
+pub(crate) static PERM64: [i64; 512] = [
     151, 160, 137, 91, 90, 15, 131, 13, 201, 95, 96, 53, 194, 233, 7, 225, 140, 36, 103, 30, 69,
     142, 8, 99, 37, 240, 21, 10, 23, 190, 6, 148, 247, 120, 234, 75, 0, 26, 197, 62, 94, 252, 219,
     203, 117, 35, 11, 32, 57, 177, 33, 88, 237, 149, 56, 87, 174, 20, 125, 136, 171, 168, 68, 175,
@@ -37,41 +38,43 @@ static PERM64: [i64; 512] = [
     222, 114, 67, 29, 24, 72, 243, 141, 128, 195, 78, 66, 215, 61, 156, 180,
 ];
 
+/// Like `simplex_1d`, but also computes the derivative
 #[inline(always)]
-fn assert_in_perm_range<S: Simd>(values: S::Vi64) {
-    debug_assert!(values
-        .cmp_lt(S::Vi64::set1(PERM64.len() as i64))
-        .iter()
-        .all(|is_less_than| is_less_than != 0));
+pub fn simplex_1d_deriv<S: Simd>(x: S::Vf64, seed: i64) -> (S::Vf64, S::Vf64) {
+    simplex_1d_deriv_with::<S>(x, |i| {
+        // Safety: masked with 0xff, so in 0..=255
+        grad1::<S>(seed, unsafe { gather_64::<S>(&PERM64, i & S::Vi64::set1(0xff)) })
+    })
 }
 
-/// Like `simplex_1d`, but also computes the derivative
+/// Like `hashed_simplex_1d`, but also computes the derivative
 #[inline(always)]
-pub fn simplex_1d_deriv<S: Simd>(x: S::Vf64, seed: i64) -> (S::Vf64, S::Vf64) {
+pub fn hashed_simplex_1d_deriv<S: Simd>(x: S::Vf64, seed: i64) -> (S::Vf64, S::Vf64) {
+    simplex_1d_deriv_with::<S>(x, |i| grad1::<S>(0, hash::<S, 1>([i], seed)))
+}
+
+/// Samples 1d simplex noise and its derivative, with `gradient` picking the gradient of a lattice
+/// point
+#[inline(always)]
+fn simplex_1d_deriv_with<S: Simd>(
+    x: S::Vf64,
+    gradient: impl Fn(S::Vi64) -> S::Vf64,
+) -> (S::Vf64, S::Vf64) {
     // Gradients are selected deterministically based on the whole part of `x`
     let ips = x.fast_floor();
-    let mut i0 = ips.cast_i64();
-    let i1 = (i0 + S::Vi64::set1(1)) & S::Vi64::set1(0xff);
+    let i0 = ips.cast_i64();
 
     // the fractional part of x, i.e. the distance to the left gradient node. 0 ≤ x0 < 1.
     let x0 = x - ips;
     // signed distance to the right gradient node
     let x1 = x0 - S::Vf64::set1(1.0);
 
-    i0 = i0 & S::Vi64::set1(0xff);
-    let (gi0, gi1) = unsafe {
-        // Safety: We just masked i0 and i1 with 0xff, so they're in 0..255.
-        let gi0 = gather_64::<S>(&PERM64, i0);
-        let gi1 = gather_64::<S>(&PERM64, i1);
-        (gi0, gi1)
-    };
-
     // Compute the contribution from the first gradient
     let x20 = x0 * x0; // x^2_0
     let t0 = S::Vf64::set1(1.0) - x20; // t_0
     let t20 = t0 * t0; // t^2_0
     let t40 = t20 * t20; // t^4_0
-    let gx0 = grad1::<S>(seed, gi0);
+    let gx0 = gradient(i0);
     let n0 = t40 * gx0 * x0;
     // n0 = (1 - x0^2)^4 * x0 * grad
 
@@ -80,7 +83,7 @@ pub fn simplex_1d_deriv<S: Simd>(x: S::Vf64, seed: i64) -> (S::Vf64, S::Vf64) {
     let t1 = S::Vf64::set1(1.0) - x21; // t_1
     let t21 = t1 * t1; // t^2_1
     let t41 = t21 * t21; // t^4_1
-    let gx1 = grad1::<S>(seed, gi1);
+    let gx1 = gradient(i0 + S::Vi64::set1(1));
     let n1 = t41 * gx1 * x1;
 
     // n0 + n1 =
@@ -106,23 +109,66 @@ pub fn simplex_1d_deriv<S: Simd>(x: S::Vf64, seed: i64) -> (S::Vf64, S::Vf64) {
 
 /// Samples 1-dimensional simplex noise
 ///
-/// Produces a value -1 ≤ n ≤ 1.
+/// Produces a value -1 ≤ n ≤ 1. Only the low 4 bits of the seed pick the gradients.
 #[inline(always)]
 pub fn simplex_1d<S: Simd>(x: S::Vf64, seed: i64) -> S::Vf64 {
     simplex_1d_deriv::<S>(x, seed).0
 }
 
+/// Like `simplex_1d`, but hashes the whole seed into the gradients, so every seed gives different
+/// noise. This is what `Lattice::Simplex` samples, `simplex_1d` is kept for `SeedMode::Legacy`.
+#[inline(always)]
+pub fn hashed_simplex_1d<S: Simd>(x: S::Vf64, seed: i64) -> S::Vf64 {
+    hashed_simplex_1d_deriv::<S>(x, seed).0
+}
+
 /// Samples 2-dimensional simplex noise
 ///
-/// Produces a value -1 ≤ n ≤ 1.
+/// Produces a value -1 ≤ n ≤ 1. Only the low 3 bits of the seed pick the gradients.
 #[inline(always)]
 pub fn simplex_2d<S: Simd>(x: S::Vf64, y: S::Vf64, seed: i64) -> S::Vf64 {
     simplex_2d_deriv::<S>(x, y, seed).0
 }
 
+/// Like `simplex_2d`, but hashes the whole seed into the gradients, so every seed gives different
+/// noise. This is what `Lattice::Simplex` samples, `simplex_2d` is kept for `SeedMode::Legacy`.
+#[inline(always)]
+pub fn hashed_simplex_2d<S: Simd>(x: S::Vf64, y: S::Vf64, seed: i64) -> S::Vf64 {
+    hashed_simplex_2d_deriv::<S>(x, y, seed).0
+}
+
 /// Like `simplex_2d`, but also computes the derivative
 #[inline(always)]
 pub fn simplex_2d_deriv<S: Simd>(x: S::Vf64, y: S::Vf64, seed: i64) -> (S::Vf64, [S::Vf64; 2]) {
+    // Scaling factor found by numerical approximation
+    simplex_2d_deriv_with::<S>(x, y, 45.26450774985561631259, |i, j| {
+        let ii = i & S::Vi64::set1(0xff);
+        let jj = j & S::Vi64::set1(0xff);
+        // Safety: both summands are in 0..=255, the table is 512 long
+        grad2::<S>(seed, unsafe { gather_64::<S>(&PERM64, ii + gather_64::<S>(&PERM64, jj)) })
+    })
+}
+
+/// Like `hashed_simplex_2d`, but also computes the derivative
+#[inline(always)]
+pub fn hashed_simplex_2d_deriv<S: Simd>(
+    x: S::Vf64,
+    y: S::Vf64,
+    seed: i64,
+) -> (S::Vf64, [S::Vf64; 2]) {
+    // Every corner can pick any gradient, their contributions add up to at most 1 / 45.23
+    simplex_2d_deriv_with::<S>(x, y, 45.23, |i, j| grad2::<S>(0, hash::<S, 2>([i, j], seed)))
+}
+
+/// Samples 2d simplex noise and its derivative, with `gradient` picking the gradient of a lattice
+/// point and `scale` mapping the result into [-1, 1]
+#[inline(always)]
+fn simplex_2d_deriv_with<S: Simd>(
+    x: S::Vf64,
+    y: S::Vf64,
+    scale: f64,
+    gradient: impl Fn(S::Vi64, S::Vi64) -> [S::Vf64; 2],
+) -> (S::Vf64, [S::Vf64; 2]) {
     // Skew to distort simplexes with side length sqrt(2)/sqrt(3) until they make up
     // squares
     let s = S::Vf64::set1(F2_64) * (x + y);
@@ -149,24 +195,6 @@ pub fn simplex_2d_deriv<S: Simd>(x: S::Vf64, y: S::Vf64, seed: i64) -> (S::Vf64,
     let x2 = (x0 + S::Vf64::set1(-1.0)) + S::Vf64::set1(G22_64);
     let y2 = (y0 + S::Vf64::set1(-1.0)) + S::Vf64::set1(G22_64);
 
-    let ii = i & S::Vi64::set1(0xff);
-    let jj = j & S::Vi64::set1(0xff);
-
-    let (gi0, gi1, gi2) = unsafe {
-        assert_in_perm_range::<S>(ii);
-        assert_in_perm_range::<S>(jj);
-        assert_in_perm_range::<S>(ii - i1);
-        assert_in_perm_range::<S>(jj - j1);
-        assert_in_perm_range::<S>(ii + 1);
-        assert_in_perm_range::<S>(jj + 1);
-
-        let gi0 = gather_64::<S>(&PERM64, ii + gather_64::<S>(&PERM64, jj));
-        let gi1 = gather_64::<S>(&PERM64, (ii - i1) + gather_64::<S>(&PERM64, jj - j1));
-        let gi2 = gather_64::<S>(&PERM64, (ii - -1) + gather_64::<S>(&PERM64, jj - -1));
-
-        (gi0, gi1, gi2)
-    };
-
     // Weights associated with the gradients at each corner
     // These FMA operations are equivalent to: let t = 0.5 - x*x - y*y
     let mut t0 = S::Vf64::neg_mul_add(y0, y0, S::Vf64::neg_mul_add(x0, x0, S::Vf64::set1(0.5)));
@@ -185,18 +213,17 @@ pub fn simplex_2d_deriv<S: Simd>(x: S::Vf64, y: S::Vf64, seed: i64) -> (S::Vf64,
     let t22 = t2 * t2;
     let t42 = t22 * t22;
 
-    let [gx0, gy0] = grad2::<S>(seed, gi0);
+    let [gx0, gy0] = gradient(i, j);
     let g0 = gx0 * x0 + gy0 * y0;
     let n0 = t40 * g0;
-    let [gx1, gy1] = grad2::<S>(seed, gi1);
+    let [gx1, gy1] = gradient(i - i1, j - j1);
     let g1 = gx1 * x1 + gy1 * y1;
     let n1 = t41 * g1;
-    let [gx2, gy2] = grad2::<S>(seed, gi2);
+    let [gx2, gy2] = gradient(i + S::Vi64::set1(1), j + S::Vi64::set1(1));
     let g2 = gx2 * x2 + gy2 * y2;
     let n2 = t42 * g2;
 
-    // Scaling factor found by numerical approximation
-    let scale = S::Vf64::set1(45.26450774985561631259);
+    let scale = S::Vf64::set1(scale);
     let value = (n0 + n1 + n2) * scale;
     let derivative = {
         let temp0 = t20 * t0 * g0;
@@ -372,6 +399,141 @@ pub fn simplex_3d_deriv<S: Simd>(
     (result, derivative)
 }
 
+/// Samples 4-dimensional simplex noise
+///
+/// Produces a value -1 ≤ n ≤ 1.
+#[inline(always)]
+pub fn simplex_4d<S: Simd>(
+    x: S::Vf64,
+    y: S::Vf64,
+    z: S::Vf64,
+    w: S::Vf64,
+    seed: i64,
+) -> S::Vf64 {
+    simplex_4d_deriv::<S>(x, y, z, w, seed).0
+}
+
+/// Like `simplex_4d`, but also computes the derivative
+#[inline(always)]
+pub fn simplex_4d_deriv<S: Simd>(
+    x: S::Vf64,
+    y: S::Vf64,
+    z: S::Vf64,
+    w: S::Vf64,
+    seed: i64,
+) -> (S::Vf64, [S::Vf64; 4]) {
+    // Skew the input space to find the hypercube the point is in
+    let s = S::Vf64::set1(F4_64) * (((x + y) + z) + w);
+    let ips = (x + s).floor();
+    let jps = (y + s).floor();
+    let kps = (z + s).floor();
+    let lps = (w + s).floor();
+
+    // Unskewed distances to the first corner of the enclosing simplex
+    let t = S::Vf64::set1(G4_64) * (((ips + jps) + kps) + lps);
+    let x0 = x - (ips - t);
+    let y0 = y - (jps - t);
+    let z0 = z - (kps - t);
+    let w0 = w - (lps - t);
+
+    // Rank every axis by magnitude, the simplex walks from the largest to the smallest.
+    // Masks are -1 where set, so subtracting a mask counts it.
+    let x_gt_y = x0.cmp_gt(y0).bitcast_i64();
+    let x_gt_z = x0.cmp_gt(z0).bitcast_i64();
+    let x_gt_w = x0.cmp_gt(w0).bitcast_i64();
+    let y_gt_z = y0.cmp_gt(z0).bitcast_i64();
+    let y_gt_w = y0.cmp_gt(w0).bitcast_i64();
+    let z_gt_w = z0.cmp_gt(w0).bitcast_i64();
+    let one = S::Vi64::set1(1);
+    let rank_x = S::Vi64::zeroes() - x_gt_y - x_gt_z - x_gt_w;
+    let rank_y = (one + x_gt_y) - y_gt_z - y_gt_w;
+    let rank_z = (one + x_gt_z) + (one + y_gt_z) - z_gt_w;
+    let rank_w = (one + x_gt_w) + (one + y_gt_w) + (one + z_gt_w);
+
+    // Corner offsets, -1 where the axis steps and 0 otherwise
+    let i1 = rank_x.cmp_gt(S::Vi64::set1(2));
+    let j1 = rank_y.cmp_gt(S::Vi64::set1(2));
+    let k1 = rank_z.cmp_gt(S::Vi64::set1(2));
+    let l1 = rank_w.cmp_gt(S::Vi64::set1(2));
+    let i2 = rank_x.cmp_gt(one);
+    let j2 = rank_y.cmp_gt(one);
+    let k2 = rank_z.cmp_gt(one);
+    let l2 = rank_w.cmp_gt(one);
+    let i3 = rank_x.cmp_gt(S::Vi64::zeroes());
+    let j3 = rank_y.cmp_gt(S::Vi64::zeroes());
+    let k3 = rank_z.cmp_gt(S::Vi64::zeroes());
+    let l3 = rank_w.cmp_gt(S::Vi64::zeroes());
+
+    // Distances to the remaining corners of the enclosing simplex
+    let step = |d: S::Vf64, mask: S::Vi64, g: f64| d + mask.cast_f64() + S::Vf64::set1(g);
+    let corners = [
+        [x0, y0, z0, w0],
+        [
+            step(x0, i1, G4_64),
+            step(y0, j1, G4_64),
+            step(z0, k1, G4_64),
+            step(w0, l1, G4_64),
+        ],
+        [
+            step(x0, i2, G24_64),
+            step(y0, j2, G24_64),
+            step(z0, k2, G24_64),
+            step(w0, l2, G24_64),
+        ],
+        [
+            step(x0, i3, G34_64),
+            step(y0, j3, G34_64),
+            step(z0, k3, G34_64),
+            step(w0, l3, G34_64),
+        ],
+        [
+            x0 + S::Vf64::set1(G44_64),
+            y0 + S::Vf64::set1(G44_64),
+            z0 + S::Vf64::set1(G44_64),
+            w0 + S::Vf64::set1(G44_64),
+        ],
+    ];
+
+    let ii = ips.cast_i64();
+    let jj = jps.cast_i64();
+    let kk = kps.cast_i64();
+    let ll = lps.cast_i64();
+
+    // One byte of the seed per axis
+    let hashes = [
+        hash::<S, 4>([ii, jj, kk, ll], seed),
+        hash::<S, 4>([ii - i1, jj - j1, kk - k1, ll - l1], seed),
+        hash::<S, 4>([ii - i2, jj - j2, kk - k2, ll - l2], seed),
+        hash::<S, 4>([ii - i3, jj - j3, kk - k3, ll - l3], seed),
+        hash::<S, 4>([ii + one, jj + one, kk + one, ll + one], seed),
+    ];
+
+    // Same `0.6 - v . v` falloff as the 3d noise, summed over all 5 corners
+    let mut value = S::Vf64::zeroes();
+    let mut dnoise = [S::Vf64::zeroes(); 4];
+    for (d, hash) in corners.iter().zip(hashes.iter()) {
+        let mut t =
+            S::Vf64::set1(0.6) - (d[0] * d[0]) - (d[1] * d[1]) - (d[2] * d[2]) - (d[3] * d[3]);
+        t &= t.cmp_gte(S::Vf64::zeroes());
+        let t2 = t * t;
+        let t4 = t2 * t2;
+
+        let g = grad4::<S>(0, *hash);
+        let dot = g[0] * d[0] + g[1] * d[1] + g[2] * d[2] + g[3] * d[3];
+        value += t4 * dot;
+
+        let temp = S::Vf64::set1(-8.0) * t2 * t * dot;
+        for ((dn, di), gi) in dnoise.iter_mut().zip(d.iter()).zip(g.iter()) {
+            *dn += temp * *di + t4 * *gi;
+        }
+    }
+
+    // Scaling factor found by numerical approximation
+    let scale = S::Vf64::set1(27.0);
+    let [dx, dy, dz, dw] = dnoise;
+    (value * scale, [dx * scale, dy * scale, dz * scale, dw * scale])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -435,6 +597,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_noise_simplex64_hashed_1d_range() {
+        for seed in [0, 1, -1, 1337, i64::MAX] {
+            let mut min = f64::INFINITY;
+            let mut max = -f64::INFINITY;
+            for x in 0..1000 {
+                let n = hashed_simplex_1d::<Scalar>(F64x1(x as f64 / 10.0), seed).0;
+                min = min.min(n);
+                max = max.max(n);
+            }
+            check_bounds(min, max);
+        }
+    }
+
+    #[test]
+    fn test_noise_simplex64_hashed_2d_range() {
+        for seed in [0, 1, -1, 1337, i64::MAX] {
+            let mut min = f64::INFINITY;
+            let mut max = -f64::INFINITY;
+            for y in 0..100 {
+                for x in 0..100 {
+                    let n = hashed_simplex_2d::<Scalar>(
+                        F64x1(x as f64 / 10.0),
+                        F64x1(y as f64 / 10.0),
+                        seed,
+                    )
+                    .0;
+                    min = min.min(n);
+                    max = max.max(n);
+                }
+            }
+            check_bounds(min, max);
+        }
+    }
+
     #[test]
     fn test_noise_simplex64_2d_deriv_sanity() {
         let mut avg_err = 0.0;
@@ -466,7 +663,6 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "not implemented")]
     fn test_noise_simplex64_3d_range() {
         let mut min = f64::INFINITY;
         let mut max = -f64::INFINITY;
@@ -490,7 +686,6 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "not implemented")]
     fn test_noise_simplex64_3d_deriv_sanity() {
         let mut avg_err = 0.0;
         const POINTS: i64 = 10;
@@ -540,4 +735,104 @@ mod tests {
         }
         assert!(avg_err < 1e-3);
     }
+    #[test]
+    fn test_noise_simplex64_3d_matches_simplex32() {
+        use crate::noise::simplex_32;
+        use simdeez::scalar::F32x1;
+        for seed in [0, 1, -7, 1337] {
+            for i in 0..1000 {
+                let [x, y, z] = [0.37, -0.21, 0.13].map(|step| i as f32 * step + 0.1234);
+                let expected =
+                    simplex_32::simplex_3d::<Scalar>(F32x1(x), F32x1(y), F32x1(z), seed).0;
+                let actual = simplex_3d::<Scalar>(
+                    F64x1(x.into()),
+                    F64x1(y.into()),
+                    F64x1(z.into()),
+                    seed.into(),
+                )
+                .0;
+                assert!(
+                    (f64::from(expected) - actual).abs() < 1e-4,
+                    "{} != {} at {} seed {}",
+                    expected,
+                    actual,
+                    i,
+                    seed
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_noise_simplex64_4d_range() {
+        let mut min = f64::INFINITY;
+        let mut max = -f64::INFINITY;
+        const SEED: i64 = 0;
+        for w in 0..10 {
+            for z in 0..10 {
+                for y in 0..10 {
+                    for x in 0..1000 {
+                        let n = simplex_4d::<Scalar>(
+                            F64x1(x as f64 / 10.0),
+                            F64x1(y as f64 / 10.0),
+                            F64x1(z as f64 / 10.0),
+                            F64x1(w as f64 / 10.0),
+                            SEED,
+                        )
+                        .0;
+                        min = min.min(n);
+                        max = max.max(n);
+                    }
+                }
+            }
+        }
+        check_bounds(min, max);
+    }
+
+    #[test]
+    fn test_noise_simplex64_4d_deriv_sanity() {
+        let mut avg_err = 0.0;
+        const POINTS: i32 = 10;
+        const SEED: i64 = 0;
+        for w in 0..POINTS {
+            for z in 0..POINTS {
+                for y in 0..POINTS {
+                    for x in 0..POINTS {
+                        // Offset a bit so we don't check derivative at lattice points
+                        let center = [
+                            x as f64 / 10.0 + 0.1234,
+                            y as f64 / 10.0 + 0.1234,
+                            z as f64 / 10.0 + 0.1234,
+                            w as f64 / 10.0 + 0.1234,
+                        ];
+                        const H: f64 = 0.01;
+                        let sample = |p: [f64; 4]| {
+                            simplex_4d::<Scalar>(
+                                F64x1(p[0]),
+                                F64x1(p[1]),
+                                F64x1(p[2]),
+                                F64x1(p[3]),
+                                SEED,
+                            )
+                            .0
+                        };
+                        let (value, d) = simplex_4d_deriv::<Scalar>(
+                            F64x1(center[0]),
+                            F64x1(center[1]),
+                            F64x1(center[2]),
+                            F64x1(center[3]),
+                            SEED,
+                        );
+                        for (axis, da) in d.iter().enumerate() {
+                            let mut moved = center;
+                            moved[axis] += H;
+                            avg_err += (sample(moved) - (value.0 + da.0 * H)).abs()
+                                / (POINTS * POINTS * POINTS * POINTS * 4) as f64;
+                        }
+                    }
+                }
+            }
+        }
+        assert!(avg_err < 1e-3);
+    }
 }
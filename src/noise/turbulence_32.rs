@@ -0,0 +1,267 @@
+use crate::noise::lattice::Lattice4d;
+use crate::noise::octaves::OctaveOptions;
+
+use simdeez::prelude::*;
+
+#[inline(always)]
+pub fn turbulence_1d<S: Simd>(
+    mut x: S::Vf32,
+    lacunarity: S::Vf32,
+    gain: S::Vf32,
+    octaves: u8,
+    seed: i32,
+    options: OctaveOptions,
+) -> S::Vf32 {
+    let mut amp = S::Vf32::set1(1.0);
+    let mut result = options.lattice.noise_1d_32::<S>(x, seed).abs();
+    let next = |v: S::Vf32, axis| options.offset_32::<S>(v * lacunarity, axis);
+
+    for octave in 1..octaves {
+        x = next(x, 0);
+        amp = amp * gain;
+        let n = options.lattice.noise_1d_32::<S>(x, options.seed_32(seed, octave));
+        result = (n.abs() * amp) + result;
+    }
+
+    if options.fraction > 0.0 {
+        let fraction = S::Vf32::set1(options.fraction);
+        x = next(x, 0);
+        amp = amp * gain * fraction;
+        let n = options.lattice.noise_1d_32::<S>(x, options.seed_32(seed, octaves));
+        result = (n.abs() * amp) + result;
+    }
+
+    result
+}
+
+#[inline(always)]
+pub fn turbulence_2d<S: Simd>(
+    mut x: S::Vf32,
+    mut y: S::Vf32,
+    lac: S::Vf32,
+    gain: S::Vf32,
+    octaves: u8,
+    seed: i32,
+    options: OctaveOptions,
+) -> S::Vf32 {
+    let mut result = options.lattice.noise_2d_32::<S>(x, y, seed).abs();
+    let mut amp = S::Vf32::set1(1.0);
+    let next = |v: S::Vf32, axis| options.offset_32::<S>(v * lac, axis);
+
+    for octave in 1..octaves {
+        x = next(x, 0);
+        y = next(y, 1);
+        amp = amp * gain;
+        let n = options.lattice.noise_2d_32::<S>(x, y, options.seed_32(seed, octave));
+        result = (n.abs() * amp) + result;
+    }
+
+    if options.fraction > 0.0 {
+        let fraction = S::Vf32::set1(options.fraction);
+        x = next(x, 0);
+        y = next(y, 1);
+        amp = amp * gain * fraction;
+        let n = options.lattice.noise_2d_32::<S>(x, y, options.seed_32(seed, octaves));
+        result = (n.abs() * amp) + result;
+    }
+
+    result
+}
+
+#[inline(always)]
+pub fn turbulence_3d<S: Simd>(
+    mut x: S::Vf32,
+    mut y: S::Vf32,
+    mut z: S::Vf32,
+    lac: S::Vf32,
+    gain: S::Vf32,
+    octaves: u8,
+    seed: i32,
+    options: OctaveOptions,
+) -> S::Vf32 {
+    let mut result = options.lattice.noise_3d_32::<S>(x, y, z, seed).abs();
+    let mut amp = S::Vf32::set1(1.0);
+    let next = |v: S::Vf32, axis| options.offset_32::<S>(v * lac, axis);
+
+    for octave in 1..octaves {
+        x = next(x, 0);
+        y = next(y, 1);
+        z = next(z, 2);
+        amp = amp * gain;
+        let n = options.lattice.noise_3d_32::<S>(x, y, z, options.seed_32(seed, octave));
+        result = (n.abs() * amp) + result;
+    }
+
+    if options.fraction > 0.0 {
+        let fraction = S::Vf32::set1(options.fraction);
+        x = next(x, 0);
+        y = next(y, 1);
+        z = next(z, 2);
+        amp = amp * gain * fraction;
+        let n = options.lattice.noise_3d_32::<S>(x, y, z, options.seed_32(seed, octaves));
+        result = (n.abs() * amp) + result;
+    }
+
+    result
+}
+
+#[inline(always)]
+pub fn turbulence_4d<S: Simd>(
+    mut x: S::Vf32,
+    mut y: S::Vf32,
+    mut z: S::Vf32,
+    mut w: S::Vf32,
+    lac: S::Vf32,
+    gain: S::Vf32,
+    octaves: u8,
+    seed: i32,
+    options: OctaveOptions<Lattice4d>,
+) -> S::Vf32 {
+    let mut result = options.lattice.noise_4d_32::<S>(x, y, z, w, seed).abs();
+    let mut amp = S::Vf32::set1(1.0);
+    let next = |v: S::Vf32, axis| options.offset_32::<S>(v * lac, axis);
+
+    for octave in 1..octaves {
+        x = next(x, 0);
+        y = next(y, 1);
+        z = next(z, 2);
+        w = next(w, 3);
+        amp = amp * gain;
+        let n = options.lattice.noise_4d_32::<S>(x, y, z, w, options.seed_32(seed, octave));
+        result = (n.abs() * amp) + result;
+    }
+
+    if options.fraction > 0.0 {
+        let fraction = S::Vf32::set1(options.fraction);
+        x = next(x, 0);
+        y = next(y, 1);
+        z = next(z, 2);
+        w = next(w, 3);
+        amp = amp * gain * fraction;
+        let n = options.lattice.noise_4d_32::<S>(x, y, z, w, options.seed_32(seed, octaves));
+        result = (n.abs() * amp) + result;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::noise::simplex_32::hashed_simplex_2d;
+    use simdeez::scalar::{F32x1, Scalar};
+
+    #[test]
+    fn test_noise_turbulence32_2d_range() {
+        const OCTAVES: u8 = 3;
+        let mut min = f32::INFINITY;
+        let mut max = -f32::INFINITY;
+        for y in 0..100 {
+            for x in 0..100 {
+                let n = turbulence_2d::<Scalar>(
+                    F32x1(x as f32 / 10.0),
+                    F32x1(y as f32 / 10.0),
+                    F32x1(2.0),
+                    F32x1(0.5),
+                    OCTAVES,
+                    1337,
+                    OctaveOptions::default(),
+                )
+                .0;
+                min = min.min(n);
+                max = max.max(n);
+            }
+        }
+        // 1 + 0.5 + 0.25
+        assert!(min >= 0.0, "min out of range {}", min);
+        assert!(max <= 1.75, "max out of range: {}", max);
+    }
+
+    #[test]
+    fn test_noise_turbulence32_2d_matches_formula() {
+        const OCTAVES: u8 = 4;
+        let (lacunarity, gain) = (2.0, 0.5);
+        for i in 0..100 {
+            let (x, y) = (i as f32 * 0.37 + 0.1234, i as f32 * -0.21 + 0.5678);
+
+            // Sum of |n| over the octaves, each at twice the frequency and half the amplitude
+            let (mut fx, mut fy) = (x, y);
+            let (mut expected, mut amp) = (0.0, 1.0);
+            for _ in 0..OCTAVES {
+                let n = hashed_simplex_2d::<Scalar>(F32x1(fx), F32x1(fy), 1337).0;
+                expected += n.abs() * amp;
+                amp *= gain;
+                fx *= lacunarity;
+                fy *= lacunarity;
+            }
+
+            let actual = turbulence_2d::<Scalar>(
+                F32x1(x),
+                F32x1(y),
+                F32x1(lacunarity),
+                F32x1(gain),
+                OCTAVES,
+                1337,
+                OctaveOptions::default(),
+            )
+            .0;
+            assert!(
+                (expected - actual).abs() < 1e-5,
+                "{} != {} at {}",
+                expected,
+                actual,
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn test_noise_turbulence32_3d_fraction() {
+        let options = OctaveOptions {
+            seed_per_octave: true,
+            offset_per_octave: true,
+            ..OctaveOptions::default()
+        };
+        let sample = |p: [f32; 3], octaves: u8, fraction: f32| {
+            turbulence_3d::<Scalar>(
+                F32x1(p[0]),
+                F32x1(p[1]),
+                F32x1(p[2]),
+                F32x1(2.0),
+                F32x1(0.5),
+                octaves,
+                1337,
+                OctaveOptions {
+                    fraction,
+                    ..options
+                },
+            )
+            .0
+        };
+        for i in 0..100 {
+            let p = [
+                i as f32 * 0.37 - 3.0,
+                i as f32 * -0.21,
+                (i % 13) as f32 * 0.53,
+            ];
+            let (three, four) = (sample(p, 3, 0.0), sample(p, 4, 0.0));
+            // The fractional octave is the next whole octave, with its weight scaled down
+            let actual = sample(p, 3, 0.25);
+            let expected = three + (four - three) * 0.25;
+            assert!(
+                (expected - actual).abs() < 1e-5,
+                "{} != {} at {:?}",
+                expected,
+                actual,
+                p
+            );
+            assert!(
+                three <= actual && actual <= four,
+                "{} not in [{}, {}]",
+                actual,
+                three,
+                four
+            );
+        }
+    }
+}
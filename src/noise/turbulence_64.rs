@@ -0,0 +1,138 @@
+use crate::noise::lattice::Lattice4d;
+use crate::noise::octaves::OctaveOptions;
+
+use simdeez::prelude::*;
+
+#[inline(always)]
+pub fn turbulence_1d<S: Simd>(
+    mut x: S::Vf64,
+    lacunarity: S::Vf64,
+    gain: S::Vf64,
+    octaves: u8,
+    seed: i64,
+    options: OctaveOptions,
+) -> S::Vf64 {
+    let mut amp = S::Vf64::set1(1.0);
+    let mut result = options.lattice.noise_1d_64::<S>(x, seed).abs();
+    let next = |v: S::Vf64, axis| options.offset_64::<S>(v * lacunarity, axis);
+
+    for octave in 1..octaves {
+        x = next(x, 0);
+        amp = amp * gain;
+        result = (options.lattice.noise_1d_64::<S>(x, options.seed_64(seed, octave)).abs() * amp) + result;
+    }
+
+    if options.fraction > 0.0 {
+        let fraction = S::Vf64::set1(options.fraction.into());
+        x = next(x, 0);
+        amp = amp * gain * fraction;
+        result = (options.lattice.noise_1d_64::<S>(x, options.seed_64(seed, octaves)).abs() * amp) + result;
+    }
+
+    result
+}
+
+#[inline(always)]
+pub fn turbulence_2d<S: Simd>(
+    mut x: S::Vf64,
+    mut y: S::Vf64,
+    lac: S::Vf64,
+    gain: S::Vf64,
+    octaves: u8,
+    seed: i64,
+    options: OctaveOptions,
+) -> S::Vf64 {
+    let mut result = options.lattice.noise_2d_64::<S>(x, y, seed).abs();
+    let mut amp = S::Vf64::set1(1.0);
+    let next = |v: S::Vf64, axis| options.offset_64::<S>(v * lac, axis);
+
+    for octave in 1..octaves {
+        x = next(x, 0);
+        y = next(y, 1);
+        amp = amp * gain;
+        result = (options.lattice.noise_2d_64::<S>(x, y, options.seed_64(seed, octave)).abs() * amp) + result;
+    }
+
+    if options.fraction > 0.0 {
+        let fraction = S::Vf64::set1(options.fraction.into());
+        x = next(x, 0);
+        y = next(y, 1);
+        amp = amp * gain * fraction;
+        result = (options.lattice.noise_2d_64::<S>(x, y, options.seed_64(seed, octaves)).abs() * amp) + result;
+    }
+
+    result
+}
+
+#[inline(always)]
+pub fn turbulence_3d<S: Simd>(
+    mut x: S::Vf64,
+    mut y: S::Vf64,
+    mut z: S::Vf64,
+    lac: S::Vf64,
+    gain: S::Vf64,
+    octaves: u8,
+    seed: i64,
+    options: OctaveOptions,
+) -> S::Vf64 {
+    let mut result = options.lattice.noise_3d_64::<S>(x, y, z, seed).abs();
+    let mut amp = S::Vf64::set1(1.0);
+    let next = |v: S::Vf64, axis| options.offset_64::<S>(v * lac, axis);
+
+    for octave in 1..octaves {
+        x = next(x, 0);
+        y = next(y, 1);
+        z = next(z, 2);
+        amp = amp * gain;
+        result = (options.lattice.noise_3d_64::<S>(x, y, z, options.seed_64(seed, octave)).abs() * amp) + result;
+    }
+
+    if options.fraction > 0.0 {
+        let fraction = S::Vf64::set1(options.fraction.into());
+        x = next(x, 0);
+        y = next(y, 1);
+        z = next(z, 2);
+        amp = amp * gain * fraction;
+        result = (options.lattice.noise_3d_64::<S>(x, y, z, options.seed_64(seed, octaves)).abs() * amp) + result;
+    }
+
+    result
+}
+
+#[inline(always)]
+pub fn turbulence_4d<S: Simd>(
+    mut x: S::Vf64,
+    mut y: S::Vf64,
+    mut z: S::Vf64,
+    mut w: S::Vf64,
+    lac: S::Vf64,
+    gain: S::Vf64,
+    octaves: u8,
+    seed: i64,
+    options: OctaveOptions<Lattice4d>,
+) -> S::Vf64 {
+    let mut result = options.lattice.noise_4d_64::<S>(x, y, z, w, seed).abs();
+    let mut amp = S::Vf64::set1(1.0);
+    let next = |v: S::Vf64, axis| options.offset_64::<S>(v * lac, axis);
+
+    for octave in 1..octaves {
+        x = next(x, 0);
+        y = next(y, 1);
+        z = next(z, 2);
+        w = next(w, 3);
+        amp = amp * gain;
+        result = (options.lattice.noise_4d_64::<S>(x, y, z, w, options.seed_64(seed, octave)).abs() * amp) + result;
+    }
+
+    if options.fraction > 0.0 {
+        let fraction = S::Vf64::set1(options.fraction.into());
+        x = next(x, 0);
+        y = next(y, 1);
+        z = next(z, 2);
+        w = next(w, 3);
+        amp = amp * gain * fraction;
+        result = (options.lattice.noise_4d_64::<S>(x, y, z, w, options.seed_64(seed, octaves)).abs() * amp) + result;
+    }
+
+    result
+}
@@ -0,0 +1,384 @@
+//! Low-level value noise primitives
+//!
+//! Value noise gives every lattice point a random value and blends the values of the points
+//! around a sample. It is cheaper than gradient noise and blockier, the lattice shows through.
+//! The values are hashed like the gradients of `perlin_32`.
+
+use crate::noise::lattice::Interpolation;
+use crate::noise::perlin_32::hash;
+
+use simdeez::prelude::*;
+
+/// The random value in [-1, 1] of the lattice point with hash `hash`
+#[inline(always)]
+fn lattice_value<S: Simd>(hash: S::Vi32) -> S::Vf32 {
+    (hash & S::Vi32::set1(0xff)).cast_f32() * S::Vf32::set1(2.0 / 255.0) - S::Vf32::set1(1.0)
+}
+
+/// The weight of the far lattice point along an axis `t` into the cell, and its derivative
+#[inline(always)]
+fn interpolate<S: Simd>(t: S::Vf32, interpolation: Interpolation) -> (S::Vf32, S::Vf32) {
+    match interpolation {
+        Interpolation::Linear => (t, S::Vf32::set1(1.0)),
+        // 3t^2 - 2t^3 and 6t (1 - t)
+        Interpolation::Hermite => (
+            t * t * (S::Vf32::set1(3.0) - t * S::Vf32::set1(2.0)),
+            t * (S::Vf32::set1(1.0) - t) * S::Vf32::set1(6.0),
+        ),
+        // 6t^5 - 15t^4 + 10t^3 and 30t^2 (t - 1)^2
+        Interpolation::Quintic => {
+            let t1 = t - S::Vf32::set1(1.0);
+            let t3 = t * t * t;
+            (
+                t3 * (t * (t * S::Vf32::set1(6.0) - S::Vf32::set1(15.0)) + S::Vf32::set1(10.0)),
+                t * t * t1 * t1 * S::Vf32::set1(30.0),
+            )
+        }
+    }
+}
+
+/// Weights of the 4 lattice points from 1 before to 2 after the cell along an axis `t` into it,
+/// and their derivatives. They are the cubic through the 4 values with the slopes of the
+/// neighbours at the cell edges.
+#[inline(always)]
+fn cubic_weights<S: Simd>(t: S::Vf32) -> ([S::Vf32; 4], [S::Vf32; 4]) {
+    let one = S::Vf32::set1(1.0);
+    let (t2, t3) = (t * t, t * t * t);
+    let c = |v: f32| S::Vf32::set1(v);
+    (
+        [
+            t2 * c(2.0) - t3 - t,
+            t3 - t2 * c(2.0) + one,
+            t2 + t - t3,
+            t3 - t2,
+        ],
+        [
+            t * c(4.0) - t2 * c(3.0) - one,
+            t2 * c(3.0) - t * c(4.0),
+            t * c(2.0) + one - t2 * c(3.0),
+            t2 * c(3.0) - t * c(2.0),
+        ],
+    )
+}
+
+/// Samples `D`-dimensional value noise and its derivative
+#[inline(always)]
+fn value_deriv<S: Simd, const D: usize>(
+    p: [S::Vf32; D],
+    interpolation: Interpolation,
+    seed: i32,
+) -> (S::Vf32, [S::Vf32; D]) {
+    let cell = p.map(|v| v.floor());
+    let i0 = cell.map(|v| v.cast_i32());
+    let weights: [(S::Vf32, S::Vf32); D] =
+        core::array::from_fn(|i| interpolate::<S>(p[i] - cell[i], interpolation));
+
+    let mut value = S::Vf32::zeroes();
+    let mut deriv = [S::Vf32::zeroes(); D];
+    for corner in 0..1 << D {
+        let far = |axis: usize| corner >> axis & 1 == 1;
+        let coords = core::array::from_fn(|i| i0[i] + S::Vi32::set1(far(i) as i32));
+        let v = lattice_value::<S>(hash::<S, D>(coords, seed));
+        let (w, dw): ([S::Vf32; D], [S::Vf32; D]) = (
+            core::array::from_fn(|i| {
+                if far(i) { weights[i].0 } else { S::Vf32::set1(1.0) - weights[i].0 }
+            }),
+            core::array::from_fn(|i| {
+                if far(i) { weights[i].1 } else { S::Vf32::zeroes() - weights[i].1 }
+            }),
+        );
+        value = (w.iter().fold(v, |value, &w| value * w)) + value;
+        for axis in 0..D {
+            let d = (0..D).fold(v * dw[axis], |d, i| if i == axis { d } else { d * w[i] });
+            deriv[axis] = d + deriv[axis];
+        }
+    }
+    (value, deriv)
+}
+
+/// Samples `D`-dimensional cubic value noise and its derivative, scaled into [-1, 1]
+#[inline(always)]
+fn value_cubic_deriv<S: Simd, const D: usize>(
+    p: [S::Vf32; D],
+    seed: i32,
+) -> (S::Vf32, [S::Vf32; D]) {
+    let cell = p.map(|v| v.floor());
+    let i0 = cell.map(|v| v.cast_i32());
+    let weights: [_; D] = core::array::from_fn(|i| cubic_weights::<S>(p[i] - cell[i]));
+
+    let mut value = S::Vf32::zeroes();
+    let mut deriv = [S::Vf32::zeroes(); D];
+    for point in 0..4usize.pow(D as u32) {
+        let offset = |axis: usize| point / 4usize.pow(axis as u32) % 4;
+        let coords = core::array::from_fn(|i| i0[i] + S::Vi32::set1(offset(i) as i32 - 1));
+        let v = lattice_value::<S>(hash::<S, D>(coords, seed));
+        let w: [S::Vf32; D] = core::array::from_fn(|i| weights[i].0[offset(i)]);
+        value = (w.iter().fold(v, |value, &w| value * w)) + value;
+        for axis in 0..D {
+            let dw = weights[axis].1[offset(axis)];
+            let d = (0..D).fold(v * dw, |d, i| if i == axis { d } else { d * w[i] });
+            deriv[axis] = d + deriv[axis];
+        }
+    }
+    // The weights of an axis add up to at most 1.5 in absolute value
+    let scale = S::Vf32::set1((0..D).fold(1.0, |scale, _| scale / 1.5));
+    (value * scale, deriv.map(|d| d * scale))
+}
+
+/// Samples 1-dimensional value noise
+///
+/// Produces a value -1 ≤ n ≤ 1.
+#[inline(always)]
+pub fn value_1d<S: Simd>(x: S::Vf32, interpolation: Interpolation, seed: i32) -> S::Vf32 {
+    value_1d_deriv::<S>(x, interpolation, seed).0
+}
+
+/// Like `value_1d`, but also computes the derivative
+#[inline(always)]
+pub fn value_1d_deriv<S: Simd>(
+    x: S::Vf32,
+    interpolation: Interpolation,
+    seed: i32,
+) -> (S::Vf32, S::Vf32) {
+    let (value, [dx]) = value_deriv::<S, 1>([x], interpolation, seed);
+    (value, dx)
+}
+
+/// Samples 2-dimensional value noise
+///
+/// Produces a value -1 ≤ n ≤ 1.
+#[inline(always)]
+pub fn value_2d<S: Simd>(
+    x: S::Vf32,
+    y: S::Vf32,
+    interpolation: Interpolation,
+    seed: i32,
+) -> S::Vf32 {
+    value_2d_deriv::<S>(x, y, interpolation, seed).0
+}
+
+/// Like `value_2d`, but also computes the derivative
+#[inline(always)]
+pub fn value_2d_deriv<S: Simd>(
+    x: S::Vf32,
+    y: S::Vf32,
+    interpolation: Interpolation,
+    seed: i32,
+) -> (S::Vf32, [S::Vf32; 2]) {
+    value_deriv::<S, 2>([x, y], interpolation, seed)
+}
+
+/// Samples 3-dimensional value noise
+///
+/// Produces a value -1 ≤ n ≤ 1.
+#[inline(always)]
+pub fn value_3d<S: Simd>(
+    x: S::Vf32,
+    y: S::Vf32,
+    z: S::Vf32,
+    interpolation: Interpolation,
+    seed: i32,
+) -> S::Vf32 {
+    value_3d_deriv::<S>(x, y, z, interpolation, seed).0
+}
+
+/// Like `value_3d`, but also computes the derivative
+#[inline(always)]
+pub fn value_3d_deriv<S: Simd>(
+    x: S::Vf32,
+    y: S::Vf32,
+    z: S::Vf32,
+    interpolation: Interpolation,
+    seed: i32,
+) -> (S::Vf32, [S::Vf32; 3]) {
+    value_deriv::<S, 3>([x, y, z], interpolation, seed)
+}
+
+/// Samples 1-dimensional cubic value noise, smoother than value noise
+///
+/// Produces a value -1 ≤ n ≤ 1.
+#[inline(always)]
+pub fn value_cubic_1d<S: Simd>(x: S::Vf32, seed: i32) -> S::Vf32 {
+    value_cubic_1d_deriv::<S>(x, seed).0
+}
+
+/// Like `value_cubic_1d`, but also computes the derivative
+#[inline(always)]
+pub fn value_cubic_1d_deriv<S: Simd>(x: S::Vf32, seed: i32) -> (S::Vf32, S::Vf32) {
+    let (value, [dx]) = value_cubic_deriv::<S, 1>([x], seed);
+    (value, dx)
+}
+
+/// Samples 2-dimensional cubic value noise, smoother than value noise
+///
+/// Produces a value -1 ≤ n ≤ 1.
+#[inline(always)]
+pub fn value_cubic_2d<S: Simd>(x: S::Vf32, y: S::Vf32, seed: i32) -> S::Vf32 {
+    value_cubic_2d_deriv::<S>(x, y, seed).0
+}
+
+/// Like `value_cubic_2d`, but also computes the derivative
+#[inline(always)]
+pub fn value_cubic_2d_deriv<S: Simd>(
+    x: S::Vf32,
+    y: S::Vf32,
+    seed: i32,
+) -> (S::Vf32, [S::Vf32; 2]) {
+    value_cubic_deriv::<S, 2>([x, y], seed)
+}
+
+/// Samples 3-dimensional cubic value noise, smoother than value noise
+///
+/// Produces a value -1 ≤ n ≤ 1.
+#[inline(always)]
+pub fn value_cubic_3d<S: Simd>(x: S::Vf32, y: S::Vf32, z: S::Vf32, seed: i32) -> S::Vf32 {
+    value_cubic_3d_deriv::<S>(x, y, z, seed).0
+}
+
+/// Like `value_cubic_3d`, but also computes the derivative
+#[inline(always)]
+pub fn value_cubic_3d_deriv<S: Simd>(
+    x: S::Vf32,
+    y: S::Vf32,
+    z: S::Vf32,
+    seed: i32,
+) -> (S::Vf32, [S::Vf32; 3]) {
+    value_cubic_deriv::<S, 3>([x, y, z], seed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simdeez::scalar::{F32x1, Scalar};
+
+    const INTERPOLATIONS: [Interpolation; 3] =
+        [Interpolation::Linear, Interpolation::Hermite, Interpolation::Quintic];
+
+    /// Calls `sample` with the points of a grid `size` points wide along every axis, 0.37 apart so the points
+    /// land on many different spots of the cells, and a bit off the lattice
+    fn sample_grid<const D: usize>(size: usize, mut sample: impl FnMut([f32; D])) {
+        for i in 0..size.pow(D as u32) {
+            let mut i = i;
+            sample(core::array::from_fn(|_| {
+                let v = (i % size) as f32 * 0.37 + 0.1234;
+                i /= size;
+                v
+            }));
+        }
+    }
+
+    /// Checks the noise stays in [-1, 1] and gets further from 0 than `reach`
+    fn check_range<const D: usize>(size: usize, reach: f32, noise: impl Fn([f32; D], i32) -> f32) {
+        for seed in 0..10 {
+            let mut min = f32::INFINITY;
+            let mut max = -f32::INFINITY;
+            sample_grid::<D>(size, |p| {
+                let n = noise(p, seed);
+                min = min.min(n);
+                max = max.max(n);
+            });
+            assert!(min < -reach && min >= -1.0, "min out of range {}", min);
+            assert!(max > reach && max <= 1.0, "max out of range: {}", max);
+        }
+    }
+
+    fn check_deriv<const D: usize>(
+        size: usize,
+        noise: impl Fn([f32; D], i32) -> (f32, [f32; D]),
+    ) {
+        const H: f32 = 0.01;
+        const SEEDS: i32 = 10;
+        let mut avg_err = 0.0;
+        let count = (SEEDS as usize * size.pow(D as u32) * D * 2) as f32;
+        for seed in 0..SEEDS {
+            sample_grid::<D>(size, |p| {
+                let (value, d) = noise(p, seed);
+                for axis in 0..D {
+                    for h in [-H, H] {
+                        let mut moved = p;
+                        moved[axis] += h;
+                        avg_err += (noise(moved, seed).0 - (value + d[axis] * h)).abs() / count;
+                    }
+                }
+            });
+        }
+        assert!(avg_err < 1e-3, "average error {}", avg_err);
+    }
+
+    #[test]
+    fn test_noise_value32_1d_range() {
+        for interpolation in INTERPOLATIONS {
+            check_range::<1>(1000, 0.75, |[x], seed| {
+                value_1d::<Scalar>(F32x1(x), interpolation, seed).0
+            });
+        }
+        check_range::<1>(1000, 0.5, |[x], seed| value_cubic_1d::<Scalar>(F32x1(x), seed).0);
+    }
+
+    #[test]
+    fn test_noise_value32_1d_deriv_sanity() {
+        for interpolation in INTERPOLATIONS {
+            check_deriv::<1>(1000, |[x], seed| {
+                let (value, dx) = value_1d_deriv::<Scalar>(F32x1(x), interpolation, seed);
+                (value.0, [dx.0])
+            });
+        }
+        check_deriv::<1>(1000, |[x], seed| {
+            let (value, dx) = value_cubic_1d_deriv::<Scalar>(F32x1(x), seed);
+            (value.0, [dx.0])
+        });
+    }
+
+    #[test]
+    fn test_noise_value32_2d_range() {
+        for interpolation in INTERPOLATIONS {
+            check_range::<2>(100, 0.75, |[x, y], seed| {
+                value_2d::<Scalar>(F32x1(x), F32x1(y), interpolation, seed).0
+            });
+        }
+        check_range::<2>(100, 0.4, |[x, y], seed| {
+            value_cubic_2d::<Scalar>(F32x1(x), F32x1(y), seed).0
+        });
+    }
+
+    #[test]
+    fn test_noise_value32_2d_deriv_sanity() {
+        for interpolation in INTERPOLATIONS {
+            check_deriv::<2>(30, |[x, y], seed| {
+                let (value, d) = value_2d_deriv::<Scalar>(F32x1(x), F32x1(y), interpolation, seed);
+                (value.0, d.map(|d| d.0))
+            });
+        }
+        check_deriv::<2>(30, |[x, y], seed| {
+            let (value, d) = value_cubic_2d_deriv::<Scalar>(F32x1(x), F32x1(y), seed);
+            (value.0, d.map(|d| d.0))
+        });
+    }
+
+    #[test]
+    fn test_noise_value32_3d_range() {
+        for interpolation in INTERPOLATIONS {
+            check_range::<3>(25, 0.75, |[x, y, z], seed| {
+                value_3d::<Scalar>(F32x1(x), F32x1(y), F32x1(z), interpolation, seed).0
+            });
+        }
+        check_range::<3>(25, 0.3, |[x, y, z], seed| {
+            value_cubic_3d::<Scalar>(F32x1(x), F32x1(y), F32x1(z), seed).0
+        });
+    }
+
+    #[test]
+    fn test_noise_value32_3d_deriv_sanity() {
+        for interpolation in INTERPOLATIONS {
+            check_deriv::<3>(10, |[x, y, z], seed| {
+                let (value, d) =
+                    value_3d_deriv::<Scalar>(F32x1(x), F32x1(y), F32x1(z), interpolation, seed);
+                (value.0, d.map(|d| d.0))
+            });
+        }
+        check_deriv::<3>(10, |[x, y, z], seed| {
+            let (value, d) = value_cubic_3d_deriv::<Scalar>(F32x1(x), F32x1(y), F32x1(z), seed);
+            (value.0, d.map(|d| d.0))
+        });
+    }
+}
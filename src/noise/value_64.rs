@@ -0,0 +1,277 @@
+//! Low-level value noise primitives in double precision
+//!
+//! Like `value_32`, the low 32 bits of a seed give the same noise in both precisions.
+
+use crate::noise::lattice::Interpolation;
+use crate::noise::perlin_64::hash;
+
+use simdeez::prelude::*;
+
+/// The random value in [-1, 1] of the lattice point with hash `hash`
+#[inline(always)]
+fn lattice_value<S: Simd>(hash: S::Vi64) -> S::Vf64 {
+    (hash & S::Vi64::set1(0xff)).cast_f64() * S::Vf64::set1(2.0 / 255.0) - S::Vf64::set1(1.0)
+}
+
+/// The weight of the far lattice point along an axis `t` into the cell, and its derivative
+#[inline(always)]
+fn interpolate<S: Simd>(t: S::Vf64, interpolation: Interpolation) -> (S::Vf64, S::Vf64) {
+    match interpolation {
+        Interpolation::Linear => (t, S::Vf64::set1(1.0)),
+        // 3t^2 - 2t^3 and 6t (1 - t)
+        Interpolation::Hermite => (
+            t * t * (S::Vf64::set1(3.0) - t * S::Vf64::set1(2.0)),
+            t * (S::Vf64::set1(1.0) - t) * S::Vf64::set1(6.0),
+        ),
+        // 6t^5 - 15t^4 + 10t^3 and 30t^2 (t - 1)^2
+        Interpolation::Quintic => {
+            let t1 = t - S::Vf64::set1(1.0);
+            let t3 = t * t * t;
+            (
+                t3 * (t * (t * S::Vf64::set1(6.0) - S::Vf64::set1(15.0)) + S::Vf64::set1(10.0)),
+                t * t * t1 * t1 * S::Vf64::set1(30.0),
+            )
+        }
+    }
+}
+
+/// Weights of the 4 lattice points from 1 before to 2 after the cell along an axis `t` into it,
+/// and their derivatives. They are the cubic through the 4 values with the slopes of the
+/// neighbours at the cell edges.
+#[inline(always)]
+fn cubic_weights<S: Simd>(t: S::Vf64) -> ([S::Vf64; 4], [S::Vf64; 4]) {
+    let one = S::Vf64::set1(1.0);
+    let (t2, t3) = (t * t, t * t * t);
+    let c = |v: f64| S::Vf64::set1(v);
+    (
+        [
+            t2 * c(2.0) - t3 - t,
+            t3 - t2 * c(2.0) + one,
+            t2 + t - t3,
+            t3 - t2,
+        ],
+        [
+            t * c(4.0) - t2 * c(3.0) - one,
+            t2 * c(3.0) - t * c(4.0),
+            t * c(2.0) + one - t2 * c(3.0),
+            t2 * c(3.0) - t * c(2.0),
+        ],
+    )
+}
+
+/// Samples `D`-dimensional value noise and its derivative
+#[inline(always)]
+fn value_deriv<S: Simd, const D: usize>(
+    p: [S::Vf64; D],
+    interpolation: Interpolation,
+    seed: i64,
+) -> (S::Vf64, [S::Vf64; D]) {
+    let cell = p.map(|v| v.floor());
+    let i0 = cell.map(|v| v.cast_i64());
+    let weights: [(S::Vf64, S::Vf64); D] =
+        core::array::from_fn(|i| interpolate::<S>(p[i] - cell[i], interpolation));
+
+    let mut value = S::Vf64::zeroes();
+    let mut deriv = [S::Vf64::zeroes(); D];
+    for corner in 0..1 << D {
+        let far = |axis: usize| corner >> axis & 1 == 1;
+        let coords = core::array::from_fn(|i| i0[i] + S::Vi64::set1(far(i) as i64));
+        let v = lattice_value::<S>(hash::<S, D>(coords, seed));
+        let (w, dw): ([S::Vf64; D], [S::Vf64; D]) = (
+            core::array::from_fn(|i| {
+                if far(i) { weights[i].0 } else { S::Vf64::set1(1.0) - weights[i].0 }
+            }),
+            core::array::from_fn(|i| {
+                if far(i) { weights[i].1 } else { S::Vf64::zeroes() - weights[i].1 }
+            }),
+        );
+        value = (w.iter().fold(v, |value, &w| value * w)) + value;
+        for axis in 0..D {
+            let d = (0..D).fold(v * dw[axis], |d, i| if i == axis { d } else { d * w[i] });
+            deriv[axis] = d + deriv[axis];
+        }
+    }
+    (value, deriv)
+}
+
+/// Samples `D`-dimensional cubic value noise and its derivative, scaled into [-1, 1]
+#[inline(always)]
+fn value_cubic_deriv<S: Simd, const D: usize>(
+    p: [S::Vf64; D],
+    seed: i64,
+) -> (S::Vf64, [S::Vf64; D]) {
+    let cell = p.map(|v| v.floor());
+    let i0 = cell.map(|v| v.cast_i64());
+    let weights: [_; D] = core::array::from_fn(|i| cubic_weights::<S>(p[i] - cell[i]));
+
+    let mut value = S::Vf64::zeroes();
+    let mut deriv = [S::Vf64::zeroes(); D];
+    for point in 0..4usize.pow(D as u32) {
+        let offset = |axis: usize| point / 4usize.pow(axis as u32) % 4;
+        let coords = core::array::from_fn(|i| i0[i] + S::Vi64::set1(offset(i) as i64 - 1));
+        let v = lattice_value::<S>(hash::<S, D>(coords, seed));
+        let w: [S::Vf64; D] = core::array::from_fn(|i| weights[i].0[offset(i)]);
+        value = (w.iter().fold(v, |value, &w| value * w)) + value;
+        for axis in 0..D {
+            let dw = weights[axis].1[offset(axis)];
+            let d = (0..D).fold(v * dw, |d, i| if i == axis { d } else { d * w[i] });
+            deriv[axis] = d + deriv[axis];
+        }
+    }
+    // The weights of an axis add up to at most 1.5 in absolute value
+    let scale = S::Vf64::set1((0..D).fold(1.0, |scale, _| scale / 1.5));
+    (value * scale, deriv.map(|d| d * scale))
+}
+
+/// Samples 1-dimensional value noise
+///
+/// Produces a value -1 ≤ n ≤ 1.
+#[inline(always)]
+pub fn value_1d<S: Simd>(x: S::Vf64, interpolation: Interpolation, seed: i64) -> S::Vf64 {
+    value_1d_deriv::<S>(x, interpolation, seed).0
+}
+
+/// Like `value_1d`, but also computes the derivative
+#[inline(always)]
+pub fn value_1d_deriv<S: Simd>(
+    x: S::Vf64,
+    interpolation: Interpolation,
+    seed: i64,
+) -> (S::Vf64, S::Vf64) {
+    let (value, [dx]) = value_deriv::<S, 1>([x], interpolation, seed);
+    (value, dx)
+}
+
+/// Samples 2-dimensional value noise
+///
+/// Produces a value -1 ≤ n ≤ 1.
+#[inline(always)]
+pub fn value_2d<S: Simd>(
+    x: S::Vf64,
+    y: S::Vf64,
+    interpolation: Interpolation,
+    seed: i64,
+) -> S::Vf64 {
+    value_2d_deriv::<S>(x, y, interpolation, seed).0
+}
+
+/// Like `value_2d`, but also computes the derivative
+#[inline(always)]
+pub fn value_2d_deriv<S: Simd>(
+    x: S::Vf64,
+    y: S::Vf64,
+    interpolation: Interpolation,
+    seed: i64,
+) -> (S::Vf64, [S::Vf64; 2]) {
+    value_deriv::<S, 2>([x, y], interpolation, seed)
+}
+
+/// Samples 3-dimensional value noise
+///
+/// Produces a value -1 ≤ n ≤ 1.
+#[inline(always)]
+pub fn value_3d<S: Simd>(
+    x: S::Vf64,
+    y: S::Vf64,
+    z: S::Vf64,
+    interpolation: Interpolation,
+    seed: i64,
+) -> S::Vf64 {
+    value_3d_deriv::<S>(x, y, z, interpolation, seed).0
+}
+
+/// Like `value_3d`, but also computes the derivative
+#[inline(always)]
+pub fn value_3d_deriv<S: Simd>(
+    x: S::Vf64,
+    y: S::Vf64,
+    z: S::Vf64,
+    interpolation: Interpolation,
+    seed: i64,
+) -> (S::Vf64, [S::Vf64; 3]) {
+    value_deriv::<S, 3>([x, y, z], interpolation, seed)
+}
+
+/// Samples 1-dimensional cubic value noise, smoother than value noise
+///
+/// Produces a value -1 ≤ n ≤ 1.
+#[inline(always)]
+pub fn value_cubic_1d<S: Simd>(x: S::Vf64, seed: i64) -> S::Vf64 {
+    value_cubic_1d_deriv::<S>(x, seed).0
+}
+
+/// Like `value_cubic_1d`, but also computes the derivative
+#[inline(always)]
+pub fn value_cubic_1d_deriv<S: Simd>(x: S::Vf64, seed: i64) -> (S::Vf64, S::Vf64) {
+    let (value, [dx]) = value_cubic_deriv::<S, 1>([x], seed);
+    (value, dx)
+}
+
+/// Samples 2-dimensional cubic value noise, smoother than value noise
+///
+/// Produces a value -1 ≤ n ≤ 1.
+#[inline(always)]
+pub fn value_cubic_2d<S: Simd>(x: S::Vf64, y: S::Vf64, seed: i64) -> S::Vf64 {
+    value_cubic_2d_deriv::<S>(x, y, seed).0
+}
+
+/// Like `value_cubic_2d`, but also computes the derivative
+#[inline(always)]
+pub fn value_cubic_2d_deriv<S: Simd>(
+    x: S::Vf64,
+    y: S::Vf64,
+    seed: i64,
+) -> (S::Vf64, [S::Vf64; 2]) {
+    value_cubic_deriv::<S, 2>([x, y], seed)
+}
+
+/// Samples 3-dimensional cubic value noise, smoother than value noise
+///
+/// Produces a value -1 ≤ n ≤ 1.
+#[inline(always)]
+pub fn value_cubic_3d<S: Simd>(x: S::Vf64, y: S::Vf64, z: S::Vf64, seed: i64) -> S::Vf64 {
+    value_cubic_3d_deriv::<S>(x, y, z, seed).0
+}
+
+/// Like `value_cubic_3d`, but also computes the derivative
+#[inline(always)]
+pub fn value_cubic_3d_deriv<S: Simd>(
+    x: S::Vf64,
+    y: S::Vf64,
+    z: S::Vf64,
+    seed: i64,
+) -> (S::Vf64, [S::Vf64; 3]) {
+    value_cubic_deriv::<S, 3>([x, y, z], seed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::noise::value_32;
+    use simdeez::scalar::{F32x1, F64x1, Scalar};
+
+    #[test]
+    fn test_noise_value64_matches_value32() {
+        for seed in [0, 1, -7, 0x1234_5678] {
+            for i in 0..1000 {
+                let [x, y, z] = [i as f32 * 0.37, i as f32 * -0.21 + 3.1, i as f32 * 0.13 - 9.3];
+                let interpolation = Interpolation::Hermite;
+                let expected = [
+                    value_32::value_1d::<Scalar>(F32x1(x), interpolation, seed).0,
+                    value_32::value_2d::<Scalar>(F32x1(x), F32x1(y), interpolation, seed).0,
+                    value_32::value_cubic_3d::<Scalar>(F32x1(x), F32x1(y), F32x1(z), seed).0,
+                ];
+                let [x, y, z] = [x, y, z].map(|v| F64x1(v.into()));
+                let seed = seed.into();
+                let actual = [
+                    value_1d::<Scalar>(x, interpolation, seed).0,
+                    value_2d::<Scalar>(x, y, interpolation, seed).0,
+                    value_cubic_3d::<Scalar>(x, y, z, seed).0,
+                ];
+                for (expected, actual) in expected.into_iter().zip(actual) {
+                    assert!((f64::from(expected) - actual).abs() < 1e-5);
+                }
+            }
+        }
+    }
+}
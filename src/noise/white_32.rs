@@ -0,0 +1,84 @@
+//! White noise
+//!
+//! Every lattice cell gets a random value of its own, hashed from the cell coordinates and the
+//! seed like the feature points of cellular noise. Neighbouring cells are unrelated, so with a
+//! frequency and step of 1.0 every sample is an independent uniform random value.
+
+use crate::noise::cellular_32::{hash, hash_value};
+use crate::noise::simplex_32::{X_PRIME_32, Y_PRIME_32, Z_PRIME_32};
+
+use simdeez::prelude::*;
+
+/// The lattice coordinate of the cell `x` is in, pre-multiplied by `prime` for `hash`
+#[inline(always)]
+fn cell<S: Simd>(x: S::Vf32, prime: i32) -> S::Vi32 {
+    x.floor().cast_i32() * S::Vi32::set1(prime)
+}
+
+/// Samples 1-dimensional white noise
+///
+/// Produces a value -1 ≤ n ≤ 1.
+#[inline(always)]
+pub fn white_1d<S: Simd>(x: S::Vf32, seed: i32) -> S::Vf32 {
+    let zero = S::Vi32::zeroes();
+    hash_value::<S>(hash::<S>(seed, cell::<S>(x, X_PRIME_32), zero, zero))
+}
+
+/// Samples 2-dimensional white noise
+///
+/// Produces a value -1 ≤ n ≤ 1.
+#[inline(always)]
+pub fn white_2d<S: Simd>(x: S::Vf32, y: S::Vf32, seed: i32) -> S::Vf32 {
+    let (i, j) = (cell::<S>(x, X_PRIME_32), cell::<S>(y, Y_PRIME_32));
+    hash_value::<S>(hash::<S>(seed, i, j, S::Vi32::zeroes()))
+}
+
+/// Samples 3-dimensional white noise
+///
+/// Produces a value -1 ≤ n ≤ 1.
+#[inline(always)]
+pub fn white_3d<S: Simd>(x: S::Vf32, y: S::Vf32, z: S::Vf32, seed: i32) -> S::Vf32 {
+    let (i, j) = (cell::<S>(x, X_PRIME_32), cell::<S>(y, Y_PRIME_32));
+    hash_value::<S>(hash::<S>(seed, i, j, cell::<S>(z, Z_PRIME_32)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simdeez::scalar::{F32x1, Scalar};
+
+    /// Checks `values` fill 10 equal buckets of [-1, 1] evenly and that neighbours are unrelated
+    fn check_uniform(values: &[f32]) {
+        let mut buckets = [0usize; 10];
+        for &v in values {
+            assert!((-1.0..=1.0).contains(&v), "value {}", v);
+            buckets[(((v + 1.0) * 5.0) as usize).min(9)] += 1;
+        }
+        let expected = values.len() / 10;
+        for count in buckets {
+            assert!(count.abs_diff(expected) < expected / 10, "buckets {:?}", buckets);
+        }
+        let n = values.len() as f32;
+        let variance = values.iter().map(|v| v * v).sum::<f32>() / n;
+        let covariance = values.windows(2).map(|w| w[0] * w[1]).sum::<f32>() / n;
+        assert!((covariance / variance).abs() < 0.05, "correlation {}", covariance / variance);
+    }
+
+    #[test]
+    fn test_noise_white32_uniform() {
+        let seed = 1337;
+        let line: [f32; 20000] =
+            core::array::from_fn(|x| white_1d::<Scalar>(F32x1(x as f32), seed).0);
+        check_uniform(&line);
+        let plane: [f32; 20000] = core::array::from_fn(|i| {
+            let (x, y) = ((i % 100) as f32, (i / 100) as f32);
+            white_2d::<Scalar>(F32x1(x), F32x1(y), seed).0
+        });
+        check_uniform(&plane);
+        let volume: [f32; 32000] = core::array::from_fn(|i| {
+            let (x, y, z) = ((i % 40) as f32, (i / 40 % 40) as f32, (i / 1600) as f32);
+            white_3d::<Scalar>(F32x1(x), F32x1(y), F32x1(z), seed).0
+        });
+        check_uniform(&volume);
+    }
+}
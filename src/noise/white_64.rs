@@ -0,0 +1,41 @@
+//! White noise in double precision
+//!
+//! Hashes like `white_32` in 64 bit integers, the values differ from the single precision ones.
+
+use crate::noise::cellular_64::{hash, hash_value};
+use crate::noise::simplex_32::{X_PRIME_64, Y_PRIME_64, Z_PRIME_64};
+
+use simdeez::prelude::*;
+
+/// The lattice coordinate of the cell `x` is in, pre-multiplied by `prime` for `hash`
+#[inline(always)]
+fn cell<S: Simd>(x: S::Vf64, prime: i64) -> S::Vi64 {
+    x.floor().cast_i64() * S::Vi64::set1(prime)
+}
+
+/// Samples 1-dimensional white noise
+///
+/// Produces a value -1 ≤ n ≤ 1.
+#[inline(always)]
+pub fn white_1d<S: Simd>(x: S::Vf64, seed: i64) -> S::Vf64 {
+    let zero = S::Vi64::zeroes();
+    hash_value::<S>(hash::<S>(seed, cell::<S>(x, X_PRIME_64), zero, zero))
+}
+
+/// Samples 2-dimensional white noise
+///
+/// Produces a value -1 ≤ n ≤ 1.
+#[inline(always)]
+pub fn white_2d<S: Simd>(x: S::Vf64, y: S::Vf64, seed: i64) -> S::Vf64 {
+    let (i, j) = (cell::<S>(x, X_PRIME_64), cell::<S>(y, Y_PRIME_64));
+    hash_value::<S>(hash::<S>(seed, i, j, S::Vi64::zeroes()))
+}
+
+/// Samples 3-dimensional white noise
+///
+/// Produces a value -1 ≤ n ≤ 1.
+#[inline(always)]
+pub fn white_3d<S: Simd>(x: S::Vf64, y: S::Vf64, z: S::Vf64, seed: i64) -> S::Vf64 {
+    let (i, j) = (cell::<S>(x, X_PRIME_64), cell::<S>(y, Y_PRIME_64));
+    hash_value::<S>(hash::<S>(seed, i, j, cell::<S>(z, Z_PRIME_64)))
+}
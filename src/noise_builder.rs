@@ -1,6 +1,9 @@
+// The 4d offset constructors take an offset and a size per axis
+#![allow(clippy::too_many_arguments)]
+
 use super::{
-    FbmSettings, GradientSettings, NoiseDimensions
-    , Settings,
+    BillowSettings, CellularSettings, CurlSettings, DomainWarpSettings, FbmSettings,
+    GradientSettings, NoiseDimensions, RidgeSettings, Settings, TurbulenceSettings, WhiteSettings,
 };
 
 pub struct NoiseBuilder {}
@@ -16,7 +19,7 @@ impl NoiseBuilder {
     pub fn fbm_1d_offset(x_offset: f32, width: usize) -> FbmSettings {
         let mut dim = NoiseDimensions::default(1);
         dim.width = width;
-        dim.x = x_offset;
+        dim.x = x_offset.into();
         FbmSettings::default(dim)
     }
 
@@ -31,8 +34,8 @@ impl NoiseBuilder {
         let mut dim = NoiseDimensions::default(2);
         dim.width = width;
         dim.height = height;
-        dim.x = x_offset;
-        dim.y = y_offset;
+        dim.x = x_offset.into();
+        dim.y = y_offset.into();
         FbmSettings::default(dim)
     }
 
@@ -56,9 +59,40 @@ impl NoiseBuilder {
         dim.width = width;
         dim.height = height;
         dim.depth = depth;
-        dim.x = x_offset;
-        dim.y = y_offset;
-        dim.z = z_offset;
+        dim.x = x_offset.into();
+        dim.y = y_offset.into();
+        dim.z = z_offset.into();
+        FbmSettings::default(dim)
+    }
+
+    pub fn fbm_4d(width: usize, height: usize, depth: usize, time: usize) -> FbmSettings {
+        let mut dim = NoiseDimensions::default(4);
+        dim.width = width;
+        dim.height = height;
+        dim.depth = depth;
+        dim.time = time;
+        FbmSettings::default(dim)
+    }
+
+    pub fn fbm_4d_offset(
+        x_offset: f32,
+        width: usize,
+        y_offset: f32,
+        height: usize,
+        z_offset: f32,
+        depth: usize,
+        w_offset: f32,
+        time: usize,
+    ) -> FbmSettings {
+        let mut dim = NoiseDimensions::default(4);
+        dim.width = width;
+        dim.height = height;
+        dim.depth = depth;
+        dim.time = time;
+        dim.x = x_offset.into();
+        dim.y = y_offset.into();
+        dim.z = z_offset.into();
+        dim.w = w_offset.into();
         FbmSettings::default(dim)
     }
 
@@ -72,7 +106,7 @@ impl NoiseBuilder {
     pub fn gradient_1d_offset(x_offset: f32, width: usize) -> GradientSettings {
         let mut dim = NoiseDimensions::default(1);
         dim.width = width;
-        dim.x = x_offset;
+        dim.x = x_offset.into();
         GradientSettings::default(dim)
     }
 
@@ -92,8 +126,8 @@ impl NoiseBuilder {
         let mut dim = NoiseDimensions::default(2);
         dim.width = width;
         dim.height = height;
-        dim.x = x_offset;
-        dim.y = y_offset;
+        dim.x = x_offset.into();
+        dim.y = y_offset.into();
         GradientSettings::default(dim)
     }
 
@@ -118,9 +152,575 @@ impl NoiseBuilder {
         dim.width = width;
         dim.height = height;
         dim.depth = depth;
-        dim.x = x_offset;
-        dim.y = y_offset;
-        dim.z = z_offset;
+        dim.x = x_offset.into();
+        dim.y = y_offset.into();
+        dim.z = z_offset.into();
         GradientSettings::default(dim)
     }
+
+    pub fn gradient_4d(width: usize, height: usize, depth: usize, time: usize) -> GradientSettings {
+        let mut dim = NoiseDimensions::default(4);
+        dim.width = width;
+        dim.height = height;
+        dim.depth = depth;
+        dim.time = time;
+        GradientSettings::default(dim)
+    }
+
+    pub fn gradient_4d_offset(
+        x_offset: f32,
+        width: usize,
+        y_offset: f32,
+        height: usize,
+        z_offset: f32,
+        depth: usize,
+        w_offset: f32,
+        time: usize,
+    ) -> GradientSettings {
+        let mut dim = NoiseDimensions::default(4);
+        dim.width = width;
+        dim.height = height;
+        dim.depth = depth;
+        dim.time = time;
+        dim.x = x_offset.into();
+        dim.y = y_offset.into();
+        dim.z = z_offset.into();
+        dim.w = w_offset.into();
+        GradientSettings::default(dim)
+    }
+
+    // Ridge Builders
+    pub fn ridge_1d(width: usize) -> RidgeSettings {
+        let mut dim = NoiseDimensions::default(1);
+        dim.width = width;
+        RidgeSettings::default(dim)
+    }
+
+    pub fn ridge_1d_offset(x_offset: f32, width: usize) -> RidgeSettings {
+        let mut dim = NoiseDimensions::default(1);
+        dim.width = width;
+        dim.x = x_offset.into();
+        RidgeSettings::default(dim)
+    }
+
+    pub fn ridge_2d(width: usize, height: usize) -> RidgeSettings {
+        let mut dim = NoiseDimensions::default(2);
+        dim.width = width;
+        dim.height = height;
+        RidgeSettings::default(dim)
+    }
+
+    pub fn ridge_2d_offset(
+        x_offset: f32,
+        width: usize,
+        y_offset: f32,
+        height: usize,
+    ) -> RidgeSettings {
+        let mut dim = NoiseDimensions::default(2);
+        dim.width = width;
+        dim.height = height;
+        dim.x = x_offset.into();
+        dim.y = y_offset.into();
+        RidgeSettings::default(dim)
+    }
+
+    pub fn ridge_3d(width: usize, height: usize, depth: usize) -> RidgeSettings {
+        let mut dim = NoiseDimensions::default(3);
+        dim.width = width;
+        dim.height = height;
+        dim.depth = depth;
+        RidgeSettings::default(dim)
+    }
+
+    pub fn ridge_3d_offset(
+        x_offset: f32,
+        width: usize,
+        y_offset: f32,
+        height: usize,
+        z_offset: f32,
+        depth: usize,
+    ) -> RidgeSettings {
+        let mut dim = NoiseDimensions::default(3);
+        dim.width = width;
+        dim.height = height;
+        dim.depth = depth;
+        dim.x = x_offset.into();
+        dim.y = y_offset.into();
+        dim.z = z_offset.into();
+        RidgeSettings::default(dim)
+    }
+
+    pub fn ridge_4d(width: usize, height: usize, depth: usize, time: usize) -> RidgeSettings {
+        let mut dim = NoiseDimensions::default(4);
+        dim.width = width;
+        dim.height = height;
+        dim.depth = depth;
+        dim.time = time;
+        RidgeSettings::default(dim)
+    }
+
+    pub fn ridge_4d_offset(
+        x_offset: f32,
+        width: usize,
+        y_offset: f32,
+        height: usize,
+        z_offset: f32,
+        depth: usize,
+        w_offset: f32,
+        time: usize,
+    ) -> RidgeSettings {
+        let mut dim = NoiseDimensions::default(4);
+        dim.width = width;
+        dim.height = height;
+        dim.depth = depth;
+        dim.time = time;
+        dim.x = x_offset.into();
+        dim.y = y_offset.into();
+        dim.z = z_offset.into();
+        dim.w = w_offset.into();
+        RidgeSettings::default(dim)
+    }
+
+    // Turbulence Builders
+    pub fn turbulence_1d(width: usize) -> TurbulenceSettings {
+        let mut dim = NoiseDimensions::default(1);
+        dim.width = width;
+        TurbulenceSettings::default(dim)
+    }
+
+    pub fn turbulence_1d_offset(x_offset: f32, width: usize) -> TurbulenceSettings {
+        let mut dim = NoiseDimensions::default(1);
+        dim.width = width;
+        dim.x = x_offset.into();
+        TurbulenceSettings::default(dim)
+    }
+
+    pub fn turbulence_2d(width: usize, height: usize) -> TurbulenceSettings {
+        let mut dim = NoiseDimensions::default(2);
+        dim.width = width;
+        dim.height = height;
+        TurbulenceSettings::default(dim)
+    }
+
+    pub fn turbulence_2d_offset(
+        x_offset: f32,
+        width: usize,
+        y_offset: f32,
+        height: usize,
+    ) -> TurbulenceSettings {
+        let mut dim = NoiseDimensions::default(2);
+        dim.width = width;
+        dim.height = height;
+        dim.x = x_offset.into();
+        dim.y = y_offset.into();
+        TurbulenceSettings::default(dim)
+    }
+
+    pub fn turbulence_3d(width: usize, height: usize, depth: usize) -> TurbulenceSettings {
+        let mut dim = NoiseDimensions::default(3);
+        dim.width = width;
+        dim.height = height;
+        dim.depth = depth;
+        TurbulenceSettings::default(dim)
+    }
+
+    pub fn turbulence_3d_offset(
+        x_offset: f32,
+        width: usize,
+        y_offset: f32,
+        height: usize,
+        z_offset: f32,
+        depth: usize,
+    ) -> TurbulenceSettings {
+        let mut dim = NoiseDimensions::default(3);
+        dim.width = width;
+        dim.height = height;
+        dim.depth = depth;
+        dim.x = x_offset.into();
+        dim.y = y_offset.into();
+        dim.z = z_offset.into();
+        TurbulenceSettings::default(dim)
+    }
+
+    pub fn turbulence_4d(
+        width: usize,
+        height: usize,
+        depth: usize,
+        time: usize,
+    ) -> TurbulenceSettings {
+        let mut dim = NoiseDimensions::default(4);
+        dim.width = width;
+        dim.height = height;
+        dim.depth = depth;
+        dim.time = time;
+        TurbulenceSettings::default(dim)
+    }
+
+    pub fn turbulence_4d_offset(
+        x_offset: f32,
+        width: usize,
+        y_offset: f32,
+        height: usize,
+        z_offset: f32,
+        depth: usize,
+        w_offset: f32,
+        time: usize,
+    ) -> TurbulenceSettings {
+        let mut dim = NoiseDimensions::default(4);
+        dim.width = width;
+        dim.height = height;
+        dim.depth = depth;
+        dim.time = time;
+        dim.x = x_offset.into();
+        dim.y = y_offset.into();
+        dim.z = z_offset.into();
+        dim.w = w_offset.into();
+        TurbulenceSettings::default(dim)
+    }
+
+    // Billow Builders
+    pub fn billow_1d(width: usize) -> BillowSettings {
+        let mut dim = NoiseDimensions::default(1);
+        dim.width = width;
+        BillowSettings::default(dim)
+    }
+
+    pub fn billow_1d_offset(x_offset: f32, width: usize) -> BillowSettings {
+        let mut dim = NoiseDimensions::default(1);
+        dim.width = width;
+        dim.x = x_offset.into();
+        BillowSettings::default(dim)
+    }
+
+    pub fn billow_2d(width: usize, height: usize) -> BillowSettings {
+        let mut dim = NoiseDimensions::default(2);
+        dim.width = width;
+        dim.height = height;
+        BillowSettings::default(dim)
+    }
+
+    pub fn billow_2d_offset(
+        x_offset: f32,
+        width: usize,
+        y_offset: f32,
+        height: usize,
+    ) -> BillowSettings {
+        let mut dim = NoiseDimensions::default(2);
+        dim.width = width;
+        dim.height = height;
+        dim.x = x_offset.into();
+        dim.y = y_offset.into();
+        BillowSettings::default(dim)
+    }
+
+    pub fn billow_3d(width: usize, height: usize, depth: usize) -> BillowSettings {
+        let mut dim = NoiseDimensions::default(3);
+        dim.width = width;
+        dim.height = height;
+        dim.depth = depth;
+        BillowSettings::default(dim)
+    }
+
+    pub fn billow_3d_offset(
+        x_offset: f32,
+        width: usize,
+        y_offset: f32,
+        height: usize,
+        z_offset: f32,
+        depth: usize,
+    ) -> BillowSettings {
+        let mut dim = NoiseDimensions::default(3);
+        dim.width = width;
+        dim.height = height;
+        dim.depth = depth;
+        dim.x = x_offset.into();
+        dim.y = y_offset.into();
+        dim.z = z_offset.into();
+        BillowSettings::default(dim)
+    }
+
+    pub fn billow_4d(width: usize, height: usize, depth: usize, time: usize) -> BillowSettings {
+        let mut dim = NoiseDimensions::default(4);
+        dim.width = width;
+        dim.height = height;
+        dim.depth = depth;
+        dim.time = time;
+        BillowSettings::default(dim)
+    }
+
+    pub fn billow_4d_offset(
+        x_offset: f32,
+        width: usize,
+        y_offset: f32,
+        height: usize,
+        z_offset: f32,
+        depth: usize,
+        w_offset: f32,
+        time: usize,
+    ) -> BillowSettings {
+        let mut dim = NoiseDimensions::default(4);
+        dim.width = width;
+        dim.height = height;
+        dim.depth = depth;
+        dim.time = time;
+        dim.x = x_offset.into();
+        dim.y = y_offset.into();
+        dim.z = z_offset.into();
+        dim.w = w_offset.into();
+        BillowSettings::default(dim)
+    }
+
+    // Cellular Builders
+    pub fn cellular_2d(width: usize, height: usize) -> CellularSettings {
+        let mut dim = NoiseDimensions::default(2);
+        dim.width = width;
+        dim.height = height;
+        CellularSettings::default(dim)
+    }
+
+    pub fn cellular_2d_offset(
+        x_offset: f32,
+        width: usize,
+        y_offset: f32,
+        height: usize,
+    ) -> CellularSettings {
+        let mut dim = NoiseDimensions::default(2);
+        dim.width = width;
+        dim.height = height;
+        dim.x = x_offset.into();
+        dim.y = y_offset.into();
+        CellularSettings::default(dim)
+    }
+
+    pub fn cellular_3d(width: usize, height: usize, depth: usize) -> CellularSettings {
+        let mut dim = NoiseDimensions::default(3);
+        dim.width = width;
+        dim.height = height;
+        dim.depth = depth;
+        CellularSettings::default(dim)
+    }
+
+    pub fn cellular_3d_offset(
+        x_offset: f32,
+        width: usize,
+        y_offset: f32,
+        height: usize,
+        z_offset: f32,
+        depth: usize,
+    ) -> CellularSettings {
+        let mut dim = NoiseDimensions::default(3);
+        dim.width = width;
+        dim.height = height;
+        dim.depth = depth;
+        dim.x = x_offset.into();
+        dim.y = y_offset.into();
+        dim.z = z_offset.into();
+        CellularSettings::default(dim)
+    }
+
+    // White Builders
+    pub fn white_1d(width: usize) -> WhiteSettings {
+        let mut dim = NoiseDimensions::default(1);
+        dim.width = width;
+        WhiteSettings::default(dim)
+    }
+
+    pub fn white_1d_offset(x_offset: f32, width: usize) -> WhiteSettings {
+        let mut dim = NoiseDimensions::default(1);
+        dim.width = width;
+        dim.x = x_offset.into();
+        WhiteSettings::default(dim)
+    }
+
+    pub fn white_2d(width: usize, height: usize) -> WhiteSettings {
+        let mut dim = NoiseDimensions::default(2);
+        dim.width = width;
+        dim.height = height;
+        WhiteSettings::default(dim)
+    }
+
+    pub fn white_2d_offset(
+        x_offset: f32,
+        width: usize,
+        y_offset: f32,
+        height: usize,
+    ) -> WhiteSettings {
+        let mut dim = NoiseDimensions::default(2);
+        dim.width = width;
+        dim.height = height;
+        dim.x = x_offset.into();
+        dim.y = y_offset.into();
+        WhiteSettings::default(dim)
+    }
+
+    pub fn white_3d(width: usize, height: usize, depth: usize) -> WhiteSettings {
+        let mut dim = NoiseDimensions::default(3);
+        dim.width = width;
+        dim.height = height;
+        dim.depth = depth;
+        WhiteSettings::default(dim)
+    }
+
+    pub fn white_3d_offset(
+        x_offset: f32,
+        width: usize,
+        y_offset: f32,
+        height: usize,
+        z_offset: f32,
+        depth: usize,
+    ) -> WhiteSettings {
+        let mut dim = NoiseDimensions::default(3);
+        dim.width = width;
+        dim.height = height;
+        dim.depth = depth;
+        dim.x = x_offset.into();
+        dim.y = y_offset.into();
+        dim.z = z_offset.into();
+        WhiteSettings::default(dim)
+    }
+
+    // Domain Warp Builders
+    pub fn domain_warp_1d(width: usize) -> DomainWarpSettings {
+        let mut dim = NoiseDimensions::default(1);
+        dim.width = width;
+        DomainWarpSettings::default(dim)
+    }
+
+    pub fn domain_warp_1d_offset(x_offset: f32, width: usize) -> DomainWarpSettings {
+        let mut dim = NoiseDimensions::default(1);
+        dim.width = width;
+        dim.x = x_offset.into();
+        DomainWarpSettings::default(dim)
+    }
+
+    pub fn domain_warp_2d(width: usize, height: usize) -> DomainWarpSettings {
+        let mut dim = NoiseDimensions::default(2);
+        dim.width = width;
+        dim.height = height;
+        DomainWarpSettings::default(dim)
+    }
+
+    pub fn domain_warp_2d_offset(
+        x_offset: f32,
+        width: usize,
+        y_offset: f32,
+        height: usize,
+    ) -> DomainWarpSettings {
+        let mut dim = NoiseDimensions::default(2);
+        dim.width = width;
+        dim.height = height;
+        dim.x = x_offset.into();
+        dim.y = y_offset.into();
+        DomainWarpSettings::default(dim)
+    }
+
+    pub fn domain_warp_3d(width: usize, height: usize, depth: usize) -> DomainWarpSettings {
+        let mut dim = NoiseDimensions::default(3);
+        dim.width = width;
+        dim.height = height;
+        dim.depth = depth;
+        DomainWarpSettings::default(dim)
+    }
+
+    pub fn domain_warp_3d_offset(
+        x_offset: f32,
+        width: usize,
+        y_offset: f32,
+        height: usize,
+        z_offset: f32,
+        depth: usize,
+    ) -> DomainWarpSettings {
+        let mut dim = NoiseDimensions::default(3);
+        dim.width = width;
+        dim.height = height;
+        dim.depth = depth;
+        dim.x = x_offset.into();
+        dim.y = y_offset.into();
+        dim.z = z_offset.into();
+        DomainWarpSettings::default(dim)
+    }
+
+    pub fn domain_warp_4d(
+        width: usize,
+        height: usize,
+        depth: usize,
+        time: usize,
+    ) -> DomainWarpSettings {
+        let mut dim = NoiseDimensions::default(4);
+        dim.width = width;
+        dim.height = height;
+        dim.depth = depth;
+        dim.time = time;
+        DomainWarpSettings::default(dim)
+    }
+
+    pub fn domain_warp_4d_offset(
+        x_offset: f32,
+        width: usize,
+        y_offset: f32,
+        height: usize,
+        z_offset: f32,
+        depth: usize,
+        w_offset: f32,
+        time: usize,
+    ) -> DomainWarpSettings {
+        let mut dim = NoiseDimensions::default(4);
+        dim.width = width;
+        dim.height = height;
+        dim.depth = depth;
+        dim.time = time;
+        dim.x = x_offset.into();
+        dim.y = y_offset.into();
+        dim.z = z_offset.into();
+        dim.w = w_offset.into();
+        DomainWarpSettings::default(dim)
+    }
+
+    // Curl Builders
+    pub fn curl_2d(width: usize, height: usize) -> CurlSettings {
+        let mut dim = NoiseDimensions::default(2);
+        dim.width = width;
+        dim.height = height;
+        CurlSettings::default(dim)
+    }
+
+    pub fn curl_2d_offset(
+        x_offset: f32,
+        width: usize,
+        y_offset: f32,
+        height: usize,
+    ) -> CurlSettings {
+        let mut dim = NoiseDimensions::default(2);
+        dim.width = width;
+        dim.height = height;
+        dim.x = x_offset.into();
+        dim.y = y_offset.into();
+        CurlSettings::default(dim)
+    }
+
+    pub fn curl_3d(width: usize, height: usize, depth: usize) -> CurlSettings {
+        let mut dim = NoiseDimensions::default(3);
+        dim.width = width;
+        dim.height = height;
+        dim.depth = depth;
+        CurlSettings::default(dim)
+    }
+
+    pub fn curl_3d_offset(
+        x_offset: f32,
+        width: usize,
+        y_offset: f32,
+        height: usize,
+        z_offset: f32,
+        depth: usize,
+    ) -> CurlSettings {
+        let mut dim = NoiseDimensions::default(3);
+        dim.width = width;
+        dim.height = height;
+        dim.depth = depth;
+        dim.x = x_offset.into();
+        dim.y = y_offset.into();
+        dim.z = z_offset.into();
+        CurlSettings::default(dim)
+    }
 }
@@ -1,39 +1,169 @@
 #![allow(clippy::needless_return)]
 
+use crate::NoiseError;
+
+/// Which range `generate_scaled` maps onto `min..max`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ScaleMode {
+    /// The lowest and highest value in the generated block. Uses the whole output range, but
+    /// neighbouring blocks are scaled differently and show seams where they meet.
+    Observed,
+    /// The range the noise type can produce with its settings, see `Settings::bounds`. The same
+    /// coordinate always scales to the same value, whatever the size or offset of the block.
+    Theoretical,
+}
+
+/// How `NoiseDimensions::seed` turns into the seed the noise kernels hash with
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SeedMode {
+    /// The whole seed goes through an integer hash first, so neighbouring seeds give unrelated
+    /// noise
+    Mixed,
+    /// Only the low 32 bits are used, as is. Reproduces noise from before seeds were 64 bit.
+    Legacy,
+}
+
 #[derive(Copy, Clone)]
 pub struct NoiseDimensions {
     pub dim: usize,
-    pub x: f32,
-    pub y: f32,
-    pub z: f32,
+    /// Origin of the block, kept in double precision so `generate_f64` can sample far from 0
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub w: f64,
+    /// Distance between neighbouring samples along each axis, applied before the frequency
+    pub step_x: f32,
+    pub step_y: f32,
+    pub step_z: f32,
+    pub step_w: f32,
     pub width: usize,
     pub height: usize,
     pub depth: usize,
+    pub time: usize,
     pub min: f32,
     pub max: f32,
-    pub seed: i32,
+    pub scale_mode: ScaleMode,
+    pub seed: u64,
+    pub seed_mode: SeedMode,
 }
 
 impl NoiseDimensions {
+    /// Dimensions of a single value at the origin, `validate` rejects a `d` outside 1 to 4
+    ///
+    /// The seed is 1 in `SeedMode::Mixed`, so unseeded noise differs from versions before seeds
+    /// were 64 bit. Set `seed_mode` to `SeedMode::Legacy` to reproduce it.
     pub fn default(d: usize) -> NoiseDimensions {
-        if d < 1 || d > 3 {
-            panic!("dimension invalid");
-        }
         NoiseDimensions {
             dim: d,
             x: 0.0,
             y: 0.0,
             z: 0.0,
+            w: 0.0,
+            step_x: 1.0,
+            step_y: 1.0,
+            step_z: 1.0,
+            step_w: 1.0,
             width: 1,
             height: 1,
             depth: 1,
+            time: 1,
             min: 0.0,
             max: 1.0,
+            scale_mode: ScaleMode::Observed,
             seed: 1,
+            seed_mode: SeedMode::Mixed,
         }
     }
 
     pub fn len(self) -> usize {
-        return self.width * self.width * self.height;
+        return self.width * self.height * self.depth * self.time;
+    }
+
+    /// Like `len`, but `None` instead of overflowing
+    pub fn checked_len(self) -> Option<usize> {
+        self.width
+            .checked_mul(self.height)?
+            .checked_mul(self.depth)?
+            .checked_mul(self.time)
+    }
+
+    /// Checks the dimension count, that every size it uses is non zero and every size it
+    /// doesn't use is 1, and that the origin, steps and scale range are finite
+    pub fn validate(&self) -> Result<(), NoiseError> {
+        if !(1..=4).contains(&self.dim) {
+            return Err(NoiseError::UnsupportedDimension { dim: self.dim });
+        }
+        let sizes = [self.width, self.height, self.depth, self.time];
+        let (used, unused) = sizes.split_at(self.dim);
+        if used.contains(&0) {
+            return Err(NoiseError::ZeroSize);
+        }
+        if unused.iter().any(|&size| size != 1) {
+            return Err(NoiseError::DimensionMismatch { dim: self.dim });
+        }
+        if self.checked_len().is_none() {
+            return Err(NoiseError::LengthOverflow);
+        }
+        let params = [
+            self.x,
+            self.y,
+            self.z,
+            self.w,
+            self.step_x.into(),
+            self.step_y.into(),
+            self.step_z.into(),
+            self.step_w.into(),
+            self.min.into(),
+            self.max.into(),
+        ];
+        if !params.iter().all(|p| p.is_finite()) {
+            return Err(NoiseError::NonFiniteParameter);
+        }
+        Ok(())
+    }
+
+    /// The seed the 32 bit noise kernels hash with
+    pub fn seed_32(&self) -> i32 {
+        match self.seed_mode {
+            SeedMode::Mixed => mix_seed(self.seed) as i32,
+            SeedMode::Legacy => self.seed as i32,
+        }
+    }
+
+    /// The seed the 64 bit noise kernels hash with
+    pub fn seed_64(&self) -> i64 {
+        match self.seed_mode {
+            SeedMode::Mixed => mix_seed(self.seed) as i64,
+            // Legacy seeds were an i32 widened for the 64 bit kernels
+            SeedMode::Legacy => (self.seed as i32).into(),
+        }
     }
+
+    /// Checks that a buffer of `len` values can hold the whole block
+    pub fn check_buffer(self, len: usize) -> Result<(), NoiseError> {
+        self.check_channel_buffer(len, 1)
+    }
+
+    /// Checks that a buffer of `len` values can hold `channels` values for every sample
+    pub fn check_channel_buffer(self, len: usize, channels: usize) -> Result<(), NoiseError> {
+        let required = self
+            .checked_len()
+            .and_then(|samples| samples.checked_mul(channels))
+            .ok_or(NoiseError::LengthOverflow)?;
+        if len < required {
+            return Err(NoiseError::BufferTooSmall {
+                required,
+                actual: len,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// The splitmix64 finalizer, every input bit affects every output bit
+fn mix_seed(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
 }
@@ -0,0 +1,76 @@
+use core::fmt;
+
+/// Reasons a block of noise can't be generated
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NoiseError {
+    /// The dimension count is outside 1 to 4, or the noise type has no such variant
+    UnsupportedDimension { dim: usize },
+    /// One of the sizes the dimension count uses is zero
+    ZeroSize,
+    /// A size the dimension count doesn't use is not 1, e.g. a 2d block with a depth
+    DimensionMismatch { dim: usize },
+    /// `width * height * depth * time` doesn't fit in a usize
+    LengthOverflow,
+    /// The output slice holds fewer values than the dimensions ask for
+    BufferTooSmall { required: usize, actual: usize },
+    /// A frequency is NaN or infinite
+    NonFiniteFrequency,
+    /// An offset, scale range or other setting is NaN or infinite
+    NonFiniteParameter,
+    /// A fractal noise type was asked for zero octaves
+    ZeroOctaves,
+    /// The fractional octave weight is outside [0, 1)
+    InvalidOctaveFraction,
+    /// A coordinate slice holds a different number of points than the output slice
+    PointCountMismatch { expected: usize, actual: usize },
+    /// The noise type has no analytic derivatives
+    DerivativesUnsupported,
+    /// A period is negative or not finite, or doesn't fit a whole multiple of 3 lattice units
+    /// into every octave
+    InvalidPeriod,
+    /// A period was set for noise that can't repeat, only 2d and 3d noise on the simplex
+    /// lattice generated in single precision can
+    UnsupportedPeriod,
+}
+
+impl fmt::Display for NoiseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NoiseError::UnsupportedDimension { dim } => {
+                write!(f, "{}d noise is not supported", dim)
+            }
+            NoiseError::ZeroSize => write!(f, "noise size is zero"),
+            NoiseError::DimensionMismatch { dim } => {
+                write!(f, "{}d noise has a size set for an axis it doesn't use", dim)
+            }
+            NoiseError::LengthOverflow => write!(f, "noise length overflows usize"),
+            NoiseError::BufferTooSmall { required, actual } => write!(
+                f,
+                "noise buffer holds {} values but {} are needed",
+                actual, required
+            ),
+            NoiseError::NonFiniteFrequency => write!(f, "frequency is not finite"),
+            NoiseError::NonFiniteParameter => write!(f, "noise parameter is not finite"),
+            NoiseError::ZeroOctaves => write!(f, "octaves must be at least 1"),
+            NoiseError::InvalidOctaveFraction => {
+                write!(f, "fractional octave weight must be in [0, 1)")
+            }
+            NoiseError::PointCountMismatch { expected, actual } => write!(
+                f,
+                "coordinates hold {} points but the output holds {}",
+                actual, expected
+            ),
+            NoiseError::DerivativesUnsupported => {
+                write!(f, "noise type has no analytic derivatives")
+            }
+            NoiseError::InvalidPeriod => {
+                write!(f, "period must fit a whole multiple of 3 lattice units into every octave")
+            }
+            NoiseError::UnsupportedPeriod => {
+                write!(f, "only 2d and 3d simplex noise in single precision can repeat")
+            }
+        }
+    }
+}
+
+impl core::error::Error for NoiseError {}
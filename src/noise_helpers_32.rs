@@ -1,12 +1,15 @@
 use crate::dimensional_being::DimensionalBeing;
-use crate::{FbmSettings, GradientSettings, NoiseType, Settings};
+use crate::noise::curl_32::{curl_2d, curl_3d};
+use crate::{
+    BillowSettings, CellularSettings, ChannelLayout, CurlSettings, DomainWarpSettings,
+    FbmSettings, GradientSettings, NoiseError, NoiseType, RidgeSettings, Settings, Settings4d,
+    TurbulenceSettings, WhiteSettings,
+};
 
 use simdeez::prelude::*;
 
+use core::convert::Infallible;
 use core::f32;
-use simdeez::engines::avx2::Avx2;
-
-const VEC_WIDTH: usize = <Avx2 as simdeez::Simd>::Vf32::WIDTH;
 
 pub trait Sample32<S: Simd>: DimensionalBeing + Settings {
     fn sample_1d(&self, x: S::Vf32) -> S::Vf32;
@@ -14,13 +17,57 @@ pub trait Sample32<S: Simd>: DimensionalBeing + Settings {
     fn sample_3d(&self, x: S::Vf32, y: S::Vf32, z: S::Vf32) -> S::Vf32;
 }
 
+/// Settings that have 4d noise, `sample_4d` fails on lattices that have none
+pub trait Sample4d32<S: Simd>: Sample32<S> + Settings4d {
+    fn sample_4d(
+        &self,
+        x: S::Vf32,
+        y: S::Vf32,
+        z: S::Vf32,
+        w: S::Vf32,
+    ) -> Result<S::Vf32, NoiseError>;
+}
+
+/// Settings whose noise has analytic partial derivatives, with respect to the coordinates
+/// they are given
+pub trait SampleDeriv32<S: Simd>: Sample32<S> + Settings4d {
+    fn sample_1d_deriv(&self, x: S::Vf32) -> (S::Vf32, [S::Vf32; 1]);
+    fn sample_2d_deriv(&self, x: S::Vf32, y: S::Vf32) -> (S::Vf32, [S::Vf32; 2]);
+    fn sample_3d_deriv(&self, x: S::Vf32, y: S::Vf32, z: S::Vf32) -> (S::Vf32, [S::Vf32; 3]);
+    fn sample_4d_deriv(
+        &self,
+        x: S::Vf32,
+        y: S::Vf32,
+        z: S::Vf32,
+        w: S::Vf32,
+    ) -> Result<(S::Vf32, [S::Vf32; 4]), NoiseError>;
+}
+
+/// 0, 1, 2... in the lanes of a vector
+#[inline(always)]
+unsafe fn lane_indices<S: Simd>() -> S::Vf32 {
+    let mut lanes = S::Vf32::zeroes();
+    for i in 0..S::Vf32::WIDTH {
+        *lanes.get_unchecked_mut(i) = i as f32;
+    }
+    lanes
+}
+
+/// `start + (i + lane) * step` in every lane. Working it out from the sample index instead of
+/// adding the step up keeps rounding errors from building up along wide blocks.
+#[inline(always)]
+fn lane_positions<S: Simd>(start: f32, i: usize, step: f32, lanes: S::Vf32) -> S::Vf32 {
+    (S::Vf32::set1(i as f32) + lanes) * S::Vf32::set1(step) + S::Vf32::set1(start)
+}
+
 #[inline(always)]
 unsafe fn get_1d_noise_helper_f32<S: Simd, Settings: Sample32<S>>(
     settings: Settings, noise: *mut f32
 ) -> (f32, f32) {
     let dim = settings.get_dimensions();
     let freq_x = S::Vf32::set1(settings.get_freq_x());
-    let start_x = dim.x;
+    let start_x = dim.x as f32;
+    let step_x = dim.step_x;
     let width = dim.width;
     let mut min_s = S::Vf32::set1(f32::MAX);
     let mut max_s = S::Vf32::set1(f32::MIN);
@@ -30,23 +77,20 @@ unsafe fn get_1d_noise_helper_f32<S: Simd, Settings: Sample32<S>>(
 
     let result_ptr = noise;
     let mut i = 0;
-    let remainder = width % VEC_WIDTH;
-    let mut x_arr = [0.0; VEC_WIDTH];
-    let x_ptr = x_arr.as_mut_ptr();
-    for i in (0..VEC_WIDTH).rev() {
-        x_ptr.add(i).write(start_x + i as f32);
-    }
+    let vec_width = S::Vf32::WIDTH;
+    let remainder = width % vec_width;
+    let lanes = lane_indices::<S>();
     
-    let mut x = S::Vf32::load_from_ptr_unaligned(x_ptr);
-    for _ in 0..width / VEC_WIDTH {
+    for xi in (0..width - remainder).step_by(vec_width) {
+        let x = lane_positions::<S>(start_x, xi, step_x, lanes);
         let f = settings.sample_1d(x * freq_x);
         max_s = max_s.max(f);
         min_s = min_s.min(f);
         f.copy_to_ptr_unaligned(result_ptr.add(i));
-        i += VEC_WIDTH;
-        x = x + S::Vf32::set1(VEC_WIDTH as f32);
+        i += vec_width;
     }
     if remainder != 0 {
+        let x = lane_positions::<S>(start_x, width - remainder, step_x, lanes);
         let f = settings.sample_1d(x * freq_x);
         for j in 0..remainder {
             let n = f[j];
@@ -62,7 +106,7 @@ unsafe fn get_1d_noise_helper_f32<S: Simd, Settings: Sample32<S>>(
         }
     }
     
-    for i in 0..VEC_WIDTH {
+    for i in 0..vec_width {
         if min_s[i] < min {
             min = min_s[i];
         }
@@ -80,9 +124,11 @@ unsafe fn get_2d_noise_helper_f32<S: Simd, Settings: Sample32<S>>(
     let dim = settings.get_dimensions();
     let freq_x = S::Vf32::set1(settings.get_freq_x());
     let freq_y = S::Vf32::set1(settings.get_freq_y());
-    let start_x = dim.x;
+    let start_x = dim.x as f32;
+    let step_x = dim.step_x;
     let width = dim.width;
-    let start_y = dim.y;
+    let start_y = dim.y as f32;
+    let step_y = dim.step_y;
     let height = dim.height;
 
     let mut min_s = S::Vf32::set1(f32::MAX);
@@ -91,26 +137,23 @@ unsafe fn get_2d_noise_helper_f32<S: Simd, Settings: Sample32<S>>(
     let mut max = f32::MIN;
 
     let result_ptr = noise;
-    let mut y = S::Vf32::set1(start_y);
     let mut i = 0;
-    let remainder = width % VEC_WIDTH;
-    let mut x_arr = [0.0; VEC_WIDTH];
-    let x_ptr = x_arr.as_mut_ptr();
-    for i in (0..VEC_WIDTH).rev() {
-        x_ptr.add(i).write(start_x + i as f32);
-    }
+    let vec_width = S::Vf32::WIDTH;
+    let remainder = width % vec_width;
+    let lanes = lane_indices::<S>();
     
-    for _ in 0..height {
-        let mut x = S::Vf32::load_from_ptr_unaligned(x_ptr);
-        for _ in 0..width / VEC_WIDTH {
+    for yi in 0..height {
+        let y = S::Vf32::set1(start_y + yi as f32 * step_y);
+        for xi in (0..width - remainder).step_by(vec_width) {
+            let x = lane_positions::<S>(start_x, xi, step_x, lanes);
             let f = settings.sample_2d(x * freq_x, y * freq_y);
             max_s = max_s.max(f);
             min_s = min_s.min(f);
             f.copy_to_ptr_unaligned(result_ptr.add(i));
-            i += VEC_WIDTH;
-            x = x + S::Vf32::set1(VEC_WIDTH as f32);
+            i += vec_width;
         }
         if remainder != 0 {
+            let x = lane_positions::<S>(start_x, width - remainder, step_x, lanes);
             let f = settings.sample_2d(x * freq_x, y * freq_y);
             for j in 0..remainder {
                 let n = f[j];
@@ -124,10 +167,9 @@ unsafe fn get_2d_noise_helper_f32<S: Simd, Settings: Sample32<S>>(
                 i += 1;
             }
         }
-        y = y + S::Vf32::set1(1.0);
     }
     
-    for i in 0..VEC_WIDTH {
+    for i in 0..vec_width {
         if min_s[i] < min {
             min = min_s[i];
         }
@@ -146,11 +188,14 @@ unsafe fn get_3d_noise_helper_f32<S: Simd, Settings: Sample32<S>>(
     let freq_x = S::Vf32::set1(settings.get_freq_x());
     let freq_y = S::Vf32::set1(settings.get_freq_y());
     let freq_z = S::Vf32::set1(settings.get_freq_z());
-    let start_x = dim.x;
+    let start_x = dim.x as f32;
+    let step_x = dim.step_x;
     let width = dim.width;
-    let start_y = dim.y;
+    let start_y = dim.y as f32;
+    let step_y = dim.step_y;
     let height = dim.height;
-    let start_z = dim.z;
+    let start_z = dim.z as f32;
+    let step_z = dim.step_z;
     let depth = dim.depth;
 
     let mut min_s = S::Vf32::set1(f32::MAX);
@@ -160,27 +205,24 @@ unsafe fn get_3d_noise_helper_f32<S: Simd, Settings: Sample32<S>>(
 
     let result_ptr = noise;
     let mut i = 0;
-    let remainder = width % VEC_WIDTH;
-    let mut x_arr = [0.0; VEC_WIDTH];
-    let x_ptr = x_arr.as_mut_ptr();
-    for i in (0..VEC_WIDTH).rev() {
-        x_ptr.add(i).write(start_x + i as f32);
-    }
-
-    let mut z = S::Vf32::set1(start_z);
-    for _ in 0..depth {
-        let mut y = S::Vf32::set1(start_y);
-        for _ in 0..height {
-            let mut x = S::Vf32::load_from_ptr_unaligned(&x_arr[0]);
-            for _ in 0..width / VEC_WIDTH {
+    let vec_width = S::Vf32::WIDTH;
+    let remainder = width % vec_width;
+    let lanes = lane_indices::<S>();
+
+    for zi in 0..depth {
+        let z = S::Vf32::set1(start_z + zi as f32 * step_z);
+        for yi in 0..height {
+            let y = S::Vf32::set1(start_y + yi as f32 * step_y);
+            for xi in (0..width - remainder).step_by(vec_width) {
+                let x = lane_positions::<S>(start_x, xi, step_x, lanes);
                 let f = settings.sample_3d(x * freq_x, y * freq_y, z * freq_z);
                 max_s = max_s.max(f);
                 min_s = min_s.min(f);
                 f.copy_to_ptr_unaligned(result_ptr.add(i));
-                i += VEC_WIDTH;
-                x = x + S::Vf32::set1(VEC_WIDTH as f32);
+                i += vec_width;
             }
             if remainder != 0 {
+                let x = lane_positions::<S>(start_x, width - remainder, step_x, lanes);
                 let f = settings.sample_3d(x * freq_x, y * freq_y, z * freq_z);
                 for j in 0..remainder {
                     let n = f[j];
@@ -194,12 +236,10 @@ unsafe fn get_3d_noise_helper_f32<S: Simd, Settings: Sample32<S>>(
                     i += 1;
                 }
             }
-            y = y + S::Vf32::set1(1.0);
         }
-        z = z + S::Vf32::set1(1.0);
     }
 
-    for i in 0..VEC_WIDTH {
+    for i in 0..vec_width {
         if min_s[i] < min {
             min = min_s[i];
         }
@@ -211,11 +251,93 @@ unsafe fn get_3d_noise_helper_f32<S: Simd, Settings: Sample32<S>>(
 }
 
 #[inline(always)]
-#[allow(dead_code)]
+unsafe fn get_4d_noise_helper_f32<S: Simd, Settings: Sample4d32<S>>(
+    settings: Settings, noise: *mut f32
+) -> Result<(f32, f32), NoiseError> {
+    let dim = settings.get_dimensions();
+    let freq_x = S::Vf32::set1(settings.get_freq_x());
+    let freq_y = S::Vf32::set1(settings.get_freq_y());
+    let freq_z = S::Vf32::set1(settings.get_freq_z());
+    let freq_w = S::Vf32::set1(settings.get_freq_w());
+    let start_x = dim.x as f32;
+    let step_x = dim.step_x;
+    let width = dim.width;
+    let start_y = dim.y as f32;
+    let step_y = dim.step_y;
+    let height = dim.height;
+    let start_z = dim.z as f32;
+    let step_z = dim.step_z;
+    let depth = dim.depth;
+    let start_w = dim.w as f32;
+    let step_w = dim.step_w;
+    let time = dim.time;
+
+    let mut min_s = S::Vf32::set1(f32::MAX);
+    let mut max_s = S::Vf32::set1(f32::MIN);
+    let mut min = f32::MAX;
+    let mut max = f32::MIN;
+
+    let result_ptr = noise;
+    let mut i = 0;
+    let vec_width = S::Vf32::WIDTH;
+    let remainder = width % vec_width;
+    let lanes = lane_indices::<S>();
+
+    for wi in 0..time {
+        let w = S::Vf32::set1(start_w + wi as f32 * step_w);
+        for zi in 0..depth {
+            let z = S::Vf32::set1(start_z + zi as f32 * step_z);
+            for yi in 0..height {
+                let y = S::Vf32::set1(start_y + yi as f32 * step_y);
+                for xi in (0..width - remainder).step_by(vec_width) {
+                    let x = lane_positions::<S>(start_x, xi, step_x, lanes);
+                    let f = settings.sample_4d(x * freq_x, y * freq_y, z * freq_z, w * freq_w)?;
+                    max_s = max_s.max(f);
+                    min_s = min_s.min(f);
+                    f.copy_to_ptr_unaligned(result_ptr.add(i));
+                    i += vec_width;
+                }
+                if remainder != 0 {
+                    let x = lane_positions::<S>(start_x, width - remainder, step_x, lanes);
+                    let f = settings.sample_4d(x * freq_x, y * freq_y, z * freq_z, w * freq_w)?;
+                    for j in 0..remainder {
+                        let n = f[j];
+                        result_ptr.add(i).write(n);
+                        if n < min {
+                            min = n;
+                        }
+                        if n > max {
+                            max = n;
+                        }
+                        i += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    for i in 0..vec_width {
+        if min_s[i] < min {
+            min = min_s[i];
+        }
+        if max_s[i] > max {
+            max = max_s[i];
+        }
+    }
+    Ok((min, max))
+}
+
+#[inline(always)]
 pub unsafe fn get_1d_noise<S: Simd>(noise_type: &NoiseType, noise: *mut f32) -> (f32, f32) {
     match noise_type {
         NoiseType::Fbm(s) => get_1d_noise_helper_f32::<S, FbmSettings>(*s, noise),
         NoiseType::Gradient(s) => get_1d_noise_helper_f32::<S, GradientSettings>(*s, noise),
+        NoiseType::Ridge(s) => get_1d_noise_helper_f32::<S, RidgeSettings>(*s, noise),
+        NoiseType::Turbulence(s) => get_1d_noise_helper_f32::<S, TurbulenceSettings>(*s, noise),
+        NoiseType::Billow(s) => get_1d_noise_helper_f32::<S, BillowSettings>(*s, noise),
+        NoiseType::Cellular(s) => get_1d_noise_helper_f32::<S, CellularSettings>(*s, noise),
+        NoiseType::DomainWarp(s) => get_1d_noise_helper_f32::<S, DomainWarpSettings>(*s, noise),
+        NoiseType::White(s) => get_1d_noise_helper_f32::<S, WhiteSettings>(*s, noise),
     }
 }
 
@@ -225,11 +347,16 @@ pub unsafe fn get_1d_noise<S: Simd>(noise_type: &NoiseType, noise: *mut f32) ->
 /// are returned so you can scale and transform the noise as you see fit
 /// in a single pass.
 #[inline(always)]
-#[allow(dead_code)]
 pub unsafe fn get_2d_noise<S: Simd>(noise_type: &NoiseType, noise: *mut f32) -> (f32, f32) {
     match noise_type {
         NoiseType::Fbm(s) => get_2d_noise_helper_f32::<S, FbmSettings>(*s, noise),
         NoiseType::Gradient(s) => get_2d_noise_helper_f32::<S, GradientSettings>(*s, noise),
+        NoiseType::Ridge(s) => get_2d_noise_helper_f32::<S, RidgeSettings>(*s, noise),
+        NoiseType::Turbulence(s) => get_2d_noise_helper_f32::<S, TurbulenceSettings>(*s, noise),
+        NoiseType::Billow(s) => get_2d_noise_helper_f32::<S, BillowSettings>(*s, noise),
+        NoiseType::Cellular(s) => get_2d_noise_helper_f32::<S, CellularSettings>(*s, noise),
+        NoiseType::DomainWarp(s) => get_2d_noise_helper_f32::<S, DomainWarpSettings>(*s, noise),
+        NoiseType::White(s) => get_2d_noise_helper_f32::<S, WhiteSettings>(*s, noise),
     }
 }
 
@@ -239,10 +366,566 @@ pub unsafe fn get_2d_noise<S: Simd>(noise_type: &NoiseType, noise: *mut f32) ->
 /// are returned so you can scale and transform the noise as you see fit
 /// in a single pass.
 #[inline(always)]
-#[allow(dead_code)]
 pub unsafe fn get_3d_noise<S: Simd>(noise_type: &NoiseType, noise: *mut f32) -> (f32, f32) {
     match noise_type {
         NoiseType::Fbm(s) => get_3d_noise_helper_f32::<S, FbmSettings>(*s, noise),
         NoiseType::Gradient(s) => get_3d_noise_helper_f32::<S, GradientSettings>(*s, noise),
+        NoiseType::Ridge(s) => get_3d_noise_helper_f32::<S, RidgeSettings>(*s, noise),
+        NoiseType::Turbulence(s) => get_3d_noise_helper_f32::<S, TurbulenceSettings>(*s, noise),
+        NoiseType::Billow(s) => get_3d_noise_helper_f32::<S, BillowSettings>(*s, noise),
+        NoiseType::Cellular(s) => get_3d_noise_helper_f32::<S, CellularSettings>(*s, noise),
+        NoiseType::DomainWarp(s) => get_3d_noise_helper_f32::<S, DomainWarpSettings>(*s, noise),
+        NoiseType::White(s) => get_3d_noise_helper_f32::<S, WhiteSettings>(*s, noise),
+    }
+}
+
+/// Gets a width X height X depth X time sized block of 4d noise, unscaled,
+/// `start_x`,`start_y`, `start_z` and `start_w` can be used to provide an offset in the
+/// coordinates. Results are unscaled, 'min' and 'max' noise values
+/// are returned so you can scale and transform the noise as you see fit
+/// in a single pass. Fails if `noise_type` has no 4d noise.
+#[inline(always)]
+pub unsafe fn get_4d_noise<S: Simd>(
+    noise_type: &NoiseType,
+    noise: *mut f32,
+) -> Result<(f32, f32), NoiseError> {
+    noise_type.validate_dim(4)?;
+    match noise_type {
+        NoiseType::Fbm(s) => get_4d_noise_helper_f32::<S, FbmSettings>(*s, noise),
+        NoiseType::Gradient(s) => get_4d_noise_helper_f32::<S, GradientSettings>(*s, noise),
+        NoiseType::Ridge(s) => get_4d_noise_helper_f32::<S, RidgeSettings>(*s, noise),
+        NoiseType::Turbulence(s) => get_4d_noise_helper_f32::<S, TurbulenceSettings>(*s, noise),
+        NoiseType::Billow(s) => get_4d_noise_helper_f32::<S, BillowSettings>(*s, noise),
+        NoiseType::DomainWarp(s) => get_4d_noise_helper_f32::<S, DomainWarpSettings>(*s, noise),
+        NoiseType::Cellular(_) | NoiseType::White(_) => {
+            Err(NoiseError::UnsupportedDimension { dim: 4 })
+        }
+    }
+}
+
+/// Loads `count` coordinates starting at `start`, lanes past `count` are left at zero
+#[inline(always)]
+unsafe fn load_points_f32<S: Simd>(coords: &[f32], start: usize, count: usize) -> S::Vf32 {
+    if count == S::Vf32::WIDTH {
+        return S::Vf32::load_from_ptr_unaligned(coords.as_ptr().add(start));
+    }
+    let mut v = S::Vf32::zeroes();
+    for (j, &c) in coords[start..start + count].iter().enumerate() {
+        *v.get_unchecked_mut(j) = c;
+    }
+    v
+}
+
+/// Samples every point of `coords` in batches of one vector, the last batch is padded with
+/// zeroes and only `out.len() % WIDTH` of its values are written
+#[inline(always)]
+unsafe fn sample_points_helper_f32<S: Simd, const D: usize, E>(
+    freqs: [f32; D],
+    coords: [&[f32]; D],
+    out: *mut f32,
+    len: usize,
+    sample: impl Fn([S::Vf32; D]) -> Result<S::Vf32, E>,
+) -> Result<(), E> {
+    let freqs = freqs.map(S::Vf32::set1);
+    let vec_width = S::Vf32::WIDTH;
+    let mut i = 0;
+    while i < len {
+        let count = vec_width.min(len - i);
+        let mut points = [S::Vf32::zeroes(); D];
+        for (point, (axis, freq)) in points.iter_mut().zip(coords.iter().zip(freqs)) {
+            *point = load_points_f32::<S>(axis, i, count) * freq;
+        }
+        let f = sample(points)?;
+        if count == vec_width {
+            f.copy_to_ptr_unaligned(out.add(i));
+        } else {
+            for j in 0..count {
+                out.add(i + j).write(f[j]);
+            }
+        }
+        i += count;
+    }
+    Ok(())
+}
+
+#[inline(always)]
+unsafe fn sample_points_1d_helper_f32<S: Simd, Settings: Sample32<S>>(
+    settings: Settings,
+    xs: &[f32],
+    out: *mut f32,
+    len: usize,
+) {
+    let freqs = [settings.get_freq_x()];
+    let Ok(()) = sample_points_helper_f32::<S, 1, Infallible>(freqs, [xs], out, len, |[x]| {
+        Ok(settings.sample_1d(x))
+    });
+}
+
+#[inline(always)]
+unsafe fn sample_points_2d_helper_f32<S: Simd, Settings: Sample32<S>>(
+    settings: Settings,
+    xs: &[f32],
+    ys: &[f32],
+    out: *mut f32,
+    len: usize,
+) {
+    let freqs = [settings.get_freq_x(), settings.get_freq_y()];
+    let Ok(()) =
+        sample_points_helper_f32::<S, 2, Infallible>(freqs, [xs, ys], out, len, |[x, y]| {
+            Ok(settings.sample_2d(x, y))
+        });
+}
+
+#[inline(always)]
+unsafe fn sample_points_3d_helper_f32<S: Simd, Settings: Sample32<S>>(
+    settings: Settings,
+    xs: &[f32],
+    ys: &[f32],
+    zs: &[f32],
+    out: *mut f32,
+    len: usize,
+) {
+    let freqs = [settings.get_freq_x(), settings.get_freq_y(), settings.get_freq_z()];
+    let Ok(()) =
+        sample_points_helper_f32::<S, 3, Infallible>(freqs, [xs, ys, zs], out, len, |[x, y, z]| {
+            Ok(settings.sample_3d(x, y, z))
+        });
+}
+
+#[inline(always)]
+unsafe fn sample_points_4d_helper_f32<S: Simd, Settings: Sample4d32<S>>(
+    settings: Settings,
+    xs: &[f32],
+    ys: &[f32],
+    zs: &[f32],
+    ws: &[f32],
+    out: *mut f32,
+    len: usize,
+) -> Result<(), NoiseError> {
+    let freqs = [
+        settings.get_freq_x(),
+        settings.get_freq_y(),
+        settings.get_freq_z(),
+        settings.get_freq_w(),
+    ];
+    sample_points_helper_f32::<S, 4, NoiseError>(
+        freqs,
+        [xs, ys, zs, ws],
+        out,
+        len,
+        |[x, y, z, w]| settings.sample_4d(x, y, z, w),
+    )
+}
+
+/// Samples 1d noise at the points `coords[i]`, see `crate::sample_points_1d`
+///
+/// # Safety
+/// Every coordinate slice must hold at least `len` values and `out` must be valid for writes of
+/// `len` values
+#[inline(always)]
+pub unsafe fn sample_points_1d<S: Simd>(
+    noise_type: &NoiseType,
+    xs: &[f32],
+    out: *mut f32,
+    len: usize,
+) {
+    match noise_type {
+        NoiseType::Fbm(s) => {
+            sample_points_1d_helper_f32::<S, FbmSettings>(*s, xs, out, len)
+        }
+        NoiseType::Gradient(s) => {
+            sample_points_1d_helper_f32::<S, GradientSettings>(*s, xs, out, len)
+        }
+        NoiseType::Ridge(s) => {
+            sample_points_1d_helper_f32::<S, RidgeSettings>(*s, xs, out, len)
+        }
+        NoiseType::Turbulence(s) => {
+            sample_points_1d_helper_f32::<S, TurbulenceSettings>(*s, xs, out, len)
+        }
+        NoiseType::Billow(s) => {
+            sample_points_1d_helper_f32::<S, BillowSettings>(*s, xs, out, len)
+        }
+        NoiseType::Cellular(s) => {
+            sample_points_1d_helper_f32::<S, CellularSettings>(*s, xs, out, len)
+        }
+        NoiseType::DomainWarp(s) => {
+            sample_points_1d_helper_f32::<S, DomainWarpSettings>(*s, xs, out, len)
+        }
+        NoiseType::White(s) => {
+            sample_points_1d_helper_f32::<S, WhiteSettings>(*s, xs, out, len)
+        }
+    }
+}
+
+/// Samples 2d noise at the points `coords[i]`, see `crate::sample_points_2d`
+///
+/// # Safety
+/// Every coordinate slice must hold at least `len` values and `out` must be valid for writes of
+/// `len` values
+#[inline(always)]
+pub unsafe fn sample_points_2d<S: Simd>(
+    noise_type: &NoiseType,
+    xs: &[f32],
+    ys: &[f32],
+    out: *mut f32,
+    len: usize,
+) {
+    match noise_type {
+        NoiseType::Fbm(s) => {
+            sample_points_2d_helper_f32::<S, FbmSettings>(*s, xs, ys, out, len)
+        }
+        NoiseType::Gradient(s) => {
+            sample_points_2d_helper_f32::<S, GradientSettings>(*s, xs, ys, out, len)
+        }
+        NoiseType::Ridge(s) => {
+            sample_points_2d_helper_f32::<S, RidgeSettings>(*s, xs, ys, out, len)
+        }
+        NoiseType::Turbulence(s) => {
+            sample_points_2d_helper_f32::<S, TurbulenceSettings>(*s, xs, ys, out, len)
+        }
+        NoiseType::Billow(s) => {
+            sample_points_2d_helper_f32::<S, BillowSettings>(*s, xs, ys, out, len)
+        }
+        NoiseType::Cellular(s) => {
+            sample_points_2d_helper_f32::<S, CellularSettings>(*s, xs, ys, out, len)
+        }
+        NoiseType::DomainWarp(s) => {
+            sample_points_2d_helper_f32::<S, DomainWarpSettings>(*s, xs, ys, out, len)
+        }
+        NoiseType::White(s) => {
+            sample_points_2d_helper_f32::<S, WhiteSettings>(*s, xs, ys, out, len)
+        }
+    }
+}
+
+/// Samples 3d noise at the points `coords[i]`, see `crate::sample_points_3d`
+///
+/// # Safety
+/// Every coordinate slice must hold at least `len` values and `out` must be valid for writes of
+/// `len` values
+#[inline(always)]
+pub unsafe fn sample_points_3d<S: Simd>(
+    noise_type: &NoiseType,
+    xs: &[f32],
+    ys: &[f32],
+    zs: &[f32],
+    out: *mut f32,
+    len: usize,
+) {
+    match noise_type {
+        NoiseType::Fbm(s) => {
+            sample_points_3d_helper_f32::<S, FbmSettings>(*s, xs, ys, zs, out, len)
+        }
+        NoiseType::Gradient(s) => {
+            sample_points_3d_helper_f32::<S, GradientSettings>(*s, xs, ys, zs, out, len)
+        }
+        NoiseType::Ridge(s) => {
+            sample_points_3d_helper_f32::<S, RidgeSettings>(*s, xs, ys, zs, out, len)
+        }
+        NoiseType::Turbulence(s) => {
+            sample_points_3d_helper_f32::<S, TurbulenceSettings>(*s, xs, ys, zs, out, len)
+        }
+        NoiseType::Billow(s) => {
+            sample_points_3d_helper_f32::<S, BillowSettings>(*s, xs, ys, zs, out, len)
+        }
+        NoiseType::Cellular(s) => {
+            sample_points_3d_helper_f32::<S, CellularSettings>(*s, xs, ys, zs, out, len)
+        }
+        NoiseType::DomainWarp(s) => {
+            sample_points_3d_helper_f32::<S, DomainWarpSettings>(*s, xs, ys, zs, out, len)
+        }
+        NoiseType::White(s) => {
+            sample_points_3d_helper_f32::<S, WhiteSettings>(*s, xs, ys, zs, out, len)
+        }
+    }
+}
+
+/// Samples 4d noise at the points `coords[i]`, see `crate::sample_points_4d`. Fails if
+/// `noise_type` has no 4d noise.
+///
+/// # Safety
+/// Every coordinate slice must hold at least `len` values and `out` must be valid for writes of
+/// `len` values
+#[inline(always)]
+pub unsafe fn sample_points_4d<S: Simd>(
+    noise_type: &NoiseType,
+    xs: &[f32],
+    ys: &[f32],
+    zs: &[f32],
+    ws: &[f32],
+    out: *mut f32,
+    len: usize,
+) -> Result<(), NoiseError> {
+    match noise_type {
+        NoiseType::Fbm(s) => {
+            sample_points_4d_helper_f32::<S, FbmSettings>(*s, xs, ys, zs, ws, out, len)
+        }
+        NoiseType::Gradient(s) => {
+            sample_points_4d_helper_f32::<S, GradientSettings>(*s, xs, ys, zs, ws, out, len)
+        }
+        NoiseType::Ridge(s) => {
+            sample_points_4d_helper_f32::<S, RidgeSettings>(*s, xs, ys, zs, ws, out, len)
+        }
+        NoiseType::Turbulence(s) => {
+            sample_points_4d_helper_f32::<S, TurbulenceSettings>(*s, xs, ys, zs, ws, out, len)
+        }
+        NoiseType::Billow(s) => {
+            sample_points_4d_helper_f32::<S, BillowSettings>(*s, xs, ys, zs, ws, out, len)
+        }
+        NoiseType::DomainWarp(s) => {
+            sample_points_4d_helper_f32::<S, DomainWarpSettings>(*s, xs, ys, zs, ws, out, len)
+        }
+        NoiseType::Cellular(_) | NoiseType::White(_) => {
+            Err(NoiseError::UnsupportedDimension { dim: 4 })
+        }
+    }
+}
+
+/// Writes the `C` components of the `count` samples starting at sample `i` of a block of `len`
+#[inline(always)]
+unsafe fn write_channels<S: Simd, const C: usize>(
+    v: [S::Vf32; C],
+    noise: *mut f32,
+    i: usize,
+    count: usize,
+    len: usize,
+    layout: ChannelLayout,
+) {
+    match layout {
+        ChannelLayout::Interleaved => {
+            for j in 0..count {
+                for (c, v) in v.iter().enumerate() {
+                    noise.add((i + j) * C + c).write(v[j]);
+                }
+            }
+        }
+        ChannelLayout::Planar => {
+            for (c, v) in v.iter().enumerate() {
+                let plane = noise.add(c * len + i);
+                if count == S::Vf32::WIDTH {
+                    v.copy_to_ptr_unaligned(plane);
+                } else {
+                    for j in 0..count {
+                        plane.add(j).write(v[j]);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Gets a width X height block of 2d curl noise, two components per sample
+///
+/// # Safety
+/// `noise` must be valid for writes of twice `NoiseDimensions::len` values
+#[inline(always)]
+pub unsafe fn get_2d_curl<S: Simd>(settings: &CurlSettings, noise: *mut f32) {
+    let dim = settings.get_dimensions();
+    let freq = [S::Vf32::set1(settings.freq_x), S::Vf32::set1(settings.freq_y)];
+    let lac = S::Vf32::set1(settings.lacunarity);
+    let gain = S::Vf32::set1(settings.gain);
+    let seed = dim.seed_32();
+    let len = dim.len();
+
+    let vec_width = S::Vf32::WIDTH;
+    let lanes = lane_indices::<S>();
+    let start_x = dim.x as f32;
+
+    let mut i = 0;
+    for yi in 0..dim.height {
+        let y = S::Vf32::set1(dim.y as f32 + yi as f32 * dim.step_y);
+        let mut remaining = dim.width;
+        while remaining > 0 {
+            let count = vec_width.min(remaining);
+            let x = lane_positions::<S>(start_x, dim.width - remaining, dim.step_x, lanes);
+            let v = curl_2d::<S>(
+                x * freq[0],
+                y * freq[1],
+                freq,
+                lac,
+                gain,
+                settings.octaves,
+                seed,
+                settings.octave_options.for_seed_mode(dim.seed_mode),
+            );
+            write_channels::<S, 2>(v, noise, i, count, len, settings.layout);
+            i += count;
+            remaining -= count;
+        }
     }
-}
\ No newline at end of file
+}
+
+/// Gets a width X height X depth block of 3d curl noise, three components per sample
+///
+/// # Safety
+/// `noise` must be valid for writes of three times `NoiseDimensions::len` values
+#[inline(always)]
+pub unsafe fn get_3d_curl<S: Simd>(settings: &CurlSettings, noise: *mut f32) {
+    let dim = settings.get_dimensions();
+    let freq = [
+        S::Vf32::set1(settings.freq_x),
+        S::Vf32::set1(settings.freq_y),
+        S::Vf32::set1(settings.freq_z),
+    ];
+    let lac = S::Vf32::set1(settings.lacunarity);
+    let gain = S::Vf32::set1(settings.gain);
+    let seed = dim.seed_32();
+    let len = dim.len();
+
+    let vec_width = S::Vf32::WIDTH;
+    let lanes = lane_indices::<S>();
+    let start_x = dim.x as f32;
+
+    let mut i = 0;
+    for zi in 0..dim.depth {
+        let z = S::Vf32::set1(dim.z as f32 + zi as f32 * dim.step_z);
+        for yi in 0..dim.height {
+            let y = S::Vf32::set1(dim.y as f32 + yi as f32 * dim.step_y);
+            let mut remaining = dim.width;
+            while remaining > 0 {
+                let count = vec_width.min(remaining);
+                let x = lane_positions::<S>(start_x, dim.width - remaining, dim.step_x, lanes);
+                let v = curl_3d::<S>(
+                    x * freq[0],
+                    y * freq[1],
+                    z * freq[2],
+                    freq,
+                    lac,
+                    gain,
+                    settings.octaves,
+                    seed,
+                    settings.octave_options.for_seed_mode(dim.seed_mode),
+                );
+                write_channels::<S, 3>(v, noise, i, count, len, settings.layout);
+                i += count;
+                remaining -= count;
+            }
+        }
+    }
+}
+
+#[inline(always)]
+unsafe fn get_noise_deriv_helper_f32<S: Simd, Settings: SampleDeriv32<S>>(
+    settings: Settings,
+    noise: *mut f32,
+    derivatives: *mut f32,
+    layout: ChannelLayout,
+) -> Result<(f32, f32), NoiseError> {
+    let dim = settings.get_dimensions();
+    let freq = [
+        S::Vf32::set1(settings.get_freq_x()),
+        S::Vf32::set1(settings.get_freq_y()),
+        S::Vf32::set1(settings.get_freq_z()),
+        S::Vf32::set1(settings.get_freq_w()),
+    ];
+    let len = dim.len();
+
+    let mut min_s = S::Vf32::set1(f32::MAX);
+    let mut max_s = S::Vf32::set1(f32::MIN);
+    let mut min = f32::MAX;
+    let mut max = f32::MIN;
+
+    let vec_width = S::Vf32::WIDTH;
+    let lanes = lane_indices::<S>();
+    let start_x = dim.x as f32;
+
+    // Unused axes have a size of 1, so a single loop nest covers every dimension count
+    let mut i = 0;
+    for wi in 0..dim.time {
+        let w = S::Vf32::set1(dim.w as f32 + wi as f32 * dim.step_w);
+        for zi in 0..dim.depth {
+            let z = S::Vf32::set1(dim.z as f32 + zi as f32 * dim.step_z);
+            for yi in 0..dim.height {
+                let y = S::Vf32::set1(dim.y as f32 + yi as f32 * dim.step_y);
+                let mut remaining = dim.width;
+                while remaining > 0 {
+                    let count = vec_width.min(remaining);
+                    let x = lane_positions::<S>(start_x, dim.width - remaining, dim.step_x, lanes);
+                    let p = [x * freq[0], y * freq[1], z * freq[2], w * freq[3]];
+                    // The chain rule brings the frequency back out of every derivative
+                    let f = match dim.dim {
+                        1 => {
+                            let (f, [dx]) = settings.sample_1d_deriv(p[0]);
+                            let d = [dx * freq[0]];
+                            write_channels::<S, 1>(d, derivatives, i, count, len, layout);
+                            f
+                        }
+                        2 => {
+                            let (f, [dx, dy]) = settings.sample_2d_deriv(p[0], p[1]);
+                            let d = [dx * freq[0], dy * freq[1]];
+                            write_channels::<S, 2>(d, derivatives, i, count, len, layout);
+                            f
+                        }
+                        3 => {
+                            let (f, [dx, dy, dz]) = settings.sample_3d_deriv(p[0], p[1], p[2]);
+                            let d = [dx * freq[0], dy * freq[1], dz * freq[2]];
+                            write_channels::<S, 3>(d, derivatives, i, count, len, layout);
+                            f
+                        }
+                        _ => {
+                            let (f, [dx, dy, dz, dw]) =
+                                settings.sample_4d_deriv(p[0], p[1], p[2], p[3])?;
+                            let d = [dx * freq[0], dy * freq[1], dz * freq[2], dw * freq[3]];
+                            write_channels::<S, 4>(d, derivatives, i, count, len, layout);
+                            f
+                        }
+                    };
+                    if count == vec_width {
+                        max_s = max_s.max(f);
+                        min_s = min_s.min(f);
+                        f.copy_to_ptr_unaligned(noise.add(i));
+                    } else {
+                        for j in 0..count {
+                            let n = f[j];
+                            noise.add(i + j).write(n);
+                            if n < min {
+                                min = n;
+                            }
+                            if n > max {
+                                max = n;
+                            }
+                        }
+                    }
+                    i += count;
+                    remaining -= count;
+                }
+            }
+        }
+    }
+
+    for i in 0..vec_width {
+        if min_s[i] < min {
+            min = min_s[i];
+        }
+        if max_s[i] > max {
+            max = max_s[i];
+        }
+    }
+    Ok((min, max))
+}
+
+/// Gets a block of noise like `get_1d_noise` to `get_4d_noise` and the partial derivative along
+/// every axis of every value, laid out as `layout` says
+///
+/// # Safety
+/// `noise` must be valid for writes of `NoiseDimensions::len` values and `derivatives` for
+/// that many times the dimension count. Fails if `noise_type` isn't gradient or fbm noise, or
+/// if its lattice has no noise in its dimension count.
+#[inline(always)]
+pub unsafe fn get_noise_deriv<S: Simd>(
+    noise_type: &NoiseType,
+    noise: *mut f32,
+    derivatives: *mut f32,
+    layout: ChannelLayout,
+) -> Result<(f32, f32), NoiseError> {
+    noise_type.validate_dim(noise_type.get_dimensions().dim)?;
+    match noise_type {
+        NoiseType::Fbm(s) => {
+            get_noise_deriv_helper_f32::<S, FbmSettings>(*s, noise, derivatives, layout)
+        }
+        NoiseType::Gradient(s) => {
+            get_noise_deriv_helper_f32::<S, GradientSettings>(*s, noise, derivatives, layout)
+        }
+        _ => Err(NoiseError::DerivativesUnsupported),
+    }
+}
@@ -3,12 +3,13 @@ use simdeez::prelude::*;
 use super::NoiseType;
 use crate::dimensional_being::DimensionalBeing;
 
-use crate::{FbmSettings, GradientSettings, Settings};
+use crate::{
+    BillowSettings, CellularSettings, DomainWarpSettings, FbmSettings, GradientSettings,
+    NoiseError, RidgeSettings, Settings, Settings4d, TurbulenceSettings, WhiteSettings,
+};
 
+use core::convert::Infallible;
 use core::f64;
-use simdeez::engines::avx2::Avx2;
-
-const VEC_WIDTH: usize = <Avx2 as simdeez::Simd>::Vf64::WIDTH;
 
 pub trait Sample64<S: Simd>: DimensionalBeing + Settings {
     fn sample_1d(&self, x: S::Vf64) -> S::Vf64;
@@ -16,13 +17,42 @@ pub trait Sample64<S: Simd>: DimensionalBeing + Settings {
     fn sample_3d(&self, x: S::Vf64, y: S::Vf64, z: S::Vf64) -> S::Vf64;
 }
 
+/// Settings that have 4d noise, `sample_4d` fails on lattices that have none
+pub trait Sample4d64<S: Simd>: Sample64<S> + Settings4d {
+    fn sample_4d(
+        &self,
+        x: S::Vf64,
+        y: S::Vf64,
+        z: S::Vf64,
+        w: S::Vf64,
+    ) -> Result<S::Vf64, NoiseError>;
+}
+
+/// 0, 1, 2... in the lanes of a vector
+#[inline(always)]
+unsafe fn lane_indices<S: Simd>() -> S::Vf64 {
+    let mut lanes = S::Vf64::zeroes();
+    for i in 0..S::Vf64::WIDTH {
+        *lanes.get_unchecked_mut(i) = i as f64;
+    }
+    lanes
+}
+
+/// `start + (i + lane) * step` in every lane. Working it out from the sample index instead of
+/// adding the step up keeps rounding errors from building up along wide blocks.
+#[inline(always)]
+fn lane_positions<S: Simd>(start: f64, i: usize, step: f64, lanes: S::Vf64) -> S::Vf64 {
+    (S::Vf64::set1(i as f64) + lanes) * S::Vf64::set1(step) + S::Vf64::set1(start)
+}
+
 #[inline(always)]
 unsafe fn get_1d_noise_helper_f64<S: Simd, Settings: Sample64<S>>(
     settings: Settings, noise: *mut f64
 ) -> (f64, f64) {
     let dim = settings.get_dimensions();
     let freq_x = S::Vf64::set1(settings.get_freq_x() as f64);
-    let start_x = dim.x as f64;
+    let start_x = dim.x;
+    let step_x = dim.step_x as f64;
     let width = dim.width;
     let mut min_s = S::Vf64::set1(f64::MAX);
     let mut max_s = S::Vf64::set1(f64::MIN);
@@ -32,23 +62,20 @@ unsafe fn get_1d_noise_helper_f64<S: Simd, Settings: Sample64<S>>(
 
     let result_ptr = noise;
     let mut i = 0;
-    let remainder = width % VEC_WIDTH;
-    let mut x_arr = [0.0; VEC_WIDTH];
-    let x_ptr = x_arr.as_mut_ptr();
-    for i in (0..VEC_WIDTH).rev() {
-        x_ptr.add(i).write(start_x + i as f64);
-    }
+    let vec_width = S::Vf64::WIDTH;
+    let remainder = width % vec_width;
+    let lanes = lane_indices::<S>();
 
-    let mut x = S::Vf64::load_from_ptr_unaligned(&x_arr[0]);
-    for _ in 0..width / VEC_WIDTH {
+    for xi in (0..width - remainder).step_by(vec_width) {
+        let x = lane_positions::<S>(start_x, xi, step_x, lanes);
         let f = settings.sample_1d(x * freq_x);
         max_s = max_s.max(f);
         min_s = min_s.min(f);
         f.copy_to_ptr_unaligned(result_ptr.add(i));
-        i += VEC_WIDTH;
-        x = x + S::Vf64::set1(VEC_WIDTH as f64);
+        i += vec_width;
     }
     if remainder != 0 {
+        let x = lane_positions::<S>(start_x, width - remainder, step_x, lanes);
         let f = settings.sample_1d(x * freq_x);
         for j in 0..remainder {
             let n = f[j];
@@ -64,7 +91,7 @@ unsafe fn get_1d_noise_helper_f64<S: Simd, Settings: Sample64<S>>(
         }
     }
 
-    for i in 0..VEC_WIDTH {
+    for i in 0..vec_width {
         if min_s[i] < min {
             min = min_s[i];
         }
@@ -82,9 +109,11 @@ unsafe fn get_2d_noise_helper_f64<S: Simd, Settings: Sample64<S>>(
     let dim = settings.get_dimensions();
     let freq_x = S::Vf64::set1(settings.get_freq_x() as f64);
     let freq_y = S::Vf64::set1(settings.get_freq_y() as f64);
-    let start_x = dim.x as f64;
+    let start_x = dim.x;
+    let step_x = dim.step_x as f64;
     let width = dim.width;
-    let start_y = dim.y as f64;
+    let start_y = dim.y;
+    let step_y = dim.step_y as f64;
     let height = dim.height;
 
     let mut min_s = S::Vf64::set1(f64::MAX);
@@ -93,26 +122,23 @@ unsafe fn get_2d_noise_helper_f64<S: Simd, Settings: Sample64<S>>(
     let mut max = f64::MIN;
 
     let result_ptr = noise;
-    let mut y = S::Vf64::set1(start_y);
     let mut i = 0;
-    let remainder = width % VEC_WIDTH;
-    let mut x_arr = [0.0; VEC_WIDTH];
-    let x_ptr = x_arr.as_mut_ptr();
-    for i in (0..VEC_WIDTH).rev() {
-        x_ptr.add(i).write(start_x + i as f64);
-    }
+    let vec_width = S::Vf64::WIDTH;
+    let remainder = width % vec_width;
+    let lanes = lane_indices::<S>();
 
-    for _ in 0..height {
-        let mut x = S::Vf64::load_from_ptr_unaligned(&x_arr[0]);
-        for _ in 0..width / VEC_WIDTH {
+    for yi in 0..height {
+        let y = S::Vf64::set1(start_y + yi as f64 * step_y);
+        for xi in (0..width - remainder).step_by(vec_width) {
+            let x = lane_positions::<S>(start_x, xi, step_x, lanes);
             let f = settings.sample_2d(x * freq_x, y * freq_y);
             max_s = max_s.max(f);
             min_s = min_s.min(f);
             f.copy_to_ptr_unaligned(result_ptr.add(i));
-            i += VEC_WIDTH;
-            x = x + S::Vf64::set1(VEC_WIDTH as f64);
+            i += vec_width;
         }
         if remainder != 0 {
+            let x = lane_positions::<S>(start_x, width - remainder, step_x, lanes);
             let f = settings.sample_2d(x * freq_x, y * freq_y);
             for j in 0..remainder {
                 let n = f[j];
@@ -126,10 +152,9 @@ unsafe fn get_2d_noise_helper_f64<S: Simd, Settings: Sample64<S>>(
                 i += 1;
             }
         }
-        y = y + S::Vf64::set1(1.0);
     }
 
-    for i in 0..VEC_WIDTH {
+    for i in 0..vec_width {
         if min_s[i] < min {
             min = min_s[i];
         }
@@ -148,11 +173,14 @@ unsafe fn get_3d_noise_helper_f64<S: Simd, Settings: Sample64<S>>(
     let freq_x = S::Vf64::set1(settings.get_freq_x() as f64);
     let freq_y = S::Vf64::set1(settings.get_freq_y() as f64);
     let freq_z = S::Vf64::set1(settings.get_freq_z() as f64);
-    let start_x = dim.x as f64;
+    let start_x = dim.x;
+    let step_x = dim.step_x as f64;
     let width = dim.width;
-    let start_y = dim.y as f64;
+    let start_y = dim.y;
+    let step_y = dim.step_y as f64;
     let height = dim.height;
-    let start_z = dim.z as f64;
+    let start_z = dim.z;
+    let step_z = dim.step_z as f64;
     let depth = dim.depth;
 
     let mut min_s = S::Vf64::set1(f64::MAX);
@@ -162,27 +190,24 @@ unsafe fn get_3d_noise_helper_f64<S: Simd, Settings: Sample64<S>>(
 
     let result_ptr = noise;
     let mut i = 0;
-    let remainder = width % VEC_WIDTH;
-    let mut x_arr = [0.0; VEC_WIDTH];
-    let x_ptr = x_arr.as_mut_ptr();
-    for i in (0..VEC_WIDTH).rev() {
-        x_ptr.add(i).write(start_x + i as f64);
-    }
-
-    let mut z = S::Vf64::set1(start_z);
-    for _ in 0..depth {
-        let mut y = S::Vf64::set1(start_y);
-        for _ in 0..height {
-            let mut x = S::Vf64::load_from_ptr_unaligned(&x_arr[0]);
-            for _ in 0..width / VEC_WIDTH {
+    let vec_width = S::Vf64::WIDTH;
+    let remainder = width % vec_width;
+    let lanes = lane_indices::<S>();
+
+    for zi in 0..depth {
+        let z = S::Vf64::set1(start_z + zi as f64 * step_z);
+        for yi in 0..height {
+            let y = S::Vf64::set1(start_y + yi as f64 * step_y);
+            for xi in (0..width - remainder).step_by(vec_width) {
+                let x = lane_positions::<S>(start_x, xi, step_x, lanes);
                 let f = settings.sample_3d(x * freq_x, y * freq_y, z * freq_z);
                 max_s = max_s.max(f);
                 min_s = min_s.min(f);
                 f.copy_to_ptr_unaligned(result_ptr.add(i));
-                i += VEC_WIDTH;
-                x = x + S::Vf64::set1(VEC_WIDTH as f64);
+                i += vec_width;
             }
             if remainder != 0 {
+                let x = lane_positions::<S>(start_x, width - remainder, step_x, lanes);
                 let f = settings.sample_3d(x * freq_x, y * freq_y, z * freq_z);
                 for j in 0..remainder {
                     let n = f[j];
@@ -196,12 +221,10 @@ unsafe fn get_3d_noise_helper_f64<S: Simd, Settings: Sample64<S>>(
                     i += 1;
                 }
             }
-            y = y + S::Vf64::set1(1.0);
         }
-        z = z + S::Vf64::set1(1.0);
     }
 
-    for i in 0..VEC_WIDTH {
+    for i in 0..vec_width {
         if min_s[i] < min {
             min = min_s[i];
         }
@@ -213,11 +236,93 @@ unsafe fn get_3d_noise_helper_f64<S: Simd, Settings: Sample64<S>>(
 }
 
 #[inline(always)]
-#[allow(dead_code)]
+unsafe fn get_4d_noise_helper_f64<S: Simd, Settings: Sample4d64<S>>(
+    settings: Settings, noise: *mut f64
+) -> Result<(f64, f64), NoiseError> {
+    let dim = settings.get_dimensions();
+    let freq_x = S::Vf64::set1(settings.get_freq_x() as f64);
+    let freq_y = S::Vf64::set1(settings.get_freq_y() as f64);
+    let freq_z = S::Vf64::set1(settings.get_freq_z() as f64);
+    let freq_w = S::Vf64::set1(settings.get_freq_w() as f64);
+    let start_x = dim.x;
+    let step_x = dim.step_x as f64;
+    let width = dim.width;
+    let start_y = dim.y;
+    let step_y = dim.step_y as f64;
+    let height = dim.height;
+    let start_z = dim.z;
+    let step_z = dim.step_z as f64;
+    let depth = dim.depth;
+    let start_w = dim.w;
+    let step_w = dim.step_w as f64;
+    let time = dim.time;
+
+    let mut min_s = S::Vf64::set1(f64::MAX);
+    let mut max_s = S::Vf64::set1(f64::MIN);
+    let mut min = f64::MAX;
+    let mut max = f64::MIN;
+
+    let result_ptr = noise;
+    let mut i = 0;
+    let vec_width = S::Vf64::WIDTH;
+    let remainder = width % vec_width;
+    let lanes = lane_indices::<S>();
+
+    for wi in 0..time {
+        let w = S::Vf64::set1(start_w + wi as f64 * step_w);
+        for zi in 0..depth {
+            let z = S::Vf64::set1(start_z + zi as f64 * step_z);
+            for yi in 0..height {
+                let y = S::Vf64::set1(start_y + yi as f64 * step_y);
+                for xi in (0..width - remainder).step_by(vec_width) {
+                    let x = lane_positions::<S>(start_x, xi, step_x, lanes);
+                    let f = settings.sample_4d(x * freq_x, y * freq_y, z * freq_z, w * freq_w)?;
+                    max_s = max_s.max(f);
+                    min_s = min_s.min(f);
+                    f.copy_to_ptr_unaligned(result_ptr.add(i));
+                    i += vec_width;
+                }
+                if remainder != 0 {
+                    let x = lane_positions::<S>(start_x, width - remainder, step_x, lanes);
+                    let f = settings.sample_4d(x * freq_x, y * freq_y, z * freq_z, w * freq_w)?;
+                    for j in 0..remainder {
+                        let n = f[j];
+                        result_ptr.add(i).write(n);
+                        if n < min {
+                            min = n;
+                        }
+                        if n > max {
+                            max = n;
+                        }
+                        i += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    for i in 0..vec_width {
+        if min_s[i] < min {
+            min = min_s[i];
+        }
+        if max_s[i] > max {
+            max = max_s[i];
+        }
+    }
+    Ok((min, max))
+}
+
+#[inline(always)]
 pub unsafe fn get_1d_noise<S: Simd>(noise_type: &NoiseType, noise: *mut f64) -> (f64, f64) {
     match noise_type {
         NoiseType::Fbm(s) => get_1d_noise_helper_f64::<S, FbmSettings>(*s, noise),
         NoiseType::Gradient(s) => get_1d_noise_helper_f64::<S, GradientSettings>(*s, noise),
+        NoiseType::Ridge(s) => get_1d_noise_helper_f64::<S, RidgeSettings>(*s, noise),
+        NoiseType::Turbulence(s) => get_1d_noise_helper_f64::<S, TurbulenceSettings>(*s, noise),
+        NoiseType::Billow(s) => get_1d_noise_helper_f64::<S, BillowSettings>(*s, noise),
+        NoiseType::Cellular(s) => get_1d_noise_helper_f64::<S, CellularSettings>(*s, noise),
+        NoiseType::DomainWarp(s) => get_1d_noise_helper_f64::<S, DomainWarpSettings>(*s, noise),
+        NoiseType::White(s) => get_1d_noise_helper_f64::<S, WhiteSettings>(*s, noise),
     }
 }
 
@@ -227,11 +332,16 @@ pub unsafe fn get_1d_noise<S: Simd>(noise_type: &NoiseType, noise: *mut f64) ->
 /// are returned so you can scale and transform the noise as you see fit
 /// in a single pass.
 #[inline(always)]
-#[allow(dead_code)]
 pub unsafe fn get_2d_noise<S: Simd>(noise_type: &NoiseType, noise: *mut f64) -> (f64, f64) {
     match noise_type {
         NoiseType::Fbm(s) => get_2d_noise_helper_f64::<S, FbmSettings>(*s, noise),
         NoiseType::Gradient(s) => get_2d_noise_helper_f64::<S, GradientSettings>(*s, noise),
+        NoiseType::Ridge(s) => get_2d_noise_helper_f64::<S, RidgeSettings>(*s, noise),
+        NoiseType::Turbulence(s) => get_2d_noise_helper_f64::<S, TurbulenceSettings>(*s, noise),
+        NoiseType::Billow(s) => get_2d_noise_helper_f64::<S, BillowSettings>(*s, noise),
+        NoiseType::Cellular(s) => get_2d_noise_helper_f64::<S, CellularSettings>(*s, noise),
+        NoiseType::DomainWarp(s) => get_2d_noise_helper_f64::<S, DomainWarpSettings>(*s, noise),
+        NoiseType::White(s) => get_2d_noise_helper_f64::<S, WhiteSettings>(*s, noise),
     }
 }
 
@@ -241,10 +351,321 @@ pub unsafe fn get_2d_noise<S: Simd>(noise_type: &NoiseType, noise: *mut f64) ->
 /// are returned so you can scale and transform the noise as you see fit
 /// in a single pass.
 #[inline(always)]
-#[allow(dead_code)]
 pub unsafe fn get_3d_noise<S: Simd>(noise_type: &NoiseType, noise: *mut f64) -> (f64, f64) {
     match noise_type {
         NoiseType::Fbm(s) => get_3d_noise_helper_f64::<S, FbmSettings>(*s, noise),
         NoiseType::Gradient(s) => get_3d_noise_helper_f64::<S, GradientSettings>(*s, noise),
+        NoiseType::Ridge(s) => get_3d_noise_helper_f64::<S, RidgeSettings>(*s, noise),
+        NoiseType::Turbulence(s) => get_3d_noise_helper_f64::<S, TurbulenceSettings>(*s, noise),
+        NoiseType::Billow(s) => get_3d_noise_helper_f64::<S, BillowSettings>(*s, noise),
+        NoiseType::Cellular(s) => get_3d_noise_helper_f64::<S, CellularSettings>(*s, noise),
+        NoiseType::DomainWarp(s) => get_3d_noise_helper_f64::<S, DomainWarpSettings>(*s, noise),
+        NoiseType::White(s) => get_3d_noise_helper_f64::<S, WhiteSettings>(*s, noise),
+    }
+}
+
+/// Gets a width X height X depth X time sized block of 4d noise, unscaled,
+/// `start_x`,`start_y`, `start_z` and `start_w` can be used to provide an offset in the
+/// coordinates. Results are unscaled, 'min' and 'max' noise values
+/// are returned so you can scale and transform the noise as you see fit
+/// in a single pass. Fails if `noise_type` has no 4d noise.
+#[inline(always)]
+pub unsafe fn get_4d_noise<S: Simd>(
+    noise_type: &NoiseType,
+    noise: *mut f64,
+) -> Result<(f64, f64), NoiseError> {
+    noise_type.validate_dim(4)?;
+    match noise_type {
+        NoiseType::Fbm(s) => get_4d_noise_helper_f64::<S, FbmSettings>(*s, noise),
+        NoiseType::Gradient(s) => get_4d_noise_helper_f64::<S, GradientSettings>(*s, noise),
+        NoiseType::Ridge(s) => get_4d_noise_helper_f64::<S, RidgeSettings>(*s, noise),
+        NoiseType::Turbulence(s) => get_4d_noise_helper_f64::<S, TurbulenceSettings>(*s, noise),
+        NoiseType::Billow(s) => get_4d_noise_helper_f64::<S, BillowSettings>(*s, noise),
+        NoiseType::DomainWarp(s) => get_4d_noise_helper_f64::<S, DomainWarpSettings>(*s, noise),
+        NoiseType::Cellular(_) | NoiseType::White(_) => {
+            Err(NoiseError::UnsupportedDimension { dim: 4 })
+        }
+    }
+}
+
+/// Loads `count` coordinates starting at `start`, lanes past `count` are left at zero
+#[inline(always)]
+unsafe fn load_points_f64<S: Simd>(coords: &[f64], start: usize, count: usize) -> S::Vf64 {
+    if count == S::Vf64::WIDTH {
+        return S::Vf64::load_from_ptr_unaligned(coords.as_ptr().add(start));
+    }
+    let mut v = S::Vf64::zeroes();
+    for (j, &c) in coords[start..start + count].iter().enumerate() {
+        *v.get_unchecked_mut(j) = c;
+    }
+    v
+}
+
+/// Samples every point of `coords` in batches of one vector, the last batch is padded with
+/// zeroes and only `out.len() % WIDTH` of its values are written
+#[inline(always)]
+unsafe fn sample_points_helper_f64<S: Simd, const D: usize, E>(
+    freqs: [f64; D],
+    coords: [&[f64]; D],
+    out: *mut f64,
+    len: usize,
+    sample: impl Fn([S::Vf64; D]) -> Result<S::Vf64, E>,
+) -> Result<(), E> {
+    let freqs = freqs.map(S::Vf64::set1);
+    let vec_width = S::Vf64::WIDTH;
+    let mut i = 0;
+    while i < len {
+        let count = vec_width.min(len - i);
+        let mut points = [S::Vf64::zeroes(); D];
+        for (point, (axis, freq)) in points.iter_mut().zip(coords.iter().zip(freqs)) {
+            *point = load_points_f64::<S>(axis, i, count) * freq;
+        }
+        let f = sample(points)?;
+        if count == vec_width {
+            f.copy_to_ptr_unaligned(out.add(i));
+        } else {
+            for j in 0..count {
+                out.add(i + j).write(f[j]);
+            }
+        }
+        i += count;
+    }
+    Ok(())
+}
+
+#[inline(always)]
+unsafe fn sample_points_1d_helper_f64<S: Simd, Settings: Sample64<S>>(
+    settings: Settings,
+    xs: &[f64],
+    out: *mut f64,
+    len: usize,
+) {
+    let freqs = [settings.get_freq_x() as f64];
+    let Ok(()) = sample_points_helper_f64::<S, 1, Infallible>(freqs, [xs], out, len, |[x]| {
+        Ok(settings.sample_1d(x))
+    });
+}
+
+#[inline(always)]
+unsafe fn sample_points_2d_helper_f64<S: Simd, Settings: Sample64<S>>(
+    settings: Settings,
+    xs: &[f64],
+    ys: &[f64],
+    out: *mut f64,
+    len: usize,
+) {
+    let freqs = [settings.get_freq_x() as f64, settings.get_freq_y() as f64];
+    let Ok(()) =
+        sample_points_helper_f64::<S, 2, Infallible>(freqs, [xs, ys], out, len, |[x, y]| {
+            Ok(settings.sample_2d(x, y))
+        });
+}
+
+#[inline(always)]
+unsafe fn sample_points_3d_helper_f64<S: Simd, Settings: Sample64<S>>(
+    settings: Settings,
+    xs: &[f64],
+    ys: &[f64],
+    zs: &[f64],
+    out: *mut f64,
+    len: usize,
+) {
+    let freqs = [
+        settings.get_freq_x() as f64,
+        settings.get_freq_y() as f64,
+        settings.get_freq_z() as f64,
+    ];
+    let Ok(()) =
+        sample_points_helper_f64::<S, 3, Infallible>(freqs, [xs, ys, zs], out, len, |[x, y, z]| {
+            Ok(settings.sample_3d(x, y, z))
+        });
+}
+
+#[inline(always)]
+unsafe fn sample_points_4d_helper_f64<S: Simd, Settings: Sample4d64<S>>(
+    settings: Settings,
+    xs: &[f64],
+    ys: &[f64],
+    zs: &[f64],
+    ws: &[f64],
+    out: *mut f64,
+    len: usize,
+) -> Result<(), NoiseError> {
+    let freqs = [
+        settings.get_freq_x() as f64,
+        settings.get_freq_y() as f64,
+        settings.get_freq_z() as f64,
+        settings.get_freq_w() as f64,
+    ];
+    sample_points_helper_f64::<S, 4, NoiseError>(
+        freqs,
+        [xs, ys, zs, ws],
+        out,
+        len,
+        |[x, y, z, w]| settings.sample_4d(x, y, z, w),
+    )
+}
+
+/// Samples 1d noise at the points `coords[i]`, see `crate::sample_points_1d`
+///
+/// # Safety
+/// Every coordinate slice must hold at least `len` values and `out` must be valid for writes of
+/// `len` values
+#[inline(always)]
+pub unsafe fn sample_points_1d<S: Simd>(
+    noise_type: &NoiseType,
+    xs: &[f64],
+    out: *mut f64,
+    len: usize,
+) {
+    match noise_type {
+        NoiseType::Fbm(s) => {
+            sample_points_1d_helper_f64::<S, FbmSettings>(*s, xs, out, len)
+        }
+        NoiseType::Gradient(s) => {
+            sample_points_1d_helper_f64::<S, GradientSettings>(*s, xs, out, len)
+        }
+        NoiseType::Ridge(s) => {
+            sample_points_1d_helper_f64::<S, RidgeSettings>(*s, xs, out, len)
+        }
+        NoiseType::Turbulence(s) => {
+            sample_points_1d_helper_f64::<S, TurbulenceSettings>(*s, xs, out, len)
+        }
+        NoiseType::Billow(s) => {
+            sample_points_1d_helper_f64::<S, BillowSettings>(*s, xs, out, len)
+        }
+        NoiseType::Cellular(s) => {
+            sample_points_1d_helper_f64::<S, CellularSettings>(*s, xs, out, len)
+        }
+        NoiseType::DomainWarp(s) => {
+            sample_points_1d_helper_f64::<S, DomainWarpSettings>(*s, xs, out, len)
+        }
+        NoiseType::White(s) => {
+            sample_points_1d_helper_f64::<S, WhiteSettings>(*s, xs, out, len)
+        }
+    }
+}
+
+/// Samples 2d noise at the points `coords[i]`, see `crate::sample_points_2d`
+///
+/// # Safety
+/// Every coordinate slice must hold at least `len` values and `out` must be valid for writes of
+/// `len` values
+#[inline(always)]
+pub unsafe fn sample_points_2d<S: Simd>(
+    noise_type: &NoiseType,
+    xs: &[f64],
+    ys: &[f64],
+    out: *mut f64,
+    len: usize,
+) {
+    match noise_type {
+        NoiseType::Fbm(s) => {
+            sample_points_2d_helper_f64::<S, FbmSettings>(*s, xs, ys, out, len)
+        }
+        NoiseType::Gradient(s) => {
+            sample_points_2d_helper_f64::<S, GradientSettings>(*s, xs, ys, out, len)
+        }
+        NoiseType::Ridge(s) => {
+            sample_points_2d_helper_f64::<S, RidgeSettings>(*s, xs, ys, out, len)
+        }
+        NoiseType::Turbulence(s) => {
+            sample_points_2d_helper_f64::<S, TurbulenceSettings>(*s, xs, ys, out, len)
+        }
+        NoiseType::Billow(s) => {
+            sample_points_2d_helper_f64::<S, BillowSettings>(*s, xs, ys, out, len)
+        }
+        NoiseType::Cellular(s) => {
+            sample_points_2d_helper_f64::<S, CellularSettings>(*s, xs, ys, out, len)
+        }
+        NoiseType::DomainWarp(s) => {
+            sample_points_2d_helper_f64::<S, DomainWarpSettings>(*s, xs, ys, out, len)
+        }
+        NoiseType::White(s) => {
+            sample_points_2d_helper_f64::<S, WhiteSettings>(*s, xs, ys, out, len)
+        }
     }
-}
\ No newline at end of file
+}
+
+/// Samples 3d noise at the points `coords[i]`, see `crate::sample_points_3d`
+///
+/// # Safety
+/// Every coordinate slice must hold at least `len` values and `out` must be valid for writes of
+/// `len` values
+#[inline(always)]
+pub unsafe fn sample_points_3d<S: Simd>(
+    noise_type: &NoiseType,
+    xs: &[f64],
+    ys: &[f64],
+    zs: &[f64],
+    out: *mut f64,
+    len: usize,
+) {
+    match noise_type {
+        NoiseType::Fbm(s) => {
+            sample_points_3d_helper_f64::<S, FbmSettings>(*s, xs, ys, zs, out, len)
+        }
+        NoiseType::Gradient(s) => {
+            sample_points_3d_helper_f64::<S, GradientSettings>(*s, xs, ys, zs, out, len)
+        }
+        NoiseType::Ridge(s) => {
+            sample_points_3d_helper_f64::<S, RidgeSettings>(*s, xs, ys, zs, out, len)
+        }
+        NoiseType::Turbulence(s) => {
+            sample_points_3d_helper_f64::<S, TurbulenceSettings>(*s, xs, ys, zs, out, len)
+        }
+        NoiseType::Billow(s) => {
+            sample_points_3d_helper_f64::<S, BillowSettings>(*s, xs, ys, zs, out, len)
+        }
+        NoiseType::Cellular(s) => {
+            sample_points_3d_helper_f64::<S, CellularSettings>(*s, xs, ys, zs, out, len)
+        }
+        NoiseType::DomainWarp(s) => {
+            sample_points_3d_helper_f64::<S, DomainWarpSettings>(*s, xs, ys, zs, out, len)
+        }
+        NoiseType::White(s) => {
+            sample_points_3d_helper_f64::<S, WhiteSettings>(*s, xs, ys, zs, out, len)
+        }
+    }
+}
+
+/// Samples 4d noise at the points `coords[i]`, see `crate::sample_points_4d`. Fails if
+/// `noise_type` has no 4d noise.
+///
+/// # Safety
+/// Every coordinate slice must hold at least `len` values and `out` must be valid for writes of
+/// `len` values
+#[inline(always)]
+pub unsafe fn sample_points_4d<S: Simd>(
+    noise_type: &NoiseType,
+    xs: &[f64],
+    ys: &[f64],
+    zs: &[f64],
+    ws: &[f64],
+    out: *mut f64,
+    len: usize,
+) -> Result<(), NoiseError> {
+    match noise_type {
+        NoiseType::Fbm(s) => {
+            sample_points_4d_helper_f64::<S, FbmSettings>(*s, xs, ys, zs, ws, out, len)
+        }
+        NoiseType::Gradient(s) => {
+            sample_points_4d_helper_f64::<S, GradientSettings>(*s, xs, ys, zs, ws, out, len)
+        }
+        NoiseType::Ridge(s) => {
+            sample_points_4d_helper_f64::<S, RidgeSettings>(*s, xs, ys, zs, ws, out, len)
+        }
+        NoiseType::Turbulence(s) => {
+            sample_points_4d_helper_f64::<S, TurbulenceSettings>(*s, xs, ys, zs, ws, out, len)
+        }
+        NoiseType::Billow(s) => {
+            sample_points_4d_helper_f64::<S, BillowSettings>(*s, xs, ys, zs, ws, out, len)
+        }
+        NoiseType::DomainWarp(s) => {
+            sample_points_4d_helper_f64::<S, DomainWarpSettings>(*s, xs, ys, zs, ws, out, len)
+        }
+        NoiseType::Cellular(_) | NoiseType::White(_) => {
+            Err(NoiseError::UnsupportedDimension { dim: 4 })
+        }
+    }
+}
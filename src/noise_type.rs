@@ -1,13 +1,25 @@
 use super::{
-    DimensionalBeing, FbmSettings, GradientSettings,
-    NoiseDimensions,
+    BillowSettings, CellularSettings, DimensionalBeing, DomainWarpSettings, FbmSettings,
+    GradientSettings, NoiseDimensions, RidgeSettings, Settings, TurbulenceSettings, WarpSource,
+    WhiteSettings,
 };
+use crate::settings::{validate_f64, validate_lattice};
+use crate::NoiseError;
 
 /// Specifies what type of noise to generate and contains any relevant settings.
+// Domain warps hold two more settings, but settings are `Copy` and there is no allocator to box
+// them with
+#[allow(clippy::large_enum_variant)]
 #[derive(Copy, Clone)]
 pub enum NoiseType {
     Fbm(FbmSettings),
     Gradient(GradientSettings),
+    Ridge(RidgeSettings),
+    Turbulence(TurbulenceSettings),
+    Billow(BillowSettings),
+    Cellular(CellularSettings),
+    DomainWarp(DomainWarpSettings),
+    White(WhiteSettings),
 }
 
 impl DimensionalBeing for NoiseType {
@@ -15,6 +27,79 @@ impl DimensionalBeing for NoiseType {
         match self {
             NoiseType::Fbm(s) => s.get_dimensions(),
             NoiseType::Gradient(s) => s.get_dimensions(),
+            NoiseType::Ridge(s) => s.get_dimensions(),
+            NoiseType::Turbulence(s) => s.get_dimensions(),
+            NoiseType::Billow(s) => s.get_dimensions(),
+            NoiseType::Cellular(s) => s.get_dimensions(),
+            NoiseType::DomainWarp(s) => s.get_dimensions(),
+            NoiseType::White(s) => s.get_dimensions(),
+        }
+    }
+}
+
+impl From<WarpSource> for NoiseType {
+    fn from(source: WarpSource) -> NoiseType {
+        match source {
+            WarpSource::Fbm(s) => NoiseType::Fbm(s),
+            WarpSource::Gradient(s) => NoiseType::Gradient(s),
+            WarpSource::Ridge(s) => NoiseType::Ridge(s),
+            WarpSource::Turbulence(s) => NoiseType::Turbulence(s),
+            WarpSource::Billow(s) => NoiseType::Billow(s),
+            WarpSource::Cellular(s) => NoiseType::Cellular(s),
+        }
+    }
+}
+
+impl NoiseType {
+    /// The range the wrapped settings can produce, see `Settings::bounds`
+    pub fn bounds(&self) -> (f32, f32) {
+        match self {
+            NoiseType::Fbm(s) => s.bounds(),
+            NoiseType::Gradient(s) => s.bounds(),
+            NoiseType::Ridge(s) => s.bounds(),
+            NoiseType::Turbulence(s) => s.bounds(),
+            NoiseType::Billow(s) => s.bounds(),
+            NoiseType::Cellular(s) => s.bounds(),
+            NoiseType::DomainWarp(s) => s.bounds(),
+            NoiseType::White(s) => s.bounds(),
+        }
+    }
+
+    /// Checks that the wrapped settings can be sampled in `dim` dimensions, whatever dimension
+    /// count they were built with. Cellular and white noise have no 4d variant.
+    pub(crate) fn validate_dim(&self, dim: usize) -> Result<(), NoiseError> {
+        match self {
+            NoiseType::Fbm(s) => validate_lattice(dim, s.octave_options.lattice),
+            NoiseType::Gradient(s) => validate_lattice(dim, s.lattice),
+            NoiseType::Ridge(s) => validate_lattice(dim, s.octave_options.lattice),
+            NoiseType::Turbulence(s) => validate_lattice(dim, s.octave_options.lattice),
+            NoiseType::Billow(s) => validate_lattice(dim, s.octave_options.lattice),
+            NoiseType::Cellular(_) | NoiseType::White(_) if dim == 4 => {
+                Err(NoiseError::UnsupportedDimension { dim })
+            }
+            NoiseType::Cellular(_) | NoiseType::White(_) => Ok(()),
+            NoiseType::DomainWarp(s) => {
+                NoiseType::from(s.inner).validate_dim(dim)?;
+                NoiseType::from(s.warp).validate_dim(dim)
+            }
+        }
+    }
+
+    /// Checks that the wrapped settings don't repeat, every f64 generator and sampler calls this
+    /// after validating the settings
+    pub(crate) fn validate_f64(&self) -> Result<(), NoiseError> {
+        match self {
+            NoiseType::Fbm(s) => validate_f64(s.is_periodic()),
+            NoiseType::Gradient(s) => validate_f64(s.is_periodic()),
+            NoiseType::Ridge(_)
+            | NoiseType::Turbulence(_)
+            | NoiseType::Billow(_)
+            | NoiseType::Cellular(_)
+            | NoiseType::White(_) => Ok(()),
+            NoiseType::DomainWarp(s) => {
+                NoiseType::from(s.inner).validate_f64()?;
+                NoiseType::from(s.warp).validate_f64()
+            }
         }
     }
 }
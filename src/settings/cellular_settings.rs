@@ -0,0 +1,286 @@
+use simdeez::prelude::*;
+
+use crate::dimensional_being::DimensionalBeing;
+use crate::noise::cellular_32::{cellular_2d, cellular_3d};
+use crate::noise::cellular_64::{cellular_2d as cellular_2d_f64, cellular_3d as cellular_3d_f64};
+pub use crate::noise_dimensions::{NoiseDimensions, ScaleMode, SeedMode};
+use crate::noise_helpers_32::Sample32;
+use crate::noise_helpers_64::Sample64;
+pub use crate::noise_type::NoiseType;
+use crate::{ChannelLayout, NoiseError};
+use crate::{
+    get_1d_noise, get_1d_scaled_noise, get_2d_noise, get_2d_scaled_noise, get_3d_noise,
+    get_3d_scaled_noise,
+};
+
+use super::{validate_frequency, validate_parameter, Settings};
+
+/// How the distance between a sample and a feature point is measured
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CellDistanceFunction {
+    /// Straight line distance, round cells
+    Euclidean,
+    /// Sum of the axis distances, diamond shaped cells
+    Manhattan,
+    /// Largest axis distance, square cells
+    Chebyshev,
+    /// Manhattan plus squared Euclidean, a blend of both looks
+    Natural,
+}
+
+/// What a cellular sample reports
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CellReturnType {
+    /// Distance to the closest feature point
+    F1,
+    /// Distance to the second closest feature point
+    F2,
+    /// `F2 - F1`, zero on cell borders
+    F2MinusF1,
+    /// Random value in [-1, 1] shared by every sample in the same cell
+    CellValue,
+    /// Euclidean distance to the nearest cell border
+    DistanceToEdge,
+}
+
+/// Cellular (Worley / Voronoi) noise
+#[derive(Copy, Clone)]
+pub struct CellularSettings {
+    pub(crate) dim: NoiseDimensions,
+    pub freq_x: f32,
+    pub freq_y: f32,
+    pub freq_z: f32,
+    pub distance_function: CellDistanceFunction,
+    pub return_type: CellReturnType,
+    pub jitter: f32,
+}
+
+impl DimensionalBeing for CellularSettings {
+    fn get_dimensions(&self) -> NoiseDimensions {
+        return self.dim;
+    }
+}
+
+impl Settings for CellularSettings {
+    fn default(dim: NoiseDimensions) -> CellularSettings {
+        CellularSettings {
+            dim,
+            freq_x: 0.02,
+            freq_y: 0.02,
+            freq_z: 0.02,
+            distance_function: CellDistanceFunction::Euclidean,
+            return_type: CellReturnType::F1,
+            jitter: 1.0,
+        }
+    }
+
+    dimension_methods!(Settings);
+
+    fn with_freq(&mut self, freq: f32) -> &mut CellularSettings {
+        self.freq_x = freq;
+        self.freq_y = freq;
+        self.freq_z = freq;
+        self
+    }
+
+    fn with_freq_2d(&mut self, freq_x: f32, freq_y: f32) -> &mut CellularSettings {
+        self.freq_x = freq_x;
+        self.freq_y = freq_y;
+        self
+    }
+
+    fn with_freq_3d(&mut self, freq_x: f32, freq_y: f32, freq_z: f32) -> &mut CellularSettings {
+        self.freq_x = freq_x;
+        self.freq_y = freq_y;
+        self.freq_z = freq_z;
+        self
+    }
+
+    fn get_freq_x(&self) -> f32 {
+        self.freq_x
+    }
+
+    fn get_freq_y(&self) -> f32 {
+        self.freq_y
+    }
+
+    fn get_freq_z(&self) -> f32 {
+        self.freq_z
+    }
+
+    fn bounds(&self) -> (f32, f32) {
+        // The closest feature point is at most the own cell's, the second closest at most the
+        // furthest one in the searched neighbourhood, measured per axis
+        let spread = 0.5 * self.jitter.abs();
+        let distance = self.distance_function;
+        match self.return_type {
+            CellReturnType::F1 => (0.0, self.distance_bound(distance, 0.5 + spread)),
+            CellReturnType::F2 | CellReturnType::F2MinusF1 => {
+                (0.0, self.distance_bound(distance, 1.5 + spread))
+            }
+            CellReturnType::CellValue => (-1.0, 1.0),
+            CellReturnType::DistanceToEdge => {
+                let euclidean = CellDistanceFunction::Euclidean;
+                (0.0, self.distance_bound(euclidean, 1.5 + spread))
+            }
+        }
+    }
+
+    fn wrap(self) -> Result<NoiseType, NoiseError> {
+        self.validate()?;
+        Ok(NoiseType::Cellular(self))
+    }
+
+    fn validate(&self) -> Result<(), NoiseError> {
+        self.dim.validate()?;
+        if self.dim.dim == 4 {
+            return Err(NoiseError::UnsupportedDimension { dim: 4 });
+        }
+        validate_frequency(&[self.freq_x, self.freq_y, self.freq_z])?;
+        validate_parameter(&[self.jitter])
+    }
+
+    unsafe fn generate(self, noise: *mut f32) -> Result<(f32, f32), NoiseError> {
+        self.validate()?;
+        let d = self.dim.dim;
+        match d {
+            1 => Ok(get_1d_noise(&NoiseType::Cellular(self), noise)),
+            2 => Ok(get_2d_noise(&NoiseType::Cellular(self), noise)),
+            3 => Ok(get_3d_noise(&NoiseType::Cellular(self), noise)),
+            _ => Err(NoiseError::UnsupportedDimension { dim: d }),
+        }
+    }
+
+    unsafe fn generate_scaled(
+        self,
+        min: f32,
+        max: f32,
+        noise: *mut f32,
+    ) -> Result<(), NoiseError> {
+        let d = self.dim.dim;
+        let mut new_self = self;
+        new_self.dim.min = min;
+        new_self.dim.max = max;
+        new_self.validate()?;
+        match d {
+            1 => get_1d_scaled_noise(&NoiseType::Cellular(new_self), noise),
+            2 => get_2d_scaled_noise(&NoiseType::Cellular(new_self), noise),
+            3 => get_3d_scaled_noise(&NoiseType::Cellular(new_self), noise),
+            _ => return Err(NoiseError::UnsupportedDimension { dim: d }),
+        }
+        Ok(())
+    }
+
+    fn generate_with_derivatives_into(
+        self,
+        _noise: &mut [f32],
+        _derivatives: &mut [f32],
+        _layout: ChannelLayout,
+    ) -> Result<(f32, f32), NoiseError> {
+        Err(NoiseError::DerivativesUnsupported)
+    }
+
+}
+
+// A single row of cells is just the 2d noise along y = 0
+impl<S: Simd> Sample32<S> for CellularSettings {
+    #[inline(always)]
+    fn sample_1d(&self, x: S::Vf32) -> S::Vf32 {
+        <Self as Sample32<S>>::sample_2d(self, x, S::Vf32::zeroes())
+    }
+
+    #[inline(always)]
+    fn sample_2d(&self, x: S::Vf32, y: S::Vf32) -> S::Vf32 {
+        cellular_2d::<S>(
+            x,
+            y,
+            self.distance_function,
+            self.return_type,
+            S::Vf32::set1(self.jitter),
+            self.dim.seed_32(),
+        )
+    }
+
+    #[inline(always)]
+    fn sample_3d(&self, x: S::Vf32, y: S::Vf32, z: S::Vf32) -> S::Vf32 {
+        cellular_3d::<S>(
+            x,
+            y,
+            z,
+            self.distance_function,
+            self.return_type,
+            S::Vf32::set1(self.jitter),
+            self.dim.seed_32(),
+        )
+    }
+}
+
+impl<S: Simd> Sample64<S> for CellularSettings {
+    #[inline(always)]
+    fn sample_1d(&self, x: S::Vf64) -> S::Vf64 {
+        <Self as Sample64<S>>::sample_2d(self, x, S::Vf64::zeroes())
+    }
+
+    #[inline(always)]
+    fn sample_2d(&self, x: S::Vf64, y: S::Vf64) -> S::Vf64 {
+        cellular_2d_f64::<S>(
+            x,
+            y,
+            self.distance_function,
+            self.return_type,
+            S::Vf64::set1(self.jitter.into()),
+            self.dim.seed_64(),
+        )
+    }
+
+    #[inline(always)]
+    fn sample_3d(&self, x: S::Vf64, y: S::Vf64, z: S::Vf64) -> S::Vf64 {
+        cellular_3d_f64::<S>(
+            x,
+            y,
+            z,
+            self.distance_function,
+            self.return_type,
+            S::Vf64::set1(self.jitter.into()),
+            self.dim.seed_64(),
+        )
+    }
+}
+
+impl CellularSettings {
+    pub fn with_distance_function(
+        &mut self,
+        distance: CellDistanceFunction,
+    ) -> &mut CellularSettings {
+        self.distance_function = distance;
+        self
+    }
+
+    pub fn with_return_type(&mut self, return_type: CellReturnType) -> &mut CellularSettings {
+        self.return_type = return_type;
+        self
+    }
+
+    /// How far feature points may move away from their cell centre, 0.0 gives a regular grid.
+    /// Values above 1.0 let points escape the searched neighbourhood and cause artefacts.
+    pub fn with_jitter(&mut self, jitter: f32) -> &mut CellularSettings {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Largest distance `distance` reports when every axis is at most `axis` apart
+    fn distance_bound(&self, distance: CellDistanceFunction, axis: f32) -> f32 {
+        // 1d noise samples the 2d variant
+        let (axes, sqrt_axes) = if self.dim.dim == 3 {
+            (3.0, 1.732_050_8)
+        } else {
+            (2.0, core::f32::consts::SQRT_2)
+        };
+        match distance {
+            CellDistanceFunction::Euclidean => axis * sqrt_axes,
+            CellDistanceFunction::Manhattan => axis * axes,
+            CellDistanceFunction::Chebyshev => axis,
+            CellDistanceFunction::Natural => axis * axes + axis * axis * axes,
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use crate::dimensional_being::DimensionalBeing;
+use crate::noise::octaves::OctaveOptions;
+pub use crate::noise_dimensions::{NoiseDimensions, SeedMode};
+use crate::NoiseError;
+use crate::{get_2d_curl, get_3d_curl};
+
+use super::{split_octaves, validate_fractal, validate_frequency, SimplexSettings};
+
+/// How the components of a vector field are laid out in the output
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ChannelLayout {
+    /// `x0 y0 x1 y1 ...`, every sample's components next to each other
+    Interleaved,
+    /// `x0 x1 ... y0 y1 ...`, one whole block per component
+    Planar,
+}
+
+/// Divergence free vector fields, the curl of fbm layered simplex noise.
+///
+/// 2d blocks have two components per sample and 3d blocks three, so the output holds
+/// `NoiseDimensions::len` times that many values. Only 2d and 3d are supported, and the field
+/// is only generated in single precision.
+#[derive(Copy, Clone)]
+pub struct CurlSettings {
+    dim: NoiseDimensions,
+    pub freq_x: f32,
+    pub freq_y: f32,
+    pub freq_z: f32,
+    pub lacunarity: f32,
+    pub gain: f32,
+    pub octaves: u8,
+    pub octave_options: OctaveOptions,
+    pub layout: ChannelLayout,
+}
+
+impl DimensionalBeing for CurlSettings {
+    fn get_dimensions(&self) -> NoiseDimensions {
+        return self.dim;
+    }
+}
+
+impl SimplexSettings for CurlSettings {
+    fn with_lacunarity(&mut self, lacunarity: f32) -> &mut CurlSettings {
+        self.lacunarity = lacunarity;
+        self
+    }
+
+    fn with_gain(&mut self, gain: f32) -> &mut CurlSettings {
+        self.gain = gain;
+        self
+    }
+
+    fn with_octaves(&mut self, octaves: u8) -> &mut CurlSettings {
+        self.octaves = octaves;
+        self.octave_options.fraction = 0.0;
+        self
+    }
+
+    fn with_octaves_f32(&mut self, octaves: f32) -> &mut CurlSettings {
+        (self.octaves, self.octave_options.fraction) = split_octaves(octaves);
+        self
+    }
+}
+
+impl CurlSettings {
+    pub fn default(dim: NoiseDimensions) -> CurlSettings {
+        CurlSettings {
+            dim,
+            freq_x: 0.02,
+            freq_y: 0.02,
+            freq_z: 0.02,
+            lacunarity: 2.0,
+            gain: 0.5,
+            octaves: 3,
+            octave_options: OctaveOptions::default(),
+            layout: ChannelLayout::Interleaved,
+        }
+    }
+
+    /// Seeds the noise, see `Settings::with_seed`
+    pub fn with_seed(&mut self, seed: u64) -> &mut CurlSettings {
+        self.dim.seed = seed;
+        self.dim.seed_mode = SeedMode::Mixed;
+        self
+    }
+
+    /// Seeds the noise without hashing the seed, see `Settings::with_legacy_seed`
+    pub fn with_legacy_seed(&mut self, seed: i32) -> &mut CurlSettings {
+        self.dim.seed = seed as u64;
+        self.dim.seed_mode = SeedMode::Legacy;
+        self
+    }
+
+    pub fn with_freq(&mut self, freq: f32) -> &mut CurlSettings {
+        self.freq_x = freq;
+        self.freq_y = freq;
+        self.freq_z = freq;
+        self
+    }
+
+    pub fn with_freq_2d(&mut self, freq_x: f32, freq_y: f32) -> &mut CurlSettings {
+        self.freq_x = freq_x;
+        self.freq_y = freq_y;
+        self
+    }
+
+    pub fn with_freq_3d(&mut self, freq_x: f32, freq_y: f32, freq_z: f32) -> &mut CurlSettings {
+        self.freq_x = freq_x;
+        self.freq_y = freq_y;
+        self.freq_z = freq_z;
+        self
+    }
+
+    pub fn with_step(&mut self, step: f32) -> &mut CurlSettings {
+        self.dim.step_x = step;
+        self.dim.step_y = step;
+        self.dim.step_z = step;
+        self
+    }
+
+    pub fn with_step_2d(&mut self, step_x: f32, step_y: f32) -> &mut CurlSettings {
+        self.dim.step_x = step_x;
+        self.dim.step_y = step_y;
+        self
+    }
+
+    pub fn with_step_3d(&mut self, step_x: f32, step_y: f32, step_z: f32) -> &mut CurlSettings {
+        self.dim.step_x = step_x;
+        self.dim.step_y = step_y;
+        self.dim.step_z = step_z;
+        self
+    }
+
+    pub fn with_layout(&mut self, layout: ChannelLayout) -> &mut CurlSettings {
+        self.layout = layout;
+        self
+    }
+
+    /// Components per sample, 2 or 3
+    pub fn channels(&self) -> usize {
+        self.dim.dim
+    }
+
+    /// Checks the dimensions and every setting, the generate functions call this
+    pub fn validate(&self) -> Result<(), NoiseError> {
+        self.dim.validate()?;
+        if !(2..=3).contains(&self.dim.dim) {
+            return Err(NoiseError::UnsupportedDimension { dim: self.dim.dim });
+        }
+        validate_frequency(&[self.freq_x, self.freq_y, self.freq_z])?;
+        validate_fractal(self.lacunarity, self.gain, self.octaves, &self.octave_options)
+    }
+
+    /// Generates the vector field into `noise`, laid out as picked with `with_layout`
+    ///
+    /// # Safety
+    /// `noise` must be valid for writes of `NoiseDimensions::len` times `channels` values,
+    /// `generate_into` checks that for you.
+    pub unsafe fn generate(self, noise: *mut f32) -> Result<(), NoiseError> {
+        self.validate()?;
+        match self.dim.dim {
+            2 => get_2d_curl(&self, noise),
+            _ => get_3d_curl(&self, noise),
+        }
+        Ok(())
+    }
+
+    /// Like `generate`, but fails instead of writing past the end of `noise`
+    pub fn generate_into(self, noise: &mut [f32]) -> Result<(), NoiseError> {
+        self.dim.check_channel_buffer(noise.len(), self.channels())?;
+        unsafe { self.generate(noise.as_mut_ptr()) }
+    }
+}
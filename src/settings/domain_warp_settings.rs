@@ -0,0 +1,520 @@
+use simdeez::prelude::*;
+
+use core::convert::Infallible;
+
+use crate::dimensional_being::DimensionalBeing;
+use crate::noise::domain_warp_32::warp as warp_32;
+use crate::noise::domain_warp_64::warp as warp_64;
+pub use crate::noise_dimensions::{NoiseDimensions, ScaleMode, SeedMode};
+use crate::noise_helpers_32::{Sample32, Sample4d32};
+use crate::noise_helpers_64::{Sample4d64, Sample64};
+pub use crate::noise_type::NoiseType;
+use crate::{ChannelLayout, NoiseError};
+use crate::{
+    get_1d_noise, get_1d_scaled_noise, get_2d_noise, get_2d_scaled_noise, get_3d_noise,
+    get_3d_scaled_noise, get_4d_noise, get_4d_scaled_noise,
+};
+
+use super::{
+    validate_frequency, validate_parameter, BillowSettings, CellularSettings, FbmSettings,
+    GradientSettings, RidgeSettings, Settings, Settings4d, TurbulenceSettings,
+};
+
+/// How the octaves of a fractal warp are chained
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WarpMode {
+    /// Every octave reads the warp noise at the original coordinates and the displacements
+    /// are summed
+    Independent,
+    /// Every octave reads the warp noise at the coordinates the previous octaves warped, which
+    /// folds the noise over itself
+    Progressive,
+}
+
+/// A noise type a `DomainWarpSettings` can sample or warp with
+#[derive(Copy, Clone)]
+pub enum WarpSource {
+    Fbm(FbmSettings),
+    Gradient(GradientSettings),
+    Ridge(RidgeSettings),
+    Turbulence(TurbulenceSettings),
+    Billow(BillowSettings),
+    Cellular(CellularSettings),
+}
+
+/// Runs `$body` with `$s` bound to the settings inside a `WarpSource`
+macro_rules! with_source {
+    ($source:expr, $s:ident => $body:expr) => {
+        match $source {
+            WarpSource::Fbm($s) => $body,
+            WarpSource::Gradient($s) => $body,
+            WarpSource::Ridge($s) => $body,
+            WarpSource::Turbulence($s) => $body,
+            WarpSource::Billow($s) => $body,
+            WarpSource::Cellular($s) => $body,
+        }
+    };
+}
+
+impl From<FbmSettings> for WarpSource {
+    fn from(settings: FbmSettings) -> WarpSource {
+        WarpSource::Fbm(settings)
+    }
+}
+
+impl From<GradientSettings> for WarpSource {
+    fn from(settings: GradientSettings) -> WarpSource {
+        WarpSource::Gradient(settings)
+    }
+}
+
+impl From<RidgeSettings> for WarpSource {
+    fn from(settings: RidgeSettings) -> WarpSource {
+        WarpSource::Ridge(settings)
+    }
+}
+
+impl From<TurbulenceSettings> for WarpSource {
+    fn from(settings: TurbulenceSettings) -> WarpSource {
+        WarpSource::Turbulence(settings)
+    }
+}
+
+impl From<BillowSettings> for WarpSource {
+    fn from(settings: BillowSettings) -> WarpSource {
+        WarpSource::Billow(settings)
+    }
+}
+
+impl From<CellularSettings> for WarpSource {
+    fn from(settings: CellularSettings) -> WarpSource {
+        WarpSource::Cellular(settings)
+    }
+}
+
+impl WarpSource {
+    fn freqs(&self) -> [f32; 4] {
+        let [x, y, z] = with_source!(self, s => [s.get_freq_x(), s.get_freq_y(), s.get_freq_z()]);
+        let w = match self {
+            WarpSource::Fbm(s) => s.get_freq_w(),
+            WarpSource::Gradient(s) => s.get_freq_w(),
+            WarpSource::Ridge(s) => s.get_freq_w(),
+            WarpSource::Turbulence(s) => s.get_freq_w(),
+            WarpSource::Billow(s) => s.get_freq_w(),
+            // `validate` keeps cellular noise out of 4d warps, nothing reads its w frequency
+            WarpSource::Cellular(_) => 1.0,
+        };
+        [x, y, z, w]
+    }
+
+    fn bounds(&self) -> (f32, f32) {
+        with_source!(self, s => s.bounds())
+    }
+
+    fn validate(&self, dim: usize) -> Result<(), NoiseError> {
+        NoiseType::from(*self).validate_dim(dim)?;
+        with_source!(self, s => s.validate())
+    }
+
+    /// Takes over the dimensions of the `DomainWarpSettings` it is part of, so its bounds and
+    /// validation see the dimension count it is sampled in
+    fn with_dimensions(&mut self, dim: NoiseDimensions) {
+        with_source!(self, s => s.dim = dim)
+    }
+
+    fn reseed(&mut self, seed: u64, seed_mode: SeedMode) {
+        with_source!(self, s => match seed_mode {
+            SeedMode::Mixed => {
+                s.with_seed(seed);
+            }
+            SeedMode::Legacy => {
+                s.with_legacy_seed(seed as i32);
+            }
+        })
+    }
+
+    /// Sources without 4d noise fail here like they fail `validate`
+    #[inline(always)]
+    fn sample_4d_32<S: Simd>(
+        &self,
+        x: S::Vf32,
+        y: S::Vf32,
+        z: S::Vf32,
+        w: S::Vf32,
+    ) -> Result<S::Vf32, NoiseError> {
+        match self {
+            WarpSource::Fbm(s) => <_ as Sample4d32<S>>::sample_4d(s, x, y, z, w),
+            WarpSource::Gradient(s) => <_ as Sample4d32<S>>::sample_4d(s, x, y, z, w),
+            WarpSource::Ridge(s) => <_ as Sample4d32<S>>::sample_4d(s, x, y, z, w),
+            WarpSource::Turbulence(s) => <_ as Sample4d32<S>>::sample_4d(s, x, y, z, w),
+            WarpSource::Billow(s) => <_ as Sample4d32<S>>::sample_4d(s, x, y, z, w),
+            WarpSource::Cellular(_) => Err(NoiseError::UnsupportedDimension { dim: 4 }),
+        }
+    }
+
+    /// Sources without 4d noise fail here like they fail `validate`
+    #[inline(always)]
+    fn sample_4d_64<S: Simd>(
+        &self,
+        x: S::Vf64,
+        y: S::Vf64,
+        z: S::Vf64,
+        w: S::Vf64,
+    ) -> Result<S::Vf64, NoiseError> {
+        match self {
+            WarpSource::Fbm(s) => <_ as Sample4d64<S>>::sample_4d(s, x, y, z, w),
+            WarpSource::Gradient(s) => <_ as Sample4d64<S>>::sample_4d(s, x, y, z, w),
+            WarpSource::Ridge(s) => <_ as Sample4d64<S>>::sample_4d(s, x, y, z, w),
+            WarpSource::Turbulence(s) => <_ as Sample4d64<S>>::sample_4d(s, x, y, z, w),
+            WarpSource::Billow(s) => <_ as Sample4d64<S>>::sample_4d(s, x, y, z, w),
+            WarpSource::Cellular(_) => Err(NoiseError::UnsupportedDimension { dim: 4 }),
+        }
+    }
+}
+
+/// Samples an inner noise at coordinates displaced by a warp noise.
+///
+/// The block coordinates are multiplied by the frequency of these settings, 1.0 by default,
+/// moved by up to `amplitude` along every axis, then multiplied by the frequency of the inner
+/// noise. The inner and warp settings take over the dimensions and seed of these settings, so
+/// their own size, offset and seed are ignored, whichever order the setters are called in.
+#[derive(Copy, Clone)]
+pub struct DomainWarpSettings {
+    dim: NoiseDimensions,
+    pub freq_x: f32,
+    pub freq_y: f32,
+    pub freq_z: f32,
+    pub freq_w: f32,
+    pub inner: WarpSource,
+    pub warp: WarpSource,
+    /// Largest distance the first octave moves a coordinate, before the inner frequency
+    pub amplitude: f32,
+    pub lacunarity: f32,
+    pub gain: f32,
+    pub octaves: u8,
+    pub mode: WarpMode,
+}
+
+impl DimensionalBeing for DomainWarpSettings {
+    fn get_dimensions(&self) -> NoiseDimensions {
+        return self.dim;
+    }
+}
+
+impl Settings for DomainWarpSettings {
+    fn default(dim: NoiseDimensions) -> DomainWarpSettings {
+        let mut settings = DomainWarpSettings {
+            dim,
+            freq_x: 1.0,
+            freq_y: 1.0,
+            freq_z: 1.0,
+            freq_w: 1.0,
+            inner: GradientSettings::default(dim).into(),
+            warp: GradientSettings::default(dim).into(),
+            amplitude: 30.0,
+            lacunarity: 2.0,
+            gain: 0.5,
+            octaves: 1,
+            mode: WarpMode::Independent,
+        };
+        settings.reseed();
+        settings
+    }
+
+    dimension_methods!(Settings, then reseed);
+
+    fn with_freq(&mut self, freq: f32) -> &mut DomainWarpSettings {
+        self.freq_x = freq;
+        self.freq_y = freq;
+        self.freq_z = freq;
+        self.freq_w = freq;
+        self
+    }
+
+    fn with_freq_2d(&mut self, freq_x: f32, freq_y: f32) -> &mut DomainWarpSettings {
+        self.freq_x = freq_x;
+        self.freq_y = freq_y;
+        self
+    }
+
+    fn with_freq_3d(&mut self, freq_x: f32, freq_y: f32, freq_z: f32) -> &mut DomainWarpSettings {
+        self.freq_x = freq_x;
+        self.freq_y = freq_y;
+        self.freq_z = freq_z;
+        self
+    }
+
+    fn get_freq_x(&self) -> f32 {
+        self.freq_x
+    }
+
+    fn get_freq_y(&self) -> f32 {
+        self.freq_y
+    }
+
+    fn get_freq_z(&self) -> f32 {
+        self.freq_z
+    }
+
+    fn bounds(&self) -> (f32, f32) {
+        // Warping only moves the coordinates, the values are still the inner noise's
+        self.inner.bounds()
+    }
+
+    fn wrap(self) -> Result<NoiseType, NoiseError> {
+        self.validate()?;
+        Ok(NoiseType::DomainWarp(self))
+    }
+
+    fn validate(&self) -> Result<(), NoiseError> {
+        self.dim.validate()?;
+        validate_frequency(&[self.freq_x, self.freq_y, self.freq_z, self.freq_w])?;
+        if self.octaves == 0 {
+            return Err(NoiseError::ZeroOctaves);
+        }
+        validate_parameter(&[self.amplitude, self.lacunarity, self.gain])?;
+        self.inner.validate(self.dim.dim)?;
+        self.warp.validate(self.dim.dim)
+    }
+
+    unsafe fn generate(self, noise: *mut f32) -> Result<(f32, f32), NoiseError> {
+        self.validate()?;
+        let d = self.dim.dim;
+        match d {
+            1 => Ok(get_1d_noise(&NoiseType::DomainWarp(self), noise)),
+            2 => Ok(get_2d_noise(&NoiseType::DomainWarp(self), noise)),
+            3 => Ok(get_3d_noise(&NoiseType::DomainWarp(self), noise)),
+            4 => get_4d_noise(&NoiseType::DomainWarp(self), noise),
+            _ => Err(NoiseError::UnsupportedDimension { dim: d }),
+        }
+    }
+
+    unsafe fn generate_scaled(
+        self,
+        min: f32,
+        max: f32,
+        noise: *mut f32,
+    ) -> Result<(), NoiseError> {
+        let d = self.dim.dim;
+        let mut new_self = self;
+        new_self.dim.min = min;
+        new_self.dim.max = max;
+        new_self.validate()?;
+        match d {
+            1 => get_1d_scaled_noise(&NoiseType::DomainWarp(new_self), noise),
+            2 => get_2d_scaled_noise(&NoiseType::DomainWarp(new_self), noise),
+            3 => get_3d_scaled_noise(&NoiseType::DomainWarp(new_self), noise),
+            4 => get_4d_scaled_noise(&NoiseType::DomainWarp(new_self), noise)?,
+            _ => return Err(NoiseError::UnsupportedDimension { dim: d }),
+        }
+        Ok(())
+    }
+
+    fn generate_with_derivatives_into(
+        self,
+        _noise: &mut [f32],
+        _derivatives: &mut [f32],
+        _layout: ChannelLayout,
+    ) -> Result<(f32, f32), NoiseError> {
+        Err(NoiseError::DerivativesUnsupported)
+    }
+
+}
+
+impl Settings4d for DomainWarpSettings {
+    dimension_methods!(Settings4d);
+
+    fn with_freq_4d(
+        &mut self,
+        freq_x: f32,
+        freq_y: f32,
+        freq_z: f32,
+        freq_w: f32,
+    ) -> &mut DomainWarpSettings {
+        self.freq_x = freq_x;
+        self.freq_y = freq_y;
+        self.freq_z = freq_z;
+        self.freq_w = freq_w;
+        self
+    }
+
+    fn get_freq_w(&self) -> f32 {
+        self.freq_w
+    }
+}
+
+impl<S: Simd> Sample32<S> for DomainWarpSettings {
+    #[inline(always)]
+    fn sample_1d(&self, x: S::Vf32) -> S::Vf32 {
+        let Ok(n) = self.sample_warped_32::<S, 1, Infallible>([x], |source, [x]| {
+            Ok(with_source!(source, s => <_ as Sample32<S>>::sample_1d(s, x)))
+        });
+        n
+    }
+
+    #[inline(always)]
+    fn sample_2d(&self, x: S::Vf32, y: S::Vf32) -> S::Vf32 {
+        let Ok(n) = self.sample_warped_32::<S, 2, Infallible>([x, y], |source, [x, y]| {
+            Ok(with_source!(source, s => <_ as Sample32<S>>::sample_2d(s, x, y)))
+        });
+        n
+    }
+
+    #[inline(always)]
+    fn sample_3d(&self, x: S::Vf32, y: S::Vf32, z: S::Vf32) -> S::Vf32 {
+        let Ok(n) = self.sample_warped_32::<S, 3, Infallible>([x, y, z], |source, [x, y, z]| {
+            Ok(with_source!(source, s => <_ as Sample32<S>>::sample_3d(s, x, y, z)))
+        });
+        n
+    }
+}
+
+impl<S: Simd> Sample4d32<S> for DomainWarpSettings {
+    #[inline(always)]
+    fn sample_4d(
+        &self,
+        x: S::Vf32,
+        y: S::Vf32,
+        z: S::Vf32,
+        w: S::Vf32,
+    ) -> Result<S::Vf32, NoiseError> {
+        self.sample_warped_32::<S, 4, NoiseError>([x, y, z, w], |source, [x, y, z, w]| {
+            source.sample_4d_32::<S>(x, y, z, w)
+        })
+    }
+}
+
+impl<S: Simd> Sample64<S> for DomainWarpSettings {
+    #[inline(always)]
+    fn sample_1d(&self, x: S::Vf64) -> S::Vf64 {
+        let Ok(n) = self.sample_warped_64::<S, 1, Infallible>([x], |source, [x]| {
+            Ok(with_source!(source, s => <_ as Sample64<S>>::sample_1d(s, x)))
+        });
+        n
+    }
+
+    #[inline(always)]
+    fn sample_2d(&self, x: S::Vf64, y: S::Vf64) -> S::Vf64 {
+        let Ok(n) = self.sample_warped_64::<S, 2, Infallible>([x, y], |source, [x, y]| {
+            Ok(with_source!(source, s => <_ as Sample64<S>>::sample_2d(s, x, y)))
+        });
+        n
+    }
+
+    #[inline(always)]
+    fn sample_3d(&self, x: S::Vf64, y: S::Vf64, z: S::Vf64) -> S::Vf64 {
+        let Ok(n) = self.sample_warped_64::<S, 3, Infallible>([x, y, z], |source, [x, y, z]| {
+            Ok(with_source!(source, s => <_ as Sample64<S>>::sample_3d(s, x, y, z)))
+        });
+        n
+    }
+}
+
+impl<S: Simd> Sample4d64<S> for DomainWarpSettings {
+    #[inline(always)]
+    fn sample_4d(
+        &self,
+        x: S::Vf64,
+        y: S::Vf64,
+        z: S::Vf64,
+        w: S::Vf64,
+    ) -> Result<S::Vf64, NoiseError> {
+        self.sample_warped_64::<S, 4, NoiseError>([x, y, z, w], |source, [x, y, z, w]| {
+            source.sample_4d_64::<S>(x, y, z, w)
+        })
+    }
+}
+
+impl DomainWarpSettings {
+    /// The noise that is sampled at the warped coordinates
+    pub fn with_inner(&mut self, inner: impl Into<WarpSource>) -> &mut DomainWarpSettings {
+        self.inner = inner.into();
+        self.inner.with_dimensions(self.dim);
+        self.reseed();
+        self
+    }
+
+    /// The noise the displacement along every axis is read from
+    pub fn with_warp(&mut self, warp: impl Into<WarpSource>) -> &mut DomainWarpSettings {
+        self.warp = warp.into();
+        self.warp.with_dimensions(self.dim);
+        self.reseed();
+        self
+    }
+
+    pub fn with_amplitude(&mut self, amplitude: f32) -> &mut DomainWarpSettings {
+        self.amplitude = amplitude;
+        self
+    }
+
+    /// Layers the warp noise `octaves` times, every octave at `lacunarity` times the frequency
+    /// and `gain` times the amplitude of the one before. A single octave is a plain warp.
+    pub fn with_fractal(
+        &mut self,
+        octaves: u8,
+        lacunarity: f32,
+        gain: f32,
+        mode: WarpMode,
+    ) -> &mut DomainWarpSettings {
+        self.octaves = octaves;
+        self.lacunarity = lacunarity;
+        self.gain = gain;
+        self.mode = mode;
+        self
+    }
+
+    /// Seeds the inner noise with the seed of these settings and the warp noise with the next
+    /// seed, so the two are unrelated
+    fn reseed(&mut self) {
+        let (seed, seed_mode) = (self.dim.seed, self.dim.seed_mode);
+        self.inner.reseed(seed, seed_mode);
+        self.warp.reseed(seed.wrapping_add(1), seed_mode);
+    }
+
+    /// Warps `p` by the warp noise and samples the inner noise there, `sample` samples either
+    /// of them in `D` dimensions
+    #[inline(always)]
+    fn sample_warped_32<S: Simd, const D: usize, E>(
+        &self,
+        p: [S::Vf32; D],
+        sample: impl Fn(&WarpSource, [S::Vf32; D]) -> Result<S::Vf32, E>,
+    ) -> Result<S::Vf32, E> {
+        let warp_freq = self.warp.freqs();
+        let p = warp_32::<S, D, E>(
+            p,
+            core::array::from_fn(|i| S::Vf32::set1(warp_freq[i])),
+            self.amplitude,
+            self.lacunarity,
+            self.gain,
+            self.octaves,
+            self.mode,
+            |q| sample(&self.warp, q),
+        )?;
+        let inner_freq = self.inner.freqs();
+        let p = core::array::from_fn(|i| p[i] * S::Vf32::set1(inner_freq[i]));
+        sample(&self.inner, p)
+    }
+
+    /// Like `sample_warped_32`, in double precision
+    #[inline(always)]
+    fn sample_warped_64<S: Simd, const D: usize, E>(
+        &self,
+        p: [S::Vf64; D],
+        sample: impl Fn(&WarpSource, [S::Vf64; D]) -> Result<S::Vf64, E>,
+    ) -> Result<S::Vf64, E> {
+        let warp_freq = self.warp.freqs();
+        let p = warp_64::<S, D, E>(
+            p,
+            core::array::from_fn(|i| S::Vf64::set1(warp_freq[i].into())),
+            self.amplitude.into(),
+            self.lacunarity.into(),
+            self.gain.into(),
+            self.octaves,
+            self.mode,
+            |q| sample(&self.warp, q),
+        )?;
+        let inner_freq = self.inner.freqs();
+        let p = core::array::from_fn(|i| p[i] * S::Vf64::set1(inner_freq[i].into()));
+        sample(&self.inner, p)
+    }
+}
@@ -1,27 +1,51 @@
 use simdeez::prelude::*;
 
 use crate::dimensional_being::DimensionalBeing;
-use crate::noise::fbm_32::{fbm_1d, fbm_2d, fbm_3d};
+use crate::noise::fbm_32::{
+    fbm_1d, fbm_1d_deriv, fbm_2d, fbm_2d_deriv, fbm_3d, fbm_3d_deriv, fbm_4d, fbm_4d_deriv,
+    fbm_periodic, fbm_periodic_deriv,
+};
+use crate::noise::lattice::Lattice;
+use crate::noise::octaves::OctaveOptions;
 use crate::noise::fbm_64::{
-    fbm_1d as fbm_1d_f64, fbm_2d as fbm_2d_f64, fbm_3d as fbm_3d_f64,
+    fbm_1d as fbm_1d_f64, fbm_2d as fbm_2d_f64,
+    fbm_3d as fbm_3d_f64, fbm_4d as fbm_4d_f64,
 };
-pub use crate::noise_dimensions::NoiseDimensions;
-use crate::noise_helpers_32::Sample32;
-use crate::noise_helpers_64::Sample64;
+pub use crate::noise_dimensions::{NoiseDimensions, ScaleMode, SeedMode};
+use crate::noise_helpers_32::{Sample32, Sample4d32, SampleDeriv32};
+use crate::noise_helpers_64::{Sample4d64, Sample64};
 pub use crate::noise_type::NoiseType;
-use crate::{get_1d_noise, get_1d_scaled_noise, get_2d_noise, get_2d_scaled_noise, get_3d_noise, get_3d_scaled_noise};
+use crate::{get_noise_deriv, ChannelLayout, NoiseError};
+use crate::{
+    get_1d_noise, get_1d_scaled_noise, get_2d_noise, get_2d_scaled_noise, get_3d_noise,
+    get_3d_scaled_noise, get_4d_noise, get_4d_scaled_noise,
+};
 
-use super::{Settings, SimplexSettings};
+use super::{
+    amplitude_range, lattice_periods, split_octaves, validate_fractal, validate_frequency,
+    validate_lattice, validate_period, LatticeSettings, PeriodicSettings, Settings, Settings4d,
+    SimplexSettings,
+};
 
+/// Fractal brownian motion, octaves of simplex noise summed together.
+///
+/// The default lacunarity of 0.5 and gain of 2.0 are kept so old noise stays the same, the
+/// usual choice is a lacunarity of 2.0 and a gain of 0.5.
 #[derive(Copy, Clone)]
 pub struct FbmSettings {
-    dim: NoiseDimensions,
+    pub(crate) dim: NoiseDimensions,
     pub freq_x: f32,
     pub freq_y: f32,
     pub freq_z: f32,
+    pub freq_w: f32,
     pub lacunarity: f32,
     pub gain: f32,
     pub octaves: u8,
+    pub octave_options: OctaveOptions,
+    /// Repeat distances along x, y and z, 0 for the axes that don't repeat
+    pub period_x: f32,
+    pub period_y: f32,
+    pub period_z: f32,
 }
 
 impl DimensionalBeing for FbmSettings {
@@ -37,20 +61,24 @@ impl Settings for FbmSettings {
             freq_x: 0.02,
             freq_y: 0.02,
             freq_z: 0.02,
+            freq_w: 0.02,
             lacunarity: 0.5,
             gain: 2.0,
             octaves: 3,
+            octave_options: OctaveOptions::default(),
+            period_x: 0.0,
+            period_y: 0.0,
+            period_z: 0.0,
         }
     }
-    fn with_seed(&mut self, seed: i32) -> &mut FbmSettings {
-        self.dim.seed = seed;
-        self
-    }
+
+    dimension_methods!(Settings);
 
     fn with_freq(&mut self, freq: f32) -> &mut FbmSettings {
         self.freq_x = freq;
         self.freq_y = freq;
         self.freq_z = freq;
+        self.freq_w = freq;
         self
     }
 
@@ -79,36 +107,109 @@ impl Settings for FbmSettings {
         self.freq_z
     }
 
-    fn wrap(self) -> NoiseType {
-        self.validate();
-        NoiseType::Fbm(self)
+    fn bounds(&self) -> (f32, f32) {
+        if self.octave_options.normalize {
+            return (-1.0, 1.0);
+        }
+        // Unweighted 1d fbm adds every octave at full strength
+        if self.dim.dim == 1 && !self.octave_options.weighted_1d {
+            let octaves = self.octaves as f32 + self.octave_options.fraction;
+            return (-octaves, octaves);
+        }
+        let (negative, positive) =
+            amplitude_range(self.gain, self.octaves, self.octave_options.fraction);
+        let amplitude = positive - negative;
+        (-amplitude, amplitude)
     }
 
-    fn validate(&self) {
-        //todo
+    fn wrap(self) -> Result<NoiseType, NoiseError> {
+        self.validate()?;
+        Ok(NoiseType::Fbm(self))
     }
 
-    fn generate(self, noise: *mut f32) -> (f32, f32) {
+    fn validate(&self) -> Result<(), NoiseError> {
+        self.dim.validate()?;
+        validate_frequency(&[self.freq_x, self.freq_y, self.freq_z, self.freq_w])?;
+        validate_lattice(self.dim.dim, self.octave_options.lattice)?;
+        validate_fractal(self.lacunarity, self.gain, self.octaves, &self.octave_options)?;
+        let sampled = usize::from(self.octaves) + usize::from(self.octave_options.fraction > 0.0);
+        validate_period(
+            self.dim.dim,
+            self.periods(),
+            self.freqs(),
+            self.lacunarity,
+            sampled,
+            self.octave_options.lattice,
+        )
+    }
+
+    unsafe fn generate(self, noise: *mut f32) -> Result<(f32, f32), NoiseError> {
+        self.validate()?;
         let d = self.dim.dim;
         match d {
-            1 => get_1d_noise(&NoiseType::Fbm(self), noise),
-            2 => get_2d_noise(&NoiseType::Fbm(self), noise),
-            3 => get_3d_noise(&NoiseType::Fbm(self), noise),
-            _ => panic!("not implemented"),
+            1 => Ok(get_1d_noise(&NoiseType::Fbm(self), noise)),
+            2 => Ok(get_2d_noise(&NoiseType::Fbm(self), noise)),
+            3 => Ok(get_3d_noise(&NoiseType::Fbm(self), noise)),
+            4 => get_4d_noise(&NoiseType::Fbm(self), noise),
+            _ => Err(NoiseError::UnsupportedDimension { dim: d }),
         }
     }
 
-    fn generate_scaled(self, min: f32, max: f32, noise: *mut f32) {
+    unsafe fn generate_scaled(
+        self,
+        min: f32,
+        max: f32,
+        noise: *mut f32,
+    ) -> Result<(), NoiseError> {
         let d = self.dim.dim;
         let mut new_self = self;
         new_self.dim.min = min;
         new_self.dim.max = max;
+        new_self.validate()?;
         match d {
             1 => get_1d_scaled_noise(&NoiseType::Fbm(new_self), noise),
             2 => get_2d_scaled_noise(&NoiseType::Fbm(new_self), noise),
             3 => get_3d_scaled_noise(&NoiseType::Fbm(new_self), noise),
-            _ => panic!("not implemented"),
+            4 => get_4d_scaled_noise(&NoiseType::Fbm(new_self), noise)?,
+            _ => return Err(NoiseError::UnsupportedDimension { dim: d }),
         }
+        Ok(())
+    }
+
+    fn generate_with_derivatives_into(
+        self,
+        noise: &mut [f32],
+        derivatives: &mut [f32],
+        layout: ChannelLayout,
+    ) -> Result<(f32, f32), NoiseError> {
+        self.validate()?;
+        self.dim.check_buffer(noise.len())?;
+        self.dim.check_channel_buffer(derivatives.len(), self.dim.dim)?;
+        let (noise, derivatives) = (noise.as_mut_ptr(), derivatives.as_mut_ptr());
+        unsafe { get_noise_deriv(&NoiseType::Fbm(self), noise, derivatives, layout) }
+    }
+
+}
+
+impl Settings4d for FbmSettings {
+    dimension_methods!(Settings4d);
+
+    fn with_freq_4d(
+        &mut self,
+        freq_x: f32,
+        freq_y: f32,
+        freq_z: f32,
+        freq_w: f32,
+    ) -> &mut FbmSettings {
+        self.freq_x = freq_x;
+        self.freq_y = freq_y;
+        self.freq_z = freq_z;
+        self.freq_w = freq_w;
+        self
+    }
+
+    fn get_freq_w(&self) -> f32 {
+        self.freq_w
     }
 }
 
@@ -125,6 +226,12 @@ impl SimplexSettings for FbmSettings {
 
     fn with_octaves(&mut self, octaves: u8) -> &mut FbmSettings {
         self.octaves = octaves;
+        self.octave_options.fraction = 0.0;
+        self
+    }
+
+    fn with_octaves_f32(&mut self, octaves: f32) -> &mut FbmSettings {
+        (self.octaves, self.octave_options.fraction) = split_octaves(octaves);
         self
     }
 }
@@ -137,24 +244,48 @@ impl<S: Simd> Sample32<S> for FbmSettings {
             S::Vf32::set1(self.lacunarity),
             S::Vf32::set1(self.gain),
             self.octaves,
-            self.dim.seed,
+            self.dim.seed_32(),
+            self.octave_options.for_seed_mode(self.dim.seed_mode),
         )
     }
 
     #[inline(always)]
     fn sample_2d(&self, x: S::Vf32, y: S::Vf32) -> S::Vf32 {
+        if self.is_periodic() {
+            return fbm_periodic::<S, 2>(
+                [x, y],
+                lattice_periods::<S, 2>(self.periods(), self.freqs()),
+                S::Vf32::set1(self.lacunarity),
+                S::Vf32::set1(self.gain),
+                self.octaves,
+                self.dim.seed_32(),
+                self.octave_options.for_seed_mode(self.dim.seed_mode),
+            );
+        }
         fbm_2d::<S>(
             x,
             y,
             S::Vf32::set1(self.lacunarity),
             S::Vf32::set1(self.gain),
             self.octaves,
-            self.dim.seed,
+            self.dim.seed_32(),
+            self.octave_options.for_seed_mode(self.dim.seed_mode),
         )
     }
 
     #[inline(always)]
     fn sample_3d(&self, x: S::Vf32, y: S::Vf32, z: S::Vf32) -> S::Vf32 {
+        if self.is_periodic() {
+            return fbm_periodic::<S, 3>(
+                [x, y, z],
+                lattice_periods::<S, 3>(self.periods(), self.freqs()),
+                S::Vf32::set1(self.lacunarity),
+                S::Vf32::set1(self.gain),
+                self.octaves,
+                self.dim.seed_32(),
+                self.octave_options.for_seed_mode(self.dim.seed_mode),
+            );
+        }
         fbm_3d::<S>(
             x,
             y,
@@ -162,9 +293,122 @@ impl<S: Simd> Sample32<S> for FbmSettings {
             S::Vf32::set1(self.lacunarity),
             S::Vf32::set1(self.gain),
             self.octaves,
-            self.dim.seed,
+            self.dim.seed_32(),
+            self.octave_options.for_seed_mode(self.dim.seed_mode),
+        )
+    }
+}
+
+impl<S: Simd> Sample4d32<S> for FbmSettings {
+    #[inline(always)]
+    fn sample_4d(
+        &self,
+        x: S::Vf32,
+        y: S::Vf32,
+        z: S::Vf32,
+        w: S::Vf32,
+    ) -> Result<S::Vf32, NoiseError> {
+        Ok(fbm_4d::<S>(
+            x,
+            y,
+            z,
+            w,
+            S::Vf32::set1(self.lacunarity),
+            S::Vf32::set1(self.gain),
+            self.octaves,
+            self.dim.seed_32(),
+            self.octave_options
+                .for_seed_mode(self.dim.seed_mode)
+                .for_4d()?,
+        ))
+    }
+}
+
+impl<S: Simd> SampleDeriv32<S> for FbmSettings {
+    #[inline(always)]
+    fn sample_1d_deriv(&self, x: S::Vf32) -> (S::Vf32, [S::Vf32; 1]) {
+        let (value, dx) = fbm_1d_deriv::<S>(
+            x,
+            S::Vf32::set1(self.lacunarity),
+            S::Vf32::set1(self.gain),
+            self.octaves,
+            self.dim.seed_32(),
+            self.octave_options.for_seed_mode(self.dim.seed_mode),
+        );
+        (value, [dx])
+    }
+
+    #[inline(always)]
+    fn sample_2d_deriv(&self, x: S::Vf32, y: S::Vf32) -> (S::Vf32, [S::Vf32; 2]) {
+        if self.is_periodic() {
+            return fbm_periodic_deriv::<S, 2>(
+                [x, y],
+                lattice_periods::<S, 2>(self.periods(), self.freqs()),
+                S::Vf32::set1(self.lacunarity),
+                S::Vf32::set1(self.gain),
+                self.octaves,
+                self.dim.seed_32(),
+                self.octave_options.for_seed_mode(self.dim.seed_mode),
+            );
+        }
+        fbm_2d_deriv::<S>(
+            x,
+            y,
+            S::Vf32::set1(self.lacunarity),
+            S::Vf32::set1(self.gain),
+            self.octaves,
+            self.dim.seed_32(),
+            self.octave_options.for_seed_mode(self.dim.seed_mode),
+        )
+    }
+
+    #[inline(always)]
+    fn sample_3d_deriv(&self, x: S::Vf32, y: S::Vf32, z: S::Vf32) -> (S::Vf32, [S::Vf32; 3]) {
+        if self.is_periodic() {
+            return fbm_periodic_deriv::<S, 3>(
+                [x, y, z],
+                lattice_periods::<S, 3>(self.periods(), self.freqs()),
+                S::Vf32::set1(self.lacunarity),
+                S::Vf32::set1(self.gain),
+                self.octaves,
+                self.dim.seed_32(),
+                self.octave_options.for_seed_mode(self.dim.seed_mode),
+            );
+        }
+        fbm_3d_deriv::<S>(
+            x,
+            y,
+            z,
+            S::Vf32::set1(self.lacunarity),
+            S::Vf32::set1(self.gain),
+            self.octaves,
+            self.dim.seed_32(),
+            self.octave_options.for_seed_mode(self.dim.seed_mode),
         )
     }
+
+    #[inline(always)]
+    fn sample_4d_deriv(
+        &self,
+        x: S::Vf32,
+        y: S::Vf32,
+        z: S::Vf32,
+        w: S::Vf32,
+    ) -> Result<(S::Vf32, [S::Vf32; 4]), NoiseError> {
+        Ok(fbm_4d_deriv::<S>(
+            x,
+            y,
+            z,
+            w,
+            S::Vf32::set1(self.lacunarity),
+            S::Vf32::set1(self.gain),
+            self.octaves,
+            self.dim.seed_32(),
+            self.octave_options
+                .for_seed_mode(self.dim.seed_mode)
+                .for_4d()?,
+        ))
+    }
 }
 
 impl<S: Simd> Sample64<S> for FbmSettings {
@@ -175,7 +419,8 @@ impl<S: Simd> Sample64<S> for FbmSettings {
             S::Vf64::set1(self.lacunarity.into()),
             S::Vf64::set1(self.gain.into()),
             self.octaves,
-            self.dim.seed.into(),
+            self.dim.seed_64(),
+            self.octave_options.for_seed_mode(self.dim.seed_mode),
         )
     }
 
@@ -187,7 +432,8 @@ impl<S: Simd> Sample64<S> for FbmSettings {
             S::Vf64::set1(self.lacunarity.into()),
             S::Vf64::set1(self.gain.into()),
             self.octaves,
-            self.dim.seed.into(),
+            self.dim.seed_64(),
+            self.octave_options.for_seed_mode(self.dim.seed_mode),
         )
     }
 
@@ -200,9 +446,95 @@ impl<S: Simd> Sample64<S> for FbmSettings {
             S::Vf64::set1(self.lacunarity.into()),
             S::Vf64::set1(self.gain.into()),
             self.octaves,
-            self.dim.seed.into(),
+            self.dim.seed_64(),
+            self.octave_options.for_seed_mode(self.dim.seed_mode),
         )
     }
 }
 
-impl FbmSettings {}
+impl<S: Simd> Sample4d64<S> for FbmSettings {
+    #[inline(always)]
+    fn sample_4d(
+        &self,
+        x: S::Vf64,
+        y: S::Vf64,
+        z: S::Vf64,
+        w: S::Vf64,
+    ) -> Result<S::Vf64, NoiseError> {
+        Ok(fbm_4d_f64::<S>(
+            x,
+            y,
+            z,
+            w,
+            S::Vf64::set1(self.lacunarity.into()),
+            S::Vf64::set1(self.gain.into()),
+            self.octaves,
+            self.dim.seed_64(),
+            self.octave_options
+                .for_seed_mode(self.dim.seed_mode)
+                .for_4d()?,
+        ))
+    }
+}
+
+impl PeriodicSettings for FbmSettings {
+    fn with_period_2d(&mut self, period_x: f32, period_y: f32) -> &mut FbmSettings {
+        self.period_x = period_x;
+        self.period_y = period_y;
+        self
+    }
+
+    fn with_period_3d(&mut self, period_x: f32, period_y: f32, period_z: f32) -> &mut FbmSettings {
+        self.period_x = period_x;
+        self.period_y = period_y;
+        self.period_z = period_z;
+        self
+    }
+}
+
+impl LatticeSettings for FbmSettings {
+    fn with_lattice(&mut self, lattice: Lattice) -> &mut FbmSettings {
+        self.octave_options.lattice = lattice;
+        self
+    }
+}
+
+impl FbmSettings {
+    /// Gives every octave its own seed, so the octaves don't repeat each other's features
+    pub fn with_octave_seeds(&mut self, enabled: bool) -> &mut FbmSettings {
+        self.octave_options.seed_per_octave = enabled;
+        self
+    }
+
+    /// Shifts every octave by a fixed offset, so they don't all line up at the origin
+    pub fn with_octave_offsets(&mut self, enabled: bool) -> &mut FbmSettings {
+        self.octave_options.offset_per_octave = enabled;
+        self
+    }
+
+    /// Divides by the summed octave weights, keeping the output in [-1, 1] whatever the
+    /// octave count
+    pub fn with_normalized(&mut self, enabled: bool) -> &mut FbmSettings {
+        self.octave_options.normalize = enabled;
+        self
+    }
+
+    /// Weighs the octaves of 1d noise by the gain like the other dimensions, instead of adding
+    /// every octave at full strength
+    pub fn with_weighted_1d(&mut self, enabled: bool) -> &mut FbmSettings {
+        self.octave_options.weighted_1d = enabled;
+        self
+    }
+
+    fn periods(&self) -> [f32; 3] {
+        [self.period_x, self.period_y, self.period_z]
+    }
+
+    fn freqs(&self) -> [f32; 3] {
+        [self.freq_x, self.freq_y, self.freq_z]
+    }
+
+    pub(crate) fn is_periodic(&self) -> bool {
+        self.periods() != [0.0; 3]
+    }
+}
@@ -1,25 +1,35 @@
 use simdeez::prelude::*;
 
 use crate::dimensional_being::DimensionalBeing;
-use crate::noise::simplex_32::{simplex_1d, simplex_2d, simplex_3d};
-use crate::noise::simplex_64::{
-    simplex_1d as simplex_1d_f64, simplex_2d as simplex_2d_f64, simplex_3d as simplex_3d_f64
-    ,
-};
-pub use crate::noise_dimensions::NoiseDimensions;
-use crate::noise_helpers_32::Sample32;
-use crate::noise_helpers_64::Sample64;
+use crate::noise::lattice::{Lattice, Lattice4d};
+use crate::noise::simplex_32::{simplex_periodic, simplex_periodic_deriv};
+pub use crate::noise_dimensions::{NoiseDimensions, ScaleMode, SeedMode};
+use crate::noise_helpers_32::{Sample32, Sample4d32, SampleDeriv32};
+use crate::noise_helpers_64::{Sample4d64, Sample64};
 pub use crate::noise_type::NoiseType;
-use crate::{get_1d_noise, get_1d_scaled_noise, get_2d_noise, get_2d_scaled_noise, get_3d_noise, get_3d_scaled_noise};
+use crate::{get_noise_deriv, ChannelLayout, NoiseError};
+use crate::{
+    get_1d_noise, get_1d_scaled_noise, get_2d_noise, get_2d_scaled_noise, get_3d_noise,
+    get_3d_scaled_noise, get_4d_noise, get_4d_scaled_noise,
+};
 
-use crate::settings::Settings;
+use crate::settings::{
+    lattice_periods, validate_frequency, validate_lattice, validate_period, LatticeSettings,
+    PeriodicSettings, Settings, Settings4d,
+};
 
 #[derive(Copy, Clone)]
 pub struct GradientSettings {
-    dim: NoiseDimensions,
+    pub(crate) dim: NoiseDimensions,
     pub freq_x: f32,
     pub freq_y: f32,
     pub freq_z: f32,
+    pub freq_w: f32,
+    /// Repeat distances along x, y and z, 0 for the axes that don't repeat
+    pub period_x: f32,
+    pub period_y: f32,
+    pub period_z: f32,
+    pub lattice: Lattice,
 }
 
 impl DimensionalBeing for GradientSettings {
@@ -35,19 +45,23 @@ impl Settings for GradientSettings {
             freq_x: 0.02,
             freq_y: 0.02,
             freq_z: 0.02,
+            freq_w: 0.02,
+            period_x: 0.0,
+            period_y: 0.0,
+            period_z: 0.0,
+            lattice: Lattice::Simplex,
         }
     }
 
+    dimension_methods!(Settings);
+
     #[inline(always)]
-    fn with_seed(&mut self, seed: i32) -> &mut GradientSettings {
-        self.dim.seed = seed;
-        self
-    }
 
     fn with_freq(&mut self, freq: f32) -> &mut GradientSettings {
         self.freq_x = freq;
         self.freq_y = freq;
         self.freq_z = freq;
+        self.freq_w = freq;
         self
     }
 
@@ -77,72 +91,242 @@ impl Settings for GradientSettings {
         self.freq_z
     }
 
-    fn wrap(self) -> NoiseType {
-        self.validate();
-        NoiseType::Gradient(self)
+    fn bounds(&self) -> (f32, f32) {
+        (-1.0, 1.0)
+    }
+
+    fn wrap(self) -> Result<NoiseType, NoiseError> {
+        self.validate()?;
+        Ok(NoiseType::Gradient(self))
     }
 
-    fn validate(&self) {
-        //todo
+    fn validate(&self) -> Result<(), NoiseError> {
+        self.dim.validate()?;
+        validate_frequency(&[self.freq_x, self.freq_y, self.freq_z, self.freq_w])?;
+        validate_lattice(self.dim.dim, self.lattice)?;
+        validate_period(self.dim.dim, self.periods(), self.freqs(), 1.0, 1, self.lattice)
     }
 
-    fn generate(self, noise: *mut f32) -> (f32, f32) {
+    unsafe fn generate(self, noise: *mut f32) -> Result<(f32, f32), NoiseError> {
+        self.validate()?;
         let d = self.dim.dim;
         match d {
-            1 => get_1d_noise(&NoiseType::Gradient(self), noise),
-            2 => get_2d_noise(&NoiseType::Gradient(self), noise),
-            3 => get_3d_noise(&NoiseType::Gradient(self), noise),
-            _ => panic!("not implemented"),
+            1 => Ok(get_1d_noise(&NoiseType::Gradient(self), noise)),
+            2 => Ok(get_2d_noise(&NoiseType::Gradient(self), noise)),
+            3 => Ok(get_3d_noise(&NoiseType::Gradient(self), noise)),
+            4 => get_4d_noise(&NoiseType::Gradient(self), noise),
+            _ => Err(NoiseError::UnsupportedDimension { dim: d }),
         }
     }
 
     #[inline(always)]
-    fn generate_scaled(self, min: f32, max: f32, noise: *mut f32) {
+    unsafe fn generate_scaled(
+        self,
+        min: f32,
+        max: f32,
+        noise: *mut f32,
+    ) -> Result<(), NoiseError> {
         let d = self.dim.dim;
         let mut new_self = self;
         new_self.dim.min = min;
         new_self.dim.max = max;
+        new_self.validate()?;
         match d {
             1 => get_1d_scaled_noise(&NoiseType::Gradient(new_self), noise),
             2 => get_2d_scaled_noise(&NoiseType::Gradient(new_self), noise),
             3 => get_3d_scaled_noise(&NoiseType::Gradient(new_self), noise),
-            _ => panic!("not implemented"),
+            4 => get_4d_scaled_noise(&NoiseType::Gradient(new_self), noise)?,
+            _ => return Err(NoiseError::UnsupportedDimension { dim: d }),
         }
+        Ok(())
+    }
+
+    fn generate_with_derivatives_into(
+        self,
+        noise: &mut [f32],
+        derivatives: &mut [f32],
+        layout: ChannelLayout,
+    ) -> Result<(f32, f32), NoiseError> {
+        self.validate()?;
+        self.dim.check_buffer(noise.len())?;
+        self.dim.check_channel_buffer(derivatives.len(), self.dim.dim)?;
+        let (noise, derivatives) = (noise.as_mut_ptr(), derivatives.as_mut_ptr());
+        unsafe { get_noise_deriv(&NoiseType::Gradient(self), noise, derivatives, layout) }
+    }
+
+}
+
+impl Settings4d for GradientSettings {
+    dimension_methods!(Settings4d);
+
+    fn with_freq_4d(
+        &mut self,
+        freq_x: f32,
+        freq_y: f32,
+        freq_z: f32,
+        freq_w: f32,
+    ) -> &mut GradientSettings {
+        self.freq_x = freq_x;
+        self.freq_y = freq_y;
+        self.freq_z = freq_z;
+        self.freq_w = freq_w;
+        self
+    }
+
+    fn get_freq_w(&self) -> f32 {
+        self.freq_w
     }
 }
 
 impl<S: Simd> Sample32<S> for GradientSettings {
     #[inline(always)]
     fn sample_1d(&self, x: S::Vf32) -> S::Vf32 {
-        simplex_1d::<S>(x, self.dim.seed)
+        self.sampled_lattice().noise_1d_32::<S>(x, self.dim.seed_32())
     }
 
     #[inline(always)]
     fn sample_2d(&self, x: S::Vf32, y: S::Vf32) -> S::Vf32 {
-        simplex_2d::<S>(x, y, self.dim.seed)
+        if self.is_periodic() {
+            let period = lattice_periods::<S, 2>(self.periods(), self.freqs());
+            return simplex_periodic::<S, 2>([x, y], period, self.dim.seed_32());
+        }
+        self.sampled_lattice().noise_2d_32::<S>(x, y, self.dim.seed_32())
     }
 
     #[inline(always)]
     fn sample_3d(&self, x: S::Vf32, y: S::Vf32, z: S::Vf32) -> S::Vf32 {
-        simplex_3d::<S>(x, y, z, self.dim.seed)
+        if self.is_periodic() {
+            let period = lattice_periods::<S, 3>(self.periods(), self.freqs());
+            return simplex_periodic::<S, 3>([x, y, z], period, self.dim.seed_32());
+        }
+        self.sampled_lattice().noise_3d_32::<S>(x, y, z, self.dim.seed_32())
+    }
+}
+
+impl<S: Simd> Sample4d32<S> for GradientSettings {
+    #[inline(always)]
+    fn sample_4d(
+        &self,
+        x: S::Vf32,
+        y: S::Vf32,
+        z: S::Vf32,
+        w: S::Vf32,
+    ) -> Result<S::Vf32, NoiseError> {
+        let lattice = Lattice4d::try_from(self.sampled_lattice())?;
+        Ok(lattice.noise_4d_32::<S>(x, y, z, w, self.dim.seed_32()))
+    }
+}
+
+impl<S: Simd> SampleDeriv32<S> for GradientSettings {
+    #[inline(always)]
+    fn sample_1d_deriv(&self, x: S::Vf32) -> (S::Vf32, [S::Vf32; 1]) {
+        let (value, dx) = self.sampled_lattice().noise_1d_deriv_32::<S>(x, self.dim.seed_32());
+        (value, [dx])
+    }
+
+    #[inline(always)]
+    fn sample_2d_deriv(&self, x: S::Vf32, y: S::Vf32) -> (S::Vf32, [S::Vf32; 2]) {
+        if self.is_periodic() {
+            let period = lattice_periods::<S, 2>(self.periods(), self.freqs());
+            return simplex_periodic_deriv::<S, 2>([x, y], period, self.dim.seed_32());
+        }
+        self.sampled_lattice().noise_2d_deriv_32::<S>(x, y, self.dim.seed_32())
+    }
+
+    #[inline(always)]
+    fn sample_3d_deriv(&self, x: S::Vf32, y: S::Vf32, z: S::Vf32) -> (S::Vf32, [S::Vf32; 3]) {
+        if self.is_periodic() {
+            let period = lattice_periods::<S, 3>(self.periods(), self.freqs());
+            return simplex_periodic_deriv::<S, 3>([x, y, z], period, self.dim.seed_32());
+        }
+        self.sampled_lattice().noise_3d_deriv_32::<S>(x, y, z, self.dim.seed_32())
+    }
+
+    #[inline(always)]
+    fn sample_4d_deriv(
+        &self,
+        x: S::Vf32,
+        y: S::Vf32,
+        z: S::Vf32,
+        w: S::Vf32,
+    ) -> Result<(S::Vf32, [S::Vf32; 4]), NoiseError> {
+        let lattice = Lattice4d::try_from(self.sampled_lattice())?;
+        Ok(lattice.noise_4d_deriv_32::<S>(x, y, z, w, self.dim.seed_32()))
     }
 }
 
 impl<S: Simd> Sample64<S> for GradientSettings {
     #[inline(always)]
     fn sample_1d(&self, x: S::Vf64) -> S::Vf64 {
-        simplex_1d_f64::<S>(x, self.dim.seed.into())
+        self.sampled_lattice().noise_1d_64::<S>(x, self.dim.seed_64())
     }
 
     #[inline(always)]
     fn sample_2d(&self, x: S::Vf64, y: S::Vf64) -> S::Vf64 {
-        simplex_2d_f64::<S>(x, y, self.dim.seed.into())
+        self.sampled_lattice().noise_2d_64::<S>(x, y, self.dim.seed_64())
     }
 
     #[inline(always)]
     fn sample_3d(&self, x: S::Vf64, y: S::Vf64, z: S::Vf64) -> S::Vf64 {
-        simplex_3d_f64::<S>(x, y, z, self.dim.seed.into())
+        self.sampled_lattice().noise_3d_64::<S>(x, y, z, self.dim.seed_64())
+    }
+}
+
+impl<S: Simd> Sample4d64<S> for GradientSettings {
+    #[inline(always)]
+    fn sample_4d(
+        &self,
+        x: S::Vf64,
+        y: S::Vf64,
+        z: S::Vf64,
+        w: S::Vf64,
+    ) -> Result<S::Vf64, NoiseError> {
+        let lattice = Lattice4d::try_from(self.sampled_lattice())?;
+        Ok(lattice.noise_4d_64::<S>(x, y, z, w, self.dim.seed_64()))
+    }
+}
+
+impl PeriodicSettings for GradientSettings {
+    fn with_period_2d(&mut self, period_x: f32, period_y: f32) -> &mut GradientSettings {
+        self.period_x = period_x;
+        self.period_y = period_y;
+        self
+    }
+
+    fn with_period_3d(
+        &mut self,
+        period_x: f32,
+        period_y: f32,
+        period_z: f32,
+    ) -> &mut GradientSettings {
+        self.period_x = period_x;
+        self.period_y = period_y;
+        self.period_z = period_z;
+        self
+    }
+}
+
+impl LatticeSettings for GradientSettings {
+    fn with_lattice(&mut self, lattice: Lattice) -> &mut GradientSettings {
+        self.lattice = lattice;
+        self
     }
 }
 
-impl GradientSettings {}
+impl GradientSettings {
+    fn periods(&self) -> [f32; 3] {
+        [self.period_x, self.period_y, self.period_z]
+    }
+
+    fn freqs(&self) -> [f32; 3] {
+        [self.freq_x, self.freq_y, self.freq_z]
+    }
+
+    pub(crate) fn is_periodic(&self) -> bool {
+        self.periods() != [0.0; 3]
+    }
+
+    fn sampled_lattice(&self) -> Lattice {
+        self.lattice.for_seed_mode(self.dim.seed_mode)
+    }
+}
@@ -1,38 +1,427 @@
-pub use crate::noise_dimensions::NoiseDimensions;
+pub use crate::noise_dimensions::{NoiseDimensions, ScaleMode};
 pub use crate::noise_type::NoiseType;
+use crate::noise::lattice::{Lattice, Lattice4d};
+use crate::noise::octaves::OctaveOptions;
+use crate::NoiseError;
+
+use simdeez::prelude::*;
 
 pub trait Settings {
     fn default(dim: NoiseDimensions) -> Self;
-    fn with_seed(&mut self, seed: i32) -> &mut Self;
+    /// Seeds the noise, the seed is hashed first so neighbouring seeds give unrelated noise
+    fn with_seed(&mut self, seed: u64) -> &mut Self;
+    /// Seeds the noise the way it was seeded before `with_seed` took a u64, use this to
+    /// reproduce noise generated with older versions
+    fn with_legacy_seed(&mut self, seed: i32) -> &mut Self;
     fn with_freq(&mut self, freq: f32) -> &mut Self;
     fn with_freq_2d(&mut self, freq_x: f32, freq_y: f32) -> &mut Self;
     fn with_freq_3d(&mut self, freq_x: f32, freq_y: f32, freq_z: f32) -> &mut Self;
+    fn with_scale_mode(&mut self, scale_mode: ScaleMode) -> &mut Self;
+
+    /// Sets the distance between neighbouring samples of a block, independent of the frequency.
+    /// Sample `i` along x lands on `(x + i * step_x) * freq_x`.
+    fn with_step(&mut self, step: f32) -> &mut Self;
+    fn with_step_2d(&mut self, step_x: f32, step_y: f32) -> &mut Self;
+    fn with_step_3d(&mut self, step_x: f32, step_y: f32, step_z: f32) -> &mut Self;
 
     fn get_freq_x(&self) -> f32;
     fn get_freq_y(&self) -> f32;
     fn get_freq_z(&self) -> f32;
 
+    /// The lowest and highest value these settings can produce, worked out from the settings
+    /// alone. Some bounds are loose, the noise rarely or never reaches them.
+    fn bounds(&self) -> (f32, f32);
+
     /// If you want to call noise functions by hand, call wrap on the settings
     /// to get back a NoiseType to call the noise functions with
-    fn wrap(self) -> NoiseType;
+    fn wrap(self) -> Result<NoiseType, NoiseError>;
 
     /// Generate a chunk of noise based on your settings, and the min and max value
     /// generated, so you can scale it as you wish
-    fn generate(self, noise: *mut f32) -> (f32, f32);
-    fn validate(&self);
+    ///
+    /// # Safety
+    /// `noise` must be valid for writes of `NoiseDimensions::len` values, `generate_into`
+    /// checks that for you.
+    unsafe fn generate(self, noise: *mut f32) -> Result<(f32, f32), NoiseError>;
+
+    /// Checks the dimensions and every setting, `wrap` and the generate functions call this
+    fn validate(&self) -> Result<(), NoiseError>;
+
+    /// Generate a chunk of noise with values scaled from min to max, what is mapped onto that
+    /// range is picked with `with_scale_mode`
+    ///
+    /// # Safety
+    /// `noise` must be valid for writes of `NoiseDimensions::len` values, `generate_scaled_into`
+    /// checks that for you.
+    unsafe fn generate_scaled(self, min: f32, max: f32, noise: *mut f32)
+        -> Result<(), NoiseError>;
+
+    /// Like `generate`, but fails instead of writing past the end of `noise`
+    fn generate_into(self, noise: &mut [f32]) -> Result<(f32, f32), NoiseError>;
+
+    /// Like `generate_scaled`, but fails instead of writing past the end of `noise`
+    fn generate_scaled_into(self, min: f32, max: f32, noise: &mut [f32]) -> Result<(), NoiseError>;
 
-    /// Generate a chunk of noise with values scaled from min to max
-    fn generate_scaled(self, min: f32, max: f32, noise: *mut f32);
+    /// Like `generate_into`, also writes the partial derivative of every value along every axis
+    /// of the block to `derivatives`, laid out as `layout` says. The derivatives are with respect
+    /// to the block coordinates, the frequency is already applied. Gradient and fbm noise have
+    /// derivatives, the other types fail with `NoiseError::DerivativesUnsupported`.
+    fn generate_with_derivatives_into(
+        self,
+        noise: &mut [f32],
+        derivatives: &mut [f32],
+        layout: ChannelLayout,
+    ) -> Result<(f32, f32), NoiseError>;
+
+    /// Like `generate`, with every coordinate and value in double precision
+    ///
+    /// # Safety
+    /// `noise` must be valid for writes of `NoiseDimensions::len` values, `generate_into_f64`
+    /// checks that for you.
+    unsafe fn generate_f64(self, noise: *mut f64) -> Result<(f64, f64), NoiseError>;
+
+    /// Like `generate_scaled`, with every coordinate and value in double precision
+    ///
+    /// # Safety
+    /// `noise` must be valid for writes of `NoiseDimensions::len` values,
+    /// `generate_scaled_into_f64` checks that for you.
+    unsafe fn generate_scaled_f64(self, min: f64, max: f64, noise: *mut f64)
+        -> Result<(), NoiseError>;
+
+    /// Like `generate_f64`, but fails instead of writing past the end of `noise`
+    fn generate_into_f64(self, noise: &mut [f64]) -> Result<(f64, f64), NoiseError>;
+
+    /// Like `generate_scaled_f64`, but fails instead of writing past the end of `noise`
+    fn generate_scaled_into_f64(
+        self,
+        min: f64,
+        max: f64,
+        noise: &mut [f64],
+    ) -> Result<(), NoiseError>;
+}
+
+/// Every frequency has to be finite
+pub(crate) fn validate_frequency(freqs: &[f32]) -> Result<(), NoiseError> {
+    if freqs.iter().all(|f| f.is_finite()) {
+        Ok(())
+    } else {
+        Err(NoiseError::NonFiniteFrequency)
+    }
+}
+
+/// Shared checks for the fractal noise types
+pub(crate) fn validate_fractal(
+    lacunarity: f32,
+    gain: f32,
+    octaves: u8,
+    options: &OctaveOptions,
+) -> Result<(), NoiseError> {
+    if octaves == 0 {
+        return Err(NoiseError::ZeroOctaves);
+    }
+    if !(0.0..1.0).contains(&options.fraction) {
+        return Err(NoiseError::InvalidOctaveFraction);
+    }
+    validate_parameter(&[lacunarity, gain])
+}
+
+/// Sums the octave amplitudes `1, gain, gain^2, ...` into the negative and the positive ones,
+/// the octave past the count weighted by `fraction`
+pub(crate) fn amplitude_range(gain: f32, octaves: u8, fraction: f32) -> (f32, f32) {
+    let mut amp = 1.0;
+    let mut range = (0.0, 0.0);
+    for octave in 0..=octaves {
+        let weight = if octave == octaves { amp * fraction } else { amp };
+        if weight < 0.0 {
+            range.0 += weight;
+        } else {
+            range.1 += weight;
+        }
+        amp *= gain;
+    }
+    range
+}
+
+/// Every other float setting has to be finite
+pub(crate) fn validate_parameter(params: &[f32]) -> Result<(), NoiseError> {
+    if params.iter().all(|p| p.is_finite()) {
+        Ok(())
+    } else {
+        Err(NoiseError::NonFiniteParameter)
+    }
+}
+
+/// How far `period * freq` may be from the nearest multiple of 3, relative to it
+const PERIOD_TOLERANCE: f32 = 1e-4;
+
+/// A period in lattice units, rounded to the nearest multiple of 3
+pub(crate) fn lattice_period(period: f32, freq: f32) -> f32 {
+    let thirds = lattice_units(period, freq) / 3.0;
+    // `as` truncates, so adding a half first rounds
+    (thirds + 0.5) as u32 as f32 * 3.0
+}
+
+/// How many lattice units fit into a period, either way along the axis
+fn lattice_units(period: f32, freq: f32) -> f32 {
+    let units = period * freq;
+    if units < 0.0 { -units } else { units }
+}
+
+/// The lattice periods of the first `D` axes, for the periodic kernels
+#[inline(always)]
+pub(crate) fn lattice_periods<S: Simd, const D: usize>(
+    periods: [f32; 3],
+    freqs: [f32; 3],
+) -> [S::Vf32; D] {
+    core::array::from_fn(|axis| S::Vf32::set1(lattice_period(periods[axis], freqs[axis])))
+}
+
+/// Checks that `lattice` has noise with `dim` dimensions
+pub(crate) fn validate_lattice(dim: usize, lattice: Lattice) -> Result<(), NoiseError> {
+    if dim == 4 {
+        Lattice4d::try_from(lattice)?;
+    }
+    Ok(())
+}
+
+/// Checks that noise can be generated in double precision, periodic noise can't
+pub(crate) fn validate_f64(periodic: bool) -> Result<(), NoiseError> {
+    if periodic {
+        return Err(NoiseError::UnsupportedPeriod);
+    }
+    Ok(())
+}
+
+/// Checks the periods of `PeriodicSettings`, `octaves` counts every octave that gets sampled
+pub(crate) fn validate_period(
+    dim: usize,
+    periods: [f32; 3],
+    freqs: [f32; 3],
+    lacunarity: f32,
+    octaves: usize,
+    lattice: Lattice,
+) -> Result<(), NoiseError> {
+    if periods == [0.0; 3] {
+        return Ok(());
+    }
+    if !(2..=3).contains(&dim)
+        || periods[dim..].iter().any(|&p| p != 0.0)
+        || !matches!(lattice, Lattice::Simplex | Lattice::LegacySimplex)
+    {
+        return Err(NoiseError::UnsupportedPeriod);
+    }
+    for (period, freq) in periods.into_iter().zip(freqs) {
+        if period == 0.0 {
+            continue;
+        }
+        if !(period > 0.0 && period.is_finite()) {
+            return Err(NoiseError::InvalidPeriod);
+        }
+        let mut lattice = lattice_period(period, freq);
+        let tolerance = lattice * PERIOD_TOLERANCE;
+        let error = lattice_units(period, freq) - lattice;
+        if lattice == 0.0 || !(-tolerance..=tolerance).contains(&error) {
+            return Err(NoiseError::InvalidPeriod);
+        }
+        // Scaled the same way the kernels scale it, so the check sees the exact same periods
+        for _ in 1..octaves {
+            lattice *= lacunarity;
+            let thirds = lattice / 3.0;
+            if !(thirds >= 1.0 && thirds == thirds as u32 as f32) {
+                return Err(NoiseError::InvalidPeriod);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Noise that can repeat, to make textures that tile
+pub trait PeriodicSettings {
+    /// Repeats 2d noise every `period_x` along x and `period_y` along y, in the units of the
+    /// block origin and step, so a block `width * step_x` wide tiles with `period_x` of the same
+    /// width. A period of 0 keeps that axis from repeating.
+    ///
+    /// The simplex lattice only repeats every 3 units along an axis, so `period * freq` has to
+    /// be a whole multiple of 3, and stay one in every octave the lacunarity scales it to.
+    /// Repeating 2d noise is a slice through 3d simplex noise, it looks a little different from
+    /// 2d noise that doesn't repeat. Only single precision noise on the simplex lattice can
+    /// repeat.
+    fn with_period_2d(&mut self, period_x: f32, period_y: f32) -> &mut Self;
+    /// Like `with_period_2d`, for 3d noise
+    fn with_period_3d(&mut self, period_x: f32, period_y: f32, period_z: f32) -> &mut Self;
+}
+
+/// Noise that has a 4d variant, only these settings can be built in 4d
+pub trait Settings4d: Settings {
+    fn with_freq_4d(&mut self, freq_x: f32, freq_y: f32, freq_z: f32, freq_w: f32) -> &mut Self;
+    fn with_step_4d(&mut self, step_x: f32, step_y: f32, step_z: f32, step_w: f32) -> &mut Self;
+    fn get_freq_w(&self) -> f32;
+}
+
+pub trait LatticeSettings {
+    /// Samples the noise on `lattice` instead of the simplex lattice. Noise on another lattice
+    /// can't repeat.
+    fn with_lattice(&mut self, lattice: Lattice) -> &mut Self;
 }
 
 pub trait SimplexSettings {
     fn with_lacunarity(&mut self, lacunarity: f32) -> &mut Self;
     fn with_gain(&mut self, gain: f32) -> &mut Self;
     fn with_octaves(&mut self, octaves: u8) -> &mut Self;
+    /// Like `with_octaves`, the fractional part fades in one more octave so the detail can
+    /// change smoothly. Has to be at least 1.0.
+    fn with_octaves_f32(&mut self, octaves: f32) -> &mut Self;
+}
+
+/// Splits a fractional octave count into the whole octaves and the fraction of the octave past
+/// them, for `with_octaves_f32`
+pub(crate) fn split_octaves(octaves: f32) -> (u8, f32) {
+    // `as` truncates and saturates, the floor of anything a u8 can hold
+    let whole = octaves as u8;
+    let fraction = if whole == u8::MAX { 0.0 } else { octaves - whole as f32 };
+    (whole, fraction)
+}
+
+/// The `Settings` and `Settings4d` methods that only touch the `dim` field, the same for every
+/// settings type. `then` names a method to call after the seed changes.
+macro_rules! dimension_methods {
+    (Settings $(, then $after_seed:ident)?) => {
+        fn with_seed(&mut self, seed: u64) -> &mut Self {
+            self.dim.seed = seed;
+            self.dim.seed_mode = $crate::SeedMode::Mixed;
+            $(self.$after_seed();)?
+            self
+        }
+
+        fn with_legacy_seed(&mut self, seed: i32) -> &mut Self {
+            self.dim.seed = seed as u64;
+            self.dim.seed_mode = $crate::SeedMode::Legacy;
+            $(self.$after_seed();)?
+            self
+        }
+
+        fn with_scale_mode(&mut self, scale_mode: $crate::ScaleMode) -> &mut Self {
+            self.dim.scale_mode = scale_mode;
+            self
+        }
+
+        fn with_step(&mut self, step: f32) -> &mut Self {
+            self.dim.step_x = step;
+            self.dim.step_y = step;
+            self.dim.step_z = step;
+            self.dim.step_w = step;
+            self
+        }
+
+        fn with_step_2d(&mut self, step_x: f32, step_y: f32) -> &mut Self {
+            self.dim.step_x = step_x;
+            self.dim.step_y = step_y;
+            self
+        }
+
+        fn with_step_3d(&mut self, step_x: f32, step_y: f32, step_z: f32) -> &mut Self {
+            self.dim.step_x = step_x;
+            self.dim.step_y = step_y;
+            self.dim.step_z = step_z;
+            self
+        }
+
+        fn generate_into(self, noise: &mut [f32]) -> Result<(f32, f32), $crate::NoiseError> {
+            self.dim.check_buffer(noise.len())?;
+            unsafe { self.generate(noise.as_mut_ptr()) }
+        }
+
+        fn generate_scaled_into(
+            self,
+            min: f32,
+            max: f32,
+            noise: &mut [f32],
+        ) -> Result<(), $crate::NoiseError> {
+            self.dim.check_buffer(noise.len())?;
+            unsafe { self.generate_scaled(min, max, noise.as_mut_ptr()) }
+        }
+
+        unsafe fn generate_f64(self, noise: *mut f64) -> Result<(f64, f64), $crate::NoiseError> {
+            let noise_type = self.wrap()?;
+            noise_type.validate_f64()?;
+            match self.dim.dim {
+                1 => Ok($crate::get_1d_noise_f64(&noise_type, noise)),
+                2 => Ok($crate::get_2d_noise_f64(&noise_type, noise)),
+                3 => Ok($crate::get_3d_noise_f64(&noise_type, noise)),
+                4 => $crate::get_4d_noise_f64(&noise_type, noise),
+                d => Err($crate::NoiseError::UnsupportedDimension { dim: d }),
+            }
+        }
+
+        unsafe fn generate_scaled_f64(
+            self,
+            min: f64,
+            max: f64,
+            noise: *mut f64,
+        ) -> Result<(), $crate::NoiseError> {
+            if !(min.is_finite() && max.is_finite()) {
+                return Err($crate::NoiseError::NonFiniteParameter);
+            }
+            let noise_type = self.wrap()?;
+            noise_type.validate_f64()?;
+            match self.dim.dim {
+                1 => $crate::get_1d_scaled_noise_f64(&noise_type, min, max, noise),
+                2 => $crate::get_2d_scaled_noise_f64(&noise_type, min, max, noise),
+                3 => $crate::get_3d_scaled_noise_f64(&noise_type, min, max, noise),
+                4 => $crate::get_4d_scaled_noise_f64(&noise_type, min, max, noise)?,
+                d => return Err($crate::NoiseError::UnsupportedDimension { dim: d }),
+            }
+            Ok(())
+        }
+
+        fn generate_into_f64(self, noise: &mut [f64]) -> Result<(f64, f64), $crate::NoiseError> {
+            self.dim.check_buffer(noise.len())?;
+            unsafe { self.generate_f64(noise.as_mut_ptr()) }
+        }
+
+        fn generate_scaled_into_f64(
+            self,
+            min: f64,
+            max: f64,
+            noise: &mut [f64],
+        ) -> Result<(), $crate::NoiseError> {
+            self.dim.check_buffer(noise.len())?;
+            unsafe { self.generate_scaled_f64(min, max, noise.as_mut_ptr()) }
+        }
+    };
+    (Settings4d) => {
+        fn with_step_4d(
+            &mut self,
+            step_x: f32,
+            step_y: f32,
+            step_z: f32,
+            step_w: f32,
+        ) -> &mut Self {
+            self.dim.step_x = step_x;
+            self.dim.step_y = step_y;
+            self.dim.step_z = step_z;
+            self.dim.step_w = step_w;
+            self
+        }
+    };
 }
 
+mod billow_settings;
+mod cellular_settings;
+mod curl_settings;
+mod domain_warp_settings;
 mod fbm_settings;
 mod gradient_settings;
+mod ridge_settings;
+mod turbulence_settings;
+mod white_settings;
 
+pub use billow_settings::BillowSettings;
+pub use cellular_settings::{CellDistanceFunction, CellReturnType, CellularSettings};
+pub use curl_settings::{ChannelLayout, CurlSettings};
+pub use domain_warp_settings::{DomainWarpSettings, WarpMode, WarpSource};
 pub use fbm_settings::FbmSettings;
 pub use gradient_settings::GradientSettings;
+pub use ridge_settings::RidgeSettings;
+pub use turbulence_settings::TurbulenceSettings;
+pub use white_settings::WhiteSettings;
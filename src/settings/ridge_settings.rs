@@ -0,0 +1,386 @@
+use simdeez::prelude::*;
+
+use crate::dimensional_being::DimensionalBeing;
+use crate::noise::ridge_32::{ridge_1d, ridge_2d, ridge_3d, ridge_4d};
+use crate::noise::ridge_64::{
+    ridge_1d as ridge_1d_f64, ridge_2d as ridge_2d_f64,
+    ridge_3d as ridge_3d_f64, ridge_4d as ridge_4d_f64,
+};
+pub use crate::noise_dimensions::{NoiseDimensions, ScaleMode, SeedMode};
+use crate::noise::lattice::Lattice;
+use crate::noise::octaves::OctaveOptions;
+use crate::noise_helpers_32::{Sample32, Sample4d32};
+use crate::noise_helpers_64::{Sample4d64, Sample64};
+pub use crate::noise_type::NoiseType;
+use crate::{ChannelLayout, NoiseError};
+use crate::{
+    get_1d_noise, get_1d_scaled_noise, get_2d_noise, get_2d_scaled_noise, get_3d_noise,
+    get_3d_scaled_noise, get_4d_noise, get_4d_scaled_noise,
+};
+
+use super::{
+    amplitude_range, split_octaves, validate_fractal, validate_frequency, validate_lattice,
+    validate_parameter, LatticeSettings, Settings, Settings4d, SimplexSettings,
+};
+
+/// Ridged multifractal noise.
+///
+/// Every octave is folded into `(offset - |n|)^2` and weighted by the previous octave's
+/// signal times `gain`, so detail piles up along the ridges instead of everywhere. Octave `i`
+/// is also scaled by `persistence^i`, so finer octaves add less. The defaults are Musgrave's,
+/// a lacunarity of 2, a gain of 2, an offset of 1 and H = 1.
+#[derive(Copy, Clone)]
+pub struct RidgeSettings {
+    pub(crate) dim: NoiseDimensions,
+    pub freq_x: f32,
+    pub freq_y: f32,
+    pub freq_z: f32,
+    pub freq_w: f32,
+    pub lacunarity: f32,
+    pub gain: f32,
+    pub persistence: f32,
+    pub offset: f32,
+    pub octaves: u8,
+    pub octave_options: OctaveOptions,
+}
+
+impl DimensionalBeing for RidgeSettings {
+    fn get_dimensions(&self) -> NoiseDimensions {
+        return self.dim;
+    }
+}
+
+impl Settings for RidgeSettings {
+    fn default(dim: NoiseDimensions) -> RidgeSettings {
+        RidgeSettings {
+            dim,
+            freq_x: 0.02,
+            freq_y: 0.02,
+            freq_z: 0.02,
+            freq_w: 0.02,
+            lacunarity: 2.0,
+            gain: 2.0,
+            persistence: 0.5,
+            offset: 1.0,
+            octaves: 3,
+            octave_options: OctaveOptions::default(),
+        }
+    }
+
+    dimension_methods!(Settings);
+
+    fn with_freq(&mut self, freq: f32) -> &mut RidgeSettings {
+        self.freq_x = freq;
+        self.freq_y = freq;
+        self.freq_z = freq;
+        self.freq_w = freq;
+        self
+    }
+
+    fn with_freq_2d(&mut self, freq_x: f32, freq_y: f32) -> &mut RidgeSettings {
+        self.freq_x = freq_x;
+        self.freq_y = freq_y;
+        self
+    }
+
+    fn with_freq_3d(&mut self, freq_x: f32, freq_y: f32, freq_z: f32) -> &mut RidgeSettings {
+        self.freq_x = freq_x;
+        self.freq_y = freq_y;
+        self.freq_z = freq_z;
+        self
+    }
+
+    fn get_freq_x(&self) -> f32 {
+        self.freq_x
+    }
+
+    fn get_freq_y(&self) -> f32 {
+        self.freq_y
+    }
+
+    fn get_freq_z(&self) -> f32 {
+        self.freq_z
+    }
+
+    fn bounds(&self) -> (f32, f32) {
+        // (offset - |n|)^2 peaks at |n| = 0 or |n| = 1, the signal weights are at most 1
+        let low = self.offset - 1.0;
+        let signal = (self.offset * self.offset).max(low * low);
+        let fraction = self.octave_options.fraction;
+        let (negative, positive) = amplitude_range(self.persistence, self.octaves, fraction);
+        (signal * negative, signal * positive)
+    }
+
+    fn wrap(self) -> Result<NoiseType, NoiseError> {
+        self.validate()?;
+        Ok(NoiseType::Ridge(self))
+    }
+
+    fn validate(&self) -> Result<(), NoiseError> {
+        self.dim.validate()?;
+        validate_frequency(&[self.freq_x, self.freq_y, self.freq_z, self.freq_w])?;
+        validate_lattice(self.dim.dim, self.octave_options.lattice)?;
+        validate_fractal(self.lacunarity, self.gain, self.octaves, &self.octave_options)?;
+        validate_parameter(&[self.persistence, self.offset])
+    }
+
+    unsafe fn generate(self, noise: *mut f32) -> Result<(f32, f32), NoiseError> {
+        self.validate()?;
+        let d = self.dim.dim;
+        match d {
+            1 => Ok(get_1d_noise(&NoiseType::Ridge(self), noise)),
+            2 => Ok(get_2d_noise(&NoiseType::Ridge(self), noise)),
+            3 => Ok(get_3d_noise(&NoiseType::Ridge(self), noise)),
+            4 => get_4d_noise(&NoiseType::Ridge(self), noise),
+            _ => Err(NoiseError::UnsupportedDimension { dim: d }),
+        }
+    }
+
+    unsafe fn generate_scaled(
+        self,
+        min: f32,
+        max: f32,
+        noise: *mut f32,
+    ) -> Result<(), NoiseError> {
+        let d = self.dim.dim;
+        let mut new_self = self;
+        new_self.dim.min = min;
+        new_self.dim.max = max;
+        new_self.validate()?;
+        match d {
+            1 => get_1d_scaled_noise(&NoiseType::Ridge(new_self), noise),
+            2 => get_2d_scaled_noise(&NoiseType::Ridge(new_self), noise),
+            3 => get_3d_scaled_noise(&NoiseType::Ridge(new_self), noise),
+            4 => get_4d_scaled_noise(&NoiseType::Ridge(new_self), noise)?,
+            _ => return Err(NoiseError::UnsupportedDimension { dim: d }),
+        }
+        Ok(())
+    }
+
+    fn generate_with_derivatives_into(
+        self,
+        _noise: &mut [f32],
+        _derivatives: &mut [f32],
+        _layout: ChannelLayout,
+    ) -> Result<(f32, f32), NoiseError> {
+        Err(NoiseError::DerivativesUnsupported)
+    }
+
+}
+
+impl Settings4d for RidgeSettings {
+    dimension_methods!(Settings4d);
+
+    fn with_freq_4d(
+        &mut self,
+        freq_x: f32,
+        freq_y: f32,
+        freq_z: f32,
+        freq_w: f32,
+    ) -> &mut RidgeSettings {
+        self.freq_x = freq_x;
+        self.freq_y = freq_y;
+        self.freq_z = freq_z;
+        self.freq_w = freq_w;
+        self
+    }
+
+    fn get_freq_w(&self) -> f32 {
+        self.freq_w
+    }
+}
+
+impl SimplexSettings for RidgeSettings {
+    fn with_lacunarity(&mut self, lacunarity: f32) -> &mut RidgeSettings {
+        self.lacunarity = lacunarity;
+        self
+    }
+
+    fn with_gain(&mut self, gain: f32) -> &mut RidgeSettings {
+        self.gain = gain;
+        self
+    }
+
+    fn with_octaves(&mut self, octaves: u8) -> &mut RidgeSettings {
+        self.octaves = octaves;
+        self.octave_options.fraction = 0.0;
+        self
+    }
+
+    fn with_octaves_f32(&mut self, octaves: f32) -> &mut RidgeSettings {
+        (self.octaves, self.octave_options.fraction) = split_octaves(octaves);
+        self
+    }
+}
+
+impl<S: Simd> Sample32<S> for RidgeSettings {
+    #[inline(always)]
+    fn sample_1d(&self, x: S::Vf32) -> S::Vf32 {
+        ridge_1d::<S>(
+            x,
+            S::Vf32::set1(self.lacunarity),
+            S::Vf32::set1(self.gain),
+            S::Vf32::set1(self.persistence),
+            S::Vf32::set1(self.offset),
+            self.octaves,
+            self.dim.seed_32(),
+            self.octave_options.for_seed_mode(self.dim.seed_mode),
+        )
+    }
+
+    #[inline(always)]
+    fn sample_2d(&self, x: S::Vf32, y: S::Vf32) -> S::Vf32 {
+        ridge_2d::<S>(
+            x,
+            y,
+            S::Vf32::set1(self.lacunarity),
+            S::Vf32::set1(self.gain),
+            S::Vf32::set1(self.persistence),
+            S::Vf32::set1(self.offset),
+            self.octaves,
+            self.dim.seed_32(),
+            self.octave_options.for_seed_mode(self.dim.seed_mode),
+        )
+    }
+
+    #[inline(always)]
+    fn sample_3d(&self, x: S::Vf32, y: S::Vf32, z: S::Vf32) -> S::Vf32 {
+        ridge_3d::<S>(
+            x,
+            y,
+            z,
+            S::Vf32::set1(self.lacunarity),
+            S::Vf32::set1(self.gain),
+            S::Vf32::set1(self.persistence),
+            S::Vf32::set1(self.offset),
+            self.octaves,
+            self.dim.seed_32(),
+            self.octave_options.for_seed_mode(self.dim.seed_mode),
+        )
+    }
+}
+
+impl<S: Simd> Sample4d32<S> for RidgeSettings {
+    #[inline(always)]
+    fn sample_4d(
+        &self,
+        x: S::Vf32,
+        y: S::Vf32,
+        z: S::Vf32,
+        w: S::Vf32,
+    ) -> Result<S::Vf32, NoiseError> {
+        Ok(ridge_4d::<S>(
+            x,
+            y,
+            z,
+            w,
+            S::Vf32::set1(self.lacunarity),
+            S::Vf32::set1(self.gain),
+            S::Vf32::set1(self.persistence),
+            S::Vf32::set1(self.offset),
+            self.octaves,
+            self.dim.seed_32(),
+            self.octave_options
+                .for_seed_mode(self.dim.seed_mode)
+                .for_4d()?,
+        ))
+    }
+}
+
+impl<S: Simd> Sample64<S> for RidgeSettings {
+    #[inline(always)]
+    fn sample_1d(&self, x: S::Vf64) -> S::Vf64 {
+        ridge_1d_f64::<S>(
+            x,
+            S::Vf64::set1(self.lacunarity.into()),
+            S::Vf64::set1(self.gain.into()),
+            S::Vf64::set1(self.persistence.into()),
+            S::Vf64::set1(self.offset.into()),
+            self.octaves,
+            self.dim.seed_64(),
+            self.octave_options.for_seed_mode(self.dim.seed_mode),
+        )
+    }
+
+    #[inline(always)]
+    fn sample_2d(&self, x: S::Vf64, y: S::Vf64) -> S::Vf64 {
+        ridge_2d_f64::<S>(
+            x,
+            y,
+            S::Vf64::set1(self.lacunarity.into()),
+            S::Vf64::set1(self.gain.into()),
+            S::Vf64::set1(self.persistence.into()),
+            S::Vf64::set1(self.offset.into()),
+            self.octaves,
+            self.dim.seed_64(),
+            self.octave_options.for_seed_mode(self.dim.seed_mode),
+        )
+    }
+
+    #[inline(always)]
+    fn sample_3d(&self, x: S::Vf64, y: S::Vf64, z: S::Vf64) -> S::Vf64 {
+        ridge_3d_f64::<S>(
+            x,
+            y,
+            z,
+            S::Vf64::set1(self.lacunarity.into()),
+            S::Vf64::set1(self.gain.into()),
+            S::Vf64::set1(self.persistence.into()),
+            S::Vf64::set1(self.offset.into()),
+            self.octaves,
+            self.dim.seed_64(),
+            self.octave_options.for_seed_mode(self.dim.seed_mode),
+        )
+    }
+}
+
+impl<S: Simd> Sample4d64<S> for RidgeSettings {
+    #[inline(always)]
+    fn sample_4d(
+        &self,
+        x: S::Vf64,
+        y: S::Vf64,
+        z: S::Vf64,
+        w: S::Vf64,
+    ) -> Result<S::Vf64, NoiseError> {
+        Ok(ridge_4d_f64::<S>(
+            x,
+            y,
+            z,
+            w,
+            S::Vf64::set1(self.lacunarity.into()),
+            S::Vf64::set1(self.gain.into()),
+            S::Vf64::set1(self.persistence.into()),
+            S::Vf64::set1(self.offset.into()),
+            self.octaves,
+            self.dim.seed_64(),
+            self.octave_options
+                .for_seed_mode(self.dim.seed_mode)
+                .for_4d()?,
+        ))
+    }
+}
+
+impl LatticeSettings for RidgeSettings {
+    fn with_lattice(&mut self, lattice: Lattice) -> &mut RidgeSettings {
+        self.octave_options.lattice = lattice;
+        self
+    }
+}
+
+impl RidgeSettings {
+    /// Amplitude of every octave relative to the one before, the `lacunarity^-H` spectral
+    /// weight of Musgrave's ridged multifractal. 0.5 with a lacunarity of 2 is H = 1, smaller
+    /// values leave fewer fine ridges.
+    pub fn with_persistence(&mut self, persistence: f32) -> &mut RidgeSettings {
+        self.persistence = persistence;
+        self
+    }
+
+    /// Value the absolute noise is subtracted from before squaring, usually 1.0.
+    /// Larger values raise the floor between ridges.
+    pub fn with_offset(&mut self, offset: f32) -> &mut RidgeSettings {
+        self.offset = offset;
+        self
+    }
+}
@@ -0,0 +1,342 @@
+use simdeez::prelude::*;
+
+use crate::dimensional_being::DimensionalBeing;
+use crate::noise::turbulence_32::{turbulence_1d, turbulence_2d, turbulence_3d, turbulence_4d};
+use crate::noise::turbulence_64::{
+    turbulence_1d as turbulence_1d_f64, turbulence_2d as turbulence_2d_f64,
+    turbulence_3d as turbulence_3d_f64, turbulence_4d as turbulence_4d_f64,
+};
+pub use crate::noise_dimensions::{NoiseDimensions, ScaleMode, SeedMode};
+use crate::noise::lattice::Lattice;
+use crate::noise::octaves::OctaveOptions;
+use crate::noise_helpers_32::{Sample32, Sample4d32};
+use crate::noise_helpers_64::{Sample4d64, Sample64};
+pub use crate::noise_type::NoiseType;
+use crate::{ChannelLayout, NoiseError};
+use crate::{
+    get_1d_noise, get_1d_scaled_noise, get_2d_noise, get_2d_scaled_noise, get_3d_noise,
+    get_3d_scaled_noise, get_4d_noise, get_4d_scaled_noise,
+};
+
+use super::{
+    amplitude_range, split_octaves, validate_fractal, validate_frequency, validate_lattice,
+    LatticeSettings, Settings, Settings4d, SimplexSettings,
+};
+
+/// Fractal sum of the absolute value of simplex noise.
+///
+/// Folding every octave at zero gives the creased, billowing look used for marble
+/// and fire. Output is never negative.
+#[derive(Copy, Clone)]
+pub struct TurbulenceSettings {
+    pub(crate) dim: NoiseDimensions,
+    pub freq_x: f32,
+    pub freq_y: f32,
+    pub freq_z: f32,
+    pub freq_w: f32,
+    pub lacunarity: f32,
+    pub gain: f32,
+    pub octaves: u8,
+    pub octave_options: OctaveOptions,
+}
+
+impl DimensionalBeing for TurbulenceSettings {
+    fn get_dimensions(&self) -> NoiseDimensions {
+        return self.dim;
+    }
+}
+
+impl Settings for TurbulenceSettings {
+    fn default(dim: NoiseDimensions) -> TurbulenceSettings {
+        TurbulenceSettings {
+            dim,
+            freq_x: 0.02,
+            freq_y: 0.02,
+            freq_z: 0.02,
+            freq_w: 0.02,
+            lacunarity: 2.0,
+            gain: 0.5,
+            octaves: 3,
+            octave_options: OctaveOptions::default(),
+        }
+    }
+
+    dimension_methods!(Settings);
+
+    fn with_freq(&mut self, freq: f32) -> &mut TurbulenceSettings {
+        self.freq_x = freq;
+        self.freq_y = freq;
+        self.freq_z = freq;
+        self.freq_w = freq;
+        self
+    }
+
+    fn with_freq_2d(&mut self, freq_x: f32, freq_y: f32) -> &mut TurbulenceSettings {
+        self.freq_x = freq_x;
+        self.freq_y = freq_y;
+        self
+    }
+
+    fn with_freq_3d(&mut self, freq_x: f32, freq_y: f32, freq_z: f32) -> &mut TurbulenceSettings {
+        self.freq_x = freq_x;
+        self.freq_y = freq_y;
+        self.freq_z = freq_z;
+        self
+    }
+
+    fn get_freq_x(&self) -> f32 {
+        self.freq_x
+    }
+
+    fn get_freq_y(&self) -> f32 {
+        self.freq_y
+    }
+
+    fn get_freq_z(&self) -> f32 {
+        self.freq_z
+    }
+
+    fn bounds(&self) -> (f32, f32) {
+        // Every octave is |n| * amp, a negative gain makes some octaves pull down
+        amplitude_range(self.gain, self.octaves, self.octave_options.fraction)
+    }
+
+    fn wrap(self) -> Result<NoiseType, NoiseError> {
+        self.validate()?;
+        Ok(NoiseType::Turbulence(self))
+    }
+
+    fn validate(&self) -> Result<(), NoiseError> {
+        self.dim.validate()?;
+        validate_frequency(&[self.freq_x, self.freq_y, self.freq_z, self.freq_w])?;
+        validate_lattice(self.dim.dim, self.octave_options.lattice)?;
+        validate_fractal(self.lacunarity, self.gain, self.octaves, &self.octave_options)
+    }
+
+    unsafe fn generate(self, noise: *mut f32) -> Result<(f32, f32), NoiseError> {
+        self.validate()?;
+        let d = self.dim.dim;
+        match d {
+            1 => Ok(get_1d_noise(&NoiseType::Turbulence(self), noise)),
+            2 => Ok(get_2d_noise(&NoiseType::Turbulence(self), noise)),
+            3 => Ok(get_3d_noise(&NoiseType::Turbulence(self), noise)),
+            4 => get_4d_noise(&NoiseType::Turbulence(self), noise),
+            _ => Err(NoiseError::UnsupportedDimension { dim: d }),
+        }
+    }
+
+    unsafe fn generate_scaled(
+        self,
+        min: f32,
+        max: f32,
+        noise: *mut f32,
+    ) -> Result<(), NoiseError> {
+        let d = self.dim.dim;
+        let mut new_self = self;
+        new_self.dim.min = min;
+        new_self.dim.max = max;
+        new_self.validate()?;
+        match d {
+            1 => get_1d_scaled_noise(&NoiseType::Turbulence(new_self), noise),
+            2 => get_2d_scaled_noise(&NoiseType::Turbulence(new_self), noise),
+            3 => get_3d_scaled_noise(&NoiseType::Turbulence(new_self), noise),
+            4 => get_4d_scaled_noise(&NoiseType::Turbulence(new_self), noise)?,
+            _ => return Err(NoiseError::UnsupportedDimension { dim: d }),
+        }
+        Ok(())
+    }
+
+    fn generate_with_derivatives_into(
+        self,
+        _noise: &mut [f32],
+        _derivatives: &mut [f32],
+        _layout: ChannelLayout,
+    ) -> Result<(f32, f32), NoiseError> {
+        Err(NoiseError::DerivativesUnsupported)
+    }
+
+}
+
+impl Settings4d for TurbulenceSettings {
+    dimension_methods!(Settings4d);
+
+    fn with_freq_4d(
+        &mut self,
+        freq_x: f32,
+        freq_y: f32,
+        freq_z: f32,
+        freq_w: f32,
+    ) -> &mut TurbulenceSettings {
+        self.freq_x = freq_x;
+        self.freq_y = freq_y;
+        self.freq_z = freq_z;
+        self.freq_w = freq_w;
+        self
+    }
+
+    fn get_freq_w(&self) -> f32 {
+        self.freq_w
+    }
+}
+
+impl SimplexSettings for TurbulenceSettings {
+    fn with_lacunarity(&mut self, lacunarity: f32) -> &mut TurbulenceSettings {
+        self.lacunarity = lacunarity;
+        self
+    }
+
+    fn with_gain(&mut self, gain: f32) -> &mut TurbulenceSettings {
+        self.gain = gain;
+        self
+    }
+
+    fn with_octaves(&mut self, octaves: u8) -> &mut TurbulenceSettings {
+        self.octaves = octaves;
+        self.octave_options.fraction = 0.0;
+        self
+    }
+
+    fn with_octaves_f32(&mut self, octaves: f32) -> &mut TurbulenceSettings {
+        (self.octaves, self.octave_options.fraction) = split_octaves(octaves);
+        self
+    }
+}
+
+impl<S: Simd> Sample32<S> for TurbulenceSettings {
+    #[inline(always)]
+    fn sample_1d(&self, x: S::Vf32) -> S::Vf32 {
+        turbulence_1d::<S>(
+            x,
+            S::Vf32::set1(self.lacunarity),
+            S::Vf32::set1(self.gain),
+            self.octaves,
+            self.dim.seed_32(),
+            self.octave_options.for_seed_mode(self.dim.seed_mode),
+        )
+    }
+
+    #[inline(always)]
+    fn sample_2d(&self, x: S::Vf32, y: S::Vf32) -> S::Vf32 {
+        turbulence_2d::<S>(
+            x,
+            y,
+            S::Vf32::set1(self.lacunarity),
+            S::Vf32::set1(self.gain),
+            self.octaves,
+            self.dim.seed_32(),
+            self.octave_options.for_seed_mode(self.dim.seed_mode),
+        )
+    }
+
+    #[inline(always)]
+    fn sample_3d(&self, x: S::Vf32, y: S::Vf32, z: S::Vf32) -> S::Vf32 {
+        turbulence_3d::<S>(
+            x,
+            y,
+            z,
+            S::Vf32::set1(self.lacunarity),
+            S::Vf32::set1(self.gain),
+            self.octaves,
+            self.dim.seed_32(),
+            self.octave_options.for_seed_mode(self.dim.seed_mode),
+        )
+    }
+}
+
+impl<S: Simd> Sample4d32<S> for TurbulenceSettings {
+    #[inline(always)]
+    fn sample_4d(
+        &self,
+        x: S::Vf32,
+        y: S::Vf32,
+        z: S::Vf32,
+        w: S::Vf32,
+    ) -> Result<S::Vf32, NoiseError> {
+        Ok(turbulence_4d::<S>(
+            x,
+            y,
+            z,
+            w,
+            S::Vf32::set1(self.lacunarity),
+            S::Vf32::set1(self.gain),
+            self.octaves,
+            self.dim.seed_32(),
+            self.octave_options
+                .for_seed_mode(self.dim.seed_mode)
+                .for_4d()?,
+        ))
+    }
+}
+
+impl<S: Simd> Sample64<S> for TurbulenceSettings {
+    #[inline(always)]
+    fn sample_1d(&self, x: S::Vf64) -> S::Vf64 {
+        turbulence_1d_f64::<S>(
+            x,
+            S::Vf64::set1(self.lacunarity.into()),
+            S::Vf64::set1(self.gain.into()),
+            self.octaves,
+            self.dim.seed_64(),
+            self.octave_options.for_seed_mode(self.dim.seed_mode),
+        )
+    }
+
+    #[inline(always)]
+    fn sample_2d(&self, x: S::Vf64, y: S::Vf64) -> S::Vf64 {
+        turbulence_2d_f64::<S>(
+            x,
+            y,
+            S::Vf64::set1(self.lacunarity.into()),
+            S::Vf64::set1(self.gain.into()),
+            self.octaves,
+            self.dim.seed_64(),
+            self.octave_options.for_seed_mode(self.dim.seed_mode),
+        )
+    }
+
+    #[inline(always)]
+    fn sample_3d(&self, x: S::Vf64, y: S::Vf64, z: S::Vf64) -> S::Vf64 {
+        turbulence_3d_f64::<S>(
+            x,
+            y,
+            z,
+            S::Vf64::set1(self.lacunarity.into()),
+            S::Vf64::set1(self.gain.into()),
+            self.octaves,
+            self.dim.seed_64(),
+            self.octave_options.for_seed_mode(self.dim.seed_mode),
+        )
+    }
+}
+
+impl<S: Simd> Sample4d64<S> for TurbulenceSettings {
+    #[inline(always)]
+    fn sample_4d(
+        &self,
+        x: S::Vf64,
+        y: S::Vf64,
+        z: S::Vf64,
+        w: S::Vf64,
+    ) -> Result<S::Vf64, NoiseError> {
+        Ok(turbulence_4d_f64::<S>(
+            x,
+            y,
+            z,
+            w,
+            S::Vf64::set1(self.lacunarity.into()),
+            S::Vf64::set1(self.gain.into()),
+            self.octaves,
+            self.dim.seed_64(),
+            self.octave_options
+                .for_seed_mode(self.dim.seed_mode)
+                .for_4d()?,
+        ))
+    }
+}
+
+impl LatticeSettings for TurbulenceSettings {
+    fn with_lattice(&mut self, lattice: Lattice) -> &mut TurbulenceSettings {
+        self.octave_options.lattice = lattice;
+        self
+    }
+}
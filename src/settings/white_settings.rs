@@ -0,0 +1,172 @@
+use simdeez::prelude::*;
+
+use crate::dimensional_being::DimensionalBeing;
+use crate::noise::white_32::{white_1d, white_2d, white_3d};
+use crate::noise::white_64::{
+    white_1d as white_1d_f64, white_2d as white_2d_f64, white_3d as white_3d_f64,
+};
+pub use crate::noise_dimensions::{NoiseDimensions, ScaleMode, SeedMode};
+use crate::noise_helpers_32::Sample32;
+use crate::noise_helpers_64::Sample64;
+pub use crate::noise_type::NoiseType;
+use crate::{ChannelLayout, NoiseError};
+use crate::{
+    get_1d_noise, get_1d_scaled_noise, get_2d_noise, get_2d_scaled_noise, get_3d_noise,
+    get_3d_scaled_noise,
+};
+
+use super::{validate_frequency, Settings};
+
+/// White noise, an independent uniform random value in [-1, 1] for every lattice cell. The
+/// frequency defaults to 1.0, so every sample of a block with the default step gets a cell of
+/// its own.
+#[derive(Copy, Clone)]
+pub struct WhiteSettings {
+    dim: NoiseDimensions,
+    pub freq_x: f32,
+    pub freq_y: f32,
+    pub freq_z: f32,
+}
+
+impl DimensionalBeing for WhiteSettings {
+    fn get_dimensions(&self) -> NoiseDimensions {
+        return self.dim;
+    }
+}
+
+impl Settings for WhiteSettings {
+    fn default(dim: NoiseDimensions) -> WhiteSettings {
+        WhiteSettings {
+            dim,
+            freq_x: 1.0,
+            freq_y: 1.0,
+            freq_z: 1.0,
+        }
+    }
+
+    dimension_methods!(Settings);
+
+    fn with_freq(&mut self, freq: f32) -> &mut WhiteSettings {
+        self.freq_x = freq;
+        self.freq_y = freq;
+        self.freq_z = freq;
+        self
+    }
+
+    fn with_freq_2d(&mut self, freq_x: f32, freq_y: f32) -> &mut WhiteSettings {
+        self.freq_x = freq_x;
+        self.freq_y = freq_y;
+        self
+    }
+
+    fn with_freq_3d(&mut self, freq_x: f32, freq_y: f32, freq_z: f32) -> &mut WhiteSettings {
+        self.freq_x = freq_x;
+        self.freq_y = freq_y;
+        self.freq_z = freq_z;
+        self
+    }
+
+    fn get_freq_x(&self) -> f32 {
+        self.freq_x
+    }
+
+    fn get_freq_y(&self) -> f32 {
+        self.freq_y
+    }
+
+    fn get_freq_z(&self) -> f32 {
+        self.freq_z
+    }
+
+    fn bounds(&self) -> (f32, f32) {
+        (-1.0, 1.0)
+    }
+
+    fn wrap(self) -> Result<NoiseType, NoiseError> {
+        self.validate()?;
+        Ok(NoiseType::White(self))
+    }
+
+    fn validate(&self) -> Result<(), NoiseError> {
+        self.dim.validate()?;
+        if self.dim.dim == 4 {
+            return Err(NoiseError::UnsupportedDimension { dim: 4 });
+        }
+        validate_frequency(&[self.freq_x, self.freq_y, self.freq_z])
+    }
+
+    unsafe fn generate(self, noise: *mut f32) -> Result<(f32, f32), NoiseError> {
+        self.validate()?;
+        let d = self.dim.dim;
+        match d {
+            1 => Ok(get_1d_noise(&NoiseType::White(self), noise)),
+            2 => Ok(get_2d_noise(&NoiseType::White(self), noise)),
+            3 => Ok(get_3d_noise(&NoiseType::White(self), noise)),
+            _ => Err(NoiseError::UnsupportedDimension { dim: d }),
+        }
+    }
+
+    unsafe fn generate_scaled(
+        self,
+        min: f32,
+        max: f32,
+        noise: *mut f32,
+    ) -> Result<(), NoiseError> {
+        let d = self.dim.dim;
+        let mut new_self = self;
+        new_self.dim.min = min;
+        new_self.dim.max = max;
+        new_self.validate()?;
+        match d {
+            1 => get_1d_scaled_noise(&NoiseType::White(new_self), noise),
+            2 => get_2d_scaled_noise(&NoiseType::White(new_self), noise),
+            3 => get_3d_scaled_noise(&NoiseType::White(new_self), noise),
+            _ => return Err(NoiseError::UnsupportedDimension { dim: d }),
+        }
+        Ok(())
+    }
+
+    fn generate_with_derivatives_into(
+        self,
+        _noise: &mut [f32],
+        _derivatives: &mut [f32],
+        _layout: ChannelLayout,
+    ) -> Result<(f32, f32), NoiseError> {
+        Err(NoiseError::DerivativesUnsupported)
+    }
+
+}
+
+impl<S: Simd> Sample32<S> for WhiteSettings {
+    #[inline(always)]
+    fn sample_1d(&self, x: S::Vf32) -> S::Vf32 {
+        white_1d::<S>(x, self.dim.seed_32())
+    }
+
+    #[inline(always)]
+    fn sample_2d(&self, x: S::Vf32, y: S::Vf32) -> S::Vf32 {
+        white_2d::<S>(x, y, self.dim.seed_32())
+    }
+
+    #[inline(always)]
+    fn sample_3d(&self, x: S::Vf32, y: S::Vf32, z: S::Vf32) -> S::Vf32 {
+        white_3d::<S>(x, y, z, self.dim.seed_32())
+    }
+}
+
+impl<S: Simd> Sample64<S> for WhiteSettings {
+    #[inline(always)]
+    fn sample_1d(&self, x: S::Vf64) -> S::Vf64 {
+        white_1d_f64::<S>(x, self.dim.seed_64())
+    }
+
+    #[inline(always)]
+    fn sample_2d(&self, x: S::Vf64, y: S::Vf64) -> S::Vf64 {
+        white_2d_f64::<S>(x, y, self.dim.seed_64())
+    }
+
+    #[inline(always)]
+    fn sample_3d(&self, x: S::Vf64, y: S::Vf64, z: S::Vf64) -> S::Vf64 {
+        white_3d_f64::<S>(x, y, z, self.dim.seed_64())
+    }
+}
@@ -1,6 +1,6 @@
 use simdeez::prelude::*;
 
-use crate::{dimensional_being::DimensionalBeing, NoiseType};
+use crate::{dimensional_being::DimensionalBeing, NoiseType, ScaleMode};
 
 #[inline(always)]
 pub unsafe fn scale_noise<S: Simd>(
@@ -31,8 +31,87 @@ pub unsafe fn scale_noise<S: Simd>(
     }
 }
 
-pub(crate) unsafe fn get_scaled_noise<S: Simd, F: Fn(&NoiseType, *mut f32) -> (f32, f32)>(noise_type: &NoiseType, noise: *mut f32, noise_fn: F) {
-    let (min, max) = noise_fn(noise_type, noise);
+#[inline(always)]
+pub unsafe fn scale_noise_f64<S: Simd>(
+    scale_min: f64,
+    scale_max: f64,
+    min: f64,
+    max: f64,
+    data: *mut f64,
+    len: usize
+) {
+    let scale_range = scale_max - scale_min;
+    let range = max - min;
+    let multiplier = scale_range / range;
+    let offset = scale_min - min * multiplier;
+    let vector_width = S::Vf64::WIDTH;
+    let mut i = 0;
+    if len >= vector_width {
+        while i <= len - vector_width {
+            let value = (S::Vf64::set1(multiplier) * S::Vf64::load_from_ptr_unaligned(data.add(i))) + S::Vf64::set1(offset);
+            value.copy_to_ptr_unaligned(data.add(i));
+            i += vector_width;
+        }
+    }
+    i = len - (len % vector_width);
+    while i < len {
+        *data.add(i) = *data.add(i) * multiplier + offset;
+        i += 1;
+    }
+}
+
+/// The range `scale_noise` maps from, the `observed` min and max of the block or the bounds of
+/// the noise type, depending on the scale mode
+pub(crate) fn scale_source<F: From<f32>>(noise_type: &NoiseType, observed: (F, F)) -> (F, F) {
+    match noise_type.get_dimensions().scale_mode {
+        ScaleMode::Observed => observed,
+        ScaleMode::Theoretical => {
+            let (min, max) = noise_type.bounds();
+            (min.into(), max.into())
+        }
+    }
+}
+
+pub(crate) unsafe fn get_scaled_noise<S: Simd>(
+    noise_type: &NoiseType,
+    noise: *mut f32,
+    noise_fn: unsafe fn(&NoiseType, *mut f32) -> (f32, f32),
+) {
+    scale_block::<S>(noise_type, noise, noise_fn(noise_type, noise));
+}
+
+/// Scales a block generated for `noise_type` whose values span `observed`
+pub(crate) unsafe fn scale_block<S: Simd>(
+    noise_type: &NoiseType,
+    noise: *mut f32,
+    observed: (f32, f32),
+) {
+    let (min, max) = scale_source(noise_type, observed);
     let dim = noise_type.get_dimensions();
     scale_noise::<S>(dim.min, dim.max, min, max, noise, dim.len());
 }
+
+pub(crate) unsafe fn get_scaled_noise_f64<S: Simd>(
+    noise_type: &NoiseType,
+    scale_min: f64,
+    scale_max: f64,
+    noise: *mut f64,
+    noise_fn: unsafe fn(&NoiseType, *mut f64) -> (f64, f64),
+) {
+    let observed = noise_fn(noise_type, noise);
+    scale_block_f64::<S>(noise_type, scale_min, scale_max, noise, observed);
+}
+
+/// Like `scale_block`, in double precision. The block is scaled onto `scale_min..scale_max`
+/// rather than the single precision range of the dimensions.
+pub(crate) unsafe fn scale_block_f64<S: Simd>(
+    noise_type: &NoiseType,
+    scale_min: f64,
+    scale_max: f64,
+    noise: *mut f64,
+    observed: (f64, f64),
+) {
+    let (min, max) = scale_source(noise_type, observed);
+    let len = noise_type.get_dimensions().len();
+    scale_noise_f64::<S>(scale_min, scale_max, min, max, noise, len);
+}
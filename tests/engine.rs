@@ -0,0 +1,90 @@
+use cursednoise::{
+    active_engine, detected_engine, force_engine, reset_engine, Engine, NoiseBuilder, Settings,
+    SimplexSettings,
+};
+
+// Odd sizes so every engine also runs its remainder path
+const W: usize = 37;
+const H: usize = 11;
+const D: usize = 5;
+
+fn fbm_3d() -> Vec<f32> {
+    let mut noise = vec![0.0; W * H * D];
+    NoiseBuilder::fbm_3d(W, H, D)
+        .with_freq(0.05)
+        .with_seed(1337)
+        .with_octaves(3)
+        .generate_into(&mut noise)
+        .unwrap();
+    noise
+}
+
+fn gradient_2d() -> Vec<f32> {
+    let mut noise = vec![0.0; W * H];
+    NoiseBuilder::gradient_2d(W, H)
+        .with_seed(1337)
+        .generate_into(&mut noise)
+        .unwrap();
+    noise
+}
+
+// What crsnoise's generateNoise writes, start scaled by the step and frequency 1. The start and
+// steps are exact in binary, so both ways of getting there land on the same positions.
+fn crsnoise() -> Vec<f32> {
+    let mut noise = vec![0.0; 9 * 3 * 2];
+    NoiseBuilder::gradient_3d_offset(137.25 * 5.0, 9, -58.75 * 15.0, 3, 211.875 * 35.0, 2)
+        .with_step_3d(5.0, 15.0, 35.0)
+        .with_freq(1.0)
+        .with_legacy_seed(1337)
+        .generate_scaled_into(0.0, 255.0, &mut noise)
+        .unwrap();
+    noise
+}
+
+// What generateNoise wrote before steps existed, the step passed as the frequency
+fn crsnoise_by_freq() -> Vec<f32> {
+    let mut noise = vec![0.0; 9 * 3 * 2];
+    NoiseBuilder::gradient_3d_offset(137.25, 9, -58.75, 3, 211.875, 2)
+        .with_freq_3d(5.0, 15.0, 35.0)
+        .with_legacy_seed(1337)
+        .generate_scaled_into(0.0, 255.0, &mut noise)
+        .unwrap();
+    noise
+}
+
+fn assert_close(expected: &[f32], actual: &[f32], engine: Engine) {
+    for (i, (e, a)) in expected.iter().zip(actual).enumerate() {
+        assert!(
+            (e - a).abs() < 1e-5,
+            "{:?} differs from scalar at {}: {} != {}",
+            engine,
+            i,
+            a,
+            e
+        );
+    }
+}
+
+// The forced engine is global, so everything that touches it lives in this one test
+#[test]
+fn test_engine_override() {
+    assert!(force_engine(Engine::Scalar));
+    assert_eq!(active_engine(), Engine::Scalar);
+    let fbm_scalar = fbm_3d();
+    let gradient_scalar = gradient_2d();
+    assert_eq!(crsnoise_by_freq(), crsnoise());
+
+    for engine in [Engine::Sse2, Engine::Sse41, Engine::Avx2] {
+        if !force_engine(engine) {
+            assert!(engine > detected_engine());
+            continue;
+        }
+        assert_eq!(active_engine(), engine);
+        assert_close(&fbm_scalar, &fbm_3d(), engine);
+        assert_close(&gradient_scalar, &gradient_2d(), engine);
+        assert_eq!(crsnoise_by_freq(), crsnoise(), "{:?}", engine);
+    }
+
+    reset_engine();
+    assert_eq!(active_engine(), detected_engine());
+}
@@ -1,176 +1,100 @@
-#![allow(unsafe_op_in_unsafe_fn)]
-use cursednoise::intrinsics::avx2;
-use cursednoise::{GradientSettings, NoiseDimensions, Settings};
+use cursednoise::intrinsics::{avx2, sse2, sse41};
+use cursednoise::{GradientSettings, NoiseDimensions, NoiseType, Settings};
 
 mod helpers;
 use helpers::{read_from_file_f32, read_from_file_f64, BIN_PATH};
-use simdeez::engines::avx2::Avx2;
+use simdeez::engines::{avx2::Avx2, sse2::Sse2, sse41::Sse41};
 
 const W: usize = 64;
 const H: usize = 32;
 const D: usize = 16;
 
-#[target_feature(enable = "avx2")]
-unsafe fn do_intrinsic_gradient_1_avx2_32_normal() -> [f32; W] {
-    let dims = NoiseDimensions {
-        width: W,
-        ..NoiseDimensions::default(1)
-    };
-
-    let noise_type = GradientSettings::default(dims).with_seed(1337).wrap();
-    let mut noise = [0.0; W];
-    let (_min, _max) = avx2::get_1d_noise::<Avx2>(&noise_type, noise.as_mut_ptr());
-    noise
+/// A block function of one of the engine modules, in either precision
+#[derive(Copy, Clone)]
+enum Block {
+    F32(unsafe fn(&NoiseType, *mut f32) -> (f32, f32)),
+    F64(unsafe fn(&NoiseType, *mut f64) -> (f64, f64)),
 }
 
-#[test]
-fn test_intrinsic_gradient_1_avx2_32_normal() {
-    let file_name = format!(
-        "{}/{}_{}_{}_{}_{}_{}.bin",
-        BIN_PATH, "intrinsics", "gradient", "32", "1d", "avx2", "normal"
-    );
-    unsafe {
-        let expected = read_from_file_f32(&file_name).unwrap();
-        let noise = &do_intrinsic_gradient_1_avx2_32_normal()[0..expected.len()];
-        assert_eq!(expected, noise);
+/// Every engine, dimension count and precision, with the engine whose fixture the block has to
+/// match
+#[rustfmt::skip]
+const CASES: [(&str, usize, Block, &str); 18] = [
+    ("avx2", 1, Block::F32(avx2::get_1d_noise::<Avx2>), "avx2"),
+    ("avx2", 1, Block::F64(avx2::get_1d_noise_64::<Avx2>), "avx2"),
+    ("avx2", 2, Block::F32(avx2::get_2d_noise::<Avx2>), "avx2"),
+    ("avx2", 2, Block::F64(avx2::get_2d_noise_64::<Avx2>), "avx2"),
+    ("avx2", 3, Block::F32(avx2::get_3d_noise::<Avx2>), "avx2"),
+    ("avx2", 3, Block::F64(avx2::get_3d_noise_64::<Avx2>), "avx2"),
+    ("sse2", 1, Block::F32(sse2::get_1d_noise::<Sse2>), "sse2"),
+    ("sse2", 1, Block::F64(sse2::get_1d_noise_64::<Sse2>), "sse2"),
+    ("sse2", 2, Block::F32(sse2::get_2d_noise::<Sse2>), "sse2"),
+    // The sse2 fixture holds one sample followed by zeros, which no engine produces, SSE2 has to
+    // match the scalar engine like SSE4.1 does
+    ("sse2", 2, Block::F64(sse2::get_2d_noise_64::<Sse2>), "scalar"),
+    ("sse2", 3, Block::F32(sse2::get_3d_noise::<Sse2>), "sse2"),
+    ("sse2", 3, Block::F64(sse2::get_3d_noise_64::<Sse2>), "sse2"),
+    ("sse41", 1, Block::F32(sse41::get_1d_noise::<Sse41>), "sse41"),
+    ("sse41", 1, Block::F64(sse41::get_1d_noise_64::<Sse41>), "sse41"),
+    ("sse41", 2, Block::F32(sse41::get_2d_noise::<Sse41>), "sse41"),
+    ("sse41", 2, Block::F64(sse41::get_2d_noise_64::<Sse41>), "sse41"),
+    ("sse41", 3, Block::F32(sse41::get_3d_noise::<Sse41>), "sse41"),
+    ("sse41", 3, Block::F64(sse41::get_3d_noise_64::<Sse41>), "sse41"),
+];
+
+/// Generates every block of `engine` with gradient noise and compares it with its fixture
+fn check_engine(engine: &str) {
+    for (_, dim, block, fixture) in CASES.iter().filter(|case| case.0 == engine) {
+        let mut dims = NoiseDimensions::default(*dim);
+        dims.width = W;
+        if *dim > 1 {
+            dims.height = H;
+        }
+        if *dim > 2 {
+            dims.depth = D;
+        }
+        let len = dims.len();
+        let noise_type = GradientSettings::default(dims)
+            .with_legacy_seed(1337)
+            .wrap()
+            .unwrap();
+        let bits = match block {
+            Block::F32(_) => 32,
+            Block::F64(_) => 64,
+        };
+        let file_name = format!(
+            "{}/intrinsics_gradient_{}_{}d_{}_normal.bin",
+            BIN_PATH, bits, dim, fixture
+        );
+        let case = format!("{} {}d {} bit", engine, dim, bits);
+        match block {
+            Block::F32(get_noise) => {
+                let mut noise = vec![0.0; len];
+                unsafe { get_noise(&noise_type, noise.as_mut_ptr()) };
+                let expected = read_from_file_f32(&file_name).unwrap();
+                assert!(expected == noise, "{} differs from {}", case, file_name);
+            }
+            Block::F64(get_noise) => {
+                let mut noise = vec![0.0; len];
+                unsafe { get_noise(&noise_type, noise.as_mut_ptr()) };
+                let expected = read_from_file_f64(&file_name).unwrap();
+                assert!(expected == noise, "{} differs from {}", case, file_name);
+            }
+        }
     }
 }
 
-#[target_feature(enable = "avx2")]
-unsafe fn do_intrinsic_gradient_1_avx2_64_normal() -> [f64; W] {
-    let dims = NoiseDimensions {
-        width: W,
-        ..NoiseDimensions::default(1)
-    };
-
-    let noise_type = GradientSettings::default(dims).with_seed(1337).wrap();
-    let mut noise = [0.0; W];
-    let (_min, _max) = avx2::get_1d_noise_64::<Avx2>(&noise_type, noise.as_mut_ptr());
-    noise
-}
-
 #[test]
-fn test_intrinsic_gradient_1_avx2_64_normal() {
-    let file_name = format!(
-        "{}/{}_{}_{}_{}_{}_{}.bin",
-        BIN_PATH, "intrinsics", "gradient", "64", "1d", "avx2", "normal"
-    );
-    unsafe {
-        let expected = read_from_file_f64(&file_name).unwrap();
-        let noise = &do_intrinsic_gradient_1_avx2_64_normal()[0..expected.len()];
-        assert_eq!(expected, noise);
-    }
-}
-
-#[target_feature(enable = "avx2")]
-unsafe fn do_intrinsic_gradient_2_avx2_32_normal() -> [f32; W * H] {
-    let dims = NoiseDimensions {
-        width: W,
-        height: H,
-        ..NoiseDimensions::default(2)
-    };
-
-    let noise_type = GradientSettings::default(dims).with_seed(1337).wrap();
-    let mut noise = [0.0; W * H];
-    let (_min, _max) = avx2::get_2d_noise::<Avx2>(&noise_type, noise.as_mut_ptr());
-    noise
+fn test_intrinsic_gradient_avx2() {
+    check_engine("avx2");
 }
 
 #[test]
-fn test_intrinsic_gradient_2_avx2_32_normal() {
-    let file_name = format!(
-        "{}/{}_{}_{}_{}_{}_{}.bin",
-        BIN_PATH, "intrinsics", "gradient", "32", "2d", "avx2", "normal"
-    );
-    unsafe {
-        let expected = read_from_file_f32(&file_name).unwrap();
-        let noise = &do_intrinsic_gradient_2_avx2_32_normal()[0..expected.len()];
-        assert_eq!(expected, noise);
-    }
-}
-
-#[target_feature(enable = "avx2")]
-unsafe fn do_intrinsic_gradient_2_avx2_64_normal() -> [f64; W * H] {
-    let dims = NoiseDimensions {
-        width: W,
-        height: H,
-        ..NoiseDimensions::default(2)
-    };
-
-    let noise_type = GradientSettings::default(dims).with_seed(1337).wrap();
-    let mut noise = [0.0; W * H];
-    let (_min, _max) = avx2::get_2d_noise_64::<Avx2>(&noise_type, noise.as_mut_ptr());
-    noise
+fn test_intrinsic_gradient_sse2() {
+    check_engine("sse2");
 }
 
 #[test]
-fn test_intrinsic_gradient_2_avx2_64_normal() {
-    let file_name = format!(
-        "{}/{}_{}_{}_{}_{}_{}.bin",
-        BIN_PATH, "intrinsics", "gradient", "64", "2d", "avx2", "normal"
-    );
-    unsafe {
-        let expected = read_from_file_f64(&file_name).unwrap();
-        let noise = &do_intrinsic_gradient_2_avx2_64_normal()[0..expected.len()];
-        assert_eq!(expected, noise);
-    }
+fn test_intrinsic_gradient_sse41() {
+    check_engine("sse41");
 }
-
-#[target_feature(enable = "avx2")]
-unsafe fn do_intrinsic_gradient_3_avx2_32_normal() -> [f32; W * H * D] {
-    let dims = NoiseDimensions {
-        width: W,
-        height: H,
-        depth: D,
-        ..NoiseDimensions::default(3)
-    };
-
-    let noise_type = GradientSettings::default(dims).with_seed(1337).wrap();
-    let mut noise = [0.0; W * H * D];
-    let (_min, _max) = avx2::get_3d_noise::<Avx2>(&noise_type, noise.as_mut_ptr());
-    noise
-}
-
-#[test]
-fn test_intrinsic_gradient_3_avx2_32_normal() {
-    let file_name = format!(
-        "{}/{}_{}_{}_{}_{}_{}.bin",
-        BIN_PATH, "intrinsics", "gradient", "32", "3d", "avx2", "normal"
-    );
-    unsafe {
-        let noise = do_intrinsic_gradient_3_avx2_32_normal();
-        //save_to_file_f32(&file_name, noise.as_slice()).unwrap();
-        let expected = read_from_file_f32(&file_name).unwrap();
-        assert_eq!(expected, noise);
-    }
-}
-
-#[target_feature(enable = "avx2")]
-unsafe fn do_intrinsic_gradient_3_avx2_64_normal() -> [f64; W * H * D] {
-    let dims = NoiseDimensions {
-        width: W,
-        height: H,
-        depth: D,
-        ..NoiseDimensions::default(3)
-    };
-
-    let noise_type = GradientSettings::default(dims).with_seed(1337).wrap();
-    let mut noise = [0.0; W * H * D];
-    let (_min, _max) = avx2::get_3d_noise_64::<Avx2>(&noise_type, noise.as_mut_ptr());
-    noise
-}
-
-#[test]
-#[should_panic(expected = "not implemented")]
-fn test_intrinsic_gradient_3_avx2_64_normal() {
-    let file_name = format!(
-        "{}/{}_{}_{}_{}_{}_{}.bin",
-        BIN_PATH, "intrinsics", "gradient", "64", "3d", "avx2", "normal"
-    );
-    unsafe {
-        let noise = do_intrinsic_gradient_3_avx2_64_normal();
-        //save_to_file_f64(&file_name, noise.as_slice()).unwrap();
-        let expected = read_from_file_f64(&file_name).unwrap();
-        assert_eq!(expected, noise);
-    }
-}
\ No newline at end of file
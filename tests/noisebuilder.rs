@@ -1,4 +1,10 @@
-use cursednoise::{NoiseBuilder, Settings, SimplexSettings};
+use cursednoise::{
+    get_4d_noise, get_4d_scaled_noise_f64, get_noise_deriv, sample_points_2d, sample_points_2d_f64,
+    sample_points_4d, CellDistanceFunction, CellReturnType, CellularSettings, ChannelLayout,
+    FbmSettings, GradientSettings, Interpolation, Lattice, LatticeSettings, NoiseBuilder,
+    NoiseDimensions, NoiseError, NoiseType, PeriodicSettings, ScaleMode, SeedMode, Settings,
+    SimplexSettings, WarpMode,
+};
 
 mod helpers;
 use helpers::{read_from_file_f32, BIN_PATH};
@@ -7,6 +13,48 @@ const W: usize = 64;
 const H: usize = 32;
 const D: usize = 16;
 
+/// Generates `len` samples of `settings`, for tests that only compare the noise itself
+fn generate<T: Settings + Copy>(settings: &mut T, len: usize) -> Vec<f32> {
+    let mut noise = vec![0.0; len];
+    settings.generate_into(&mut noise).unwrap();
+    noise
+}
+
+fn correlation(a: &[f32], b: &[f32]) -> f32 {
+    let n = a.len() as f32;
+    let mean_a = a.iter().sum::<f32>() / n;
+    let mean_b = b.iter().sum::<f32>() / n;
+    let mut cov = 0.0;
+    let mut var_a = 0.0;
+    let mut var_b = 0.0;
+    for (x, y) in a.iter().zip(b) {
+        cov += (x - mean_a) * (y - mean_b);
+        var_a += (x - mean_a) * (x - mean_a);
+        var_b += (y - mean_b) * (y - mean_b);
+    }
+    cov / (var_a * var_b).sqrt()
+}
+
+fn assert_within(range: (f32, f32), bounds: (f32, f32)) {
+    assert!(
+        bounds.0 <= range.0 && range.1 <= bounds.1,
+        "{:?} outside {:?}",
+        range,
+        bounds
+    );
+}
+
+/// Generates `settings` in both precisions and checks they agree
+fn assert_f64_matches_f32<T: Settings + Copy>(settings: T, len: usize) {
+    let mut noise = vec![0.0; len];
+    let mut noise_f64 = vec![0.0; len];
+    settings.generate_into(&mut noise).unwrap();
+    settings.generate_into_f64(&mut noise_f64).unwrap();
+    for (n, n64) in noise.iter().zip(&noise_f64) {
+        assert!((*n as f64 - n64).abs() < 1e-3, "{} != {}", n, n64);
+    }
+}
+
 mod noise {
     use super::*;
 
@@ -27,11 +75,12 @@ mod noise {
                     let mut noise = [0.0; W];
                     let (_min, _max) = NoiseBuilder::fbm_1d(W)
                         .with_freq(0.01)
-                        .with_seed(1337)
+                        .with_legacy_seed(1337)
                         .with_octaves(5)
                         .with_gain(2.0)
                         .with_lacunarity(0.5)
-                        .generate(noise.as_mut_ptr());
+                        .generate_into(&mut noise)
+                        .unwrap();
 
                     let expected = read_from_file_f32(&file_name).unwrap();
                     assert_eq!(expected, noise);
@@ -45,11 +94,12 @@ mod noise {
                     let mut noise = [0.0; W * H];
                     let (_min, _max) = NoiseBuilder::fbm_2d(W, H)
                         .with_freq_2d(0.04, 0.01)
-                        .with_seed(1337)
+                        .with_legacy_seed(1337)
                         .with_octaves(5)
                         .with_gain(2.0)
                         .with_lacunarity(0.5)
-                        .generate(noise.as_mut_ptr());
+                        .generate_into(&mut noise)
+                        .unwrap();
 
                     //save_to_file_f32(&file_name, noise.as_slice()).unwrap();
                     let expected = read_from_file_f32(&file_name).unwrap();
@@ -64,11 +114,12 @@ mod noise {
                     let mut noise = [0.0; W * H * D];
                     let (_min, _max) = NoiseBuilder::fbm_3d(W, H, D)
                         .with_freq_3d(0.05, 0.04, 0.01)
-                        .with_seed(1337)
+                        .with_legacy_seed(1337)
                         .with_octaves(5)
                         .with_gain(2.0)
                         .with_lacunarity(0.5)
-                        .generate(noise.as_mut_ptr());
+                        .generate_into(&mut noise)
+                        .unwrap();
 
                     //save_to_file_f32(&file_name, noise.as_slice()).unwrap();
                     let expected = read_from_file_f32(&file_name).unwrap();
@@ -88,11 +139,12 @@ mod noise {
                     let mut noise = [0.0; W];
                     let (_min, _max) = NoiseBuilder::fbm_1d_offset(16.0, W)
                         .with_freq(0.01)
-                        .with_seed(1337)
+                        .with_legacy_seed(1337)
                         .with_octaves(5)
                         .with_gain(2.0)
                         .with_lacunarity(0.5)
-                        .generate(noise.as_mut_ptr());
+                        .generate_into(&mut noise)
+                        .unwrap();
 
                     //save_to_file_f32(&file_name, noise.as_slice()).unwrap();
                     let expected = read_from_file_f32(&file_name).unwrap();
@@ -107,11 +159,12 @@ mod noise {
                     let mut noise = [0.0; W * H];
                     let (_min, _max) = NoiseBuilder::fbm_2d_offset(16.0, W, 32.0, H)
                         .with_freq_2d(0.04, 0.01)
-                        .with_seed(1337)
+                        .with_legacy_seed(1337)
                         .with_octaves(5)
                         .with_gain(2.0)
                         .with_lacunarity(0.5)
-                        .generate(noise.as_mut_ptr());
+                        .generate_into(&mut noise)
+                        .unwrap();
 
                     //save_to_file_f32(&file_name, noise.as_slice()).unwrap();
                     let expected = read_from_file_f32(&file_name).unwrap();
@@ -127,11 +180,12 @@ mod noise {
                     let (_min, _max) =
                         NoiseBuilder::fbm_3d_offset(16.0, W, 32.0, H, 64.0, D)
                             .with_freq_3d(0.05, 0.04, 0.01)
-                            .with_seed(1337)
+                            .with_legacy_seed(1337)
                             .with_octaves(5)
                             .with_gain(2.0)
                             .with_lacunarity(0.5)
-                            .generate(noise.as_mut_ptr());
+                            .generate_into(&mut noise)
+                            .unwrap();
 
                     //save_to_file_f32(&file_name, noise.as_slice()).unwrap();
                     let expected = read_from_file_f32(&file_name).unwrap();
@@ -139,6 +193,203 @@ mod noise {
                 }
             }
         }
+
+        mod octaves {
+            use super::*;
+
+            #[test]
+            fn test_noisebuilder_fbm_octave_options() {
+                let mut classic = vec![0.0; W * H];
+                let mut fbm = NoiseBuilder::fbm_2d(W, H);
+                fbm.with_seed(9)
+                    .with_freq(0.1)
+                    .with_lacunarity(2.0)
+                    .with_gain(0.5)
+                    .with_octaves(5);
+                let bounds = fbm.bounds();
+                fbm.generate_into(&mut classic).unwrap();
+                // Every octave samples its lattice origin at (0, 0), where simplex noise is zero
+                assert_eq!(classic[0], 0.0);
+
+                let mut decorrelated = vec![0.0; W * H];
+                let range = fbm
+                    .with_octave_seeds(true)
+                    .with_octave_offsets(true)
+                    .generate_into(&mut decorrelated)
+                    .unwrap();
+                assert_ne!(decorrelated[0], 0.0);
+                assert_ne!(classic, decorrelated);
+                assert!(bounds.0 <= range.0 && range.1 <= bounds.1);
+
+                let mut seeds_only = vec![0.0; W * H];
+                fbm.with_octave_offsets(false)
+                    .generate_into(&mut seeds_only)
+                    .unwrap();
+                assert_ne!(classic, seeds_only);
+                // The first octave keeps the base seed and origin
+                assert_eq!(classic[0], seeds_only[0]);
+            }
+
+            #[test]
+            fn test_noisebuilder_octave_seeds_uncorrelated() {
+                // At a lacunarity and gain of 1 both octaves sample the same points, so the second
+                // octave is what it adds to the first
+                let octaves = |fbm: &mut FbmSettings, len: usize| {
+                    fbm.with_freq(0.37)
+                        .with_lacunarity(1.0)
+                        .with_gain(1.0)
+                        .with_octave_seeds(true);
+                    let first = generate(fbm.with_octaves(1), len);
+                    let both = generate(fbm.with_octaves(2), len);
+                    let second: Vec<f32> = both.iter().zip(&first).map(|(b, f)| b - f).collect();
+                    (first, second)
+                };
+                let blocks = [
+                    (1, NoiseBuilder::fbm_1d(W * H), W * H),
+                    (2, NoiseBuilder::fbm_2d(W, H), W * H),
+                    (3, NoiseBuilder::fbm_3d(W, H, 1), W * H),
+                    (4, NoiseBuilder::fbm_4d(8, 8, 8, 8), 8 * 8 * 8 * 8),
+                ];
+                for (dim, mut fbm, len) in blocks {
+                    for legacy in [false, true] {
+                        if legacy {
+                            fbm.with_legacy_seed(1337);
+                        } else {
+                            fbm.with_seed(1337);
+                        }
+                        let (first, second) = octaves(&mut fbm, len);
+                        let r = correlation(&first, &second);
+                        assert!(
+                            r.abs() < 0.3,
+                            "{}d, legacy {}: correlation {}",
+                            dim,
+                            legacy,
+                            r
+                        );
+                    }
+                }
+            }
+
+            #[test]
+            fn test_noisebuilder_fbm_normalized() {
+                let mut noise = vec![0.0; W * H * D];
+                for octaves in [1, 3, 8] {
+                    let mut fbm = NoiseBuilder::fbm_1d(W);
+                    fbm.with_lacunarity(2.0)
+                        .with_gain(0.5)
+                        .with_octaves(octaves)
+                        .with_normalized(true);
+                    let (min, max) = fbm.generate_into(&mut noise).unwrap();
+                    assert!(
+                        -1.0 <= min && max <= 1.0,
+                        "1d, {} octaves: {} to {}",
+                        octaves,
+                        min,
+                        max
+                    );
+
+                    let mut fbm = NoiseBuilder::fbm_3d(W, H, D);
+                    fbm.with_lacunarity(2.0)
+                        .with_gain(0.5)
+                        .with_octaves(octaves)
+                        .with_normalized(true);
+                    let (min, max) = fbm.generate_into(&mut noise).unwrap();
+                    assert!(
+                        -1.0 <= min && max <= 1.0,
+                        "3d, {} octaves: {} to {}",
+                        octaves,
+                        min,
+                        max
+                    );
+                }
+
+                // A single normalized octave is just the gradient noise
+                assert_eq!(
+                    generate(
+                        NoiseBuilder::fbm_2d(W, H)
+                            .with_seed(3)
+                            .with_octaves(1)
+                            .with_normalized(true),
+                        W * H
+                    ),
+                    generate(NoiseBuilder::gradient_2d(W, H).with_seed(3), W * H)
+                );
+
+                // Normalizing leaves 1d unweighted, every octave still counts equally
+                let mut fbm = NoiseBuilder::fbm_1d(W);
+                let plain = generate(fbm.with_gain(0.5), W);
+                let normalized = generate(fbm.with_normalized(true), W);
+                let octaves = fbm.octaves as f32;
+                for (p, n) in plain.iter().zip(&normalized) {
+                    assert!((p / octaves - n).abs() < 1e-5, "{} != {}", p / octaves, n);
+                }
+            }
+
+            #[test]
+            fn test_noisebuilder_fbm_weighted_1d() {
+                let (freq, lacunarity, gain) = (0.05, 2.0, 0.5);
+                let mut fbm = NoiseBuilder::fbm_1d(W);
+                fbm.with_seed(9)
+                    .with_freq(freq)
+                    .with_lacunarity(lacunarity)
+                    .with_gain(gain)
+                    .with_octaves(4);
+                let plain = generate(&mut fbm, W);
+                let weighted = generate(fbm.with_weighted_1d(true), W);
+                assert_ne!(plain, weighted);
+
+                // Octave i is the gradient noise at lacunarity^i times the frequency, times gain^i
+                let mut expected = vec![0.0; W];
+                for octave in 0..4 {
+                    let scale = lacunarity.powi(octave);
+                    let mut gradient = NoiseBuilder::gradient_1d(W);
+                    gradient.with_seed(9).with_freq(freq * scale);
+                    for (e, n) in expected.iter_mut().zip(generate(&mut gradient, W)) {
+                        *e += n * gain.powi(octave);
+                    }
+                }
+                for (e, w) in expected.iter().zip(&weighted) {
+                    assert!((e - w).abs() < 1e-5, "{} != {}", e, w);
+                }
+
+                // The bounds follow the weighting, unweighted octaves each count fully
+                let mut noise = vec![0.0; W];
+                assert_within(fbm.generate_into(&mut noise).unwrap(), fbm.bounds());
+                assert_eq!(fbm.with_weighted_1d(false).bounds(), (-4.0, 4.0));
+                fbm.with_weighted_1d(true).with_normalized(true);
+                assert_within(fbm.generate_into(&mut noise).unwrap(), (-1.0, 1.0));
+            }
+
+            fn fractional_octaves<T: Settings + SimplexSettings + Copy>(mut settings: T) {
+                let mut octaves = |count: f32| generate(settings.with_octaves_f32(count), W * H);
+                let three = octaves(3.0);
+                let half = octaves(3.5);
+                let almost_four = octaves(3.999);
+                let four = octaves(4.0);
+
+                assert_eq!(three, generate(settings.with_octaves(3), W * H));
+
+                for i in 0..W * H {
+                    let (low, high) = (three[i].min(four[i]), three[i].max(four[i]));
+                    assert!(low - 1e-5 <= half[i] && half[i] <= high + 1e-5);
+                    assert!((almost_four[i] - four[i]).abs() < 1e-2);
+                }
+                assert_ne!(three, half);
+            }
+
+            #[test]
+            fn test_noisebuilder_fractional_octaves() {
+                fractional_octaves(*NoiseBuilder::fbm_2d(W, H).with_seed(5).with_gain(0.5));
+                fractional_octaves(*NoiseBuilder::ridge_2d(W, H).with_seed(5));
+                fractional_octaves(*NoiseBuilder::turbulence_2d(W, H).with_seed(5));
+
+                let mut fbm = NoiseBuilder::fbm_2d(W, H);
+                fbm.with_octaves_f32(2.5);
+                assert_eq!(fbm.octaves, 2);
+                fbm.octave_options.fraction = 1.5;
+                assert_eq!(fbm.validate(), Err(NoiseError::InvalidOctaveFraction));
+            }
+        }
     }
     mod gradient {
         use super::*;
@@ -157,8 +408,9 @@ mod noise {
                     let mut noise = [0.0; W];
                     let (_min, _max) = NoiseBuilder::gradient_1d(W)
                         .with_freq(0.01)
-                        .with_seed(1337)
-                        .generate(noise.as_mut_ptr());
+                        .with_legacy_seed(1337)
+                        .generate_into(&mut noise)
+                        .unwrap();
 
                     //save_to_file_f32(&file_name, noise.as_slice()).unwrap();
                     let expected = read_from_file_f32(&file_name).unwrap();
@@ -173,8 +425,9 @@ mod noise {
                     let mut noise = [0.0; W * H];
                     let (_min, _max) = NoiseBuilder::gradient_2d(W, H)
                         .with_freq_2d(0.04, 0.01)
-                        .with_seed(1337)
-                        .generate(noise.as_mut_ptr());
+                        .with_legacy_seed(1337)
+                        .generate_into(&mut noise)
+                        .unwrap();
 
                     //save_to_file_f32(&file_name, noise.as_slice()).unwrap();
                     let expected = read_from_file_f32(&file_name).unwrap();
@@ -189,8 +442,9 @@ mod noise {
                     let mut noise = [0.0; W * H * D];
                     let (_min, _max) = NoiseBuilder::gradient_3d(W, H, D)
                         .with_freq_3d(0.05, 0.04, 0.01)
-                        .with_seed(1337)
-                        .generate(noise.as_mut_ptr());
+                        .with_legacy_seed(1337)
+                        .generate_into(&mut noise)
+                        .unwrap();
 
                     //save_to_file_f32(&file_name, noise.as_slice()).unwrap();
                     let expected = read_from_file_f32(&file_name).unwrap();
@@ -210,8 +464,9 @@ mod noise {
                     let mut noise = [0.0; W];
                     let (_min, _max) = NoiseBuilder::gradient_1d_offset(16.0, W)
                         .with_freq(0.01)
-                        .with_seed(1337)
-                        .generate(noise.as_mut_ptr());
+                        .with_legacy_seed(1337)
+                        .generate_into(&mut noise)
+                        .unwrap();
 
                     //save_to_file_f32(&file_name, noise.as_slice()).unwrap();
                     let expected = read_from_file_f32(&file_name).unwrap();
@@ -226,8 +481,9 @@ mod noise {
                     let mut noise = [0.0; W * H];
                     let (_min, _max) = NoiseBuilder::gradient_2d_offset(16.0, W, 32.0, H)
                         .with_freq_2d(0.04, 0.01)
-                        .with_seed(1337)
-                        .generate(noise.as_mut_ptr());
+                        .with_legacy_seed(1337)
+                        .generate_into(&mut noise)
+                        .unwrap();
 
                     //save_to_file_f32(&file_name, noise.as_slice()).unwrap();
                     let expected = read_from_file_f32(&file_name).unwrap();
@@ -243,8 +499,9 @@ mod noise {
                     let (_min, _max) =
                         NoiseBuilder::gradient_3d_offset(16.0, W, 32.0, H, 64.0, D)
                             .with_freq_3d(0.05, 0.04, 0.01)
-                            .with_seed(1337)
-                            .generate(noise.as_mut_ptr());
+                            .with_legacy_seed(1337)
+                            .generate_into(&mut noise)
+                            .unwrap();
 
                     //save_to_file_f32(&file_name, noise.as_slice()).unwrap();
                     let expected = read_from_file_f32(&file_name).unwrap();
@@ -252,5 +509,1210 @@ mod noise {
                 }
             }
         }
+
+        mod seed {
+            use super::*;
+
+            #[test]
+            fn test_noisebuilder_seed() {
+                // High enough frequency for a few hundred cells, so the correlation is meaningful
+                let seeded = |seed: u64| {
+                    generate(
+                        NoiseBuilder::gradient_2d(W, H)
+                            .with_seed(seed)
+                            .with_freq(0.37),
+                        W * H,
+                    )
+                };
+                let legacy = |seed: i32| {
+                    generate(
+                        NoiseBuilder::gradient_2d(W, H)
+                            .with_legacy_seed(seed)
+                            .with_freq(0.37),
+                        W * H,
+                    )
+                };
+
+                assert_eq!(seeded(1), seeded(1));
+                assert!(correlation(&seeded(1), &seeded(2)).abs() < 0.3);
+                // Bits above the 32 the kernels hash with still change the noise
+                assert_ne!(seeded(5), seeded(5 + (1 << 40)));
+                assert_ne!(seeded(1337), legacy(1337));
+
+                let mut dim = NoiseDimensions::default(2);
+                dim.seed = -3i32 as u64;
+                dim.seed_mode = SeedMode::Legacy;
+                assert_eq!(dim.seed_32(), -3);
+                assert_eq!(dim.seed_64(), -3);
+            }
+
+            #[test]
+            fn test_noisebuilder_seed_1d() {
+                // 1d gradients only take 16 values, every bit of the seed has to reach the hash for
+                // this many seeds to all give different noise
+                let mut noises: Vec<Vec<f32>> = (0..4096)
+                    .map(|seed| {
+                        generate(
+                            NoiseBuilder::gradient_1d(64)
+                                .with_seed(seed)
+                                .with_freq(0.37),
+                            64,
+                        )
+                    })
+                    .collect();
+                noises.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                noises.dedup();
+                assert_eq!(noises.len(), 4096);
+            }
+
+            #[test]
+            fn test_noisebuilder_seed_4d() {
+                // Legacy seeds reach the kernels as they are, the 4d gradients used to only see 5
+                // bits of them
+                let legacy = |seed: i32| {
+                    let mut gradient = NoiseBuilder::gradient_4d(8, 8, 8, 8);
+                    generate(
+                        gradient.with_legacy_seed(seed).with_freq(0.37),
+                        8 * 8 * 8 * 8,
+                    )
+                };
+                assert!(correlation(&legacy(0), &legacy(32)).abs() < 0.3);
+            }
+        }
+
+        mod step {
+            use super::*;
+
+            #[test]
+            fn test_noisebuilder_step() {
+                let mut stepped = vec![0.0; W * H];
+                NoiseBuilder::gradient_2d_offset(4.0, W, -2.0, H)
+                    .with_seed(1337)
+                    .with_step_2d(2.0, 0.5)
+                    .with_freq(0.03)
+                    .generate_into(&mut stepped)
+                    .unwrap();
+
+                let xs: Vec<f32> = (0..W * H).map(|i| 4.0 + (i % W) as f32 * 2.0).collect();
+                let ys: Vec<f32> = (0..W * H).map(|i| -2.0 + (i / W) as f32 * 0.5).collect();
+                let mut points = vec![0.0; W * H];
+                let settings = *NoiseBuilder::gradient_2d(1, 1)
+                    .with_seed(1337)
+                    .with_freq(0.03);
+                sample_points_2d(&settings, &xs, &ys, &mut points).unwrap();
+
+                for (i, (s, p)) in stepped.iter().zip(&points).enumerate() {
+                    assert!((s - p).abs() < 1e-5, "sample {} differs: {} != {}", i, s, p);
+                }
+            }
+        }
+
+        mod lattice {
+            use super::*;
+
+            #[test]
+            fn test_noisebuilder_lattice() {
+                let generate = |lattice: Lattice| {
+                    let mut noise = vec![0.0; W * H * D];
+                    let range = NoiseBuilder::gradient_3d(W, H, D)
+                        .with_seed(3)
+                        .with_freq(0.1)
+                        .with_lattice(lattice)
+                        .generate_into(&mut noise)
+                        .unwrap();
+                    assert!(
+                        -1.0 <= range.0 && range.1 <= 1.0,
+                        "{:?} out of range",
+                        lattice
+                    );
+                    // Spread over most of the range, not squashed by a scale that is far off
+                    assert!(
+                        range.0 < -0.5 && range.1 > 0.5,
+                        "{:?} only reaches {:?}",
+                        lattice,
+                        range
+                    );
+                    noise
+                };
+                let simplex = generate(Lattice::Simplex);
+                let fast = generate(Lattice::OpenSimplex2);
+                let smooth = generate(Lattice::OpenSimplex2S);
+                assert_ne!(simplex, fast);
+                assert_ne!(fast, smooth);
+
+                let mut fbm = NoiseBuilder::fbm_4d(8, 8, 8, 8);
+                fbm.with_seed(3)
+                    .with_octaves(3)
+                    .with_lattice(Lattice::OpenSimplex2S);
+                let bounds = fbm.bounds();
+                let mut noise = vec![0.0; 8 * 8 * 8 * 8];
+                let range = fbm.generate_into(&mut noise).unwrap();
+                assert!(bounds.0 <= range.0 && range.1 <= bounds.1);
+
+                // OpenSimplex noise hashes the low 32 bits of the seed in both precisions
+                let mut noise_f64 = vec![0.0; 8 * 8 * 8 * 8];
+                fbm.generate_into_f64(&mut noise_f64).unwrap();
+                for (a, b) in noise.iter().zip(&noise_f64) {
+                    assert!((f64::from(*a) - b).abs() < 1e-3);
+                }
+                let mut gradient = NoiseBuilder::gradient_2d(W, H);
+                gradient.with_lattice(Lattice::OpenSimplex2);
+                let xs: Vec<f32> = (0..8).map(|i| i as f32 * 0.7 + 0.5).collect();
+                let ys: Vec<f32> = (0..8).map(|i| i as f32 * -1.3 + 1.5).collect();
+                let mut points = [0.0; 8];
+                sample_points_2d(&gradient, &xs, &ys, &mut points).unwrap();
+                let xs_f64: Vec<f64> = xs.iter().map(|&x| x.into()).collect();
+                let ys_f64: Vec<f64> = ys.iter().map(|&y| y.into()).collect();
+                let mut points_f64 = [0.0; 8];
+                sample_points_2d_f64(&gradient, &xs_f64, &ys_f64, &mut points_f64).unwrap();
+                for (a, b) in points.iter().zip(&points_f64) {
+                    assert!((f64::from(*a) - b).abs() < 1e-3);
+                }
+                let mut warp = NoiseBuilder::domain_warp_2d(W, H);
+                warp.with_inner(gradient).with_warp(gradient);
+                warp.generate_scaled_into_f64(0.0, 1.0, &mut vec![0.0; W * H])
+                    .unwrap();
+                assert_eq!(
+                    NoiseBuilder::gradient_2d(W, H)
+                        .with_freq(3.0 / W as f32)
+                        .with_period_2d(W as f32, 0.0)
+                        .with_lattice(Lattice::OpenSimplex2)
+                        .generate_into(&mut vec![0.0; W * H]),
+                    Err(NoiseError::UnsupportedPeriod)
+                );
+            }
+
+            #[test]
+            fn test_noisebuilder_perlin() {
+                let mut fbm = NoiseBuilder::fbm_3d(W, H, D);
+                fbm.with_seed(11)
+                    .with_freq(0.05)
+                    .with_octaves(3)
+                    .with_lattice(Lattice::Perlin);
+                let bounds = fbm.bounds();
+                let mut noise = vec![0.0; W * H * D];
+                let range = fbm.generate_into(&mut noise).unwrap();
+                assert!(bounds.0 <= range.0 && range.1 <= bounds.1);
+                // Perlin noise hashes the low 32 bits of the seed in both precisions
+                let mut noise_f64 = vec![0.0; W * H * D];
+                fbm.generate_into_f64(&mut noise_f64).unwrap();
+                for (a, b) in noise.iter().zip(&noise_f64) {
+                    assert!((f64::from(*a) - b).abs() < 1e-3);
+                }
+
+                assert_ne!(
+                    generate(
+                        NoiseBuilder::ridge_2d(W, H).with_lattice(Lattice::Perlin),
+                        W * H
+                    ),
+                    generate(
+                        NoiseBuilder::turbulence_2d(W, H).with_lattice(Lattice::Perlin),
+                        W * H
+                    )
+                );
+
+                assert_eq!(
+                    NoiseBuilder::gradient_4d(4, 4, 4, 4)
+                        .with_lattice(Lattice::Perlin)
+                        .generate_into(&mut vec![0.0; 4 * 4 * 4 * 4]),
+                    Err(NoiseError::UnsupportedDimension { dim: 4 })
+                );
+                // Settings built in 2d are still checked against the dimension they are sampled in
+                let mut gradient = NoiseBuilder::gradient_2d(W, H);
+                gradient.with_lattice(Lattice::Perlin);
+                let points = vec![0.5; 8];
+                assert_eq!(
+                    sample_points_4d(&gradient, &points, &points, &points, &points, &mut [0.0; 8]),
+                    Err(NoiseError::UnsupportedDimension { dim: 4 })
+                );
+                let mut warp = NoiseBuilder::domain_warp_4d(4, 4, 4, 4);
+                warp.with_inner(gradient);
+                assert_eq!(
+                    warp.validate(),
+                    Err(NoiseError::UnsupportedDimension { dim: 4 })
+                );
+            }
+
+            #[test]
+            fn test_noisebuilder_value() {
+                let mut hermite = vec![0.0; W * H];
+                let mut cubic = vec![0.0; W * H];
+                let mut fbm = NoiseBuilder::fbm_2d(W, H);
+                fbm.with_seed(5)
+                    .with_freq(0.1)
+                    .with_lattice(Lattice::Value(Interpolation::Hermite));
+                let bounds = fbm.bounds();
+                let range = fbm.generate_into(&mut hermite).unwrap();
+                assert!(bounds.0 <= range.0 && range.1 <= bounds.1);
+                fbm.with_lattice(Lattice::ValueCubic)
+                    .generate_into(&mut cubic)
+                    .unwrap();
+                assert_ne!(hermite, cubic);
+                // Value noise hashes the low 32 bits of the seed in both precisions
+                let mut cubic_f64 = vec![0.0; W * H];
+                fbm.generate_into_f64(&mut cubic_f64).unwrap();
+                for (a, b) in cubic.iter().zip(&cubic_f64) {
+                    assert!((f64::from(*a) - b).abs() < 1e-3);
+                }
+
+                assert_eq!(
+                    NoiseBuilder::turbulence_4d(4, 4, 4, 4)
+                        .with_lattice(Lattice::Value(Interpolation::Linear))
+                        .generate_into(&mut vec![0.0; 4 * 4 * 4 * 4]),
+                    Err(NoiseError::UnsupportedDimension { dim: 4 })
+                );
+                let points = vec![0.5; 8];
+                assert_eq!(
+                    sample_points_4d(&fbm, &points, &points, &points, &points, &mut [0.0; 8]),
+                    Err(NoiseError::UnsupportedDimension { dim: 4 })
+                );
+                let mut warp = NoiseBuilder::domain_warp_4d(4, 4, 4, 4);
+                warp.with_warp(
+                    *NoiseBuilder::turbulence_3d(4, 4, 4).with_lattice(Lattice::ValueCubic),
+                );
+                let mut noise = vec![0.0; 4 * 4 * 4 * 4];
+                assert_eq!(
+                    warp.generate_into(&mut noise),
+                    Err(NoiseError::UnsupportedDimension { dim: 4 })
+                );
+            }
+        }
+    }
+    mod turbulence {
+        use super::*;
+
+        #[test]
+        fn test_noisebuilder_turbulence() {
+            // A single octave folds the gradient noise of the same seed at zero
+            let gradient = generate(NoiseBuilder::gradient_3d(W, H, D).with_seed(3), W * H * D);
+            let mut settings = NoiseBuilder::turbulence_3d(W, H, D);
+            settings.with_seed(3).with_octaves(1);
+            let single = generate(&mut settings, W * H * D);
+            for (t, g) in single.iter().zip(&gradient) {
+                assert_eq!(*t, g.abs());
+            }
+
+            // and every octave after it only adds
+            let mut noise = vec![0.0; W * H * D];
+            let range = settings.with_octaves(3).generate_into(&mut noise).unwrap();
+            assert_within(range, settings.bounds());
+            assert!(noise.iter().zip(&single).all(|(n, s)| n >= s));
+            assert_ne!(noise, generate(settings.with_lacunarity(3.0), W * H * D));
+            assert_ne!(
+                noise,
+                generate(settings.with_lacunarity(2.0).with_gain(0.7), W * H * D)
+            );
+
+            let mut noise = vec![0.0; 8 * 8 * 4 * 2];
+            let settings = *NoiseBuilder::turbulence_4d(8, 8, 4, 2)
+                .with_seed(3)
+                .with_octaves_f32(2.5);
+            assert_within(
+                settings.generate_into(&mut noise).unwrap(),
+                settings.bounds(),
+            );
+            assert!(noise.iter().all(|&n| n >= 0.0));
+
+            assert_eq!(
+                NoiseBuilder::turbulence_2d(W, H).with_octaves(0).validate(),
+                Err(NoiseError::ZeroOctaves)
+            );
+            assert_eq!(
+                NoiseBuilder::turbulence_2d(W, H)
+                    .with_lacunarity(f32::NAN)
+                    .validate(),
+                Err(NoiseError::NonFiniteParameter)
+            );
+        }
+    }
+
+    mod cellular {
+        use super::*;
+
+        const RETURN_TYPES: [CellReturnType; 5] = [
+            CellReturnType::F1,
+            CellReturnType::F2,
+            CellReturnType::F2MinusF1,
+            CellReturnType::CellValue,
+            CellReturnType::DistanceToEdge,
+        ];
+
+        #[test]
+        fn test_noisebuilder_cellular_return_types() {
+            let mut settings = NoiseBuilder::cellular_3d(W, H, D);
+            settings.with_seed(5).with_freq(0.1);
+            let [f1, f2, gap, value, edge] = RETURN_TYPES.map(|return_type| {
+                let mut noise = vec![0.0; W * H * D];
+                let range = settings
+                    .with_return_type(return_type)
+                    .generate_into(&mut noise)
+                    .unwrap();
+                assert_within(range, settings.bounds());
+                noise
+            });
+
+            for i in 0..W * H * D {
+                assert!(f1[i] <= f2[i], "f1 {} f2 {}", f1[i], f2[i]);
+                assert_eq!(gap[i], f2[i] - f1[i]);
+                // The border with the second closest point is at most halfway to it, and no
+                // border is closer than half the gap between both points
+                assert!(
+                    edge[i] >= gap[i] * 0.5 - 1e-4,
+                    "edge {} gap {}",
+                    edge[i],
+                    gap[i]
+                );
+                assert!(edge[i] <= (f1[i] + f2[i]) * 0.5 + 1e-4, "edge {}", edge[i]);
+            }
+
+            // Samples 0.1 apart mostly share their closest point and so its value
+            let mut values: Vec<u32> = value.iter().map(|v| v.to_bits()).collect();
+            values.sort_unstable();
+            values.dedup();
+            assert!(
+                1 < values.len() && values.len() < W * H * D / 100,
+                "{} distinct values",
+                values.len()
+            );
+        }
+
+        #[test]
+        fn test_noisebuilder_cellular_distance_functions() {
+            let mut settings = NoiseBuilder::cellular_2d(W, H);
+            settings.with_seed(5).with_freq(0.1);
+            let [euclidean, manhattan, chebyshev, natural] = [
+                CellDistanceFunction::Euclidean,
+                CellDistanceFunction::Manhattan,
+                CellDistanceFunction::Chebyshev,
+                CellDistanceFunction::Natural,
+            ]
+            .map(|distance| {
+                let mut noise = vec![0.0; W * H];
+                let range = settings
+                    .with_distance_function(distance)
+                    .generate_into(&mut noise)
+                    .unwrap();
+                assert_within(range, settings.bounds());
+                noise
+            });
+            // Each measure is at least the one before for every point, so for the closest too
+            for i in 0..W * H {
+                assert!(chebyshev[i] <= euclidean[i] + 1e-6);
+                assert!(euclidean[i] <= manhattan[i] + 1e-6);
+                assert!(manhattan[i] <= natural[i]);
+            }
+
+            // 1d cellular noise is the row of 2d noise at y = 0
+            let mut dim = NoiseDimensions::default(1);
+            dim.width = W;
+            let row = generate(
+                CellularSettings::default(dim)
+                    .with_seed(5)
+                    .with_freq(0.1)
+                    .with_distance_function(CellDistanceFunction::Natural),
+                W,
+            );
+            assert_eq!(row, natural[..W]);
+        }
+
+        #[test]
+        fn test_noisebuilder_cellular_validate() {
+            let mut noise = vec![0.0; 4 * 4 * 4 * 4];
+            let mut dim = NoiseDimensions::default(4);
+            dim.width = 4;
+            dim.height = 4;
+            dim.depth = 4;
+            dim.time = 4;
+            let settings = CellularSettings::default(dim);
+            assert_eq!(
+                settings.validate(),
+                Err(NoiseError::UnsupportedDimension { dim: 4 })
+            );
+            assert_eq!(
+                settings.generate_into(&mut noise),
+                Err(NoiseError::UnsupportedDimension { dim: 4 })
+            );
+            assert!(noise.iter().all(|&n| n == 0.0));
+
+            assert_eq!(
+                NoiseBuilder::cellular_2d(W, H)
+                    .with_jitter(f32::NAN)
+                    .validate(),
+                Err(NoiseError::NonFiniteParameter)
+            );
+        }
+    }
+
+    mod billow {
+        use super::*;
+
+        #[test]
+        fn test_noisebuilder_billow() {
+            let mut billow = vec![0.0; W * H];
+            let mut settings = NoiseBuilder::billow_2d(W, H);
+            settings
+                .with_seed(3)
+                .with_freq(0.05)
+                .with_lacunarity(2.0)
+                .with_gain(0.5)
+                .with_octaves(3);
+            assert_within(
+                settings.generate_into(&mut billow).unwrap(),
+                settings.bounds(),
+            );
+            let mut turbulence = NoiseBuilder::turbulence_2d(W, H);
+            turbulence
+                .with_seed(3)
+                .with_freq(0.05)
+                .with_lacunarity(2.0)
+                .with_gain(0.5)
+                .with_octaves(3);
+            let turbulence = generate(&mut turbulence, W * H);
+            // Every octave is 2|n| - 1 instead of |n|, 1 + 0.5 + 0.25 is taken off in total
+            for (b, t) in billow.iter().zip(&turbulence) {
+                assert!(
+                    (b - (2.0 * t - 1.75)).abs() < 1e-5,
+                    "{} != 2 * {} - 1.75",
+                    b,
+                    t
+                );
+            }
+
+            let mut noise = vec![0.0; W * H * D];
+            let settings = *NoiseBuilder::billow_3d(W, H, D)
+                .with_seed(3)
+                .with_freq(0.05);
+            assert_within(
+                settings.generate_into(&mut noise).unwrap(),
+                settings.bounds(),
+            );
+            let mut noise_f64 = vec![0.0; W * H];
+            let settings = *NoiseBuilder::billow_2d_offset(-7.0, W, 3.0, H).with_seed(3);
+            settings.generate_into(&mut billow).unwrap();
+            settings.generate_into_f64(&mut noise_f64).unwrap();
+            for (n, n64) in billow.iter().zip(&noise_f64) {
+                assert!((*n as f64 - n64).abs() < 1e-4, "{} != {}", n, n64);
+            }
+
+            // Billow folds the same octaves as turbulence in 4d too
+            let mut billow = vec![0.0; 8 * 8 * 4 * 2];
+            let settings = *NoiseBuilder::billow_4d(8, 8, 4, 2)
+                .with_seed(3)
+                .with_freq(0.05);
+            assert_within(
+                settings.generate_into(&mut billow).unwrap(),
+                settings.bounds(),
+            );
+            let turbulence = generate(
+                NoiseBuilder::turbulence_4d(8, 8, 4, 2)
+                    .with_seed(3)
+                    .with_freq(0.05),
+                8 * 8 * 4 * 2,
+            );
+            for (b, t) in billow.iter().zip(&turbulence) {
+                assert!(
+                    (b - (2.0 * t - 1.75)).abs() < 1e-5,
+                    "{} != 2 * {} - 1.75",
+                    b,
+                    t
+                );
+            }
+            let points = vec![0.5; 8];
+            let mut sampled = [0.0; 8];
+            sample_points_4d(&settings, &points, &points, &points, &points, &mut sampled).unwrap();
+            assert!(sampled.iter().all(|&n| -1.75 <= n && n <= 1.75));
+
+            let mut warp = NoiseBuilder::domain_warp_4d(8, 8, 4, 2);
+            warp.with_warp(NoiseBuilder::billow_4d(8, 8, 4, 2));
+            assert_eq!(warp.validate(), Ok(()));
+            assert_within(warp.generate_into(&mut billow).unwrap(), warp.bounds());
+        }
+    }
+
+    mod white {
+        use super::*;
+
+        #[test]
+        fn test_noisebuilder_white() {
+            let mut block = vec![0.0; W * H];
+            let mut white = NoiseBuilder::white_2d(W, H);
+            white.with_seed(42);
+            let range = white.generate_into(&mut block).unwrap();
+            assert!(-1.0 <= range.0 && range.1 <= 1.0);
+
+            // Every value depends on its lattice coordinate alone, not on the block it is
+            // generated in
+            let mut corner = vec![0.0; 4 * 4];
+            let mut offset = NoiseBuilder::white_2d_offset(10.0, 4, 5.0, 4);
+            offset.with_seed(42).generate_into(&mut corner).unwrap();
+            for (i, value) in corner.iter().enumerate() {
+                assert_eq!(*value, block[(5 + i / 4) * W + 10 + i % 4]);
+            }
+            // Samples in the same cell share their value
+            let halves = generate(
+                NoiseBuilder::white_1d(2 * W).with_seed(42).with_step(0.5),
+                2 * W,
+            );
+            assert!(halves.chunks(2).all(|pair| pair[0] == pair[1]));
+
+            assert_ne!(block, generate(white.with_seed(43), W * H));
+            let mut noise_f64 = vec![0.0; W * H * D];
+            let range = NoiseBuilder::white_3d(W, H, D)
+                .generate_into_f64(&mut noise_f64)
+                .unwrap();
+            assert!(-1.0 <= range.0 && range.1 <= 1.0);
+            let points = vec![0.5; 8];
+            assert_eq!(
+                sample_points_4d(&white, &points, &points, &points, &points, &mut [0.0; 8]),
+                Err(NoiseError::UnsupportedDimension { dim: 4 })
+            );
+            assert_eq!(
+                unsafe { get_4d_noise(&white.wrap().unwrap(), block.as_mut_ptr()) },
+                Err(NoiseError::UnsupportedDimension { dim: 4 })
+            );
+        }
+    }
+
+    mod domain_warp {
+        use super::*;
+
+        #[test]
+        fn test_noisebuilder_domain_warp() {
+            let mut gradient = NoiseBuilder::gradient_2d(W, H);
+            let plain = generate(gradient.with_seed(3), W * H);
+
+            // Without any displacement the warp samples the inner noise as is
+            let mut warp = NoiseBuilder::domain_warp_2d(W, H);
+            warp.with_seed(3).with_inner(gradient);
+            assert_eq!(plain, generate(warp.with_amplitude(0.0), W * H));
+
+            let mut warped = vec![0.0; W * H];
+            let (min, max) = warp
+                .with_amplitude(20.0)
+                .generate_into(&mut warped)
+                .unwrap();
+            assert_ne!(plain, warped);
+            assert_within((min, max), warp.bounds());
+
+            let mut progressive = vec![0.0; W * H];
+            let mut independent = vec![0.0; W * H];
+            warp.with_fractal(3, 2.0, 0.5, WarpMode::Progressive)
+                .generate_into(&mut progressive)
+                .unwrap();
+            warp.with_fractal(3, 2.0, 0.5, WarpMode::Independent)
+                .generate_into(&mut independent)
+                .unwrap();
+            assert_ne!(progressive, independent);
+            assert_ne!(warped, independent);
+
+            let mut warped_f64 = vec![0.0; W * H];
+            warp.generate_into_f64(&mut warped_f64).unwrap();
+            for (a, b) in independent.iter().zip(&warped_f64) {
+                assert!((*a as f64 - b).abs() < 1e-3);
+            }
+
+            let mut cellular = NoiseBuilder::domain_warp_4d(W, H, D, 1);
+            cellular.with_warp(NoiseBuilder::cellular_3d(W, H, D));
+            assert_eq!(
+                cellular.validate(),
+                Err(NoiseError::UnsupportedDimension { dim: 4 })
+            );
+            warp.octaves = 0;
+            assert_eq!(warp.validate(), Err(NoiseError::ZeroOctaves));
+        }
+
+        #[test]
+        fn test_noisebuilder_domain_warp_setter_order() {
+            let gradient = NoiseBuilder::gradient_2d(W, H);
+            let fbm = *NoiseBuilder::fbm_2d(W, H).with_seed(42).with_octaves(3);
+
+            // The sources always end up with the seed of the warp, whenever it was set
+            assert_eq!(
+                generate(
+                    NoiseBuilder::domain_warp_2d(W, H)
+                        .with_seed(7)
+                        .with_inner(gradient)
+                        .with_warp(fbm),
+                    W * H
+                ),
+                generate(
+                    NoiseBuilder::domain_warp_2d(W, H)
+                        .with_warp(fbm)
+                        .with_inner(gradient)
+                        .with_seed(7),
+                    W * H
+                )
+            );
+
+            // and with its dimensions, so 1d fbm bounds apply to a 1d warp of 2d built fbm
+            let mut warp = NoiseBuilder::domain_warp_1d(W);
+            warp.with_inner(*NoiseBuilder::fbm_2d(W, H).with_octaves(3));
+            assert_eq!(
+                warp.bounds(),
+                NoiseBuilder::fbm_1d(W).with_octaves(3).bounds()
+            );
+            assert_eq!(warp.validate(), Ok(()));
+        }
+    }
+
+    mod curl {
+        use super::*;
+
+        #[test]
+        fn test_noisebuilder_curl() {
+            // Central differences of the planar field, the divergence should cancel out while the
+            // individual derivatives don't
+            let mut curl = NoiseBuilder::curl_2d(W, H);
+            curl.with_seed(9).with_layout(ChannelLayout::Planar);
+            let mut planar = vec![0.0; W * H * 2];
+            curl.generate_into(&mut planar).unwrap();
+            let (vx, vy) = planar.split_at(W * H);
+            let (mut divergence, mut derivatives) = (0.0, 0.0);
+            for y in 1..H - 1 {
+                for x in 1..W - 1 {
+                    let i = y * W + x;
+                    let dx = (vx[i + 1] - vx[i - 1]) / 2.0;
+                    let dy = (vy[i + W] - vy[i - W]) / 2.0;
+                    divergence += (dx + dy).abs();
+                    derivatives += dx.abs() + dy.abs();
+                }
+            }
+            assert!(
+                divergence < 0.05 * derivatives,
+                "{} vs {}",
+                divergence,
+                derivatives
+            );
+
+            let mut interleaved = vec![0.0; W * H * 2];
+            curl.with_layout(ChannelLayout::Interleaved)
+                .generate_into(&mut interleaved)
+                .unwrap();
+            for i in 0..W * H {
+                assert_eq!(interleaved[2 * i], vx[i]);
+                assert_eq!(interleaved[2 * i + 1], vy[i]);
+            }
+
+            let mut curl = NoiseBuilder::curl_3d(W, H, D);
+            curl.with_seed(9).with_layout(ChannelLayout::Planar);
+            let mut planar = vec![0.0; W * H * D * 3];
+            curl.generate_into(&mut planar).unwrap();
+            let (vx, rest) = planar.split_at(W * H * D);
+            let (vy, vz) = rest.split_at(W * H * D);
+            let (mut divergence, mut derivatives) = (0.0, 0.0);
+            for z in 1..D - 1 {
+                for y in 1..H - 1 {
+                    for x in 1..W - 1 {
+                        let i = (z * H + y) * W + x;
+                        let dx = (vx[i + 1] - vx[i - 1]) / 2.0;
+                        let dy = (vy[i + W] - vy[i - W]) / 2.0;
+                        let dz = (vz[i + W * H] - vz[i - W * H]) / 2.0;
+                        divergence += (dx + dy + dz).abs();
+                        derivatives += dx.abs() + dy.abs() + dz.abs();
+                    }
+                }
+            }
+            assert!(
+                divergence < 0.05 * derivatives,
+                "{} vs {}",
+                divergence,
+                derivatives
+            );
+
+            assert_eq!(
+                curl.generate_into(&mut [0.0; W * H * D]),
+                Err(NoiseError::BufferTooSmall {
+                    required: W * H * D * 3,
+                    actual: W * H * D,
+                })
+            );
+        }
+    }
+}
+
+mod settings {
+    use super::*;
+
+    #[test]
+    fn test_noisebuilder_generate_into_too_small() {
+        let mut noise = [0.0; W * H - 1];
+        let result = NoiseBuilder::gradient_2d(W, H).generate_into(&mut noise);
+        assert_eq!(
+            result,
+            Err(NoiseError::BufferTooSmall {
+                required: W * H,
+                actual: W * H - 1
+            })
+        );
+        assert!(
+            noise.iter().all(|&n| n == 0.0),
+            "nothing may be written on failure"
+        );
+    }
+
+    #[test]
+    fn test_noisebuilder_validate() {
+        let mut noise = [0.0; W * H];
+        assert_eq!(
+            NoiseBuilder::fbm_2d(W, H)
+                .with_octaves(0)
+                .generate_into(&mut noise),
+            Err(NoiseError::ZeroOctaves)
+        );
+        assert_eq!(
+            NoiseBuilder::gradient_2d(W, H)
+                .with_freq(f32::NAN)
+                .generate_into(&mut noise),
+            Err(NoiseError::NonFiniteFrequency)
+        );
+        assert_eq!(
+            NoiseBuilder::ridge_2d(W, H)
+                .with_offset(f32::INFINITY)
+                .generate_into(&mut noise),
+            Err(NoiseError::NonFiniteParameter)
+        );
+        assert_eq!(
+            NoiseBuilder::gradient_2d(0, H).generate_into(&mut noise),
+            Err(NoiseError::ZeroSize)
+        );
+        assert_eq!(
+            NoiseBuilder::gradient_2d(W, H).generate_scaled_into(0.0, f32::NAN, &mut noise),
+            Err(NoiseError::NonFiniteParameter)
+        );
+        assert!(
+            NoiseBuilder::cellular_2d(W, H).wrap().is_ok(),
+            "valid settings must wrap"
+        );
+        assert!(
+            noise.iter().all(|&n| n == 0.0),
+            "nothing may be written on failure"
+        );
+
+        let mut dim = NoiseDimensions::default(5);
+        assert_eq!(
+            GradientSettings::default(dim).wrap().err(),
+            Some(NoiseError::UnsupportedDimension { dim: 5 })
+        );
+        dim = NoiseDimensions::default(2);
+        dim.depth = 3;
+        assert_eq!(
+            GradientSettings::default(dim).wrap().err(),
+            Some(NoiseError::DimensionMismatch { dim: 2 })
+        );
+        dim = NoiseDimensions::default(4);
+        assert_eq!(
+            CellularSettings::default(dim).wrap().err(),
+            Some(NoiseError::UnsupportedDimension { dim: 4 })
+        );
+        // Noise types without 4d noise fail in the 4d generators instead of panicking
+        let cellular = NoiseBuilder::cellular_2d(W, H).wrap().unwrap();
+        let mut noise_f64 = [0.0; W * H];
+        unsafe {
+            assert_eq!(
+                get_4d_noise(&cellular, noise.as_mut_ptr()),
+                Err(NoiseError::UnsupportedDimension { dim: 4 })
+            );
+            assert_eq!(
+                get_4d_scaled_noise_f64(&cellular, 0.0, 1.0, noise_f64.as_mut_ptr()),
+                Err(NoiseError::UnsupportedDimension { dim: 4 })
+            );
+        }
+        assert!(
+            noise.iter().all(|&n| n == 0.0),
+            "nothing may be written on failure"
+        );
+        dim = NoiseDimensions::default(2);
+        dim.width = usize::MAX;
+        dim.height = 2;
+        assert_eq!(
+            GradientSettings::default(dim).wrap().err(),
+            Some(NoiseError::LengthOverflow)
+        );
+    }
+
+    #[test]
+    fn test_noisebuilder_theoretical_scale_is_chunk_independent() {
+        let mut whole = vec![0.0; W * H];
+        NoiseBuilder::fbm_2d_offset(0.0, W, 0.0, H)
+            .with_seed(1337)
+            .with_scale_mode(ScaleMode::Theoretical)
+            .generate_scaled_into(0.0, 1.0, &mut whole)
+            .unwrap();
+        let half = W / 2;
+        let mut right = vec![0.0; half * H];
+        NoiseBuilder::fbm_2d_offset(half as f32, half, 0.0, H)
+            .with_seed(1337)
+            .with_scale_mode(ScaleMode::Theoretical)
+            .generate_scaled_into(0.0, 1.0, &mut right)
+            .unwrap();
+
+        for y in 0..H {
+            for x in 0..half {
+                let expected = whole[y * W + half + x];
+                let actual = right[y * half + x];
+                assert!(
+                    (expected - actual).abs() < 1e-5,
+                    "{} != {}",
+                    actual,
+                    expected
+                );
+            }
+        }
+        assert!(whole.iter().all(|n| (0.0..=1.0).contains(n)));
+    }
+
+    #[test]
+    fn test_noisebuilder_bounds() {
+        let mut noise = vec![0.0; W * H];
+
+        let mut fbm = NoiseBuilder::fbm_2d(W, H);
+        fbm.with_seed(7).with_gain(-0.5).with_octaves(4);
+        assert_within(fbm.generate_into(&mut noise).unwrap(), fbm.bounds());
+
+        // Each octave has twice the frequency and half the amplitude of the one before
+        let mut turbulence = NoiseBuilder::turbulence_2d(W, H);
+        assert_eq!(turbulence.bounds(), (0.0, 1.75));
+        assert_within(
+            turbulence.generate_into(&mut noise).unwrap(),
+            turbulence.bounds(),
+        );
+        turbulence.with_seed(7).with_gain(-0.5);
+        assert!(turbulence.bounds().0 < 0.0);
+        assert_within(
+            turbulence.generate_into(&mut noise).unwrap(),
+            turbulence.bounds(),
+        );
+
+        // Octave i of ridged noise adds at most 0.5^i by default
+        let mut ridge = NoiseBuilder::ridge_2d(W, H);
+        assert_eq!(ridge.bounds(), (0.0, 1.75));
+        assert_within(ridge.generate_into(&mut noise).unwrap(), ridge.bounds());
+        ridge.with_persistence(-0.5);
+        assert_eq!(ridge.bounds(), (-0.5, 1.0));
+        assert_within(ridge.generate_into(&mut noise).unwrap(), ridge.bounds());
+
+        for return_type in [
+            CellReturnType::F1,
+            CellReturnType::F2,
+            CellReturnType::F2MinusF1,
+            CellReturnType::CellValue,
+            CellReturnType::DistanceToEdge,
+        ] {
+            let mut cellular = NoiseBuilder::cellular_2d(W, H);
+            cellular.with_freq(0.1).with_return_type(return_type);
+            assert_within(
+                cellular.generate_into(&mut noise).unwrap(),
+                cellular.bounds(),
+            );
+        }
+    }
+}
+
+mod double_precision {
+    use super::*;
+
+    #[test]
+    fn test_noisebuilder_generate_f64() {
+        let mut noise = vec![0.0; W * H];
+        let mut noise_f64 = vec![0.0; W * H];
+        let mut fbm = NoiseBuilder::fbm_2d_offset(-7.0, W, 3.0, H);
+        fbm.with_seed(1337).with_freq(0.04);
+        fbm.generate_scaled_into(-1.0, 1.0, &mut noise).unwrap();
+        fbm.generate_scaled_into_f64(-1.0, 1.0, &mut noise_f64)
+            .unwrap();
+        for (n, n64) in noise.iter().zip(&noise_f64) {
+            assert!((*n as f64 - n64).abs() < 1e-4, "{} != {}", n, n64);
+        }
+        assert_eq!(
+            fbm.generate_into_f64(&mut noise_f64[1..]),
+            Err(NoiseError::BufferTooSmall {
+                required: W * H,
+                actual: W * H - 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_noisebuilder_generate_scaled_f64_bounds() {
+        // Neither bound is an f32, a range narrowed to single precision misses both by ~1e-8
+        let (min, max) = (0.1, 0.3);
+        let mut noise_f64 = vec![0.0; W * H];
+        let mut fbm = NoiseBuilder::fbm_2d(W, H);
+        fbm.with_seed(1337).with_freq(0.04);
+        fbm.generate_scaled_into_f64(min, max, &mut noise_f64)
+            .unwrap();
+        let lowest = noise_f64.iter().copied().fold(f64::INFINITY, f64::min);
+        let highest = noise_f64.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        assert!((lowest - min).abs() < 1e-12, "{} != {}", lowest, min);
+        assert!((highest - max).abs() < 1e-12, "{} != {}", highest, max);
+        assert_eq!(
+            fbm.generate_scaled_into_f64(0.0, f64::NAN, &mut noise_f64),
+            Err(NoiseError::NonFiniteParameter)
+        );
+    }
+
+    #[test]
+    fn test_noisebuilder_generate_f64_3d() {
+        // 3d noise on the default lattice, which every fractal type samples
+        let mut gradient = NoiseBuilder::gradient_3d(W, H, D);
+        gradient.with_seed(7).with_freq(0.05);
+        assert_f64_matches_f32(gradient, W * H * D);
+        let mut fbm = NoiseBuilder::fbm_3d(W, H, D);
+        fbm.with_seed(7).with_octaves(3);
+        assert_f64_matches_f32(fbm, W * H * D);
+        assert_f64_matches_f32(NoiseBuilder::ridge_3d(W, H, D), W * H * D);
+        assert_f64_matches_f32(NoiseBuilder::turbulence_3d(W, H, D), W * H * D);
+        assert_f64_matches_f32(NoiseBuilder::billow_3d(W, H, D), W * H * D);
+        let mut warp = NoiseBuilder::domain_warp_3d(W, H, D);
+        warp.with_inner(gradient).with_warp(fbm);
+        assert_f64_matches_f32(warp, W * H * D);
+    }
+
+    #[test]
+    fn test_noisebuilder_generate_f64_far_from_origin() {
+        // f32 can only step by 0.0625 out here, so every 6 or so samples would be identical
+        let mut dim = NoiseDimensions::default(2);
+        dim.width = W;
+        dim.height = 2;
+        dim.x = 1_000_000.25;
+        dim.step_x = 0.01;
+        let mut settings = GradientSettings::default(dim);
+        settings.with_freq(1.0);
+        let mut noise = vec![0.0; W * 2];
+        settings.generate_into_f64(&mut noise).unwrap();
+
+        for pair in noise[..W].windows(2) {
+            assert_ne!(pair[0], pair[1]);
+        }
+    }
+}
+
+mod derivatives {
+    use super::*;
+
+    #[test]
+    fn test_noisebuilder_derivatives() {
+        let mut gradient = NoiseBuilder::gradient_2d(W, H);
+        gradient.with_seed(4);
+        let mut values = vec![0.0; W * H];
+        let mut derivatives = vec![0.0; W * H * 2];
+        gradient
+            .generate_with_derivatives_into(&mut values, &mut derivatives, ChannelLayout::Planar)
+            .unwrap();
+        let mut plain = vec![0.0; W * H];
+        gradient.generate_into(&mut plain).unwrap();
+        assert_eq!(values, plain);
+        let (dx, dy) = derivatives.split_at(W * H);
+        let (mut error, mut total) = (0.0, 0.0);
+        for y in 1..H - 1 {
+            for x in 1..W - 1 {
+                let i = y * W + x;
+                error += ((values[i + 1] - values[i - 1]) / 2.0 - dx[i]).abs();
+                error += ((values[i + W] - values[i - W]) / 2.0 - dy[i]).abs();
+                total += dx[i].abs() + dy[i].abs();
+            }
+        }
+        assert!(error < 0.02 * total, "{} vs {}", error, total);
+
+        let mut fbm = NoiseBuilder::fbm_3d(W, H, D);
+        // Low enough frequency for the central differences to be a good estimate in every octave
+        fbm.with_seed(4)
+            .with_freq(0.01)
+            .with_lacunarity(2.0)
+            .with_gain(0.5)
+            .with_octaves(3);
+        let mut values = vec![0.0; W * H * D];
+        let mut derivatives = vec![0.0; W * H * D * 3];
+        let range = fbm
+            .generate_with_derivatives_into(
+                &mut values,
+                &mut derivatives,
+                ChannelLayout::Interleaved,
+            )
+            .unwrap();
+        let mut plain = vec![0.0; W * H * D];
+        assert_eq!(fbm.generate_into(&mut plain).unwrap(), range);
+        assert_eq!(values, plain);
+        let (mut error, mut total) = (0.0, 0.0);
+        for z in 1..D - 1 {
+            for y in 1..H - 1 {
+                for x in 1..W - 1 {
+                    let i = (z * H + y) * W + x;
+                    for (axis, stride) in [1, W, W * H].into_iter().enumerate() {
+                        let d = derivatives[i * 3 + axis];
+                        error += ((values[i + stride] - values[i - stride]) / 2.0 - d).abs();
+                        total += d.abs();
+                    }
+                }
+            }
+        }
+        assert!(error < 0.02 * total, "{} vs {}", error, total);
+
+        assert_eq!(
+            fbm.generate_with_derivatives_into(&mut values, &mut [0.0; 4], ChannelLayout::Planar),
+            Err(NoiseError::BufferTooSmall {
+                required: W * H * D * 3,
+                actual: 4,
+            })
+        );
+        let mut ridge = vec![0.0; W * H];
+        assert_eq!(
+            NoiseBuilder::ridge_2d(W, H).generate_with_derivatives_into(
+                &mut ridge,
+                &mut derivatives,
+                ChannelLayout::Planar
+            ),
+            Err(NoiseError::DerivativesUnsupported)
+        );
+        // The raw function checks the noise type too, rather than panicking
+        let cellular = NoiseBuilder::cellular_2d(W, H).wrap().unwrap();
+        assert_eq!(
+            unsafe {
+                get_noise_deriv(
+                    &cellular,
+                    ridge.as_mut_ptr(),
+                    derivatives.as_mut_ptr(),
+                    ChannelLayout::Planar,
+                )
+            },
+            Err(NoiseError::DerivativesUnsupported)
+        );
+        // A noise type built around the setters' checks fails on its lattice the same way
+        let mut perlin = *NoiseBuilder::gradient_4d(4, 4, 4, 4).with_seed(4);
+        perlin.lattice = Lattice::Perlin;
+        let mut values = vec![0.0; 4 * 4 * 4 * 4];
+        let mut derivatives = vec![0.0; 4 * 4 * 4 * 4 * 4];
+        assert_eq!(
+            unsafe {
+                get_noise_deriv(
+                    &NoiseType::Gradient(perlin),
+                    values.as_mut_ptr(),
+                    derivatives.as_mut_ptr(),
+                    ChannelLayout::Planar,
+                )
+            },
+            Err(NoiseError::UnsupportedDimension { dim: 4 })
+        );
+    }
+}
+
+mod periodic {
+    use super::*;
+
+    #[test]
+    fn test_noisebuilder_periodic() {
+        // 3 lattice units per period, the shortest distance the simplex lattice repeats over
+        const P: usize = 32;
+        const FREQ: f32 = 3.0 / P as f32;
+        let close = |a: f32, b: f32| (a - b).abs() < 1e-4;
+
+        let mut gradient = NoiseBuilder::gradient_2d(2 * P, 2 * P);
+        gradient
+            .with_seed(5)
+            .with_freq(FREQ)
+            .with_period_2d(P as f32, P as f32);
+        let mut noise = vec![0.0; 4 * P * P];
+        gradient.generate_into(&mut noise).unwrap();
+        for y in 0..P {
+            for x in 0..P {
+                let i = y * 2 * P + x;
+                assert!(close(noise[i], noise[i + P]), "x wraps at {} {}", x, y);
+                assert!(
+                    close(noise[i], noise[i + 2 * P * P]),
+                    "y wraps at {} {}",
+                    x,
+                    y
+                );
+            }
+        }
+        assert!(noise[..P].windows(2).any(|pair| !close(pair[0], pair[1])));
+
+        let mut fbm = NoiseBuilder::fbm_3d(P + 8, P + 8, P + 8);
+        fbm.with_seed(5)
+            .with_freq(FREQ)
+            .with_lacunarity(2.0)
+            .with_gain(0.5)
+            .with_octaves(3)
+            .with_period_3d(P as f32, P as f32, P as f32);
+        let n = P + 8;
+        let mut noise = vec![0.0; n * n * n];
+        let mut derivatives = vec![0.0; n * n * n * 3];
+        fbm.generate_with_derivatives_into(
+            &mut noise,
+            &mut derivatives,
+            ChannelLayout::Interleaved,
+        )
+        .unwrap();
+        for z in 0..8 {
+            for y in 0..8 {
+                for x in 0..8 {
+                    let i = (z * n + y) * n + x;
+                    for stride in [P, P * n, P * n * n] {
+                        assert!(
+                            close(noise[i], noise[i + stride]),
+                            "wraps at {} {} {}",
+                            x,
+                            y,
+                            z
+                        );
+                        let (a, b) = (i * 3, (i + stride) * 3);
+                        for (da, db) in derivatives[a..a + 3].iter().zip(&derivatives[b..b + 3]) {
+                            assert!(
+                                (da - db).abs() < 1e-3,
+                                "derivative wraps at {} {} {}",
+                                x,
+                                y,
+                                z
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        // 100 * 0.02 is 2 lattice units, not a multiple of 3
+        let mut noise = vec![0.0; 4 * P * P];
+        assert_eq!(
+            NoiseBuilder::gradient_2d(2 * P, 2 * P)
+                .with_period_2d(100.0, 0.0)
+                .generate_into(&mut noise),
+            Err(NoiseError::InvalidPeriod)
+        );
+        // The default lacunarity halves the period every octave, 3 units fit the first only
+        assert_eq!(
+            NoiseBuilder::fbm_2d(2 * P, 2 * P)
+                .with_freq(FREQ)
+                .with_period_2d(P as f32, P as f32)
+                .generate_into(&mut noise),
+            Err(NoiseError::InvalidPeriod)
+        );
+        assert_eq!(
+            NoiseBuilder::gradient_4d(4, 4, 4, 4)
+                .with_freq(FREQ)
+                .with_period_3d(P as f32, P as f32, P as f32)
+                .generate_into(&mut noise),
+            Err(NoiseError::UnsupportedPeriod)
+        );
+        assert_eq!(
+            gradient.generate_into_f64(&mut vec![0.0; 4 * P * P]),
+            Err(NoiseError::UnsupportedPeriod)
+        );
+        let points = vec![0.5; 8];
+        assert_eq!(
+            sample_points_2d_f64(&gradient, &points, &points, &mut [0.0; 8]),
+            Err(NoiseError::UnsupportedPeriod)
+        );
+        let mut warp = NoiseBuilder::domain_warp_2d(2 * P, 2 * P);
+        warp.with_inner(gradient);
+        assert_eq!(
+            warp.generate_into_f64(&mut vec![0.0; 4 * P * P]),
+            Err(NoiseError::UnsupportedPeriod)
+        );
+        warp.with_inner(NoiseBuilder::gradient_2d(2 * P, 2 * P))
+            .with_warp(gradient);
+        assert_eq!(
+            warp.generate_scaled_into_f64(0.0, 1.0, &mut vec![0.0; 4 * P * P]),
+            Err(NoiseError::UnsupportedPeriod)
+        );
     }
 }
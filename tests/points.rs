@@ -0,0 +1,104 @@
+use cursednoise::{
+    sample_points_1d, sample_points_2d, sample_points_2d_f64, sample_points_3d,
+    sample_points_3d_f64, NoiseBuilder, NoiseError, Settings, SimplexSettings,
+};
+
+// Odd sizes so the last batch of points is always a partial one
+const W: usize = 37;
+const H: usize = 11;
+const D: usize = 5;
+
+fn assert_close(expected: &[f32], actual: &[f32]) {
+    for (i, (e, a)) in expected.iter().zip(actual).enumerate() {
+        assert!((e - a).abs() < 1e-6, "point {} differs: {} != {}", i, a, e);
+    }
+}
+
+#[test]
+fn test_sample_points_match_blocks() {
+    let mut fbm = NoiseBuilder::fbm_1d_offset(3.0, W);
+    fbm.with_seed(42).with_octaves(4);
+    let mut block = vec![0.0; W];
+    fbm.generate_into(&mut block).unwrap();
+    let xs: Vec<f32> = (0..W).map(|x| 3.0 + x as f32).collect();
+    let mut points = vec![0.0; W];
+    sample_points_1d(&fbm, &xs, &mut points).unwrap();
+    assert_close(&block, &points);
+
+    let mut gradient = NoiseBuilder::gradient_2d(W, H);
+    gradient.with_seed(42).with_freq_2d(0.05, 0.1);
+    let mut block = vec![0.0; W * H];
+    gradient.generate_into(&mut block).unwrap();
+    let xs: Vec<f32> = (0..W * H).map(|i| (i % W) as f32).collect();
+    let ys: Vec<f32> = (0..W * H).map(|i| (i / W) as f32).collect();
+    let mut points = vec![0.0; W * H];
+    sample_points_2d(&gradient, &xs, &ys, &mut points).unwrap();
+    assert_close(&block, &points);
+
+    let ridge = NoiseBuilder::ridge_3d(W, H, D);
+    let mut block = vec![0.0; W * H * D];
+    ridge.generate_into(&mut block).unwrap();
+    let xs: Vec<f32> = (0..W * H * D).map(|i| (i % W) as f32).collect();
+    let ys: Vec<f32> = (0..W * H * D).map(|i| (i / W % H) as f32).collect();
+    let zs: Vec<f32> = (0..W * H * D).map(|i| (i / (W * H)) as f32).collect();
+    let mut points = vec![0.0; W * H * D];
+    sample_points_3d(&ridge, &xs, &ys, &zs, &mut points).unwrap();
+    assert_close(&block, &points);
+}
+
+#[test]
+fn test_sample_points_f64() {
+    let gradient = NoiseBuilder::gradient_2d(1, 1);
+    let xs = [0.5, 17.25, -3.0, 1000.0, 12.0];
+    let ys = [2.0, -8.5, 0.0, 3.75, 12.0];
+    let mut points = [0.0; 5];
+    let mut points_f64 = [0.0; 5];
+    sample_points_2d(&gradient, &xs, &ys, &mut points).unwrap();
+    let xs = xs.map(f64::from);
+    let ys = ys.map(f64::from);
+    sample_points_2d_f64(&gradient, &xs, &ys, &mut points_f64).unwrap();
+    for (p, p64) in points.iter().zip(points_f64) {
+        assert!((*p as f64 - p64).abs() < 1e-5, "{} != {}", p, p64);
+    }
+
+    // 3d noise on the default simplex lattice, sampled at the points of a block
+    let mut fbm = NoiseBuilder::fbm_3d(W, H, D);
+    fbm.with_seed(42).with_octaves(3);
+    let mut block = vec![0.0; W * H * D];
+    fbm.generate_into_f64(&mut block).unwrap();
+    let xs: Vec<f64> = (0..W * H * D).map(|i| (i % W) as f64).collect();
+    let ys: Vec<f64> = (0..W * H * D).map(|i| (i / W % H) as f64).collect();
+    let zs: Vec<f64> = (0..W * H * D).map(|i| (i / (W * H)) as f64).collect();
+    let mut points_f64 = vec![0.0; W * H * D];
+    sample_points_3d_f64(&fbm, &xs, &ys, &zs, &mut points_f64).unwrap();
+    let xs: Vec<f32> = xs.iter().map(|&x| x as f32).collect();
+    let ys: Vec<f32> = ys.iter().map(|&y| y as f32).collect();
+    let zs: Vec<f32> = zs.iter().map(|&z| z as f32).collect();
+    let mut points = vec![0.0; W * H * D];
+    sample_points_3d(&fbm, &xs, &ys, &zs, &mut points).unwrap();
+    for (i, ((b, p64), p)) in block.iter().zip(&points_f64).zip(&points).enumerate() {
+        assert!((b - p64).abs() < 1e-6, "point {} differs: {} != {}", i, p64, b);
+        assert!((*p as f64 - p64).abs() < 1e-4, "point {} differs: {} != {}", i, p, p64);
+    }
+}
+
+#[test]
+fn test_sample_points_errors() {
+    let gradient = NoiseBuilder::gradient_2d(1, 1);
+    let mut out = [0.0; 4];
+    assert_eq!(
+        sample_points_2d(&gradient, &[0.0; 4], &[0.0; 3], &mut out),
+        Err(NoiseError::PointCountMismatch {
+            expected: 4,
+            actual: 3
+        })
+    );
+    assert!(sample_points_2d(&gradient, &[], &[], &mut []).is_ok());
+
+    let mut fbm = NoiseBuilder::fbm_2d(1, 1);
+    fbm.with_octaves(0);
+    assert_eq!(
+        sample_points_2d(&fbm, &[0.0; 4], &[0.0; 4], &mut out),
+        Err(NoiseError::ZeroOctaves)
+    );
+}